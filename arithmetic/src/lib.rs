@@ -6,16 +6,28 @@
 
 mod errors;
 mod multilinear_polynomial;
+mod next_row;
+mod small_multilinear_polynomial;
+mod sparse_multilinear_polynomial;
 mod univariate_polynomial;
 mod util;
 mod virtual_polynomial;
 
 pub use errors::ArithErrors;
 pub use multilinear_polynomial::{
-    evaluate_no_par, evaluate_opt, fix_last_variables, fix_last_variables_no_par, fix_variables,
-    identity_permutation, identity_permutation_mles, merge_polynomials, random_mle_list,
-    random_permutation, random_permutation_mles, random_zero_mle_list, DenseMultilinearExtension,
+    evaluate_no_par, evaluate_opt, fix_first_variable, fix_first_variable_in_place,
+    fix_last_variables, fix_last_variables_no_par, fix_variables, identity_permutation,
+    identity_permutation_mles, merge_polynomials, random_mle_list, random_permutation,
+    random_permutation_mles, random_zero_mle_list, DenseMultilinearExtension,
+    IntoMultilinearPolynomial, MultilinearPolynomial,
 };
+pub use next_row::{
+    next_row, next_row_index, next_row_points, next_row_polynomial, prev_row, shift_to_next_row,
+};
+pub use small_multilinear_polynomial::{
+    fix_first_variable_small, small_value, SmallMultilinearPolynomial,
+};
+pub use sparse_multilinear_polynomial::SparseMultilinearPolynomial;
 pub use univariate_polynomial::{build_l, get_uni_domain};
 pub use util::{bit_decompose, gen_eval_point, get_batched_nv, get_index};
 pub use virtual_polynomial::{
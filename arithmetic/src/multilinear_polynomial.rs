@@ -4,16 +4,132 @@
 // You should have received a copy of the MIT License
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
-use crate::{util::get_batched_nv, ArithErrors};
+use crate::{
+    fix_first_variable_small, util::get_batched_nv, ArithErrors, SparseMultilinearPolynomial,
+};
 use ark_ff::{Field, PrimeField};
 use ark_poly::MultilinearExtension;
 use ark_std::{end_timer, rand::RngCore, start_timer};
 #[cfg(feature = "parallel")]
 use rayon::prelude::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
-use std::sync::Arc;
+use std::{fmt::Debug, sync::Arc};
 
 pub use ark_poly::DenseMultilinearExtension;
 
+/// A multilinear polynomial, given by its evaluations over the boolean
+/// hypercube. The evaluation at `x` is stored at index `x_0 + 2 x_1 + ... +
+/// 2^{n-1} x_{n-1}`.
+///
+/// The evaluations are either stored densely, as in
+/// `DenseMultilinearExtension`, or only the non-zero ones are, as in
+/// `SparseMultilinearPolynomial`, or they are small integers, as in
+/// `SmallMultilinearPolynomial`. Algorithms can use the representation to
+/// run in time proportional to the number of non-zero evaluations, or to
+/// work with integers rather than field elements.
+pub trait MultilinearPolynomial<F: Field>: Debug + Send + Sync {
+    /// Number of variables of the polynomial
+    fn num_variables(&self) -> usize;
+
+    /// The evaluation at `index`
+    fn evaluation(&self, index: usize) -> F;
+
+    /// All the evaluations, if they are stored densely
+    fn dense_evaluations(&self) -> Option<&[F]> {
+        None
+    }
+
+    /// The non-zero evaluations `(index, evaluation)` by increasing index, if
+    /// only those are stored
+    fn sparse_evaluations(&self) -> Option<&[(usize, F)]> {
+        None
+    }
+
+    /// The evaluations as integers, with a bound `num_bits` such that they are
+    /// all less than `2^num_bits`, if they are stored as such
+    fn small_evaluations(&self) -> Option<(&[u64], u32)> {
+        None
+    }
+
+    /// Convert the polynomial into a dense one
+    fn to_dense(&self) -> DenseMultilinearExtension<F> {
+        DenseMultilinearExtension::from_evaluations_vec(
+            self.num_variables(),
+            (0..1 << self.num_variables())
+                .map(|index| self.evaluation(index))
+                .collect(),
+        )
+    }
+}
+
+impl<F: Field> MultilinearPolynomial<F> for DenseMultilinearExtension<F> {
+    fn num_variables(&self) -> usize {
+        self.num_vars
+    }
+
+    fn evaluation(&self, index: usize) -> F {
+        self.evaluations[index]
+    }
+
+    fn dense_evaluations(&self) -> Option<&[F]> {
+        Some(&self.evaluations)
+    }
+
+    fn to_dense(&self) -> DenseMultilinearExtension<F> {
+        self.clone()
+    }
+}
+
+impl<F: Field, M: MultilinearPolynomial<F> + ?Sized> MultilinearPolynomial<F> for Arc<M> {
+    fn num_variables(&self) -> usize {
+        self.as_ref().num_variables()
+    }
+
+    fn evaluation(&self, index: usize) -> F {
+        self.as_ref().evaluation(index)
+    }
+
+    fn dense_evaluations(&self) -> Option<&[F]> {
+        self.as_ref().dense_evaluations()
+    }
+
+    fn sparse_evaluations(&self) -> Option<&[(usize, F)]> {
+        self.as_ref().sparse_evaluations()
+    }
+
+    fn small_evaluations(&self) -> Option<(&[u64], u32)> {
+        self.as_ref().small_evaluations()
+    }
+
+    fn to_dense(&self) -> DenseMultilinearExtension<F> {
+        self.as_ref().to_dense()
+    }
+}
+
+impl<F: Field> PartialEq for dyn MultilinearPolynomial<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.num_variables() == other.num_variables()
+            && (0..1 << self.num_variables())
+                .all(|index| self.evaluation(index) == other.evaluation(index))
+    }
+}
+
+/// Conversion into a shared multilinear polynomial of any representation.
+pub trait IntoMultilinearPolynomial<F: Field> {
+    fn into_multilinear_polynomial(self) -> Arc<dyn MultilinearPolynomial<F>>;
+}
+
+impl<F: Field, M: MultilinearPolynomial<F> + 'static> IntoMultilinearPolynomial<F> for Arc<M> {
+    fn into_multilinear_polynomial(self) -> Arc<dyn MultilinearPolynomial<F>> {
+        self
+    }
+}
+
+impl<F: Field> IntoMultilinearPolynomial<F> for Arc<dyn MultilinearPolynomial<F>> {
+    fn into_multilinear_polynomial(self) -> Arc<dyn MultilinearPolynomial<F>> {
+        self
+    }
+}
+
 /// Sample a random list of multilinear polynomials.
 /// Returns
 /// - the list of polynomials,
@@ -132,30 +248,70 @@ pub fn random_permutation_mles<F: PrimeField, R: RngCore>(
     res
 }
 
-pub fn evaluate_opt<F: Field>(poly: &DenseMultilinearExtension<F>, point: &[F]) -> F {
-    assert_eq!(poly.num_vars, point.len());
+pub fn evaluate_opt<F: Field, M: MultilinearPolynomial<F> + ?Sized>(poly: &M, point: &[F]) -> F {
+    assert_eq!(poly.num_variables(), point.len());
     fix_variables(poly, point).evaluations[0]
 }
 
-pub fn fix_variables<F: Field>(
-    poly: &DenseMultilinearExtension<F>,
+/// Fix the first `partial_point.len()` variables of `poly` to
+/// `partial_point`.
+///
+/// The non-zero evaluations of a sparse polynomial are folded as long as the
+/// result is larger than them, so this takes time proportional to their number
+/// plus the size of the result.
+pub fn fix_variables<F: Field, M: MultilinearPolynomial<F> + ?Sized>(
+    poly: &M,
     partial_point: &[F],
 ) -> DenseMultilinearExtension<F> {
     assert!(
-        partial_point.len() <= poly.num_vars,
+        partial_point.len() <= poly.num_variables(),
         "invalid size of partial point"
     );
-    let nv = poly.num_vars;
-    let mut poly = poly.evaluations.to_vec();
+    let nv = poly.num_variables();
     let dim = partial_point.len();
+    if let Some(evaluations) = poly.sparse_evaluations() {
+        let mut poly =
+            SparseMultilinearPolynomial::from_sorted_evaluations(nv, evaluations.to_vec());
+        for point in partial_point.iter() {
+            poly.fix_first_variable_in_place(point);
+        }
+        return poly.to_dense();
+    }
+    let (mut poly, num_fixed) = match (poly.dense_evaluations(), poly.small_evaluations()) {
+        (Some(evaluations), _) => (evaluations.to_vec(), 0),
+        // the first variable is fixed straight from the integers
+        (None, Some((evaluations, _))) if dim > 0 => {
+            (fix_first_variable_small(evaluations, &partial_point[0]), 1)
+        },
+        _ => (poly.to_dense().evaluations, 0),
+    };
     // evaluate single variable of partial point from left to right
-    for (i, point) in partial_point.iter().enumerate().take(dim) {
+    for (i, point) in partial_point.iter().enumerate().take(dim).skip(num_fixed) {
         poly = fix_one_variable_helper(&poly, nv - i, point);
     }
 
     DenseMultilinearExtension::<F>::from_evaluations_slice(nv - dim, &poly[..(1 << (nv - dim))])
 }
 
+/// Fix the first variable of the evaluations `evals` to `point`, into a new
+/// table of half the size.
+pub fn fix_first_variable<F: Field>(evals: &[F], point: &F) -> Vec<F> {
+    fix_one_variable_helper(evals, ark_std::log2(evals.len()) as usize, point)
+}
+
+/// Fix the first variable of the evaluations `evals` to `point` in place,
+/// and truncate `evals` to the first half, which holds the result.
+///
+/// Entry `b` of the result only reads entries `2b` and `2b + 1`, which are
+/// not overwritten before, so no other buffer is needed.
+pub fn fix_first_variable_in_place<F: Field>(evals: &mut Vec<F>, point: &F) {
+    let half = evals.len() >> 1;
+    for b in 0..half {
+        evals[b] = evals[b << 1] + (evals[(b << 1) + 1] - evals[b << 1]) * point;
+    }
+    evals.truncate(half);
+}
+
 fn fix_one_variable_helper<F: Field>(data: &[F], nv: usize, point: &F) -> Vec<F> {
     let mut res = vec![F::zero(); 1 << (nv - 1)];
 
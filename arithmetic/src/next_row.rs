@@ -0,0 +1,242 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! The next row of a table laid out on the boolean hypercube.
+
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use std::sync::Arc;
+
+// The low terms of a primitive polynomial of degree `d` over GF(2), for
+// `d = 1, ..., 64`: bit `i` of entry `d` is the coefficient of `X^i`. Each
+// polynomial has the fewest terms possible, and then the lowest ones.
+const PRIMITIVE_POLYNOMIALS: [u64; 65] = [
+    0x0,
+    0x1,
+    0x3,
+    0x3,
+    0x3,
+    0x5,
+    0x3,
+    0x3,
+    0x87,
+    0x11,
+    0x9,
+    0x5,
+    0x107,
+    0x27,
+    0x1007,
+    0x3,
+    0x100b,
+    0x9,
+    0x81,
+    0x27,
+    0x9,
+    0x5,
+    0x3,
+    0x21,
+    0x87,
+    0x9,
+    0x47,
+    0x27,
+    0x9,
+    0x5,
+    0x800007,
+    0x9,
+    0x400007,
+    0x2001,
+    0x8000007,
+    0x5,
+    0x801,
+    0x207,
+    0x200b,
+    0x11,
+    0x800000007,
+    0x9,
+    0x20000007,
+    0x1007,
+    0x400000000b,
+    0x1b,
+    0x20b,
+    0x21,
+    0x1000000b,
+    0x201,
+    0x10007,
+    0x10000007,
+    0x9,
+    0x47,
+    0x20007,
+    0x1000001,
+    0x40000000007,
+    0x81,
+    0x80001,
+    0x1000007,
+    0x3,
+    0x27,
+    0x1000000b,
+    0x3,
+    0x807,
+];
+
+/// The low terms of the primitive polynomial `p` of degree `num_vars` that
+/// defines the next row, see [`next_row`].
+pub fn next_row_polynomial(num_vars: usize) -> u64 {
+    PRIMITIVE_POLYNOMIALS[num_vars]
+}
+
+/// The row following `row` out of `2^num_vars` rows.
+///
+/// Rows are the elements of `GF(2^num_vars) = GF(2)[X] / p(X)`, where bit
+/// `i` of a row is its coefficient of `X^i`, and the next row of `b` is
+/// `X * b`. As `X` generates the multiplicative group, the rows
+/// `1, X, ..., X^{2^num_vars - 2}` form a single cycle and row `0` is
+/// followed by itself. Unlike `b + 1`, this only shifts the bits of `b`, so
+/// the MLE of the next row is cheap to evaluate.
+///
+/// Values chained from a row to the next one are laid out along this cycle,
+/// e.g. a running sum starts at row 1 and continues at
+/// `next_row(1, num_vars)`.
+pub fn next_row(row: usize, num_vars: usize) -> usize {
+    if num_vars == 0 {
+        return row;
+    }
+    let carry = (row >> (num_vars - 1)) & 1;
+    let shifted = (row << 1) & (usize::MAX >> (usize::BITS as usize - num_vars));
+    shifted ^ (carry * next_row_polynomial(num_vars) as usize)
+}
+
+/// The row followed by `row` out of `2^num_vars` rows, i.e. the inverse of
+/// [`next_row`].
+pub fn prev_row(row: usize, num_vars: usize) -> usize {
+    if num_vars == 0 || row & 1 == 0 {
+        return row >> 1;
+    }
+    ((row ^ next_row_polynomial(num_vars) as usize) >> 1) | (1 << (num_vars - 1))
+}
+
+/// The index of the next row of index `index`, out of `2^num_vars` indices
+/// that interleave `2^log_num_instances` instances: the first
+/// `log_num_instances` bits select the instance and are kept as is.
+pub fn next_row_index(index: usize, num_vars: usize, log_num_instances: usize) -> usize {
+    let row = next_row(index >> log_num_instances, num_vars - log_num_instances);
+    (row << log_num_instances) | (index & ((1 << log_num_instances) - 1))
+}
+
+/// Build `w(next(x))`, whose evaluation at row `b` is the evaluation of `w`
+/// at row `next_row(b)`.
+///
+/// For `2^log_num_instances` interleaved instances, the first
+/// `log_num_instances` variables select the instance and are kept as is.
+pub fn shift_to_next_row<F: PrimeField>(
+    mle: &DenseMultilinearExtension<F>,
+    log_num_instances: usize,
+) -> Arc<DenseMultilinearExtension<F>> {
+    let evals = (0..1 << mle.num_vars)
+        .map(|i| mle.evaluations[next_row_index(i, mle.num_vars, log_num_instances)])
+        .collect();
+    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        mle.num_vars,
+        evals,
+    ))
+}
+
+/// Return points `p_0, p_1` and coefficients `c_0, c_1` such that for any
+/// multilinear `w`
+///     `w(next(r)) = c_0 * w(p_0) + c_1 * w(p_1)`
+/// where `w(next(x))` is the MLE of `shift_to_next_row(w)`.
+///
+/// The next row of `b` is `(0, b_0, ..., b_{n-2})` if `b_{n-1} = 0`, and
+/// `(1, b_0 ^ m_1, ..., b_{n-2} ^ m_{n-1})` otherwise, where `m_i` are the
+/// low terms of the polynomial defining `next_row`. Both are affine in
+/// `b_0, ..., b_{n-2}`, so
+/// - `p_0 = (0, r_0, ..., r_{n-2})` and `c_0 = 1 - r_{n-1}`
+/// - `p_1 = (1, r'_0, ..., r'_{n-2})` and `c_1 = r_{n-1}`, where
+///   `r'_i = 1 - r_i` if `m_{i+1} = 1` and `r'_i = r_i` otherwise.
+///
+/// With a single row, the next row is the row itself and `p_0 = r`,
+/// `c_0 = 1`. For `2^log_num_instances` interleaved instances, the first
+/// `log_num_instances` variables select the instance and are kept as is.
+pub fn next_row_points<F: PrimeField>(point: &[F], log_num_instances: usize) -> Vec<(Vec<F>, F)> {
+    let (instance_point, point) = point.split_at(log_num_instances);
+    let num_vars = point.len();
+    if num_vars == 0 {
+        return vec![(instance_point.to_vec(), F::one())];
+    }
+    let low_terms = next_row_polynomial(num_vars);
+    let (rest, last) = point.split_at(num_vars - 1);
+    let flipped: Vec<F> = rest
+        .iter()
+        .enumerate()
+        .map(|(i, &r)| match (low_terms >> (i + 1)) & 1 {
+            1 => F::one() - r,
+            _ => r,
+        })
+        .collect();
+    vec![
+        (
+            [instance_point, &[F::zero()], rest].concat(),
+            F::one() - last[0],
+        ),
+        ([instance_point, &[F::one()], &flipped].concat(), last[0]),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::evaluate_opt;
+    use ark_bls12_381::Fr;
+    use ark_poly::MultilinearExtension;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn test_next_row() {
+        // the non-zero rows form a single cycle, and row 0 is fixed
+        assert_eq!(next_row(0, 0), 0);
+        for num_vars in 1..16 {
+            assert_eq!(next_row(0, num_vars), 0);
+            let mut row = 1;
+            let mut cycle_len = 0;
+            loop {
+                row = next_row(row, num_vars);
+                assert!(row < 1 << num_vars);
+                cycle_len += 1;
+                if row == 1 {
+                    break;
+                }
+                assert!(cycle_len < 1 << num_vars);
+            }
+            assert_eq!(cycle_len, (1 << num_vars) - 1);
+            for row in 0..1 << num_vars {
+                assert_eq!(prev_row(next_row(row, num_vars), num_vars), row);
+            }
+        }
+
+        let mut rng = test_rng();
+        for num_vars in 0..6 {
+            for log_num_instances in 0..3 {
+                let total_vars = num_vars + log_num_instances;
+                let w = DenseMultilinearExtension::<Fr>::rand(total_vars, &mut rng);
+                let w_next = shift_to_next_row(&w, log_num_instances);
+                for i in 0..1 << total_vars {
+                    let instance = i % (1 << log_num_instances);
+                    let row = next_row(i >> log_num_instances, num_vars);
+                    assert_eq!(
+                        w_next.evaluations[i],
+                        w.evaluations[(row << log_num_instances) + instance]
+                    );
+                }
+
+                let point: Vec<Fr> = (0..total_vars).map(|_| Fr::rand(&mut rng)).collect();
+                let expected: Fr = next_row_points(&point, log_num_instances)
+                    .iter()
+                    .map(|(p, coeff)| *coeff * evaluate_opt(&w, p))
+                    .sum();
+                assert_eq!(evaluate_opt(&w_next, &point), expected);
+            }
+        }
+    }
+}
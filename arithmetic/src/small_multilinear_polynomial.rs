@@ -0,0 +1,171 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! This module defines multilinear polynomials whose evaluations are small
+//! integers, such as boolean or u32 witnesses.
+
+use crate::MultilinearPolynomial;
+use ark_ff::{Field, PrimeField};
+use ark_std::{cfg_into_iter, cfg_iter, marker::PhantomData, rand::RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+/// A multilinear polynomial whose evaluations are integers less than
+/// `2^num_bits`, for some `num_bits <= 64`.
+///
+/// Commitments and the first sumcheck round can then work with the integers
+/// rather than full field elements.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SmallMultilinearPolynomial<F: Field> {
+    /// Number of variables
+    pub num_vars: usize,
+    /// The evaluations
+    evaluations: Vec<u64>,
+    /// Bit size of the largest evaluation
+    num_bits: u32,
+    #[doc(hidden)]
+    phantom: PhantomData<F>,
+}
+
+impl<F: Field> SmallMultilinearPolynomial<F> {
+    /// Build a polynomial from its evaluations.
+    pub fn from_evaluations(num_vars: usize, evaluations: Vec<u64>) -> Self {
+        assert_eq!(evaluations.len(), 1 << num_vars);
+        let num_bits = u64::BITS
+            - evaluations
+                .iter()
+                .fold(0, |acc, eval| acc | eval)
+                .leading_zeros();
+        Self {
+            num_vars,
+            evaluations,
+            num_bits,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Bit size of the largest evaluation
+    pub fn num_bits(&self) -> u32 {
+        self.num_bits
+    }
+
+    /// Sample a random polynomial with evaluations less than `2^num_bits`.
+    pub fn rand<R: RngCore>(num_vars: usize, num_bits: u32, rng: &mut R) -> Self {
+        assert!(num_bits <= u64::BITS, "too many bits");
+        let mask = if num_bits == 0 {
+            0
+        } else {
+            u64::MAX >> (u64::BITS - num_bits)
+        };
+        Self::from_evaluations(
+            num_vars,
+            (0..1 << num_vars).map(|_| rng.next_u64() & mask).collect(),
+        )
+    }
+}
+
+impl<F: PrimeField> SmallMultilinearPolynomial<F> {
+    /// Build a polynomial from field evaluations, if they are all integers
+    /// that fit a `u64`.
+    pub fn try_from_field_evaluations(num_vars: usize, evaluations: &[F]) -> Option<Self> {
+        assert_eq!(evaluations.len(), 1 << num_vars);
+        let evaluations = cfg_iter!(evaluations)
+            .map(small_value)
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self::from_evaluations(num_vars, evaluations))
+    }
+}
+
+impl<F: Field> MultilinearPolynomial<F> for SmallMultilinearPolynomial<F> {
+    fn num_variables(&self) -> usize {
+        self.num_vars
+    }
+
+    fn evaluation(&self, index: usize) -> F {
+        F::from(self.evaluations[index])
+    }
+
+    fn small_evaluations(&self) -> Option<(&[u64], u32)> {
+        Some((&self.evaluations, self.num_bits))
+    }
+}
+
+/// The integer value of `eval`, if it fits a `u64`
+pub fn small_value<F: PrimeField>(eval: &F) -> Option<u64> {
+    let eval = eval.into_bigint();
+    let limbs = eval.as_ref();
+    limbs[1..].iter().all(|limb| *limb == 0).then(|| limbs[0])
+}
+
+/// Fix the first variable of the integer evaluations `evals` to `point`, into
+/// a new table of half the size.
+///
+/// Entry `b` of the result is `evals[2b] + (evals[2b + 1] - evals[2b]) *
+/// point`; the difference is usually zero or one for boolean tables, so the
+/// multiplication is mostly skipped.
+pub fn fix_first_variable_small<F: Field>(evals: &[u64], point: &F) -> Vec<F> {
+    cfg_into_iter!(0..evals.len() >> 1)
+        .map(|b| {
+            let (eval_0, eval_1) = (evals[b << 1], evals[(b << 1) + 1]);
+            let step = if eval_1 >= eval_0 {
+                match eval_1 - eval_0 {
+                    0 => F::zero(),
+                    1 => *point,
+                    step => *point * F::from(step),
+                }
+            } else {
+                match eval_0 - eval_1 {
+                    1 => -*point,
+                    step => -(*point * F::from(step)),
+                }
+            };
+            F::from(eval_0) + step
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{evaluate_opt, fix_variables};
+    use ark_bls12_381::Fr;
+    use ark_std::{test_rng, One, UniformRand};
+
+    #[test]
+    fn test_small_polynomial() {
+        let mut rng = test_rng();
+
+        for num_vars in 1..10 {
+            for num_bits in [0, 1, 8, 64] {
+                let poly = SmallMultilinearPolynomial::<Fr>::rand(num_vars, num_bits, &mut rng);
+                assert!(poly.num_bits() <= num_bits);
+                let dense = poly.to_dense();
+                assert_eq!(
+                    SmallMultilinearPolynomial::try_from_field_evaluations(
+                        num_vars,
+                        &dense.evaluations
+                    ),
+                    Some(poly.clone())
+                );
+
+                // fixing variables commutes with densifying
+                let point: Vec<_> = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+                for k in 0..=num_vars {
+                    assert_eq!(
+                        fix_variables(&poly, &point[..k]),
+                        fix_variables(&dense, &point[..k])
+                    );
+                }
+                assert_eq!(evaluate_opt(&poly, &point), evaluate_opt(&dense, &point));
+            }
+        }
+
+        // large values are rejected
+        let mut evals = vec![Fr::one(); 4];
+        evals[3] = -Fr::one();
+        assert!(SmallMultilinearPolynomial::try_from_field_evaluations(2, &evals).is_none());
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! This module defines multilinear polynomials that store only their non-zero
+//! evaluations, such as selectors.
+
+use crate::{DenseMultilinearExtension, MultilinearPolynomial};
+use ark_ff::Field;
+use ark_std::rand::RngCore;
+
+/// A multilinear polynomial whose evaluations are mostly zero, given by its
+/// non-zero evaluations `(index, evaluation)` by increasing index.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparseMultilinearPolynomial<F: Field> {
+    /// Number of variables
+    pub num_vars: usize,
+    /// The non-zero evaluations, by increasing index
+    evaluations: Vec<(usize, F)>,
+}
+
+impl<F: Field> SparseMultilinearPolynomial<F> {
+    /// Build a polynomial from a list of `(index, evaluation)` with distinct
+    /// indices, in any order. Zero evaluations are dropped.
+    pub fn from_evaluations(
+        num_vars: usize,
+        evaluations: impl IntoIterator<Item = (usize, F)>,
+    ) -> Self {
+        let mut evaluations: Vec<_> = evaluations
+            .into_iter()
+            .filter(|(_, eval)| !eval.is_zero())
+            .collect();
+        evaluations.sort_unstable_by_key(|(index, _)| *index);
+        assert!(
+            evaluations.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "indices are not distinct"
+        );
+        assert!(
+            evaluations.iter().all(|(index, _)| *index < 1 << num_vars),
+            "index out of range"
+        );
+        Self {
+            num_vars,
+            evaluations,
+        }
+    }
+
+    /// Build a polynomial from all its evaluations, keeping the non-zero ones.
+    pub fn from_dense_evaluations(num_vars: usize, evaluations: &[F]) -> Self {
+        assert_eq!(evaluations.len(), 1 << num_vars);
+        Self {
+            num_vars,
+            evaluations: evaluations
+                .iter()
+                .enumerate()
+                .filter(|(_, eval)| !eval.is_zero())
+                .map(|(index, eval)| (index, *eval))
+                .collect(),
+        }
+    }
+
+    /// Build a polynomial from non-zero evaluations already sorted by
+    /// increasing index.
+    pub(crate) fn from_sorted_evaluations(num_vars: usize, evaluations: Vec<(usize, F)>) -> Self {
+        Self {
+            num_vars,
+            evaluations,
+        }
+    }
+
+    /// Number of non-zero evaluations
+    pub fn num_nonzero(&self) -> usize {
+        self.evaluations.len()
+    }
+
+    /// Fix the first variable to `point`, into a new polynomial.
+    pub fn fix_first_variable(&self, point: &F) -> Self {
+        let mut res = self.clone();
+        res.fix_first_variable_in_place(point);
+        res
+    }
+
+    /// Fix the first variable to `point` in place.
+    ///
+    /// Entry `b` of the result only reads entries `2b` and `2b + 1`, which are
+    /// adjacent in the list of non-zero evaluations, so this takes time
+    /// proportional to their number.
+    pub fn fix_first_variable_in_place(&mut self, point: &F) {
+        assert!(self.num_vars > 0, "no variable to fix");
+        let mut len = 0;
+        let mut i = 0;
+        while i < self.evaluations.len() {
+            let (index, eval) = self.evaluations[i];
+            let b = index >> 1;
+            let (eval_0, eval_1) = if index & 1 == 1 {
+                (F::zero(), eval)
+            } else {
+                match self.evaluations.get(i + 1) {
+                    Some((next, next_eval)) if *next == index + 1 => {
+                        i += 1;
+                        (eval, *next_eval)
+                    },
+                    _ => (eval, F::zero()),
+                }
+            };
+            i += 1;
+            let eval = eval_0 + (eval_1 - eval_0) * point;
+            if !eval.is_zero() {
+                self.evaluations[len] = (b, eval);
+                len += 1;
+            }
+        }
+        self.evaluations.truncate(len);
+        self.num_vars -= 1;
+    }
+
+    /// Sample a random polynomial with about `num_nonzero` non-zero
+    /// evaluations.
+    pub fn rand<R: RngCore>(num_vars: usize, num_nonzero: usize, rng: &mut R) -> Self {
+        let mut evaluations: Vec<_> = (0..num_nonzero)
+            .map(|_| (rng.next_u64() as usize % (1 << num_vars), F::rand(rng)))
+            .collect();
+        evaluations.sort_unstable_by_key(|(index, _)| *index);
+        evaluations.dedup_by_key(|(index, _)| *index);
+        Self {
+            num_vars,
+            evaluations,
+        }
+    }
+}
+
+impl<F: Field> MultilinearPolynomial<F> for SparseMultilinearPolynomial<F> {
+    fn num_variables(&self) -> usize {
+        self.num_vars
+    }
+
+    fn evaluation(&self, index: usize) -> F {
+        match self
+            .evaluations
+            .binary_search_by_key(&index, |(index, _)| *index)
+        {
+            Ok(i) => self.evaluations[i].1,
+            Err(_) => F::zero(),
+        }
+    }
+
+    fn sparse_evaluations(&self) -> Option<&[(usize, F)]> {
+        Some(&self.evaluations)
+    }
+
+    fn to_dense(&self) -> DenseMultilinearExtension<F> {
+        let mut evaluations = vec![F::zero(); 1 << self.num_vars];
+        for (index, eval) in self.evaluations.iter() {
+            evaluations[*index] = *eval;
+        }
+        DenseMultilinearExtension::from_evaluations_vec(self.num_vars, evaluations)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{evaluate_opt, fix_variables};
+    use ark_bls12_381::Fr;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn test_sparse_polynomial() {
+        let mut rng = test_rng();
+
+        for num_vars in 1..10 {
+            for num_nonzero in [0, 1, 5, 1 << (num_vars - 1)] {
+                let poly = SparseMultilinearPolynomial::<Fr>::rand(num_vars, num_nonzero, &mut rng);
+                let dense = poly.to_dense();
+                for index in 0..1 << num_vars {
+                    assert_eq!(poly.evaluation(index), dense.evaluations[index]);
+                }
+                assert_eq!(
+                    SparseMultilinearPolynomial::from_dense_evaluations(
+                        num_vars,
+                        &dense.evaluations
+                    ),
+                    poly
+                );
+                assert_eq!(
+                    SparseMultilinearPolynomial::from_evaluations(
+                        num_vars,
+                        poly.sparse_evaluations().unwrap().iter().rev().copied()
+                    ),
+                    poly
+                );
+
+                // fixing variables commutes with densifying
+                let point: Vec<_> = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+                for k in 0..=num_vars {
+                    assert_eq!(
+                        fix_variables(&poly, &point[..k]),
+                        fix_variables(&dense, &point[..k])
+                    );
+                }
+                assert_eq!(
+                    poly.fix_first_variable(&point[0]).to_dense(),
+                    fix_variables(&dense, &point[..1])
+                );
+                assert_eq!(evaluate_opt(&poly, &point), evaluate_opt(&dense, &point));
+            }
+        }
+    }
+}
@@ -7,9 +7,12 @@
 //! This module defines our main mathematical object `VirtualPolynomial`; and
 //! various functions associated with it.
 
-use crate::{errors::ArithErrors, multilinear_polynomial::random_zero_mle_list, random_mle_list};
+use crate::{
+    errors::ArithErrors, evaluate_opt, multilinear_polynomial::random_zero_mle_list,
+    random_mle_list, IntoMultilinearPolynomial, MultilinearPolynomial,
+};
 use ark_ff::PrimeField;
-use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+use ark_poly::DenseMultilinearExtension;
 use ark_serialize::CanonicalSerialize;
 use ark_std::{
     end_timer,
@@ -22,7 +25,7 @@ use std::{cmp::max, collections::HashMap, marker::PhantomData, ops::Add, sync::A
 #[rustfmt::skip]
 /// A virtual polynomial is a sum of products of multilinear polynomials;
 /// where the multilinear polynomials are stored via their multilinear
-/// extensions:  `(coefficient, DenseMultilinearExtension)`
+/// extensions:  `(coefficient, MultilinearPolynomial)`, dense or sparse
 ///
 /// * Number of products n = `polynomial.products.len()`,
 /// * Number of multiplicands of ith product m_i =
@@ -54,9 +57,9 @@ pub struct VirtualPolynomial<F: PrimeField> {
     pub products: Vec<(F, Vec<usize>)>,
     /// Stores multilinear extensions in which product multiplicand can refer
     /// to.
-    pub flattened_ml_extensions: Vec<Arc<DenseMultilinearExtension<F>>>,
+    pub flattened_ml_extensions: Vec<Arc<dyn MultilinearPolynomial<F>>>,
     /// Pointers to the above poly extensions
-    raw_pointers_lookup_table: HashMap<*const DenseMultilinearExtension<F>, usize>,
+    raw_pointers_lookup_table: HashMap<*const (), usize>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, CanonicalSerialize)]
@@ -77,7 +80,7 @@ impl<F: PrimeField> Add for &VirtualPolynomial<F> {
         let start = start_timer!(|| "virtual poly add");
         let mut res = self.clone();
         for products in other.products.iter() {
-            let cur: Vec<Arc<DenseMultilinearExtension<F>>> = products
+            let cur: Vec<Arc<dyn MultilinearPolynomial<F>>> = products
                 .1
                 .iter()
                 .map(|&x| other.flattened_ml_extensions[x].clone())
@@ -111,8 +114,9 @@ impl<F: PrimeField> VirtualPolynomial<F> {
     }
 
     /// Creates an new virtual polynomial from a MLE and its coefficient.
-    pub fn new_from_mle(mle: &Arc<DenseMultilinearExtension<F>>, coefficient: F) -> Self {
-        let mle_ptr: *const DenseMultilinearExtension<F> = Arc::as_ptr(mle);
+    pub fn new_from_mle<M: IntoMultilinearPolynomial<F> + Clone>(mle: &M, coefficient: F) -> Self {
+        let mle = mle.clone().into_multilinear_polynomial();
+        let mle_ptr = Arc::as_ptr(&mle) as *const ();
         let mut hm = HashMap::new();
         hm.insert(mle_ptr, 0);
 
@@ -120,12 +124,12 @@ impl<F: PrimeField> VirtualPolynomial<F> {
             aux_info: VPAuxInfo {
                 // The max degree is the max degree of any individual variable
                 max_degree: 1,
-                num_variables: mle.num_vars,
+                num_variables: mle.num_variables(),
                 phantom: PhantomData::default(),
             },
             // here `0` points to the first polynomial of `flattened_ml_extensions`
             products: vec![(coefficient, vec![0])],
-            flattened_ml_extensions: vec![mle.clone()],
+            flattened_ml_extensions: vec![mle],
             raw_pointers_lookup_table: hm,
         }
     }
@@ -138,10 +142,13 @@ impl<F: PrimeField> VirtualPolynomial<F> {
     /// `coefficient`.
     pub fn add_mle_list(
         &mut self,
-        mle_list: impl IntoIterator<Item = Arc<DenseMultilinearExtension<F>>>,
+        mle_list: impl IntoIterator<Item = impl IntoMultilinearPolynomial<F>>,
         coefficient: F,
     ) -> Result<(), ArithErrors> {
-        let mle_list: Vec<Arc<DenseMultilinearExtension<F>>> = mle_list.into_iter().collect();
+        let mle_list: Vec<Arc<dyn MultilinearPolynomial<F>>> = mle_list
+            .into_iter()
+            .map(IntoMultilinearPolynomial::into_multilinear_polynomial)
+            .collect();
         let mut indexed_product = Vec::with_capacity(mle_list.len());
 
         if mle_list.is_empty() {
@@ -153,14 +160,15 @@ impl<F: PrimeField> VirtualPolynomial<F> {
         self.aux_info.max_degree = max(self.aux_info.max_degree, mle_list.len());
 
         for mle in mle_list {
-            if mle.num_vars != self.aux_info.num_variables {
+            if mle.num_variables() != self.aux_info.num_variables {
                 return Err(ArithErrors::InvalidParameters(format!(
                     "product has a multiplicand with wrong number of variables {} vs {}",
-                    mle.num_vars, self.aux_info.num_variables
+                    mle.num_variables(),
+                    self.aux_info.num_variables
                 )));
             }
 
-            let mle_ptr: *const DenseMultilinearExtension<F> = Arc::as_ptr(&mle);
+            let mle_ptr = Arc::as_ptr(&mle) as *const ();
             if let Some(index) = self.raw_pointers_lookup_table.get(&mle_ptr) {
                 indexed_product.push(*index)
             } else {
@@ -180,19 +188,21 @@ impl<F: PrimeField> VirtualPolynomial<F> {
     /// Returns an error if the MLE has a different `num_vars` from self.
     pub fn mul_by_mle(
         &mut self,
-        mle: Arc<DenseMultilinearExtension<F>>,
+        mle: impl IntoMultilinearPolynomial<F>,
         coefficient: F,
     ) -> Result<(), ArithErrors> {
         let start = start_timer!(|| "mul by mle");
 
-        if mle.num_vars != self.aux_info.num_variables {
+        let mle = mle.into_multilinear_polynomial();
+        if mle.num_variables() != self.aux_info.num_variables {
             return Err(ArithErrors::InvalidParameters(format!(
                 "product has a multiplicand with wrong number of variables {} vs {}",
-                mle.num_vars, self.aux_info.num_variables
+                mle.num_variables(),
+                self.aux_info.num_variables
             )));
         }
 
-        let mle_ptr: *const DenseMultilinearExtension<F> = Arc::as_ptr(&mle);
+        let mle_ptr = Arc::as_ptr(&mle) as *const ();
 
         // check if this mle already exists in the virtual polynomial
         let mle_index = match self.raw_pointers_lookup_table.get(&mle_ptr) {
@@ -214,7 +224,7 @@ impl<F: PrimeField> VirtualPolynomial<F> {
 
         // increase the max degree by one as the MLE has degree 1.
         self.aux_info.max_degree += 1;
-        
+
         end_timer!(start);
         Ok(())
     }
@@ -236,9 +246,9 @@ impl<F: PrimeField> VirtualPolynomial<F> {
             .flattened_ml_extensions
             .iter()
             .map(|x| {
-                x.evaluate(point).unwrap() // safe unwrap here since we have
-                                           // already checked that num_var
-                                           // matches
+                evaluate_opt(x.as_ref(), point) // safe here since we have
+                                                // already checked that num_var
+                                                // matches
             })
             .collect();
 
@@ -268,7 +278,7 @@ impl<F: PrimeField> VirtualPolynomial<F> {
                 rng.gen_range(num_multiplicands_range.0..num_multiplicands_range.1);
             let (product, product_sum) = random_mle_list(nv, num_multiplicands, rng);
             let coefficient = F::rand(rng);
-            poly.add_mle_list(product.into_iter(), coefficient)?;
+            poly.add_mle_list(product, coefficient)?;
             sum += product_sum * coefficient;
         }
 
@@ -290,7 +300,7 @@ impl<F: PrimeField> VirtualPolynomial<F> {
                 rng.gen_range(num_multiplicands_range.0..num_multiplicands_range.1);
             let product = random_zero_mle_list(nv, num_multiplicands, rng);
             let coefficient = F::rand(rng);
-            poly.add_mle_list(product.into_iter(), coefficient)?;
+            poly.add_mle_list(product, coefficient)?;
         }
 
         Ok(poly)
@@ -7,13 +7,12 @@ use ark_bls12_381::{Bls12_381, Fr};
 use ark_serialize::CanonicalSerialize;
 use futures::future::join_all;
 use hp_distributed::{
-    config::{CIRCUIT_CONFIG, DATA_DIR, WORKERS},
+    config::{CIRCUIT_CONFIG, CIRCUIT_GATE, DATA_DIR, WORKERS},
     dispatcher::HyperPlonk,
     mock::MockCircuit,
     storage::SliceStorage,
     worker::{Method, Status},
 };
-use hyperplonk::prelude::CustomizedGates;
 use rand::{thread_rng, RngCore, SeedableRng};
 use rand_chacha::ChaChaRng;
 use stubborn_io::StubbornTcpStream;
@@ -35,8 +34,7 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let rng = &mut ChaChaRng::from_seed(seed);
 
-    let jf_gate = CustomizedGates::jellyfish_turbo_plonk_gate();
-    let circuit = MockCircuit::<Fr>::new(rng, 1 << CIRCUIT_CONFIG.custom_nv, &jf_gate);
+    let circuit = MockCircuit::<Fr>::new(rng, 1 << CIRCUIT_CONFIG.custom_nv, &CIRCUIT_GATE);
     SliceStorage::new(DATA_DIR.join("dispatcher/circuit.inputs.bin"))
         .store(&circuit.public_inputs)?;
 
@@ -6,6 +6,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use hyperplonk::prelude::{CustomizedGates, Row};
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 
@@ -25,6 +26,7 @@ pub struct GpuConfig {
 #[derive(Clone, Deserialize)]
 pub struct CircuitConfig {
     pub custom_nv: usize,
+    pub gate: String,
     pub pub_input_len: usize,
     pub selectors: Vec<Vec<usize>>,
     pub permu: Vec<Vec<usize>>,
@@ -54,6 +56,17 @@ pub static CIRCUIT_CONFIG: Lazy<CircuitConfig> = Lazy::new(|| {
     toml::from_slice(&bytes).unwrap()
 });
 
+pub static CIRCUIT_GATE: Lazy<CustomizedGates> = Lazy::new(|| {
+    let gate: CustomizedGates = CIRCUIT_CONFIG.gate.parse().unwrap();
+    gate.validate().unwrap();
+    // the workers only build the gate on the current row
+    assert!(
+        gate.gates.iter().all(|(_, _, ws)| ws.iter().all(|w| w.row == Row::Current)),
+        "the distributed prover does not support gates reading the next row"
+    );
+    gate
+});
+
 pub static WORKERS: Lazy<&'static [SocketAddr]> = Lazy::new(|| &NETWOKR_CONFIG.workers);
 
 pub static IP_NAME_MAP: Lazy<HashMap<IpAddr, String>> = Lazy::new(|| {
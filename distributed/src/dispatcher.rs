@@ -32,7 +32,7 @@ use subroutines::{
     StructuredReferenceString,
 };
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use transcript::IOPTranscript;
+use transcript::{IOPTranscript, Transcript};
 
 use crate::{
     config::CIRCUIT_CONFIG,
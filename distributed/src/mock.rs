@@ -68,14 +68,14 @@ impl<F: PrimeField> MockCircuit<F> {
                         None => cur_monomial,
                     };
                     for wit_index in wit.iter() {
-                        cur_monomial *= cur_witness[*wit_index];
+                        cur_monomial *= cur_witness[wit_index.column];
                     }
                     last_selector += cur_monomial;
                 } else {
                     let mut cur_monomial =
                         if *coeff < 0 { -F::from((-coeff) as u64) } else { F::from(*coeff as u64) };
                     for wit_index in wit.iter() {
-                        cur_monomial *= cur_witness[*wit_index];
+                        cur_monomial *= cur_witness[wit_index.column];
                     }
                     last_selector /= -cur_monomial;
                 }
@@ -95,7 +95,7 @@ impl<F: PrimeField> MockCircuit<F> {
         let params = HyperPlonkParams {
             num_constraints,
             num_pub_input: public_inputs.len(),
-            gate_func: gate.clone(),
+            gate_func: gate.clone().into(),
         };
 
         let permutation = identity_permutation(merged_nv as usize, 1);
@@ -105,25 +105,6 @@ impl<F: PrimeField> MockCircuit<F> {
     }
 
     pub fn is_satisfied(&self) -> bool {
-        for current_row in 0..self.num_variables() {
-            let mut cur = F::zero();
-            for (coeff, q, wit) in self.index.params.gate_func.gates.iter() {
-                let mut cur_monomial =
-                    if *coeff < 0 { -F::from((-coeff) as u64) } else { F::from(*coeff as u64) };
-                cur_monomial = match q {
-                    Some(p) => cur_monomial * self.index.selectors[*p].0[current_row],
-                    None => cur_monomial,
-                };
-                for wit_index in wit.iter() {
-                    cur_monomial *= self.witnesses[*wit_index].0[current_row];
-                }
-                cur += cur_monomial;
-            }
-            if !cur.is_zero() {
-                return false;
-            }
-        }
-
-        true
+        matches!(self.index.check_witness(&self.witnesses), Ok(violations) if violations.is_empty())
     }
 }
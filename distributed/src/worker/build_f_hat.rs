@@ -5,10 +5,10 @@ use ark_bls12_381::Fr;
 use ark_ff::{One, PrimeField};
 use ark_std::log2;
 use fn_timer::fn_timer;
-use hyperplonk::prelude::{CustomizedGates, HyperPlonkErrors};
+use hyperplonk::prelude::HyperPlonkErrors;
 
 use super::PlonkImplInner;
-use crate::config::CIRCUIT_CONFIG;
+use crate::config::{CIRCUIT_CONFIG, CIRCUIT_GATE};
 
 impl PlonkImplInner {
     #[fn_timer]
@@ -23,7 +23,7 @@ impl PlonkImplInner {
         // TODO: check that selector and witness lengths match what is in
         // the gate definition
 
-        let gates = CustomizedGates::jellyfish_turbo_plonk_gate();
+        let gates = &*CIRCUIT_GATE;
 
         // for selector_mle in selector_mles.iter() {
         //     if selector_mle.num_vars != num_vars {
@@ -55,9 +55,9 @@ impl PlonkImplInner {
                     mle_list.push(selector_mles[s].clone())
                 }
             }
-            for &witness in witnesses.iter() {
-                if CIRCUIT_CONFIG.permu[self.me].contains(&witness) {
-                    mle_list.push(witness_mles[witness].clone())
+            for witness in witnesses.iter() {
+                if CIRCUIT_CONFIG.permu[self.me].contains(&witness.column) {
+                    mle_list.push(witness_mles[witness.column].clone())
                 }
             }
             if !mle_list.is_empty() {
@@ -2,14 +2,14 @@ use std::sync::Arc;
 
 use ark_bls12_381::{Fr, G1Projective};
 use fn_timer::fn_timer;
-use hyperplonk::prelude::{CustomizedGates, SelectorColumn, WitnessColumn};
+use hyperplonk::prelude::{SelectorColumn, WitnessColumn};
 use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 
 use super::PlonkImplInner;
 use crate::{
-    config::{CIRCUIT_CONFIG, NUM_WIRE_TYPES},
+    config::{CIRCUIT_CONFIG, CIRCUIT_GATE, NUM_WIRE_TYPES},
     mock::MockCircuit,
 };
 
@@ -17,8 +17,7 @@ impl PlonkImplInner {
     #[fn_timer]
     pub fn init_circuit(&self, seed: [u8; 32]) -> MockCircuit<Fr> {
         let mut rng = ChaCha20Rng::from_seed(seed);
-        let jf_gate = CustomizedGates::jellyfish_turbo_plonk_gate();
-        let circuit = MockCircuit::<Fr>::new(&mut rng, 1 << CIRCUIT_CONFIG.custom_nv, &jf_gate);
+        let circuit = MockCircuit::<Fr>::new(&mut rng, 1 << CIRCUIT_CONFIG.custom_nv, &CIRCUIT_GATE);
         // assert_eq!(circuit.num_wire_types, NUM_WIRE_TYPES);
         circuit
     }
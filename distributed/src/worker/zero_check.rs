@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use arithmetic::{build_eq_x_r, fix_variables, DenseMultilinearExtension, VirtualPolynomial};
+use arithmetic::{
+    build_eq_x_r, fix_variables, DenseMultilinearExtension, MultilinearPolynomial,
+    VirtualPolynomial,
+};
 use ark_bls12_381::Fr;
 use ark_ff::{One, PrimeField};
 use ark_std::{cfg_into_iter, log2, Zero};
@@ -46,7 +49,7 @@ impl PlonkImplInner {
         //
         // eval g over r_m, and mutate g to g(r_1, ... r_m,, x_{m+1}... x_n)
         let mut flattened_ml_extensions: Vec<DenseMultilinearExtension<Fr>> =
-            state.poly.flattened_ml_extensions.par_iter().map(|x| x.as_ref().clone()).collect(); // worker
+            state.poly.flattened_ml_extensions.par_iter().map(|x| x.to_dense()).collect(); // worker
 
         // if state.round == 0 {
         //     return Err(hyperplonk::prelude::HyperPlonkErrors::PolyIOPErrors(PolyIOPErrors::InvalidProver(
@@ -145,7 +148,10 @@ impl PlonkImplInner {
 
         // update prover's state to the partial evaluated polynomial
         state.poly.flattened_ml_extensions =
-            flattened_ml_extensions.par_iter().map(|x| Arc::new(x.clone())).collect();
+            flattened_ml_extensions
+            .par_iter()
+            .map(|x| Arc::new(x.clone()) as Arc<dyn MultilinearPolynomial<Fr>>)
+            .collect();
         products_sum
     }
 }
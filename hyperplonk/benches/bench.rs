@@ -4,10 +4,9 @@
 // You should have received a copy of the MIT License
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
-use std::{fs::File, io, time::Instant};
+use std::{fs::File, time::Instant};
 
 use ark_bls12_381::{Bls12_381, Fr};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Write};
 use ark_std::test_rng;
 use hyperplonk::{
     prelude::{CustomizedGates, HyperPlonkErrors, MockCircuit},
@@ -15,7 +14,7 @@ use hyperplonk::{
 };
 use subroutines::{
     pcs::{
-        prelude::{MultilinearKzgPCS, MultilinearUniversalParams},
+        prelude::{MultilinearKzgPCS, MultilinearSrsFile, MultilinearUniversalParams, PCSError},
         PolynomialCommitmentScheme,
     },
     poly_iop::PolyIOP,
@@ -44,6 +43,8 @@ fn main() -> Result<(), HyperPlonkErrors> {
         }
     };
     bench_jellyfish_plonk(&pcs_srs, thread)?;
+    println!();
+    bench_small_witness_plonk(&pcs_srs)?;
     // println!();
     // bench_vanilla_plonk(&pcs_srs, thread)?;
     // println!();
@@ -56,14 +57,19 @@ fn main() -> Result<(), HyperPlonkErrors> {
     Ok(())
 }
 
-fn read_srs() -> Result<MultilinearUniversalParams<Bls12_381>, io::Error> {
-    let mut f = File::open("srs.params")?;
-    Ok(MultilinearUniversalParams::<Bls12_381>::deserialize_compressed_unchecked(&mut f).unwrap())
+// Only the levels for `SUPPORTED_SIZE` variables are read, so a file generated
+// for larger circuits can be reused.
+fn read_srs() -> Result<MultilinearUniversalParams<Bls12_381>, PCSError> {
+    let srs = MultilinearSrsFile::<Bls12_381>::open("srs.params")?;
+    let (prover_param, verifier_param) = srs.trim(SUPPORTED_SIZE)?;
+    Ok(MultilinearUniversalParams {
+        prover_param,
+        h_mask: verifier_param.h_mask,
+    })
 }
 
 fn write_srs(pcs_srs: &MultilinearUniversalParams<Bls12_381>) {
-    let mut f = File::create("srs.params").unwrap();
-    pcs_srs.serialize_uncompressed(&mut f).unwrap();
+    pcs_srs.write_to_file("srs.params").unwrap();
 }
 
 fn bench_vanilla_plonk(
@@ -108,6 +114,50 @@ fn bench_high_degree_plonk(
     Ok(())
 }
 
+fn bench_small_witness_plonk(
+    pcs_srs: &MultilinearUniversalParams<Bls12_381>,
+) -> Result<(), HyperPlonkErrors> {
+    // the 3 witness columns of the vanilla gate are merged with 2 more variables
+    for nv in MIN_NUM_VARS..=SUPPORTED_SIZE - 2 {
+        let vanilla_gate = CustomizedGates::vanilla_plonk_gate();
+        for (name, circuit) in [
+            ("random", MockCircuit::<Fr>::new(1 << nv, &vanilla_gate)),
+            (
+                "u32",
+                MockCircuit::<Fr>::new_with_small_witnesses(1 << nv, &vanilla_gate, 32),
+            ),
+            (
+                "boolean",
+                MockCircuit::<Fr>::new_with_small_witnesses(1 << nv, &vanilla_gate, 1),
+            ),
+        ] {
+            assert!(circuit.is_satisfied());
+            let (pk, _vk) = <PolyIOP<Fr> as HyperPlonkSNARK<
+                Bls12_381,
+                MultilinearKzgPCS<Bls12_381>,
+            >>::preprocess(&circuit.index, pcs_srs)?;
+            let repetition = if nv < 10 { 5 } else { 2 };
+            let start = Instant::now();
+            for _ in 0..repetition {
+                let _proof = <PolyIOP<Fr> as HyperPlonkSNARK<
+                    Bls12_381,
+                    MultilinearKzgPCS<Bls12_381>,
+                >>::prove(
+                    &pk, &circuit.public_inputs, &circuit.witnesses
+                )?;
+            }
+            println!(
+                "proving for {} variables with {} witnesses: {} us",
+                nv,
+                name,
+                start.elapsed().as_micros() / repetition as u128
+            );
+        }
+    }
+
+    Ok(())
+}
+
 fn bench_mock_circuit_zkp_helper(
     file: &mut File,
     nv: usize,
@@ -4,10 +4,11 @@
 // You should have received a copy of the MIT License
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
-use ark_std::cmp::max;
+use crate::errors::HyperPlonkErrors;
+use ark_std::{cmp::max, fmt, str::FromStr};
 
 /// Customized gate is a list of tuples of
-///     (coefficient, selector_index, wire_indices)
+///     (coefficient, selector_index, wires)
 ///
 /// Example:
 ///     q_L(X) * W_1(X)^5 - W_2(X) = 0
@@ -17,23 +18,100 @@ use ark_std::cmp::max;
 ///     (-1,    None,           vec![id_W2])
 /// ]
 ///
-/// CustomizedGates {
-///     gates: vec![
-///         (1, Some(0), vec![0, 0, 0, 0, 0]),
-///         (-1, None, vec![1])
-///     ],
-/// };
+/// CustomizedGates::from_current_row(vec![
+///     (1, Some(0), vec![0, 0, 0, 0, 0]),
+///     (-1, None, vec![1])
+/// ]);
 /// where id_qL = 0 // first selector
 /// id_W1 = 0 // first witness
 /// id_w2 = 1 // second witness
 ///
 /// NOTE: here coeff is a signed integer, instead of a field element
+///
+/// A wire may also read a witness at the next row, i.e. `W_i(next(X))`,
+/// see [`Row::Next`]. For example a running sum
+///     W_2(next(X)) - W_2(X) - W_1(X) = 0
+/// is
+/// vec![
+///     ( 1,    None,    vec![Wire::next(id_W2)]),
+///     (-1,    None,    vec![Wire::current(id_W2)]),
+///     (-1,    None,    vec![Wire::current(id_W1)]),
+/// ]
+///
+/// Migrating from plain wire indices: `gates` used to hold `Vec<usize>`
+/// wires, which all read the current row. Such a gate is now built with
+/// [`CustomizedGates::from_current_row`], with the same tuples, and keeps
+/// the same constraints. Rows read through [`Row::Next`] do not follow the
+/// row indices: a witness chained across rows is laid out along
+/// [`next_row`](crate::prelude::next_row) instead of `0, 1, 2, ...`.
+///
+/// A gate can also be written as text, which is what `Display` prints
+/// and `FromStr` parses:
+/// ```ignore
+///     "q0*w0^5 - w1".parse::<CustomizedGates>()?
+/// ```
+/// Selectors are `q<i>`, witnesses are `w<i>` (or `w<i>'` at the next
+/// row), a witness can be raised to a constant power with `^`, and integer
+/// constants multiply into the coefficient of their monomial.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct CustomizedGates {
-    pub gates: Vec<(i64, Option<usize>, Vec<usize>)>,
+    pub gates: Vec<(i64, Option<usize>, Vec<Wire>)>,
+}
+
+/// The row at which a wire reads its witness.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Row {
+    /// The row being constrained.
+    Current,
+    /// The row following it. Rows are elements of `GF(2^n)` and the next
+    /// row of `b` is `X * b`, so the non-zero rows form a single cycle
+    /// `1, X, X^2, ...` back to row `1`, and row `0` is followed by itself.
+    Next,
+}
+
+/// A witness read by a monomial: the witness column `column` at `row`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Wire {
+    pub column: usize,
+    pub row: Row,
+}
+
+impl Wire {
+    /// The witness `column` at the current row.
+    pub const fn current(column: usize) -> Self {
+        Self {
+            column,
+            row: Row::Current,
+        }
+    }
+
+    /// The witness `column` at the next row.
+    pub const fn next(column: usize) -> Self {
+        Self {
+            column,
+            row: Row::Next,
+        }
+    }
+}
+
+impl From<usize> for Wire {
+    fn from(column: usize) -> Self {
+        Self::current(column)
+    }
 }
 
 impl CustomizedGates {
+    /// Build a gate whose monomials only read witnesses at the current row,
+    /// from their witness indices.
+    pub fn from_current_row(gates: Vec<(i64, Option<usize>, Vec<usize>)>) -> Self {
+        Self {
+            gates: gates
+                .into_iter()
+                .map(|(coeff, q, ws)| (coeff, q, ws.into_iter().map(Wire::from).collect()))
+                .collect(),
+        }
+    }
+
     /// The degree of the algebraic customized gate
     pub fn degree(&self) -> usize {
         let mut res = 0;
@@ -59,9 +137,9 @@ impl CustomizedGates {
     pub fn num_witness_columns(&self) -> usize {
         let mut res = 0;
         for (_coeff, _q, ws) in self.gates.iter() {
-            // witness list must be ordered
-            // so we just need to compare with the last one
-            if let Some(&p) = ws.last() {
+            // witness lists are not required to be ordered
+            // so we scan all of them
+            if let Some(p) = ws.iter().map(|w| w.column).max() {
                 if res < p {
                     res = p
                 }
@@ -71,6 +149,15 @@ impl CustomizedGates {
         res + 1
     }
 
+    /// Check that the gate is well formed:
+    /// - there is at least one monomial, and no coefficient is zero
+    /// - each selector is used by at most one monomial
+    /// - selector indices are `0..num_selector_columns()` without gaps
+    /// - every witness in `0..num_witness_columns()` is used by some monomial
+    pub fn validate(&self) -> Result<(), HyperPlonkErrors> {
+        CustomizedGateSet::from(self.clone()).validate()
+    }
+
     /// Return a vanilla plonk gate:
     /// ``` ignore
     ///   q_L w_1 + q_R w_2 + q_O w_3 + q_M w1w2 + q_C = 0
@@ -84,15 +171,13 @@ impl CustomizedGates {
     ///     (1,    Some(id_qC),     vec![]),
     /// ```
     pub fn vanilla_plonk_gate() -> Self {
-        Self {
-            gates: vec![
-                (1, Some(0), vec![0]),
-                (1, Some(1), vec![1]),
-                (1, Some(2), vec![2]),
-                (1, Some(3), vec![0, 1]),
-                (1, Some(4), vec![]),
-            ],
-        }
+        Self::from_current_row(vec![
+            (1, Some(0), vec![0]),
+            (1, Some(1), vec![1]),
+            (1, Some(2), vec![2]),
+            (1, Some(3), vec![0, 1]),
+            (1, Some(4), vec![]),
+        ])
     }
 
     /// Return a jellyfish turbo plonk gate:
@@ -127,22 +212,20 @@ impl CustomizedGates {
     ///     (1,    Some(q[12]),    vec![]),
     /// ```
     pub fn jellyfish_turbo_plonk_gate() -> Self {
-        CustomizedGates {
-            gates: vec![
-                (1, Some(0), vec![0]),
-                (1, Some(1), vec![1]),
-                (1, Some(2), vec![2]),
-                (1, Some(3), vec![3]),
-                (1, Some(4), vec![0, 1]),
-                (1, Some(5), vec![2, 3]),
-                (1, Some(6), vec![0, 0, 0, 0, 0]),
-                (1, Some(7), vec![1, 1, 1, 1, 1]),
-                (1, Some(8), vec![2, 2, 2, 2, 2]),
-                (1, Some(9), vec![3, 3, 3, 3, 3]),
-                (1, Some(10), vec![4]),
-                (1, Some(11), vec![]),
-            ],
-        }
+        Self::from_current_row(vec![
+            (1, Some(0), vec![0]),
+            (1, Some(1), vec![1]),
+            (1, Some(2), vec![2]),
+            (1, Some(3), vec![3]),
+            (1, Some(4), vec![0, 1]),
+            (1, Some(5), vec![2, 3]),
+            (1, Some(6), vec![0, 0, 0, 0, 0]),
+            (1, Some(7), vec![1, 1, 1, 1, 1]),
+            (1, Some(8), vec![2, 2, 2, 2, 2]),
+            (1, Some(9), vec![3, 3, 3, 3, 3]),
+            (1, Some(10), vec![4]),
+            (1, Some(11), vec![]),
+        ])
     }
 
     /// Generate a random gate for `num_witness` with a highest degree =
@@ -159,7 +242,7 @@ impl CustomizedGates {
         }
         gates.push((1, Some(num_witness + 1), vec![]));
 
-        CustomizedGates { gates }
+        Self::from_current_row(gates)
     }
 
     /// Return a plonk gate where #selector > #witness * 2
@@ -177,16 +260,591 @@ impl CustomizedGates {
     ///     (1,    Some(id_qC),     vec![]),
     /// ```
     pub fn super_long_selector_gate() -> Self {
+        Self::from_current_row(vec![
+            (1, Some(0), vec![0]),
+            (1, Some(1), vec![1]),
+            (1, Some(2), vec![2]),
+            (1, Some(3), vec![0, 1]),
+            (1, Some(4), vec![0, 2]),
+            (1, Some(5), vec![1, 2]),
+            (1, Some(6), vec![]),
+        ])
+    }
+}
+
+/// A set of customized gates, for circuits that mix several gate types.
+///
+/// Each entry is a tuple of
+///     (activation_selector_index, gate)
+/// and contributes `q_act(X) * gate(X)` to the constraint polynomial, so
+/// a gate type only constrains the rows where its activation selector is
+/// set. An entry without activation selector applies to every row.
+///
+/// Selector and witness indices are shared by all entries: gate types may
+/// read the same witness columns, but every selector (including the
+/// activation ones) is used exactly once.
+///
+/// Example:
+/// ``` ignore
+///     q_5(X) * (q_0 w_0 + q_1 w_1 + q_2 w_2 + q_3 w_0 w_1 + q_4)
+///   + q_9(X) * (q_6 w_0^5 + q_7 w_1 + q_8 w_2) = 0
+/// ```
+/// is represented as
+/// ``` ignore
+/// CustomizedGateSet {
+///     gates: vec![
+///         (Some(5), CustomizedGates::vanilla_plonk_gate()),
+///         (Some(9), CustomizedGates::from_current_row(vec![
+///             (1, Some(6), vec![0, 0, 0, 0, 0]),
+///             (1, Some(7), vec![1]),
+///             (1, Some(8), vec![2]),
+///         ])),
+///     ],
+/// };
+/// ```
+/// and printed as `q5*(q0*w0 + ... + q4) + q9*(q6*w0^5 + q7*w1 + q8*w2)`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CustomizedGateSet {
+    pub gates: Vec<(Option<usize>, CustomizedGates)>,
+}
+
+impl From<CustomizedGates> for CustomizedGateSet {
+    fn from(gate: CustomizedGates) -> Self {
         Self {
+            gates: vec![(None, gate)],
+        }
+    }
+}
+
+impl CustomizedGateSet {
+    /// The degree of the combined constraint polynomial
+    pub fn degree(&self) -> usize {
+        let mut res = 0;
+        for (activation, gate) in self.gates.iter() {
+            res = max(res, gate.degree() + (activation.is_some() as usize))
+        }
+        res
+    }
+
+    /// The number of selectors, including the activation selectors
+    pub fn num_selector_columns(&self) -> usize {
+        let mut res = 0;
+        for (activation, gate) in self.gates.iter() {
+            res += activation.is_some() as usize + gate.num_selector_columns();
+        }
+        res
+    }
+
+    /// The number of witnesses shared by all gate types
+    pub fn num_witness_columns(&self) -> usize {
+        let mut res = 0;
+        for (_activation, gate) in self.gates.iter() {
+            res = max(res, gate.num_witness_columns())
+        }
+        res
+    }
+
+    /// The witness columns that are read at the next row, in increasing order.
+    pub fn next_row_witnesses(&self) -> Vec<usize> {
+        let mut res: Vec<usize> = self
+            .gates
+            .iter()
+            .flat_map(|(_activation, gate)| gate.gates.iter())
+            .flat_map(|(_coeff, _q, ws)| ws.iter())
+            .filter(|w| w.row == Row::Next)
+            .map(|w| w.column)
+            .collect();
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+
+    /// Check that the gate set is well formed:
+    /// - every gate type has at least one monomial, and no coefficient is
+    ///   zero
+    /// - each selector is used by at most one monomial or activation
+    /// - selector indices are `0..num_selector_columns()` without gaps
+    /// - every witness in `0..num_witness_columns()` is used by some monomial
+    pub fn validate(&self) -> Result<(), HyperPlonkErrors> {
+        if self.gates.is_empty() {
+            return Err(HyperPlonkErrors::InvalidParameters(
+                "customized gate set has no gate".to_string(),
+            ));
+        }
+
+        // name the monomials by their position in the gate type only if
+        // there is more than one gate type
+        let single = self.gates.len() == 1;
+        let monomial_name = |k: usize, i: usize| {
+            if single {
+                format!("monomial {}", i)
+            } else {
+                format!("monomial {} of gate type {}", i, k)
+            }
+        };
+
+        let num_selectors = self.num_selector_columns();
+        let num_witnesses = self.num_witness_columns();
+        let mut selector_owner: Vec<Option<String>> = vec![None; num_selectors];
+        let mut witness_used = vec![false; num_witnesses];
+
+        let mut use_selector = |s: usize, user: String| {
+            if s >= num_selectors {
+                return Err(HyperPlonkErrors::InvalidParameters(format!(
+                    "{} uses selector {} but the gate only has {} selectors; \
+                     selector indices must be 0..{} without gaps",
+                    user, s, num_selectors, num_selectors
+                )));
+            }
+            if let Some(owner) = selector_owner[s].replace(user.clone()) {
+                return Err(HyperPlonkErrors::InvalidParameters(format!(
+                    "selector {} is used by both {} and {}",
+                    s, owner, user
+                )));
+            }
+            Ok(())
+        };
+
+        for (k, (activation, gate)) in self.gates.iter().enumerate() {
+            if gate.gates.is_empty() {
+                return Err(HyperPlonkErrors::InvalidParameters(if single {
+                    "customized gate has no monomial".to_string()
+                } else {
+                    format!("gate type {} has no monomial", k)
+                }));
+            }
+            if let Some(a) = *activation {
+                use_selector(a, format!("the activation of gate type {}", k))?;
+            }
+            for (i, (coeff, q, ws)) in gate.gates.iter().enumerate() {
+                if *coeff == 0 {
+                    return Err(HyperPlonkErrors::InvalidParameters(format!(
+                        "{} has a zero coefficient",
+                        monomial_name(k, i)
+                    )));
+                }
+                if let Some(s) = *q {
+                    use_selector(s, monomial_name(k, i))?;
+                }
+                for w in ws.iter() {
+                    witness_used[w.column] = true;
+                }
+            }
+        }
+
+        // the selectors are counted by their uses, so if no index is out of
+        // range and none is duplicated, every index is used exactly once.
+        if let Some(w) = witness_used.iter().position(|&used| !used) {
+            return Err(HyperPlonkErrors::InvalidParameters(format!(
+                "witness {} is not used by any monomial; witness indices must be 0..{} without gaps",
+                w, num_witnesses
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for CustomizedGates {
+    /// Print the gate as `q0*w0 + q1*w1 + q4*w0*w1 + q6*w0^5 - q11`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.gates.is_empty() {
+            return write!(f, "0");
+        }
+        for (i, (coeff, q, ws)) in self.gates.iter().enumerate() {
+            match (i, *coeff < 0) {
+                (0, false) => (),
+                (0, true) => write!(f, "-")?,
+                (_, false) => write!(f, " + ")?,
+                (_, true) => write!(f, " - ")?,
+            }
+
+            let mut factors = vec![];
+            let abs_coeff = coeff.unsigned_abs();
+            if abs_coeff != 1 || (q.is_none() && ws.is_empty()) {
+                factors.push(abs_coeff.to_string());
+            }
+            if let Some(s) = q {
+                factors.push(format!("q{}", s));
+            }
+            let mut j = 0;
+            while j < ws.len() {
+                let power = ws[j..].iter().take_while(|&&w| w == ws[j]).count();
+                let wire = match ws[j].row {
+                    Row::Current => format!("w{}", ws[j].column),
+                    Row::Next => format!("w{}'", ws[j].column),
+                };
+                if power == 1 {
+                    factors.push(wire);
+                } else {
+                    factors.push(format!("{}^{}", wire, power));
+                }
+                j += power;
+            }
+            write!(f, "{}", factors.join("*"))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CustomizedGates {
+    type Err = HyperPlonkErrors;
+
+    /// Parse a gate such as `q0*w0 + q1*w1 + q4*w0*w1 + q6*w0^5 - q11`.
+    ///
+    /// Witness indices of a monomial are sorted. The result is not
+    /// validated, call [`CustomizedGates::validate`] before using it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = |msg: String| {
+            HyperPlonkErrors::InvalidParameters(format!("cannot parse gate `{}`: {}", s, msg))
+        };
+
+        // split into signed terms
+        let mut terms = vec![];
+        let mut negative = false;
+        let mut current = String::new();
+        for c in s.chars().filter(|c| !c.is_whitespace()) {
+            match c {
+                '+' | '-' => {
+                    if current.is_empty() {
+                        // leading sign, or a sign following another sign
+                        if !terms.is_empty() || negative {
+                            return Err(err(format!("unexpected `{}`", c)));
+                        }
+                    } else {
+                        terms.push((negative, current));
+                        current = String::new();
+                    }
+                    negative = c == '-';
+                },
+                _ => current.push(c),
+            }
+        }
+        if current.is_empty() {
+            return Err(err("expected a monomial at the end".to_string()));
+        }
+        terms.push((negative, current));
+
+        let mut gates = vec![];
+        for (negative, term) in terms {
+            let mut coeff: i64 = if negative { -1 } else { 1 };
+            let mut selector = None;
+            let mut witnesses = vec![];
+            for factor in term.split('*') {
+                let (base, power) = match factor.split_once('^') {
+                    Some((base, power)) => (
+                        base,
+                        Some(
+                            parse_index(power)
+                                .ok_or_else(|| err(format!("invalid exponent in `{}`", factor)))?,
+                        ),
+                    ),
+                    None => (factor, None),
+                };
+                if let Some(index) = base.strip_prefix('w') {
+                    let (index, row) = match index.strip_suffix('\'') {
+                        Some(index) => (index, Row::Next),
+                        None => (index, Row::Current),
+                    };
+                    let column = parse_index(index)
+                        .ok_or_else(|| err(format!("invalid witness `{}`", base)))?;
+                    witnesses.extend(vec![Wire { column, row }; power.unwrap_or(1)]);
+                    continue;
+                }
+                if power.is_some() {
+                    return Err(err(format!(
+                        "only witnesses can be raised to a power, got `{}`",
+                        factor
+                    )));
+                }
+                if let Some(index) = base.strip_prefix('q') {
+                    let index = parse_index(index)
+                        .ok_or_else(|| err(format!("invalid selector `{}`", base)))?;
+                    if selector.replace(index).is_some() {
+                        return Err(err(format!("`{}` has more than one selector", term)));
+                    }
+                } else {
+                    let constant = base
+                        .parse::<i64>()
+                        .map_err(|_| err(format!("unknown factor `{}`", base)))?;
+                    coeff = coeff
+                        .checked_mul(constant)
+                        .ok_or_else(|| err(format!("coefficient of `{}` overflows", term)))?;
+                }
+            }
+            witnesses.sort_unstable();
+            gates.push((coeff, selector, witnesses));
+        }
+
+        Ok(Self { gates })
+    }
+}
+
+impl fmt::Display for CustomizedGateSet {
+    /// Print the gate set as `q0*w0 + q1*w1 + q2*(q3*w0^5 - w1)`, where the
+    /// gate types switched on by an activation selector are in parentheses.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.gates.is_empty() {
+            return write!(f, "0");
+        }
+        for (k, (activation, gate)) in self.gates.iter().enumerate() {
+            if k > 0 {
+                write!(f, " + ")?;
+            }
+            match activation {
+                Some(a) => write!(f, "q{}*({})", a, gate)?,
+                None => write!(f, "{}", gate)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CustomizedGateSet {
+    type Err = HyperPlonkErrors;
+
+    /// Parse a gate set such as `q0*w0 + q1*w1 + q2*(q3*w0^5 - w1)`.
+    ///
+    /// Each `q<i>*(...)` is a gate type activated by selector `i`, and each
+    /// run of monomials outside of parentheses is a gate type without
+    /// activation. The result is not validated, call
+    /// [`CustomizedGateSet::validate`] before using it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = |msg: String| {
+            HyperPlonkErrors::InvalidParameters(format!("cannot parse gate set `{}`: {}", s, msg))
+        };
+
+        let stripped: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut rest = stripped.as_str();
+        let mut gates = vec![];
+        // monomials outside of parentheses since the last gated entry
+        let mut ungated = String::new();
+        while let Some(open) = rest.find('(') {
+            // the gated term starts after the last sign before `(`
+            let term_start = rest[..open].rfind(['+', '-']).map_or(0, |p| p + 1);
+            let term = &rest[term_start..open];
+            let activation = term
+                .strip_prefix('q')
+                .and_then(|t| t.strip_suffix('*'))
+                .and_then(parse_index)
+                .ok_or_else(|| err(format!("expected `q<i>*(`, got `{}(`", term)))?;
+            if term_start > 0 {
+                if rest[..term_start].ends_with('-') {
+                    return Err(err(format!("gate type q{} cannot be negated", activation)));
+                }
+                ungated.push_str(&rest[..term_start - 1]);
+            }
+
+            let close = rest[open..]
+                .find(')')
+                .map(|p| p + open)
+                .ok_or_else(|| err("unbalanced parentheses".to_string()))?;
+            let body = &rest[open + 1..close];
+            if body.contains('(') {
+                return Err(err("nested parentheses".to_string()));
+            }
+
+            if !ungated.is_empty() {
+                gates.push((None, ungated.parse()?));
+                ungated.clear();
+            }
+            gates.push((Some(activation), body.parse()?));
+
+            rest = &rest[close + 1..];
+            if !rest.is_empty() && !rest.starts_with(['+', '-']) {
+                return Err(err(format!("unexpected `{}` after `)`", rest)));
+            }
+        }
+        ungated.push_str(rest);
+        if !ungated.is_empty() || gates.is_empty() {
+            gates.push((None, ungated.parse()?));
+        }
+
+        Ok(Self { gates })
+    }
+}
+
+// Parse a decimal index without sign.
+fn parse_index(s: &str) -> Option<usize> {
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_num_witness_columns_unordered() {
+        let gate = CustomizedGates::from_current_row(vec![
+            (1, Some(0), vec![3, 0]),
+            (1, Some(1), vec![1, 2]),
+        ]);
+        assert_eq!(gate.num_witness_columns(), 4);
+        assert!(gate.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(CustomizedGates::vanilla_plonk_gate().validate().is_ok());
+        assert!(CustomizedGates::jellyfish_turbo_plonk_gate()
+            .validate()
+            .is_ok());
+        assert!(CustomizedGates::super_long_selector_gate()
+            .validate()
+            .is_ok());
+        for num_witness in 2..10 {
+            for degree in [1, 2, 4, 8] {
+                assert!(CustomizedGates::mock_gate(num_witness, degree)
+                    .validate()
+                    .is_ok());
+            }
+        }
+
+        // empty gate
+        assert!(CustomizedGates::default().validate().is_err());
+        // zero coefficient
+        let gate = CustomizedGates::from_current_row(vec![(0, Some(0), vec![0])]);
+        assert!(gate.validate().is_err());
+        // duplicated selector
+        let gate =
+            CustomizedGates::from_current_row(vec![(1, Some(0), vec![0]), (1, Some(0), vec![1])]);
+        assert!(gate.validate().is_err());
+        // gap in selectors
+        let gate =
+            CustomizedGates::from_current_row(vec![(1, Some(0), vec![0]), (1, Some(2), vec![1])]);
+        assert!(gate.validate().is_err());
+        // gap in witnesses
+        let gate =
+            CustomizedGates::from_current_row(vec![(1, Some(0), vec![0]), (1, Some(1), vec![2])]);
+        assert!(gate.validate().is_err());
+    }
+
+    #[test]
+    fn test_display_and_parse() -> Result<(), HyperPlonkErrors> {
+        let gate = CustomizedGates::jellyfish_turbo_plonk_gate();
+        assert_eq!(
+            gate.to_string(),
+            "q0*w0 + q1*w1 + q2*w2 + q3*w3 + q4*w0*w1 + q5*w2*w3 + q6*w0^5 + q7*w1^5 \
+             + q8*w2^5 + q9*w3^5 + q10*w4 + q11"
+        );
+
+        for gate in [
+            CustomizedGates::vanilla_plonk_gate(),
+            CustomizedGates::jellyfish_turbo_plonk_gate(),
+            CustomizedGates::super_long_selector_gate(),
+            CustomizedGates::mock_gate(4, 8),
+        ] {
+            assert_eq!(gate.to_string().parse::<CustomizedGates>()?, gate);
+        }
+
+        let gate: CustomizedGates = " -3 * q0*w1*w0^2 + w2 - 7 ".parse()?;
+        assert_eq!(
+            gate,
+            CustomizedGates::from_current_row(vec![
+                (-3, Some(0), vec![0, 0, 1]),
+                (1, None, vec![2]),
+                (-7, None, vec![]),
+            ])
+        );
+        assert_eq!(gate.to_string(), "-3*q0*w0^2*w1 + w2 - 7");
+
+        let gate: CustomizedGates = "q0*w1'*w0 - w1 - w0'^2".parse()?;
+        assert_eq!(
+            gate,
+            CustomizedGates {
+                gates: vec![
+                    (1, Some(0), vec![Wire::current(0), Wire::next(1)]),
+                    (-1, None, vec![Wire::current(1)]),
+                    (-1, None, vec![Wire::next(0), Wire::next(0)]),
+                ],
+            }
+        );
+        assert_eq!(gate.to_string(), "q0*w0*w1' - w1 - w0'^2");
+        assert_eq!(gate.num_witness_columns(), 2);
+        gate.validate()?;
+        let gate_set = CustomizedGateSet::from(gate);
+        assert_eq!(gate_set.next_row_witnesses(), vec![0, 1]);
+
+        for bad in [
+            "",
+            "q0*w0 +",
+            "q0*w0 + - w1",
+            "q0*q1*w0",
+            "q0^2*w0",
+            "x0*w0",
+            "q*w0",
+            "qa*w0",
+            "q0*w-1",
+            "q0**w0",
+            "q0*w'",
+            "q0*w0''",
+        ] {
+            assert!(bad.parse::<CustomizedGates>().is_err(), "{}", bad);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_gate_set() -> Result<(), HyperPlonkErrors> {
+        let poseidon_round = CustomizedGates::from_current_row(vec![
+            (1, Some(6), vec![0, 0, 0, 0, 0]),
+            (1, Some(7), vec![1]),
+            (-1, None, vec![2]),
+        ]);
+        let gate_set = CustomizedGateSet {
             gates: vec![
-                (1, Some(0), vec![0]),
-                (1, Some(1), vec![1]),
-                (1, Some(2), vec![2]),
-                (1, Some(3), vec![0, 1]),
-                (1, Some(4), vec![0, 2]),
-                (1, Some(5), vec![1, 2]),
-                (1, Some(6), vec![]),
+                (Some(5), CustomizedGates::vanilla_plonk_gate()),
+                (Some(8), poseidon_round),
+                (
+                    None,
+                    CustomizedGates::from_current_row(vec![(2, Some(9), vec![3])]),
+                ),
             ],
+        };
+        gate_set.validate()?;
+        assert_eq!(gate_set.num_selector_columns(), 10);
+        assert_eq!(gate_set.num_witness_columns(), 4);
+        assert_eq!(gate_set.degree(), 7);
+
+        let text = "q5*(q0*w0 + q1*w1 + q2*w2 + q3*w0*w1 + q4) \
+                    + q8*(q6*w0^5 + q7*w1 - w2) + 2*q9*w3";
+        assert_eq!(
+            gate_set.to_string(),
+            text.split_whitespace().collect::<Vec<_>>().join(" ")
+        );
+        assert_eq!(text.parse::<CustomizedGateSet>()?, gate_set);
+
+        // ungated monomials around gated ones are kept in order
+        let parsed: CustomizedGateSet = "q0*w0 + q1*(w1) + q2*w1".parse()?;
+        assert_eq!(parsed.gates.len(), 3);
+        assert_eq!(parsed.gates[0].0, None);
+        assert_eq!(parsed.gates[1].0, Some(1));
+        assert_eq!(parsed.gates[2].0, None);
+
+        for bad in [
+            "",
+            "-q0*(w0)",
+            "q0*w0 - q1*(w1)",
+            "q0*(w0",
+            "q0*(q1*(w0))",
+            "q0*(w0)w1",
+            "w0*(w1)",
+        ] {
+            assert!(bad.parse::<CustomizedGateSet>().is_err(), "{}", bad);
         }
+
+        // the activation selector may not be reused by a monomial
+        let gate_set: CustomizedGateSet = "q0*(q0*w0 + q1)".parse()?;
+        assert!(gate_set.validate().is_err());
+        // nor by two gate types
+        let gate_set: CustomizedGateSet = "q0*(q1*w0) + q0*(q2*w0)".parse()?;
+        assert!(gate_set.validate().is_err());
+        // selectors are numbered across gate types
+        let gate_set: CustomizedGateSet = "q0*(q1*w0) + q3*(q4*w0)".parse()?;
+        assert!(gate_set.validate().is_err());
+        Ok(())
     }
 }
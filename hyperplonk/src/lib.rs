@@ -8,7 +8,10 @@
 
 use ark_ec::pairing::Pairing;
 use errors::HyperPlonkErrors;
-use subroutines::{pcs::prelude::PolynomialCommitmentScheme, poly_iop::prelude::PermutationCheck};
+use subroutines::{
+    pcs::prelude::{AccumulationScheme, PolynomialCommitmentScheme},
+    poly_iop::prelude::PermutationCheck,
+};
 use witness::WitnessColumn;
 
 pub mod custom_gate;
@@ -17,12 +20,17 @@ mod mock;
 pub mod prelude;
 mod selectors;
 mod snark;
+pub mod solidity;
 pub mod structs;
 mod utils;
 mod witness;
 
 /// A trait for HyperPlonk SNARKs.
 /// A HyperPlonk is derived from ZeroChecks and PermutationChecks.
+///
+/// It is implemented by `PolyIOP<F, T>` for any transcript `T`, so the same
+/// proof system can be verified natively, on the EVM with a Keccak
+/// transcript, or in a recursive circuit with a Poseidon transcript.
 pub trait HyperPlonkSNARK<E, PCS>: PermutationCheck<E, PCS>
 where
     E: Pairing,
@@ -48,6 +56,23 @@ where
         pcs_srs: &PCS::SRS,
     ) -> Result<(Self::ProvingKey, Self::VerifyingKey), HyperPlonkErrors>;
 
+    /// Generate the preprocessed polynomials output by the indexer, with
+    /// keys that also support proving up to `max_num_instances` instances of
+    /// the circuit in one proof.
+    ///
+    /// Inputs:
+    /// - `index`: HyperPlonk index
+    /// - `pcs_srs`: Polynomial commitment structured reference string
+    /// - `max_num_instances`: maximum number of instances in a batch
+    ///
+    /// Outputs:
+    /// - The HyperPlonk proving key and verifying key, as in `preprocess`
+    fn preprocess_batch(
+        index: &Self::Index,
+        pcs_srs: &PCS::SRS,
+        max_num_instances: usize,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), HyperPlonkErrors>;
+
     /// Generate HyperPlonk SNARK proof.
     ///
     /// Inputs:
@@ -62,6 +87,23 @@ where
         witnesses: &[WitnessColumn<E::ScalarField>],
     ) -> Result<Self::Proof, HyperPlonkErrors>;
 
+    /// Generate a single HyperPlonk SNARK proof for several instances of the
+    /// same circuit.
+    ///
+    /// Inputs:
+    /// - `pk`: circuit proving key, from `preprocess_batch` with a large
+    ///   enough `max_num_instances`
+    /// - `instances`: the online public input and witness assignment of each
+    ///   instance
+    ///
+    /// Outputs:
+    /// - The HyperPlonk SNARK proof.
+    #[allow(clippy::type_complexity)]
+    fn prove_batch(
+        pk: &Self::ProvingKey,
+        instances: &[(&[E::ScalarField], &[WitnessColumn<E::ScalarField>])],
+    ) -> Result<Self::Proof, HyperPlonkErrors>;
+
     /// Verify the HyperPlonk proof.
     ///
     /// Inputs:
@@ -75,4 +117,56 @@ where
         pub_input: &[E::ScalarField],
         proof: &Self::Proof,
     ) -> Result<bool, HyperPlonkErrors>;
+
+    /// Verify a HyperPlonk proof of several instances of the same circuit.
+    ///
+    /// Inputs:
+    /// - `vk`: verifying key
+    /// - `pub_inputs`: online public input of each instance, in the order
+    ///   they were proven
+    /// - `proof`: HyperPlonk SNARK proof
+    ///
+    /// Outputs:
+    /// - Return a boolean on whether the verification is successful
+    fn verify_batch(
+        vk: &Self::VerifyingKey,
+        pub_inputs: &[&[E::ScalarField]],
+        proof: &Self::Proof,
+    ) -> Result<bool, HyperPlonkErrors>;
+}
+
+/// A HyperPlonk SNARK whose polynomial commitment openings are deferred into
+/// an accumulator, so that many proofs are verified with a single final
+/// check of the commitment scheme, e.g. a single multi-pairing for KZG.
+pub trait HyperPlonkAccumulation<E, PCS>: HyperPlonkSNARK<E, PCS>
+where
+    E: Pairing,
+    PCS: AccumulationScheme<E>,
+{
+    /// Verify a HyperPlonk proof of several instances as in `verify_batch`,
+    /// but for the final check of the openings.
+    ///
+    /// Outputs:
+    /// - Return the accumulator of the deferred check, to be combined with
+    ///   `PCS::accumulate` and checked with `PCS::decide`
+    fn verify_deferred(
+        vk: &Self::VerifyingKey,
+        pub_inputs: &[&[E::ScalarField]],
+        proof: &Self::Proof,
+    ) -> Result<PCS::Accumulator, HyperPlonkErrors>;
+
+    /// Verify many HyperPlonk proofs of the same circuit at once.
+    ///
+    /// Inputs:
+    /// - `vk`: verifying key
+    /// - `proofs`: online public inputs of the instances of each proof, and
+    ///   the proof
+    ///
+    /// Outputs:
+    /// - Return a boolean on whether all the verifications are successful
+    #[allow(clippy::type_complexity)]
+    fn verify_many(
+        vk: &Self::VerifyingKey,
+        proofs: &[(&[&[E::ScalarField]], &Self::Proof)],
+    ) -> Result<bool, HyperPlonkErrors>;
 }
@@ -4,12 +4,12 @@
 // You should have received a copy of the MIT License
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
-use arithmetic::identity_permutation;
+use arithmetic::{identity_permutation, next_row};
 use ark_ff::PrimeField;
-use ark_std::{log2, test_rng};
+use ark_std::{log2, rand::RngCore, test_rng};
 
 use crate::{
-    custom_gate::CustomizedGates,
+    custom_gate::{CustomizedGateSet, CustomizedGates, Row, Wire},
     selectors::SelectorColumn,
     structs::{HyperPlonkIndex, HyperPlonkParams},
     witness::WitnessColumn,
@@ -41,6 +41,41 @@ impl<F: PrimeField> MockCircuit<F> {
 impl<F: PrimeField> MockCircuit<F> {
     /// Generate a mock plonk circuit for the input constraint size.
     pub fn new(num_constraints: usize, gate: &CustomizedGates) -> MockCircuit<F> {
+        Self::new_with_witness_sampler(num_constraints, gate, |rng, _| F::rand(rng))
+    }
+
+    /// Generate a mock plonk circuit for the input constraint size, whose
+    /// witnesses are random integers less than `2^num_bits`, e.g. booleans
+    /// for `num_bits = 1`.
+    ///
+    /// The witnesses of the last monomial, whose selector is solved for, are
+    /// non-zero.
+    pub fn new_with_small_witnesses(
+        num_constraints: usize,
+        gate: &CustomizedGates,
+        num_bits: u32,
+    ) -> MockCircuit<F> {
+        assert!((1..=u64::BITS).contains(&num_bits), "invalid bit size");
+        let mask = u64::MAX >> (u64::BITS - num_bits);
+        let last_witnesses = match gate.gates.last() {
+            Some((_, _, witnesses)) => witnesses.clone(),
+            None => vec![],
+        };
+        Self::new_with_witness_sampler(num_constraints, gate, |rng, i| {
+            match rng.next_u64() & mask {
+                0 if last_witnesses.iter().any(|w| w.column == i) => F::one(),
+                value => F::from(value),
+            }
+        })
+    }
+
+    /// Generate a mock plonk circuit whose `i`-th witness is sampled by
+    /// `sample_witness(rng, i)` on each row.
+    fn new_with_witness_sampler(
+        num_constraints: usize,
+        gate: &CustomizedGates,
+        mut sample_witness: impl FnMut(&mut dyn RngCore, usize) -> F,
+    ) -> MockCircuit<F> {
         let mut rng = test_rng();
         let nv = log2(num_constraints);
         let num_selectors = gate.num_selector_columns();
@@ -49,13 +84,22 @@ impl<F: PrimeField> MockCircuit<F> {
         let merged_nv = nv + log_n_wires;
 
         let mut selectors: Vec<SelectorColumn<F>> = vec![SelectorColumn::default(); num_selectors];
-        let mut witnesses: Vec<WitnessColumn<F>> = vec![WitnessColumn::default(); num_witnesses];
 
-        for _cs_counter in 0..num_constraints {
+        // the witnesses of all rows are sampled first, as the gate may read
+        // them at the next row
+        let witness_rows: Vec<Vec<F>> = (0..num_constraints)
+            .map(|_| {
+                (0..num_witnesses)
+                    .map(|i| sample_witness(&mut rng, i))
+                    .collect()
+            })
+            .collect();
+
+        for cs_counter in 0..num_constraints {
             let mut cur_selectors: Vec<F> = (0..(num_selectors - 1))
                 .map(|_| F::rand(&mut rng))
                 .collect();
-            let cur_witness: Vec<F> = (0..num_witnesses).map(|_| F::rand(&mut rng)).collect();
+            let wire_value = |wire: &Wire| witness_value(&witness_rows, wire, cs_counter, nv);
             let mut last_selector = F::zero();
             for (index, (coeff, q, wit)) in gate.gates.iter().enumerate() {
                 if index != num_selectors - 1 {
@@ -68,8 +112,8 @@ impl<F: PrimeField> MockCircuit<F> {
                         Some(p) => cur_monomial * cur_selectors[*p],
                         None => cur_monomial,
                     };
-                    for wit_index in wit.iter() {
-                        cur_monomial *= cur_witness[*wit_index];
+                    for wire in wit.iter() {
+                        cur_monomial *= wire_value(wire);
                     }
                     last_selector += cur_monomial;
                 } else {
@@ -78,8 +122,8 @@ impl<F: PrimeField> MockCircuit<F> {
                     } else {
                         F::from(*coeff as u64)
                     };
-                    for wit_index in wit.iter() {
-                        cur_monomial *= cur_witness[*wit_index];
+                    for wire in wit.iter() {
+                        cur_monomial *= wire_value(wire);
                     }
                     last_selector /= -cur_monomial;
                 }
@@ -88,17 +132,15 @@ impl<F: PrimeField> MockCircuit<F> {
             for i in 0..num_selectors {
                 selectors[i].append(cur_selectors[i]);
             }
-            for i in 0..num_witnesses {
-                witnesses[i].append(cur_witness[i]);
-            }
         }
+        let witnesses = witness_columns(&witness_rows, num_witnesses);
         let pub_input_len = ark_std::cmp::min(4, num_constraints);
         let public_inputs = witnesses[0].0[0..pub_input_len].to_vec();
 
         let params = HyperPlonkParams {
             num_constraints,
             num_pub_input: public_inputs.len(),
-            gate_func: gate.clone(),
+            gate_func: gate.clone().into(),
         };
 
         let permutation = identity_permutation(merged_nv as usize, 1);
@@ -115,45 +157,148 @@ impl<F: PrimeField> MockCircuit<F> {
         }
     }
 
-    pub fn is_satisfied(&self) -> bool {
-        for current_row in 0..self.num_variables() {
-            let mut cur = F::zero();
-            for (coeff, q, wit) in self.index.params.gate_func.gates.iter() {
+    /// Generate a mock circuit mixing the gate types of `gate_set`.
+    ///
+    /// Row `i` switches on gate type `i % #types` only. Every gate type
+    /// must have an activation selector, and its last monomial must have a
+    /// selector, which is solved for so the row is satisfied.
+    pub fn new_with_gate_set(num_constraints: usize, gate_set: &CustomizedGateSet) -> Self {
+        let mut rng = test_rng();
+        let nv = log2(num_constraints);
+        let num_selectors = gate_set.num_selector_columns();
+        let num_witnesses = gate_set.num_witness_columns();
+        let merged_nv = nv + log2(num_witnesses);
+
+        let mut selectors: Vec<SelectorColumn<F>> = vec![SelectorColumn::default(); num_selectors];
+
+        // the witnesses of all rows are sampled first, as the gates may read
+        // them at the next row
+        let witness_rows: Vec<Vec<F>> = (0..num_constraints)
+            .map(|_| (0..num_witnesses).map(|_| F::rand(&mut rng)).collect())
+            .collect();
+
+        for cs_counter in 0..num_constraints {
+            let mut cur_selectors: Vec<F> = (0..num_selectors).map(|_| F::rand(&mut rng)).collect();
+            let wire_value = |wire: &Wire| witness_value(&witness_rows, wire, cs_counter, nv);
+
+            let active = cs_counter % gate_set.gates.len();
+            for (k, (activation, _gate)) in gate_set.gates.iter().enumerate() {
+                let a = activation.expect("mock gate types must have an activation selector");
+                cur_selectors[a] = if k == active { F::one() } else { F::zero() };
+            }
+
+            let gate = &gate_set.gates[active].1;
+            let (last, rest) = gate.gates.split_last().expect("empty gate type");
+            let mut sum = F::zero();
+            for (coeff, q, wit) in rest.iter() {
                 let mut cur_monomial = if *coeff < 0 {
                     -F::from((-coeff) as u64)
                 } else {
                     F::from(*coeff as u64)
                 };
-                cur_monomial = match q {
-                    Some(p) => cur_monomial * self.index.selectors[*p].0[current_row],
-                    None => cur_monomial,
-                };
-                for wit_index in wit.iter() {
-                    cur_monomial *= self.witnesses[*wit_index].0[current_row];
+                if let Some(p) = q {
+                    cur_monomial *= cur_selectors[*p];
                 }
-                cur += cur_monomial;
+                for wire in wit.iter() {
+                    cur_monomial *= wire_value(wire);
+                }
+                sum += cur_monomial;
+            }
+            let (coeff, q, wit) = last;
+            let mut last_monomial = if *coeff < 0 {
+                -F::from((-coeff) as u64)
+            } else {
+                F::from(*coeff as u64)
+            };
+            for wire in wit.iter() {
+                last_monomial *= wire_value(wire);
             }
-            if !cur.is_zero() {
-                return false;
+            let q = q.expect("the last monomial of a mock gate type must have a selector");
+            cur_selectors[q] = -sum / last_monomial;
+
+            for i in 0..num_selectors {
+                selectors[i].append(cur_selectors[i]);
             }
         }
+        let witnesses = witness_columns(&witness_rows, num_witnesses);
+        let pub_input_len = ark_std::cmp::min(4, num_constraints);
+        let public_inputs = witnesses[0].0[0..pub_input_len].to_vec();
+
+        let params = HyperPlonkParams {
+            num_constraints,
+            num_pub_input: public_inputs.len(),
+            gate_func: gate_set.clone(),
+        };
+
+        let permutation = identity_permutation(merged_nv as usize, 1);
+        let index = HyperPlonkIndex {
+            params,
+            permutation,
+            selectors,
+        };
+
+        Self {
+            public_inputs,
+            witnesses,
+            index,
+        }
+    }
+
+    pub fn is_satisfied(&self) -> bool {
+        matches!(self.index.check_witness(&self.witnesses), Ok(violations) if violations.is_empty())
+    }
+}
+
+// The value of `wire` on row `row` out of `2^num_vars` rows.
+fn witness_value<F: PrimeField>(
+    witness_rows: &[Vec<F>],
+    wire: &Wire,
+    row: usize,
+    num_vars: u32,
+) -> F {
+    match wire.row {
+        Row::Current => witness_rows[row][wire.column],
+        Row::Next => witness_rows[next_row(row, num_vars as usize)][wire.column],
+    }
+}
 
-        true
+// Transpose the witnesses of each row into witness columns.
+fn witness_columns<F: PrimeField>(
+    witness_rows: &[Vec<F>],
+    num_witnesses: usize,
+) -> Vec<WitnessColumn<F>> {
+    let mut witnesses: Vec<WitnessColumn<F>> = vec![WitnessColumn::default(); num_witnesses];
+    for cur_witness in witness_rows.iter() {
+        for i in 0..num_witnesses {
+            witnesses[i].append(cur_witness[i]);
+        }
     }
+    witnesses
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{errors::HyperPlonkErrors, HyperPlonkSNARK};
-    use ark_bls12_381::{Bls12_381, Fr};
+    use crate::{
+        errors::HyperPlonkErrors, structs::ConstraintViolation, HyperPlonkAccumulation,
+        HyperPlonkSNARK,
+    };
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective};
+    use ark_ff::BigInteger;
+    use ark_poly::DenseMultilinearExtension;
+    use ark_std::UniformRand;
+    use std::sync::Arc;
     use subroutines::{
         pcs::{
-            prelude::{MultilinearKzgPCS, MultilinearUniversalParams},
+            prelude::{
+                BatchProof, BrakedownPCS, HyraxPCS, MultilinearKzgPCS, MultilinearUniversalParams,
+                ZeromorphPCS,
+            },
             PolynomialCommitmentScheme,
         },
         poly_iop::PolyIOP,
     };
+    use transcript::{KeccakTranscript, PoseidonTranscript, Transcript};
 
     const SUPPORTED_SIZE: usize = 20;
     const MIN_NUM_VARS: usize = 8;
@@ -181,12 +326,64 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_mock_circuit_check_witness() -> Result<(), HyperPlonkErrors> {
+        let nv = 4;
+        let gate = CustomizedGates::vanilla_plonk_gate();
+        let mut circuit = MockCircuit::<Fr>::new(1 << nv, &gate);
+        assert!(circuit.index.check_witness(&circuit.witnesses)?.is_empty());
+
+        // break the gate equation on row 3
+        circuit.witnesses[1].0[3] += Fr::from(1u64);
+        assert_eq!(
+            circuit.index.check_witness(&circuit.witnesses)?,
+            vec![ConstraintViolation::Gate { row: 3 }]
+        );
+        assert!(!circuit.is_satisfied());
+        circuit.witnesses[1].0[3] -= Fr::from(1u64);
+
+        // copy constraint between w_0[0] and w_2[5]
+        let target = 2 * (1 << nv) + 5;
+        circuit.index.permutation.swap(0, target);
+        assert_eq!(
+            circuit.index.check_witness(&circuit.witnesses)?,
+            vec![
+                ConstraintViolation::Permutation {
+                    column: 0,
+                    row: 0,
+                    target_column: 2,
+                    target_row: 5,
+                },
+                ConstraintViolation::Permutation {
+                    column: 2,
+                    row: 5,
+                    target_column: 0,
+                    target_row: 0,
+                },
+            ]
+        );
+
+        // wrong number of witness columns
+        assert!(circuit
+            .index
+            .check_witness(&circuit.witnesses[1..])
+            .is_err());
+        Ok(())
+    }
+
     fn test_mock_circuit_zkp_helper(
         nv: usize,
         gate: &CustomizedGates,
         pcs_srs: &MultilinearUniversalParams<Bls12_381>,
     ) -> Result<(), HyperPlonkErrors> {
         let circuit = MockCircuit::<Fr>::new(1 << nv, gate);
+        test_mock_circuit_prove_verify_helper(circuit, pcs_srs)
+    }
+
+    fn test_mock_circuit_prove_verify_helper(
+        circuit: MockCircuit<Fr>,
+        pcs_srs: &MultilinearUniversalParams<Bls12_381>,
+    ) -> Result<(), HyperPlonkErrors> {
         assert!(circuit.is_satisfied());
 
         let index = circuit.index;
@@ -237,6 +434,472 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_small_witness_zkp() -> Result<(), HyperPlonkErrors> {
+        let mut rng = test_rng();
+        let pcs_srs =
+            MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
+        for num_bits in [1, 32] {
+            let vanilla_gate = CustomizedGates::vanilla_plonk_gate();
+            let circuit = MockCircuit::<Fr>::new_with_small_witnesses(
+                1 << MIN_NUM_VARS,
+                &vanilla_gate,
+                num_bits,
+            );
+            assert!(circuit
+                .witnesses
+                .iter()
+                .flat_map(|witness| witness.0.iter())
+                .all(|w| w.into_bigint().num_bits() <= num_bits));
+            test_mock_circuit_prove_verify_helper(circuit, &pcs_srs)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_circuit_brakedown_zkp() -> Result<(), HyperPlonkErrors> {
+        let mut rng = test_rng();
+        // the parameters of the transparent scheme hold no secret
+        let pcs_srs = BrakedownPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
+        for gate in [
+            CustomizedGates::vanilla_plonk_gate(),
+            CustomizedGates::jellyfish_turbo_plonk_gate(),
+        ] {
+            let circuit = MockCircuit::<Fr>::new(1 << MIN_NUM_VARS, &gate);
+            let (pk, vk) =
+                <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, BrakedownPCS<Bls12_381>>>::preprocess(
+                    &circuit.index,
+                    &pcs_srs,
+                )?;
+            let proof =
+                <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, BrakedownPCS<Bls12_381>>>::prove(
+                    &pk,
+                    &circuit.public_inputs,
+                    &circuit.witnesses,
+                )?;
+            assert!(<PolyIOP<Fr> as HyperPlonkSNARK<
+                Bls12_381,
+                BrakedownPCS<Bls12_381>,
+            >>::verify(&vk, &circuit.public_inputs, &proof,)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_circuit_hyrax_zkp() -> Result<(), HyperPlonkErrors> {
+        type Hyrax = HyraxPCS<G1Projective>;
+        let mut rng = test_rng();
+        // the parameters of the transparent scheme hold no secret
+        let pcs_srs = <Hyrax as PolynomialCommitmentScheme<Bls12_381>>::gen_srs_for_testing(
+            &mut rng,
+            SUPPORTED_SIZE,
+        )?;
+        for gate in [
+            CustomizedGates::vanilla_plonk_gate(),
+            CustomizedGates::jellyfish_turbo_plonk_gate(),
+        ] {
+            let circuit = MockCircuit::<Fr>::new(1 << MIN_NUM_VARS, &gate);
+            let (pk, vk) = <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, Hyrax>>::preprocess(
+                &circuit.index,
+                &pcs_srs,
+            )?;
+            let proof = <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, Hyrax>>::prove(
+                &pk,
+                &circuit.public_inputs,
+                &circuit.witnesses,
+            )?;
+            assert!(<PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, Hyrax>>::verify(
+                &vk,
+                &circuit.public_inputs,
+                &proof,
+            )?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_circuit_zeromorph_zkp() -> Result<(), HyperPlonkErrors> {
+        let mut rng = test_rng();
+        let pcs_srs = ZeromorphPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
+        for gate in [
+            CustomizedGates::vanilla_plonk_gate(),
+            CustomizedGates::jellyfish_turbo_plonk_gate(),
+        ] {
+            let circuit = MockCircuit::<Fr>::new(1 << MIN_NUM_VARS, &gate);
+            let (pk, vk) =
+                <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, ZeromorphPCS<Bls12_381>>>::preprocess(
+                    &circuit.index,
+                    &pcs_srs,
+                )?;
+            let proof =
+                <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, ZeromorphPCS<Bls12_381>>>::prove(
+                    &pk,
+                    &circuit.public_inputs,
+                    &circuit.witnesses,
+                )?;
+            assert!(<PolyIOP<Fr> as HyperPlonkSNARK<
+                Bls12_381,
+                ZeromorphPCS<Bls12_381>,
+            >>::verify(&vk, &circuit.public_inputs, &proof,)?);
+        }
+
+        Ok(())
+    }
+
+    fn test_mock_circuit_transcript_helper<T: Transcript<Fr>>(
+        pcs_srs: &MultilinearUniversalParams<Bls12_381>,
+    ) -> Result<(), HyperPlonkErrors> {
+        type Kzg = MultilinearKzgPCS<Bls12_381>;
+        let circuit = MockCircuit::<Fr>::new(
+            1 << MIN_NUM_VARS,
+            &CustomizedGates::jellyfish_turbo_plonk_gate(),
+        );
+        let (pk, vk) = <PolyIOP<Fr, T> as HyperPlonkSNARK<Bls12_381, Kzg>>::preprocess(
+            &circuit.index,
+            pcs_srs,
+        )?;
+        let proof = <PolyIOP<Fr, T> as HyperPlonkSNARK<Bls12_381, Kzg>>::prove(
+            &pk,
+            &circuit.public_inputs,
+            &circuit.witnesses,
+        )?;
+        assert!(<PolyIOP<Fr, T> as HyperPlonkSNARK<Bls12_381, Kzg>>::verify(
+            &vk,
+            &circuit.public_inputs,
+            &proof,
+        )?);
+
+        // a wrong public input is rejected
+        let mut bad_public_inputs = circuit.public_inputs.clone();
+        bad_public_inputs[0] += Fr::from(1u64);
+        assert!(<PolyIOP<Fr, T> as HyperPlonkSNARK<Bls12_381, Kzg>>::verify(
+            &vk,
+            &bad_public_inputs,
+            &proof,
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_circuit_transcripts_zkp() -> Result<(), HyperPlonkErrors> {
+        let mut rng = test_rng();
+        let pcs_srs =
+            MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
+        test_mock_circuit_transcript_helper::<KeccakTranscript<Fr>>(&pcs_srs)?;
+        test_mock_circuit_transcript_helper::<PoseidonTranscript<Fr>>(&pcs_srs)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_gate_set_zkp() -> Result<(), HyperPlonkErrors> {
+        let mut rng = test_rng();
+        let pcs_srs =
+            MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
+
+        // an arithmetic gate, a power-5 round gate and a vanilla plonk gate
+        let gate_set: CustomizedGateSet = "q2*(w2 - q0*w0 - q1*w1) \
+             + q5*(w0^5 + w3 - q3*w1 + q4) \
+             + q6*(q7*w0 + q8*w1 + q9*w2 + q10*w0*w1 + q11)"
+            .parse()?;
+        gate_set.validate()?;
+
+        for nv in [2, 5, MIN_NUM_VARS] {
+            let circuit = MockCircuit::<Fr>::new_with_gate_set(1 << nv, &gate_set);
+            test_mock_circuit_prove_verify_helper(circuit, &pcs_srs)?;
+        }
+
+        // a gate type whose activation is flipped on a row is caught
+        let mut circuit = MockCircuit::<Fr>::new_with_gate_set(1 << 4, &gate_set);
+        circuit.index.selectors[5].0[0] = Fr::from(1u64);
+        assert_eq!(
+            circuit.index.check_witness(&circuit.witnesses)?,
+            vec![ConstraintViolation::Gate { row: 0 }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_row_gate_zkp() -> Result<(), HyperPlonkErrors> {
+        let mut rng = test_rng();
+        let pcs_srs =
+            MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
+
+        // the mock circuits solve their selectors from the next row as well
+        let gate: CustomizedGates = "q0*w0*w1' + q1*w1 + q2*w0'".parse()?;
+        let circuit = MockCircuit::<Fr>::new(1 << 4, &gate);
+        test_mock_circuit_prove_verify_helper(circuit, &pcs_srs)?;
+        let gate_set: CustomizedGateSet = "q0*(q1*w0' + q2*w1) + q3*(w0*w1 - q4*w1')".parse()?;
+        let circuit = MockCircuit::<Fr>::new_with_gate_set(1 << 4, &gate_set);
+        test_mock_circuit_prove_verify_helper(circuit, &pcs_srs)?;
+
+        // running sum: w1(next(x)) = w1(x) + w0(x) along the cycle of the
+        // non-zero rows from row 1, but on its last row and on row 0
+        let gate_set: CustomizedGateSet = "q0*(w1' - w1 - w0)".parse()?;
+        gate_set.validate()?;
+        assert_eq!(gate_set.next_row_witnesses(), vec![1]);
+
+        let nv = 4;
+        let num_constraints = 1 << nv;
+        let w0: Vec<Fr> = (0..num_constraints).map(|_| Fr::rand(&mut rng)).collect();
+        let mut w1 = vec![Fr::from(0u64); num_constraints];
+        let mut q0 = vec![Fr::from(0u64); num_constraints];
+        let mut row = 1;
+        w1[row] = Fr::rand(&mut rng);
+        for _ in 0..num_constraints - 2 {
+            q0[row] = Fr::from(1u64);
+            w1[next_row(row, nv)] = w1[row] + w0[row];
+            row = next_row(row, nv);
+        }
+
+        let new_circuit = |w1: Vec<Fr>| MockCircuit {
+            public_inputs: w0[..4].to_vec(),
+            witnesses: vec![WitnessColumn(w0.clone()), WitnessColumn(w1)],
+            index: HyperPlonkIndex {
+                params: HyperPlonkParams {
+                    num_constraints,
+                    num_pub_input: 4,
+                    gate_func: gate_set.clone(),
+                },
+                permutation: identity_permutation(nv + 1, 1),
+                selectors: vec![SelectorColumn(q0.clone())],
+            },
+        };
+        test_mock_circuit_prove_verify_helper(new_circuit(w1.clone()), &pcs_srs)?;
+
+        // breaking the running sum on row 10, which follows row 5, breaks
+        // rows 5 and 10
+        assert_eq!(next_row(5, nv), 10);
+        w1[10] += Fr::from(1u64);
+        let circuit = new_circuit(w1);
+        assert_eq!(
+            circuit.index.check_witness(&circuit.witnesses)?,
+            vec![
+                ConstraintViolation::Gate { row: 5 },
+                ConstraintViolation::Gate { row: 10 }
+            ]
+        );
+        let (pk, vk) =
+            <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, MultilinearKzgPCS<Bls12_381>>>::preprocess(
+                &circuit.index,
+                &pcs_srs,
+            )?;
+        let proof =
+            <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, MultilinearKzgPCS<Bls12_381>>>::prove(
+                &pk,
+                &circuit.public_inputs,
+                &circuit.witnesses,
+            )?;
+        let verify =
+            <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, MultilinearKzgPCS<Bls12_381>>>::verify(
+                &vk,
+                &circuit.public_inputs,
+                &proof,
+            );
+        assert!(!matches!(verify, Ok(true)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_streaming_zkp() -> Result<(), HyperPlonkErrors> {
+        type Kzg = MultilinearKzgPCS<Bls12_381>;
+        let mut rng = test_rng();
+        let pcs_srs = Kzg::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
+
+        let gate_set: CustomizedGateSet = "q0*(q1*w0' + q2*w1) + q3*(w0*w1 - q4*w1')".parse()?;
+        let nv = 4;
+        let circuit = MockCircuit::<Fr>::new_with_gate_set(1 << nv, &gate_set);
+        let (mut pk, vk) = <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, Kzg>>::preprocess_batch(
+            &circuit.index,
+            &pcs_srs,
+            2,
+        )?;
+        let instance = (&circuit.public_inputs[..], &circuit.witnesses[..]);
+
+        // streaming the first rounds of the zero check gives the same proof,
+        // for a single instance and for a batch of `nv + 1` variables
+        for num_instances in [1, 2] {
+            let instances = vec![instance; num_instances];
+            pk.num_streamed_rounds = 0;
+            let proof =
+                <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, Kzg>>::prove_batch(&pk, &instances)?;
+            for num_streamed_rounds in [1, nv + num_instances - 2] {
+                pk.num_streamed_rounds = num_streamed_rounds;
+                let streaming_proof =
+                    <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, Kzg>>::prove_batch(&pk, &instances)?;
+                assert_eq!(streaming_proof.zero_check_proof, proof.zero_check_proof);
+            }
+            let pub_inputs = vec![&circuit.public_inputs[..]; num_instances];
+            assert!(
+                <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, Kzg>>::verify_batch(
+                    &vk,
+                    &pub_inputs,
+                    &proof
+                )?
+            );
+        }
+
+        // the zero check keeps at least one round in memory
+        pk.num_streamed_rounds = nv;
+        assert!(<PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, Kzg>>::prove(
+            &pk,
+            &circuit.public_inputs,
+            &circuit.witnesses,
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_zkp() -> Result<(), HyperPlonkErrors> {
+        let mut rng = test_rng();
+        let pcs_srs =
+            MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
+        test_batch_zkp_helper::<MultilinearKzgPCS<Bls12_381>>(&pcs_srs)
+    }
+
+    #[test]
+    fn test_batch_hyrax_zkp() -> Result<(), HyperPlonkErrors> {
+        type Hyrax = HyraxPCS<G1Projective>;
+        let mut rng = test_rng();
+        let pcs_srs = <Hyrax as PolynomialCommitmentScheme<Bls12_381>>::gen_srs_for_testing(
+            &mut rng,
+            SUPPORTED_SIZE,
+        )?;
+        test_batch_zkp_helper::<Hyrax>(&pcs_srs)
+    }
+
+    fn test_batch_zkp_helper<PCS>(pcs_srs: &PCS::SRS) -> Result<(), HyperPlonkErrors>
+    where
+        PCS: PolynomialCommitmentScheme<
+            Bls12_381,
+            Polynomial = Arc<DenseMultilinearExtension<Fr>>,
+            Point = Vec<Fr>,
+            Evaluation = Fr,
+            BatchProof = BatchProof<Bls12_381, PCS>,
+        >,
+    {
+        let mut rng = test_rng();
+
+        // running sum: w1(next(x)) = w1(x) + w0(x) along the cycle of the
+        // non-zero rows but on its last row, starting from w1(1) = w0(1)
+        let gate_set: CustomizedGateSet = "q0*(w1' - w1 - w0)".parse()?;
+        let nv = 4;
+        let num_constraints = 1 << nv;
+        let mut q0 = vec![Fr::from(0u64); num_constraints];
+        let mut row = 1;
+        for _ in 0..num_constraints - 2 {
+            q0[row] = Fr::from(1u64);
+            row = next_row(row, nv);
+        }
+        let mut permutation = identity_permutation(nv + 1, 1);
+        permutation.swap(1, num_constraints + 1);
+        let index = HyperPlonkIndex {
+            params: HyperPlonkParams {
+                num_constraints,
+                num_pub_input: 4,
+                gate_func: gate_set,
+            },
+            permutation,
+            selectors: vec![SelectorColumn(q0)],
+        };
+
+        let mut new_witnesses = || {
+            let w0: Vec<Fr> = (0..num_constraints).map(|_| Fr::rand(&mut rng)).collect();
+            let mut w1 = vec![Fr::from(0u64); num_constraints];
+            let mut row = 1;
+            w1[row] = w0[row];
+            for _ in 0..num_constraints - 2 {
+                w1[next_row(row, nv)] = w1[row] + w0[row];
+                row = next_row(row, nv);
+            }
+            vec![WitnessColumn(w0), WitnessColumn(w1)]
+        };
+        // an instance count that is not a power of two is padded
+        let witnesses: Vec<_> = (0..3).map(|_| new_witnesses()).collect();
+        for w in witnesses.iter() {
+            assert!(index.check_witness(w)?.is_empty());
+        }
+        let pub_inputs: Vec<&[Fr]> = witnesses.iter().map(|w| &w[0].0[..4]).collect();
+        let instances: Vec<_> = pub_inputs
+            .iter()
+            .zip(witnesses.iter())
+            .map(|(&pi, w)| (pi, &w[..]))
+            .collect();
+
+        let (pk, vk) = <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, PCS>>::preprocess_batch(
+            &index,
+            pcs_srs,
+            instances.len(),
+        )?;
+        let proof = <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, PCS>>::prove_batch(&pk, &instances)?;
+        assert!(
+            <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, PCS>>::verify_batch(
+                &vk,
+                &pub_inputs,
+                &proof
+            )?
+        );
+
+        // the public inputs are bound to their instance
+        let swapped = [pub_inputs[1], pub_inputs[0], pub_inputs[2]];
+        let verify =
+            <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, PCS>>::verify_batch(&vk, &swapped, &proof);
+        assert!(!matches!(verify, Ok(true)));
+
+        // a single instance is still proven by the batch keys
+        let proof = <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, PCS>>::prove(
+            &pk,
+            pub_inputs[0],
+            &witnesses[0],
+        )?;
+        assert!(<PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, PCS>>::verify(
+            &vk,
+            pub_inputs[0],
+            &proof
+        )?);
+
+        // the copy constraint w1(1) = w0(1) is checked within each instance
+        let mut bad = witnesses[2].clone();
+        let mut row = 1;
+        bad[1].0[row] += Fr::from(1u64);
+        for _ in 0..num_constraints - 2 {
+            bad[1].0[next_row(row, nv)] = bad[1].0[row] + bad[0].0[row];
+            row = next_row(row, nv);
+        }
+        assert_eq!(
+            index.check_witness(&bad)?,
+            vec![
+                ConstraintViolation::Permutation {
+                    column: 0,
+                    row: 1,
+                    target_column: 1,
+                    target_row: 1
+                },
+                ConstraintViolation::Permutation {
+                    column: 1,
+                    row: 1,
+                    target_column: 0,
+                    target_row: 1
+                }
+            ]
+        );
+        let mut instances = instances;
+        instances[2].1 = &bad;
+        let proof = <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, PCS>>::prove_batch(&pk, &instances)?;
+        let verify = <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, PCS>>::verify_batch(
+            &vk,
+            &pub_inputs,
+            &proof,
+        );
+        assert!(!matches!(verify, Ok(true)));
+        Ok(())
+    }
+
     #[test]
     fn test_mock_circuit_e2e() -> Result<(), HyperPlonkErrors> {
         let mut rng = test_rng();
@@ -262,4 +925,50 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_verify_many_zkp() -> Result<(), HyperPlonkErrors> {
+        let mut rng = test_rng();
+        let pcs_srs =
+            MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
+        let circuit =
+            MockCircuit::<Fr>::new(1 << MIN_NUM_VARS, &CustomizedGates::vanilla_plonk_gate());
+        let pi = &circuit.public_inputs[..];
+        let instance = (pi, &circuit.witnesses[..]);
+
+        let (pk, vk) = <PolyIOP<Fr> as HyperPlonkSNARK<
+            Bls12_381,
+            MultilinearKzgPCS<Bls12_381>,
+        >>::preprocess_batch(&circuit.index, &pcs_srs, 2)?;
+        let proof =
+            <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, MultilinearKzgPCS<Bls12_381>>>::prove(
+                &pk,
+                pi,
+                &circuit.witnesses,
+            )?;
+        let batch_proof = <PolyIOP<Fr> as HyperPlonkSNARK<
+            Bls12_381,
+            MultilinearKzgPCS<Bls12_381>,
+        >>::prove_batch(&pk, &[instance, instance])?;
+
+        // the openings of all the proofs are checked with a single pairing
+        let single: &[&[Fr]] = &[pi];
+        let batch: &[&[Fr]] = &[pi, pi];
+        assert!(<PolyIOP<Fr> as HyperPlonkAccumulation<
+            Bls12_381,
+            MultilinearKzgPCS<Bls12_381>,
+        >>::verify_many(
+            &vk,
+            &[(single, &proof), (batch, &batch_proof), (single, &proof)]
+        )?);
+
+        // a wrong proof fails the whole verification
+        let verify = <PolyIOP<Fr> as HyperPlonkAccumulation<
+            Bls12_381,
+            MultilinearKzgPCS<Bls12_381>,
+        >>::verify_many(&vk, &[(single, &proof), (batch, &proof)]);
+        assert!(!matches!(verify, Ok(true)));
+
+        Ok(())
+    }
 }
@@ -5,11 +5,12 @@
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
 pub use crate::{
-    custom_gate::CustomizedGates,
+    custom_gate::{CustomizedGateSet, CustomizedGates, Row, Wire},
     errors::HyperPlonkErrors,
     mock::MockCircuit,
     selectors::SelectorColumn,
-    structs::{HyperPlonkIndex, HyperPlonkParams},
+    structs::{ConstraintViolation, HyperPlonkIndex, HyperPlonkParams},
     witness::WitnessColumn,
-    HyperPlonkSNARK,
+    HyperPlonkAccumulation, HyperPlonkSNARK,
 };
+pub use arithmetic::next_row;
@@ -7,11 +7,15 @@
 use crate::{
     errors::HyperPlonkErrors,
     structs::{HyperPlonkIndex, HyperPlonkProof, HyperPlonkProvingKey, HyperPlonkVerifyingKey},
-    utils::{build_f, eval_f, eval_perm_gate, prover_sanity_check, PcsAccumulator},
+    utils::{
+        build_f, build_streaming_f, eval_f, eval_perm_gate, eval_stacked_permutation,
+        interleave_instances, prover_sanity_check, replicate_instances, stack_permutation,
+        Openings, PcsAccumulator,
+    },
     witness::WitnessColumn,
-    HyperPlonkSNARK,
+    HyperPlonkAccumulation, HyperPlonkSNARK,
 };
-use arithmetic::{evaluate_opt, gen_eval_point, VPAuxInfo};
+use arithmetic::{evaluate_opt, gen_eval_point, next_row_points, VPAuxInfo};
 use ark_ec::pairing::Pairing;
 use ark_poly::DenseMultilinearExtension;
 use ark_std::{end_timer, log2, start_timer, One, Zero};
@@ -20,18 +24,19 @@ use rayon::iter::IntoParallelRefIterator;
 use rayon::iter::ParallelIterator;
 use std::{marker::PhantomData, sync::Arc};
 use subroutines::{
-    pcs::prelude::{Commitment, PolynomialCommitmentScheme},
+    pcs::prelude::{AccumulationScheme, PolynomialCommitmentScheme},
     poly_iop::{
-        prelude::{PermutationCheck, ZeroCheck},
+        prelude::{PermutationCheck, StreamingZeroCheck, ZeroCheck},
         PolyIOP,
     },
     BatchProof,
 };
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
-impl<E, PCS> HyperPlonkSNARK<E, PCS> for PolyIOP<E::ScalarField>
+impl<E, PCS, T> HyperPlonkSNARK<E, PCS> for PolyIOP<E::ScalarField, T>
 where
     E: Pairing,
+    T: Transcript<E::ScalarField>,
     // Ideally we want to access polynomial as PCS::Polynomial, instead of instantiating it here.
     // But since PCS::Polynomial can be both univariate or multivariate in our implementation
     // we cannot bound PCS::Polynomial with a property trait bound.
@@ -40,7 +45,6 @@ where
         Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
         Point = Vec<E::ScalarField>,
         Evaluation = E::ScalarField,
-        Commitment = Commitment<E>,
         BatchProof = BatchProof<E, PCS>,
     >,
 {
@@ -53,8 +57,18 @@ where
         index: &Self::Index,
         pcs_srs: &PCS::SRS,
     ) -> Result<(Self::ProvingKey, Self::VerifyingKey), HyperPlonkErrors> {
+        Self::preprocess_batch(index, pcs_srs, 1)
+    }
+
+    fn preprocess_batch(
+        index: &Self::Index,
+        pcs_srs: &PCS::SRS,
+        max_num_instances: usize,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), HyperPlonkErrors> {
+        index.params.gate_func.validate()?;
         let num_vars = index.num_variables();
-        let supported_ml_degree = num_vars;
+        // a batch of instances is proven as a circuit with more variables
+        let supported_ml_degree = num_vars + log2(max_num_instances) as usize;
 
         // extract PCS prover and verifier keys from SRS
         let (pcs_prover_param, pcs_verifier_param) =
@@ -86,6 +100,27 @@ where
             .map(|poly| PCS::commit(&pcs_prover_param, poly))
             .collect::<Result<Vec<_>, _>>()?;
 
+        // the polynomials of a batch do not depend on the instance, but a
+        // commitment to `p(x)` does not bind its replication `p(y, x)` for
+        // every scheme, so each batch size has its own commitments
+        let commit_replicated = |oracles: &[Arc<DenseMultilinearExtension<E::ScalarField>>]| {
+            (1..=log2(max_num_instances) as usize)
+                .map(|log_num_instances| {
+                    oracles
+                        .par_iter()
+                        .map(|poly| {
+                            PCS::commit(
+                                &pcs_prover_param,
+                                &replicate_instances(poly, log_num_instances),
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let batch_selector_commitments = commit_replicated(&selector_oracles)?;
+        let batch_perm_commitments = commit_replicated(&permutation_oracles)?;
+
         Ok((
             Self::ProvingKey {
                 params: index.params.clone(),
@@ -93,13 +128,18 @@ where
                 selector_oracles,
                 selector_commitments: selector_commitments.clone(),
                 permutation_commitments: perm_comms.clone(),
+                batch_selector_commitments: batch_selector_commitments.clone(),
+                batch_permutation_commitments: batch_perm_commitments.clone(),
                 pcs_param: pcs_prover_param,
+                num_streamed_rounds: 0,
             },
             Self::VerifyingKey {
                 params: index.params.clone(),
                 pcs_param: pcs_verifier_param,
                 selector_commitments,
                 perm_commitments: perm_comms,
+                batch_selector_commitments,
+                batch_perm_commitments,
             },
         ))
     }
@@ -127,7 +167,8 @@ where
     ///     f(q_l, q_r, q_m, q_o, w_a, w_b, w_c)
     ///     = q_l w_a(x) + q_r w_b(x) + q_m w_a(x)w_b(x) - q_o w_c(x)
     /// ```
-    /// in vanilla plonk, and obtain a ZeroCheckSubClaim
+    /// in vanilla plonk, and obtain a ZeroCheckSubClaim. The first
+    /// `pk.num_streamed_rounds` rounds stream the evaluations of `f`.
     ///
     /// 3. Run permutation check on `\{w_i(x)\}` and `permutation_oracle`, and
     /// obtain a PermCheckSubClaim.
@@ -146,6 +187,9 @@ where
     /// - 4.3. zero check evaluations and proofs
     ///   - 4.3.1. (deferred) wi_poly(zero_check_point)
     ///   - 4.3.2. (deferred) selector_poly(zero_check_point)
+    ///   - 4.3.3. (deferred) wi_poly at the points that determine
+    ///     wi_poly(next(zero_check_point)), for the witnesses read at the next
+    ///     row
     ///
     /// - 4.4. public input consistency checks
     ///   - pi_poly(r_pi) where r_pi is sampled from transcript
@@ -155,17 +199,72 @@ where
         pk: &Self::ProvingKey,
         pub_input: &[E::ScalarField],
         witnesses: &[WitnessColumn<E::ScalarField>],
+    ) -> Result<Self::Proof, HyperPlonkErrors> {
+        Self::prove_batch(pk, &[(pub_input, witnesses)])
+    }
+
+    /// Generate a single HyperPlonk SNARK proof for many instances of the
+    /// circuit.
+    ///
+    /// The instances, padded to `2^m` by repeating the last one, are
+    /// interleaved into one circuit with `m` more variables: row `x` of
+    /// instance `y` becomes row `x * 2^m + y`. The preprocessed polynomials
+    /// of the stacked circuit do not depend on `y`, so they are committed
+    /// once for each `m` in the proving key:
+    /// - a selector `q(y, x)` is `q(x)`
+    /// - a permutation `perm(y, x)` is `y + 2^m * perm(x)`, which is opened
+    ///   through its replicated `perm(x)`
+    ///
+    /// and the proof is generated as above, with the public input of the
+    /// stacked circuit interleaved in the same way.
+    fn prove_batch(
+        pk: &Self::ProvingKey,
+        instances: &[(&[E::ScalarField], &[WitnessColumn<E::ScalarField>])],
     ) -> Result<Self::Proof, HyperPlonkErrors> {
         let start = start_timer!(|| "hyperplonk proving");
-        let mut transcript = IOPTranscript::<E::ScalarField>::new(b"hyperplonk");
+        let mut transcript = T::new(b"hyperplonk");
 
-        prover_sanity_check(&pk.params, pub_input, witnesses)?;
+        if instances.is_empty() {
+            return Err(HyperPlonkErrors::InvalidProver(
+                "no instance to prove".to_string(),
+            ));
+        }
+        for (pub_input, witnesses) in instances.iter() {
+            prover_sanity_check(&pk.params, pub_input, witnesses)?;
+            if witnesses.len() != pk.params.num_witness_columns() {
+                return Err(HyperPlonkErrors::InvalidProver(format!(
+                    "number of witnesses is not correct: got {}, expect {}",
+                    witnesses.len(),
+                    pk.params.num_witness_columns()
+                )));
+            }
+        }
+        let log_num_instances = log2(instances.len()) as usize;
+        let params = pk.params.stack(log_num_instances);
+        let (selector_commitments, permutation_commitments) = if log_num_instances == 0 {
+            (&pk.selector_commitments, &pk.permutation_commitments)
+        } else {
+            match (
+                pk.batch_selector_commitments.get(log_num_instances - 1),
+                pk.batch_permutation_commitments.get(log_num_instances - 1),
+            ) {
+                (Some(selectors), Some(permutations)) => (selectors, permutations),
+                _ => {
+                    end_timer!(start);
+                    return Err(HyperPlonkErrors::InvalidProver(format!(
+                        "the proving key supports up to {} instances, got {}",
+                        1 << pk.batch_selector_commitments.len(),
+                        instances.len()
+                    )));
+                },
+            }
+        };
 
         // witness assignment of length 2^n
-        let num_vars = pk.params.num_variables();
+        let num_vars = params.num_variables();
 
         // online public input of length 2^\ell
-        let ell = log2(pk.params.num_pub_input) as usize;
+        let ell = log2(params.num_pub_input) as usize;
 
         // We use accumulators to store the polynomials and their eval points.
         // They are batch opened at a later stage.
@@ -177,9 +276,28 @@ where
         // =======================================================================
         let step = start_timer!(|| "commit witnesses");
 
-        let witness_polys: Vec<Arc<DenseMultilinearExtension<E::ScalarField>>> = witnesses
+        let witness_polys: Vec<Arc<DenseMultilinearExtension<E::ScalarField>>> = (0..params
+            .num_witness_columns())
+            .map(|i| {
+                let columns: Vec<&[E::ScalarField]> =
+                    instances.iter().map(|(_, w)| w[i].coeff_ref()).collect();
+                Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                    num_vars,
+                    interleave_instances(&columns, log_num_instances),
+                ))
+            })
+            .collect();
+
+        // the preprocessed polynomials of the stacked circuit
+        let selector_oracles: Vec<_> = pk
+            .selector_oracles
+            .iter()
+            .map(|s| replicate_instances(s, log_num_instances))
+            .collect();
+        let permutation_oracles: Vec<_> = pk
+            .permutation_oracles
             .iter()
-            .map(|w| Arc::new(DenseMultilinearExtension::from(w)))
+            .map(|s| stack_permutation(s, log_num_instances))
             .collect();
 
         let witness_commits = witness_polys
@@ -205,14 +323,29 @@ where
         // =======================================================================
         let step = start_timer!(|| "ZeroCheck on f");
 
-        let fx = build_f(
-            &pk.params.gate_func,
-            pk.params.num_variables(),
-            &pk.selector_oracles,
-            &witness_polys,
-        )?;
-
-        let zero_check_proof = <Self as ZeroCheck<E::ScalarField>>::prove(&fx, &mut transcript)?;
+        let zero_check_proof = if pk.num_streamed_rounds == 0 {
+            let fx = build_f(
+                &params.gate_func,
+                num_vars,
+                &selector_oracles,
+                &witness_polys,
+                log_num_instances,
+            )?;
+            <Self as ZeroCheck<E::ScalarField>>::prove(&fx, &mut transcript)?
+        } else {
+            let fx = build_streaming_f(
+                &params.gate_func,
+                num_vars,
+                &selector_oracles,
+                &witness_polys,
+                log_num_instances,
+            )?;
+            <Self as StreamingZeroCheck<E::ScalarField>>::prove_streaming(
+                &fx,
+                pk.num_streamed_rounds,
+                &mut transcript,
+            )?
+        };
         end_timer!(step);
         // =======================================================================
         // 3. Run permutation check on `\{w_i(x)\}` and `permutation_oracle`, and
@@ -224,7 +357,7 @@ where
             &pk.pcs_param,
             &witness_polys,
             &witness_polys,
-            &pk.permutation_oracles,
+            &permutation_oracles,
             &mut transcript,
         )?;
         let perm_check_point = &perm_check_proof.zero_check_proof.point;
@@ -252,6 +385,8 @@ where
         // - zero check evaluations and proofs
         //   - 4.3.1. (deferred) wi_poly(zero_check_point)
         //   - 4.3.2. (deferred) selector_poly(zero_check_point)
+        //   - 4.3.3. (deferred) wi_poly at the points that determine
+        //     wi_poly(next(zero_check_point))
         //
         // - 4.4. (deferred) public input consistency checks
         //   - pi_poly(r_pi) where r_pi is sampled from transcript
@@ -298,16 +433,18 @@ where
         );
 
         // perms(x)'s points
+        // the commitments bind the instance independent part perm(x)
         for (perm, pcom) in pk
             .permutation_oracles
             .iter()
-            .zip(pk.permutation_commitments.iter())
+            .zip(permutation_commitments.iter())
         {
-            pcs_acc.insert_poly_and_points(perm, pcom, perm_check_point);
+            let perm = replicate_instances(perm, log_num_instances);
+            pcs_acc.insert_poly_and_points(&perm, pcom, perm_check_point);
         }
 
         // witnesses' points
-        // TODO: refactor so it remains correct even if the order changed
+        // the verifier inserts the openings in the same order
         for (wpoly, wcom) in witness_polys.iter().zip(witness_commits.iter()) {
             pcs_acc.insert_poly_and_points(wpoly, wcom, perm_check_point);
         }
@@ -316,13 +453,23 @@ where
         }
 
         //   - 4.3.2. (deferred) selector_poly(zero_check_point)
-        pk.selector_oracles
+        selector_oracles
             .iter()
-            .zip(pk.selector_commitments.iter())
+            .zip(selector_commitments.iter())
             .for_each(|(poly, com)| {
-                pcs_acc.insert_poly_and_points(poly, com, &zero_check_proof.point)
+                pcs_acc.insert_poly_and_points(poly, com, &zero_check_proof.point);
             });
 
+        //   - 4.3.3. (deferred) wi_poly at the points that determine
+        //     wi_poly(next(zero_check_point)), for the witnesses read at the
+        //     next row
+        let next_row_points = next_row_points(&zero_check_proof.point, log_num_instances);
+        for i in params.gate_func.next_row_witnesses() {
+            for (point, _coeff) in next_row_points.iter() {
+                pcs_acc.insert_poly_and_points(&witness_polys[i], &witness_commits[i], point);
+            }
+        }
+
         // - 4.4. public input consistency checks
         //   - pi_poly(r_pi) where r_pi is sampled from transcript
         let r_pi = transcript.get_and_append_challenge_vectors(b"r_pi", ell)?;
@@ -390,235 +537,425 @@ where
         pub_input: &[E::ScalarField],
         proof: &Self::Proof,
     ) -> Result<bool, HyperPlonkErrors> {
-        let start = start_timer!(|| "hyperplonk verification");
-
-        let mut transcript = IOPTranscript::<E::ScalarField>::new(b"hyperplonk");
-
-        let num_selectors = vk.params.num_selector_columns();
-        let num_witnesses = vk.params.num_witness_columns();
-        let num_vars = vk.params.num_variables();
-
-        //  online public input of length 2^\ell
-        let ell = log2(vk.params.num_pub_input) as usize;
-
-        // =======================================================================
-        // 0. sanity checks
-        // =======================================================================
-        // public input length
-        if pub_input.len() != vk.params.num_pub_input {
-            return Err(HyperPlonkErrors::InvalidProver(format!(
-                "Public input length is not correct: got {}, expect {}",
-                pub_input.len(),
-                1 << ell
-            )));
-        }
+        Self::verify_batch(vk, &[pub_input], proof)
+    }
 
-        // Extract evaluations from openings
-        let prod_evals = &proof.batch_openings.f_i_eval_at_point_i[0..4];
-        let frac_evals = &proof.batch_openings.f_i_eval_at_point_i[4..7];
-        let perm_evals = &proof.batch_openings.f_i_eval_at_point_i[7..7 + num_witnesses];
-        let witness_perm_evals =
-            &proof.batch_openings.f_i_eval_at_point_i[7 + num_witnesses..7 + 2 * num_witnesses];
-        let witness_gate_evals =
-            &proof.batch_openings.f_i_eval_at_point_i[7 + 2 * num_witnesses..7 + 3 * num_witnesses];
-        let selector_evals = &proof.batch_openings.f_i_eval_at_point_i
-            [7 + 3 * num_witnesses..7 + 3 * num_witnesses + num_selectors];
-        let pi_eval = proof.batch_openings.f_i_eval_at_point_i.last().unwrap();
+    /// Verify a HyperPlonk proof of several instances.
+    ///
+    /// The proof is verified as above against the circuit that interleaves
+    /// the instances, where
+    /// - the selectors are opened through the commitments in the verifying
+    ///   key
+    /// - the permutations are opened as `perm(x)` through the commitments in
+    ///   the verifying key, and recombined into `y + 2^m * perm(x)`
+    /// - the public input is interleaved as in the witnesses
+    fn verify_batch(
+        vk: &Self::VerifyingKey,
+        pub_inputs: &[&[E::ScalarField]],
+        proof: &Self::Proof,
+    ) -> Result<bool, HyperPlonkErrors> {
+        let start = start_timer!(|| "hyperplonk verification");
+        let (comms, points, mut transcript) = match verify_iop(vk, pub_inputs, proof)? {
+            Some(res) => res,
+            None => {
+                end_timer!(start);
+                return Ok(false);
+            },
+        };
 
         // =======================================================================
-        // 1. Verify zero_check_proof on
-        //     `f(q_0(x),...q_l(x), w_0(x),...w_d(x))`
-        //
-        // where `f` is the constraint polynomial i.e.,
-        //
-        //     f(q_l, q_r, q_m, q_o, w_a, w_b, w_c)
-        //     = q_l w_a(x) + q_r w_b(x) + q_m w_a(x)w_b(x) - q_o w_c(x)
-        //
+        // 5. Verify the opening against the commitment
         // =======================================================================
-        let step = start_timer!(|| "verify zero check");
-        // Zero check and perm check have different AuxInfo
-        let zero_check_aux_info = VPAuxInfo::<E::ScalarField> {
-            max_degree: vk.params.gate_func.degree(),
-            num_variables: num_vars,
-            phantom: PhantomData::default(),
-        };
-        // push witness to transcript
-        for w_com in proof.witness_commits.iter() {
-            transcript.append_serializable_element(b"w", w_com)?;
-        }
-
-        let zero_check_sub_claim = <Self as ZeroCheck<E::ScalarField>>::verify(
-            &proof.zero_check_proof,
-            &zero_check_aux_info,
+        let step = start_timer!(|| "PCS batch verify");
+        // check proof
+        let res = PCS::batch_verify(
+            &vk.pcs_param,
+            &comms,
+            &points,
+            &proof.batch_openings,
             &mut transcript,
         )?;
 
-        let zero_check_point = zero_check_sub_claim.point.clone();
-
-        // check zero check subclaim
-        let f_eval = eval_f(&vk.params.gate_func, selector_evals, witness_gate_evals)?;
-        if f_eval != zero_check_sub_claim.expected_evaluation {
-            return Err(HyperPlonkErrors::InvalidProof(
-                "zero check evaluation failed".to_string(),
-            ));
-        }
-
         end_timer!(step);
-        // =======================================================================
-        // 2. Verify perm_check_proof on `\{w_i(x)\}` and `permutation_oracle`
-        // =======================================================================
-        let step = start_timer!(|| "verify permutation check");
-
-        // Zero check and perm check have different AuxInfo
-        let perm_check_aux_info = VPAuxInfo::<E::ScalarField> {
-            // Prod(x) has a max degree of witnesses.len() + 1
-            max_degree: proof.witness_commits.len() + 1,
-            num_variables: num_vars,
-            phantom: PhantomData::default(),
+        end_timer!(start);
+        Ok(res)
+    }
+}
+
+impl<E, PCS, T> HyperPlonkAccumulation<E, PCS> for PolyIOP<E::ScalarField, T>
+where
+    E: Pairing,
+    T: Transcript<E::ScalarField>,
+    PCS: AccumulationScheme<
+        E,
+        Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
+        Point = Vec<E::ScalarField>,
+        Evaluation = E::ScalarField,
+        BatchProof = BatchProof<E, PCS>,
+    >,
+{
+    fn verify_deferred(
+        vk: &Self::VerifyingKey,
+        pub_inputs: &[&[E::ScalarField]],
+        proof: &Self::Proof,
+    ) -> Result<PCS::Accumulator, HyperPlonkErrors> {
+        let start = start_timer!(|| "hyperplonk deferred verification");
+        let (comms, points, mut transcript) = match verify_iop(vk, pub_inputs, proof)? {
+            Some(res) => res,
+            None => {
+                end_timer!(start);
+                return Err(HyperPlonkErrors::InvalidProof(
+                    "number of evaluations is not correct".to_string(),
+                ));
+            },
         };
-        let perm_check_sub_claim = <Self as PermutationCheck<E, PCS>>::verify(
-            &proof.perm_check_proof,
-            &perm_check_aux_info,
+        let accumulator = PCS::batch_verify_deferred(
+            &vk.pcs_param,
+            &comms,
+            &points,
+            &proof.batch_openings,
             &mut transcript,
         )?;
+        end_timer!(start);
+        Ok(accumulator)
+    }
 
-        let perm_check_point = perm_check_sub_claim
-            .product_check_sub_claim
-            .zero_check_sub_claim
-            .point
-            .clone();
+    fn verify_many(
+        vk: &Self::VerifyingKey,
+        proofs: &[(&[&[E::ScalarField]], &Self::Proof)],
+    ) -> Result<bool, HyperPlonkErrors> {
+        let start = start_timer!(|| format!("hyperplonk verification of {} proofs", proofs.len()));
+        let accumulators = proofs
+            .iter()
+            .map(|(pub_inputs, proof)| Self::verify_deferred(vk, pub_inputs, proof))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let alpha = perm_check_sub_claim.product_check_sub_claim.alpha;
-        let (beta, gamma) = perm_check_sub_claim.challenges;
+        let mut transcript = T::new(b"hyperplonk accumulation");
+        let accumulator = PCS::accumulate(&accumulators, &mut transcript)?;
+        let res = PCS::decide(&vk.pcs_param, &accumulator)?;
+        end_timer!(start);
+        Ok(res)
+    }
+}
 
-        let mut id_evals = vec![];
-        for i in 0..num_witnesses {
-            let ith_point = gen_eval_point(i, log2(num_witnesses) as usize, &perm_check_point[..]);
-            id_evals.push(vk.params.eval_id_oracle(&ith_point[..])?);
+/// Steps 0 to 4 of `verify_batch`: everything but the final check of the
+/// openings, whose commitments and points are returned along with the
+/// transcript.
+///
+/// Returns `None` when the proof does not carry one evaluation per
+/// deduplicated opening, as happens when it was generated against other
+/// commitments than those of the key.
+#[allow(clippy::type_complexity)]
+fn verify_iop<E, PCS, T>(
+    vk: &HyperPlonkVerifyingKey<E, PCS>,
+    pub_inputs: &[&[E::ScalarField]],
+    proof: &HyperPlonkProof<E, PolyIOP<E::ScalarField, T>, PCS>,
+) -> Result<Option<(Vec<PCS::Commitment>, Vec<PCS::Point>, T)>, HyperPlonkErrors>
+where
+    E: Pairing,
+    T: Transcript<E::ScalarField>,
+    PCS: PolynomialCommitmentScheme<
+        E,
+        Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
+        Point = Vec<E::ScalarField>,
+        Evaluation = E::ScalarField,
+        BatchProof = BatchProof<E, PCS>,
+    >,
+{
+    let mut transcript = T::new(b"hyperplonk");
+
+    // =======================================================================
+    // 0. sanity checks
+    // =======================================================================
+    if pub_inputs.is_empty() {
+        return Err(HyperPlonkErrors::InvalidVerifier(
+            "no instance to verify".to_string(),
+        ));
+    }
+    // public input length
+    for pub_input in pub_inputs.iter() {
+        if pub_input.len() != vk.params.num_pub_input {
+            return Err(HyperPlonkErrors::InvalidProver(format!(
+                "Public input length is not correct: got {}, expect {}",
+                pub_input.len(),
+                vk.params.num_pub_input
+            )));
         }
-
-        // check evaluation subclaim
-        let perm_gate_eval = eval_perm_gate(
-            prod_evals,
-            frac_evals,
-            witness_perm_evals,
-            &id_evals[..],
-            perm_evals,
-            alpha,
-            beta,
-            gamma,
-            *perm_check_point.last().unwrap(),
-        )?;
-        if perm_gate_eval
-            != perm_check_sub_claim
-                .product_check_sub_claim
-                .zero_check_sub_claim
-                .expected_evaluation
-        {
-            return Err(HyperPlonkErrors::InvalidVerifier(
-                "evaluation failed".to_string(),
-            ));
+    }
+    let log_num_instances = log2(pub_inputs.len()) as usize;
+    let params = vk.params.stack(log_num_instances);
+    let (selector_commitments, perm_commitments) = if log_num_instances == 0 {
+        (&vk.selector_commitments, &vk.perm_commitments)
+    } else {
+        match (
+            vk.batch_selector_commitments.get(log_num_instances - 1),
+            vk.batch_perm_commitments.get(log_num_instances - 1),
+        ) {
+            (Some(selectors), Some(perms)) => (selectors, perms),
+            _ => {
+                return Err(HyperPlonkErrors::InvalidVerifier(format!(
+                    "the verifying key supports up to {} instances, got {}",
+                    1 << vk.batch_selector_commitments.len(),
+                    pub_inputs.len()
+                )))
+            },
         }
+    };
 
-        end_timer!(step);
-        // =======================================================================
-        // 3. Verify the opening against the commitment
-        // =======================================================================
-        let step = start_timer!(|| "assemble commitments");
-
-        // generate evaluation points and commitments
-        let mut comms = vec![];
-        let mut points = vec![];
+    let num_witnesses = params.num_witness_columns();
+    let num_vars = params.num_variables();
 
-        let perm_check_point_0 = [
-            &[E::ScalarField::zero()],
-            &perm_check_point[0..num_vars - 1],
-        ]
-        .concat();
-        let perm_check_point_1 =
-            [&[E::ScalarField::one()], &perm_check_point[0..num_vars - 1]].concat();
-        let prod_final_query_point = [
-            vec![E::ScalarField::zero()],
-            vec![E::ScalarField::one(); num_vars - 1],
-        ]
-        .concat();
+    //  online public input of length 2^\ell
+    let ell = log2(params.num_pub_input) as usize;
 
-        // prod(x)'s points
-        comms.push(proof.perm_check_proof.prod_x_comm);
-        comms.push(proof.perm_check_proof.prod_x_comm);
-        comms.push(proof.perm_check_proof.prod_x_comm);
-        comms.push(proof.perm_check_proof.prod_x_comm);
-        points.push(perm_check_point.clone());
-        points.push(perm_check_point_0.clone());
-        points.push(perm_check_point_1.clone());
-        points.push(prod_final_query_point);
-        // frac(x)'s points
-        comms.push(proof.perm_check_proof.frac_comm);
-        comms.push(proof.perm_check_proof.frac_comm);
-        comms.push(proof.perm_check_proof.frac_comm);
-        points.push(perm_check_point.clone());
-        points.push(perm_check_point_0);
-        points.push(perm_check_point_1);
-
-        // perms' points
-        for &pcom in vk.perm_commitments.iter() {
-            comms.push(pcom);
-            points.push(perm_check_point.clone());
-        }
+    let next_row_witnesses = params.gate_func.next_row_witnesses();
+    if proof.witness_commits.len() != num_witnesses {
+        return Err(HyperPlonkErrors::InvalidProof(format!(
+            "number of witness commitments is not correct: got {}, expect {}",
+            proof.witness_commits.len(),
+            num_witnesses
+        )));
+    }
 
-        // witnesses' points
-        // TODO: merge points
-        for &wcom in proof.witness_commits.iter() {
-            comms.push(wcom);
-            points.push(perm_check_point.clone());
-        }
-        for &wcom in proof.witness_commits.iter() {
-            comms.push(wcom);
-            points.push(zero_check_point.clone());
-        }
+    // =======================================================================
+    // 1. Verify zero_check_proof on
+    //     `f(q_0(x),...q_l(x), w_0(x),...w_d(x))`
+    //
+    // where `f` is the constraint polynomial i.e.,
+    //
+    //     f(q_l, q_r, q_m, q_o, w_a, w_b, w_c)
+    //     = q_l w_a(x) + q_r w_b(x) + q_m w_a(x)w_b(x) - q_o w_c(x)
+    //
+    // =======================================================================
+    let step = start_timer!(|| "verify zero check");
+    // Zero check and perm check have different AuxInfo
+    let zero_check_aux_info = VPAuxInfo::<E::ScalarField> {
+        max_degree: params.gate_func.degree(),
+        num_variables: num_vars,
+        phantom: PhantomData::default(),
+    };
+    // push witness to transcript
+    for w_com in proof.witness_commits.iter() {
+        transcript.append_serializable_element(b"w", w_com)?;
+    }
 
-        // selector_poly(zero_check_point)
-        for &com in vk.selector_commitments.iter() {
-            comms.push(com);
-            points.push(zero_check_point.clone());
-        }
+    let zero_check_sub_claim = <PolyIOP<E::ScalarField, T> as ZeroCheck<E::ScalarField>>::verify(
+        &proof.zero_check_proof,
+        &zero_check_aux_info,
+        &mut transcript,
+    )?;
+
+    let zero_check_point = zero_check_sub_claim.point.clone();
+    end_timer!(step);
+    // =======================================================================
+    // 2. Verify perm_check_proof on `\{w_i(x)\}` and `permutation_oracle`
+    // =======================================================================
+    let step = start_timer!(|| "verify permutation check");
+
+    // Zero check and perm check have different AuxInfo
+    let perm_check_aux_info = VPAuxInfo::<E::ScalarField> {
+        // Prod(x) has a max degree of witnesses.len() + 1
+        max_degree: proof.witness_commits.len() + 1,
+        num_variables: num_vars,
+        phantom: PhantomData::default(),
+    };
+    let perm_check_sub_claim = <PolyIOP<E::ScalarField, T> as PermutationCheck<E, PCS>>::verify(
+        &proof.perm_check_proof,
+        &perm_check_aux_info,
+        &mut transcript,
+    )?;
+
+    let perm_check_point = perm_check_sub_claim
+        .product_check_sub_claim
+        .zero_check_sub_claim
+        .point
+        .clone();
+    end_timer!(step);
+
+    // - 4.4. public input consistency checks
+    //   - pi_poly(r_pi) where r_pi is sampled from transcript
+    let r_pi = transcript.get_and_append_challenge_vectors(b"r_pi", ell)?;
+
+    // =======================================================================
+    // 3. Assemble the openings, deduplicated in the same way as the prover's
+    // accumulator, and extract the evaluations
+    // =======================================================================
+    let step = start_timer!(|| "assemble commitments");
+    let mut openings = Openings::<_, Vec<E::ScalarField>>::new();
+
+    let perm_check_point_0 = [
+        &[E::ScalarField::zero()],
+        &perm_check_point[0..num_vars - 1],
+    ]
+    .concat();
+    let perm_check_point_1 =
+        [&[E::ScalarField::one()], &perm_check_point[0..num_vars - 1]].concat();
+    let prod_final_query_point = [
+        vec![E::ScalarField::zero()],
+        vec![E::ScalarField::one(); num_vars - 1],
+    ]
+    .concat();
+
+    // prod(x)'s points
+    let prod_indices: Vec<_> = [
+        &perm_check_point,
+        &perm_check_point_0,
+        &perm_check_point_1,
+        &prod_final_query_point,
+    ]
+    .iter()
+    .map(|point| openings.insert(&proof.perm_check_proof.prod_x_comm, point))
+    .collect();
+    // frac(x)'s points
+    let frac_indices: Vec<_> = [&perm_check_point, &perm_check_point_0, &perm_check_point_1]
+        .iter()
+        .map(|point| openings.insert(&proof.perm_check_proof.frac_comm, point))
+        .collect();
+    // perms' points
+    let perm_indices: Vec<_> = perm_commitments
+        .iter()
+        .map(|pcom| openings.insert(pcom, &perm_check_point))
+        .collect();
+    // witnesses' points
+    let witness_perm_indices: Vec<_> = proof
+        .witness_commits
+        .iter()
+        .map(|wcom| openings.insert(wcom, &perm_check_point))
+        .collect();
+    let witness_gate_indices: Vec<_> = proof
+        .witness_commits
+        .iter()
+        .map(|wcom| openings.insert(wcom, &zero_check_point))
+        .collect();
+    // selector_poly(zero_check_point)
+    let selector_indices: Vec<_> = selector_commitments
+        .iter()
+        .map(|com| openings.insert(com, &zero_check_point))
+        .collect();
+    // witnesses' points for w_i(next(zero_check_point))
+    let next_row_points = next_row_points(&zero_check_point, log_num_instances);
+    let next_row_indices: Vec<Vec<_>> = next_row_witnesses
+        .iter()
+        .map(|&i| {
+            next_row_points
+                .iter()
+                .map(|(point, _coeff)| openings.insert(&proof.witness_commits[i], point))
+                .collect()
+        })
+        .collect();
+    // w_0(r_pi || 0s)
+    let r_pi_padded = [r_pi.clone(), vec![E::ScalarField::zero(); num_vars - ell]].concat();
+    let pi_index = openings.insert(&proof.witness_commits[0], &r_pi_padded);
 
-        // - 4.4. public input consistency checks
-        //   - pi_poly(r_pi) where r_pi is sampled from transcript
-        let r_pi = transcript.get_and_append_challenge_vectors(b"r_pi", ell)?;
+    let all_evals = &proof.batch_openings.f_i_eval_at_point_i;
+    if all_evals.len() != openings.len() {
+        end_timer!(step);
+        return Ok(None);
+    }
+    let evals = |indices: &[usize]| -> Vec<E::ScalarField> {
+        indices.iter().map(|&i| all_evals[i]).collect()
+    };
+    let prod_evals = evals(&prod_indices);
+    let frac_evals = evals(&frac_indices);
+    let perm_evals = evals(&perm_indices);
+    let witness_perm_evals = evals(&witness_perm_indices);
+    let witness_gate_evals = evals(&witness_gate_indices);
+    let selector_evals = evals(&selector_indices);
+    let pi_eval = all_evals[pi_index];
+    end_timer!(step);
+
+    // =======================================================================
+    // 4. Check the subclaims against the evaluations
+    // =======================================================================
+    let step = start_timer!(|| "check zero check subclaim");
+    // w_i(next(zero_check_point)) for the witnesses read at the next row
+    let mut witness_next_row_evals = vec![E::ScalarField::zero(); num_witnesses];
+    for (&i, indices) in next_row_witnesses.iter().zip(next_row_indices.iter()) {
+        witness_next_row_evals[i] = next_row_points
+            .iter()
+            .zip(evals(indices).iter())
+            .map(|((_point, coeff), &eval)| *coeff * eval)
+            .sum();
+    }
 
-        // check public evaluation
-        let pi_step = start_timer!(|| "check public evaluation");
-        let pi_poly = DenseMultilinearExtension::from_evaluations_slice(ell, pub_input);
-        let expect_pi_eval = evaluate_opt(&pi_poly, &r_pi[..]);
-        if expect_pi_eval != *pi_eval {
-            return Err(HyperPlonkErrors::InvalidProver(format!(
-                "Public input eval mismatch: got {}, expect {}",
-                pi_eval, expect_pi_eval,
-            )));
-        }
-        let r_pi_padded = [r_pi, vec![E::ScalarField::zero(); num_vars - ell]].concat();
+    // check zero check subclaim
+    let f_eval = eval_f(
+        &params.gate_func,
+        &selector_evals,
+        &witness_gate_evals,
+        &witness_next_row_evals,
+    )?;
+    if f_eval != zero_check_sub_claim.expected_evaluation {
+        return Err(HyperPlonkErrors::InvalidProof(
+            "zero check evaluation failed".to_string(),
+        ));
+    }
+    end_timer!(step);
 
-        comms.push(proof.witness_commits[0]);
-        points.push(r_pi_padded);
-        assert_eq!(comms.len(), proof.batch_openings.f_i_eval_at_point_i.len());
-        end_timer!(pi_step);
+    let step = start_timer!(|| "check permutation check subclaim");
+    let alpha = perm_check_sub_claim.product_check_sub_claim.alpha;
+    let (beta, gamma) = perm_check_sub_claim.challenges;
 
-        end_timer!(step);
-        let step = start_timer!(|| "PCS batch verify");
-        // check proof
-        let res = PCS::batch_verify(
-            &vk.pcs_param,
-            &comms,
-            &points,
-            &proof.batch_openings,
-            &mut transcript,
-        )?;
+    let mut id_evals = vec![];
+    for i in 0..num_witnesses {
+        let ith_point = gen_eval_point(i, log2(num_witnesses) as usize, &perm_check_point[..]);
+        id_evals.push(params.eval_id_oracle(&ith_point[..])?);
+    }
 
-        end_timer!(step);
-        end_timer!(start);
-        Ok(res)
+    // the openings are of perm(x), the permutations of a single instance
+    let perm_evals: Vec<E::ScalarField> = perm_evals
+        .iter()
+        .map(|&eval| eval_stacked_permutation(eval, &perm_check_point, log_num_instances))
+        .collect();
+
+    // check evaluation subclaim
+    let perm_gate_eval = eval_perm_gate(
+        &prod_evals,
+        &frac_evals,
+        &witness_perm_evals,
+        &id_evals[..],
+        &perm_evals,
+        alpha,
+        beta,
+        gamma,
+        *perm_check_point.last().unwrap(),
+    )?;
+    if perm_gate_eval
+        != perm_check_sub_claim
+            .product_check_sub_claim
+            .zero_check_sub_claim
+            .expected_evaluation
+    {
+        return Err(HyperPlonkErrors::InvalidVerifier(
+            "evaluation failed".to_string(),
+        ));
+    }
+    // check the final query prod(0, 1, ..., 1) = 1, without which the
+    // product of the fractions is unconstrained
+    if prod_evals[3] != perm_check_sub_claim.product_check_sub_claim.final_query.1 {
+        return Err(HyperPlonkErrors::InvalidProof(
+            "permutation check final product is not one".to_string(),
+        ));
+    }
+    end_timer!(step);
+
+    // check public evaluation
+    let pi_step = start_timer!(|| "check public evaluation");
+    let pi_poly = DenseMultilinearExtension::from_evaluations_vec(
+        ell,
+        interleave_instances(pub_inputs, log_num_instances),
+    );
+    let expect_pi_eval = evaluate_opt(&pi_poly, &r_pi[..]);
+    if expect_pi_eval != pi_eval {
+        return Err(HyperPlonkErrors::InvalidProver(format!(
+            "Public input eval mismatch: got {}, expect {}",
+            pi_eval, expect_pi_eval,
+        )));
     }
+    end_timer!(pi_step);
+
+    let (comms, points) = openings.expand();
+    Ok(Some((comms, points, transcript)))
 }
 
 #[cfg(test)]
@@ -648,9 +985,10 @@ mod tests {
         // 2 witnesses,
         // 2 variables for MLE,
         // 4 wires,
-        let gates = CustomizedGates {
-            gates: vec![(1, Some(0), vec![0, 0, 0, 0, 0]), (-1, None, vec![1])],
-        };
+        let gates = CustomizedGates::from_current_row(vec![
+            (1, Some(0), vec![0, 0, 0, 0, 0]),
+            (-1, None, vec![1]),
+        ]);
         test_hyperplonk_helper::<Bls12_381>(gates)
     }
 
@@ -669,7 +1007,7 @@ mod tests {
         let params = HyperPlonkParams {
             num_constraints,
             num_pub_input,
-            gate_func,
+            gate_func: gate_func.into(),
         };
         let permutation = identity_permutation(nv, num_witnesses);
         let q1 = SelectorColumn(vec![
@@ -0,0 +1,622 @@
+// SPDX-License-Identifier: MIT
+// Generated by the HyperPlonk library from a verifying key.
+pragma solidity ^0.8.0;
+
+/// @title HyperPlonk verifier over BN254
+/// @notice Verifies the HyperPlonk proofs, with the multilinear KZG
+/// commitment scheme and the Keccak transcript, of the circuit whose
+/// verifying key this contract was generated from.
+contract HyperPlonkVerifier {
+    /// order of the scalar field
+    uint256 internal constant R_MOD = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001;
+    /// order of the base field
+    uint256 internal constant Q_MOD = 0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47;
+    /// y coordinates above this bound are serialized with a flag
+    uint256 internal constant Q_HALF = (Q_MOD - 1) / 2;
+    /// 2^256 mod r
+    uint256 internal constant R_2_256 = 0x0e0a77c19a07df2f666ea36f7879462e36fc76959f60cd29ac96341c4ffffffb;
+
+    // masks swapping the bytes, then the pairs of bytes, ... of a word
+    uint256 internal constant MASK_8 = (type(uint256).max / 0xffff) * 0xff00;
+    uint256 internal constant MASK_16 = (type(uint256).max / 0xffffffff) * 0xffff0000;
+    uint256 internal constant MASK_32 = (type(uint256).max / 0xffffffffffffffff) * 0xffffffff00000000;
+    uint256 internal constant MASK_64 =
+        (type(uint256).max / 0xffffffffffffffffffffffffffffffff) * 0xffffffffffffffff0000000000000000;
+
+{{CONSTANTS}}
+    /// The state of a verification, kept in memory to stay within the stack.
+    struct Verifier {
+        // transcript
+        bytes32 state;
+        // challenges and final evaluation of the gate zero check, and
+        // eq(point, r) for its random vector r
+        uint256[] zeroPoint;
+        uint256 zeroEval;
+        uint256 zeroEq;
+        // challenges of the permutation check, and the same for its zero check
+        uint256 beta;
+        uint256 gamma;
+        uint256 alpha;
+        uint256[] permPoint;
+        uint256 permEval;
+        uint256 permEq;
+        // point of the public input
+        uint256[] rPi;
+        // the commitments of the openings, as (x, y) pairs, and the first
+        // slot holding the same commitment as each slot
+        uint256[] slots;
+        uint256[] canon;
+        // the distinct openings, as a canonical slot and a point, and the
+        // opening of each insertion
+        uint256[] openingSlot;
+        uint256[] openingPoint;
+        uint256[] index;
+        uint256 numOpenings;
+    }
+
+    /// @notice Verify a proof for the public inputs, both encoded as by
+    /// `hyperplonk::solidity::encode_calldata`.
+    function verify(uint256[] calldata publicInputs, uint256[] calldata proof) external view returns (bool) {
+        if (!checkRanges(publicInputs, proof)) {
+            return false;
+        }
+        Verifier memory v;
+        v.state = keccak256(bytes("hyperplonk"));
+        for (uint256 i = 0; i < NUM_WITNESSES; i++) {
+            appendG1(v, proof[2 * i], proof[2 * i + 1]);
+        }
+        if (!zeroCheck(v, proof) || !permCheck(v, proof)) {
+            return false;
+        }
+        v.rPi = challenges(v, LOG_NUM_PUB_INPUTS);
+        if (!collectOpenings(v, proof)) {
+            return false;
+        }
+        if (!checkGate(v, proof) || !checkPerm(v, proof) || !checkPublicInputs(v, publicInputs, proof)) {
+            return false;
+        }
+        return checkOpenings(v, proof);
+    }
+
+    /// Check the lengths, and that the coordinates and field elements are
+    /// canonical, as the native verifier only deserializes those.
+    function checkRanges(uint256[] calldata publicInputs, uint256[] calldata proof) internal pure returns (bool) {
+        if (publicInputs.length != NUM_PUB_INPUTS || proof.length < EVALS_OFFSET) {
+            return false;
+        }
+        for (uint256 i = 0; i < NUM_PUB_INPUTS; i++) {
+            if (publicInputs[i] >= R_MOD) {
+                return false;
+            }
+        }
+        for (uint256 i = 0; i < proof.length; i++) {
+            bool isCoordinate = i < ZERO_CHECK_OFFSET || (i >= PROD_OFFSET && i < PERM_CHECK_OFFSET)
+                || (i >= QUOTIENT_OFFSET && i < EVALS_OFFSET);
+            if (proof[i] >= (isCoordinate ? Q_MOD : R_MOD)) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    // =======================================================================
+    // IOPs
+    // =======================================================================
+
+    /// Zero check of the gate, whose subclaim is kept in `v`.
+    function zeroCheck(Verifier memory v, uint256[] calldata proof) internal pure returns (bool ok) {
+        uint256[] memory r = challenges(v, NUM_VARS);
+        (v.zeroPoint, v.zeroEval, ok) = sumCheck(v, proof, ZERO_CHECK_OFFSET, GATE_DEGREE + 1, 0);
+        v.zeroEq = eq(v.zeroPoint, r);
+    }
+
+    /// Permutation check of the witnesses, whose subclaim is kept in `v`.
+    function permCheck(Verifier memory v, uint256[] calldata proof) internal pure returns (bool ok) {
+        v.beta = challenge(v);
+        v.gamma = challenge(v);
+        appendG1(v, proof[FRAC_OFFSET], proof[FRAC_OFFSET + 1]);
+        appendG1(v, proof[PROD_OFFSET], proof[PROD_OFFSET + 1]);
+        v.alpha = challenge(v);
+        uint256[] memory r = challenges(v, NUM_VARS);
+        (v.permPoint, v.permEval, ok) = sumCheck(v, proof, PERM_CHECK_OFFSET, NUM_WITNESSES + 2, 0);
+        v.permEq = eq(v.permPoint, r);
+    }
+
+    /// Replay a sum check of `sum` whose rounds are the evaluations at
+    /// `0, ..., degree` of `NUM_VARS` polynomials starting at `proof[offset]`.
+    /// Returns its challenges and expected evaluation, and whether every
+    /// round is consistent with the previous one.
+    function sumCheck(Verifier memory v, uint256[] calldata proof, uint256 offset, uint256 degree, uint256 sum)
+        internal
+        pure
+        returns (uint256[] memory point, uint256 eval, bool ok)
+    {
+        // aux info: max degree and number of variables as u64
+        v.state = keccak256(abi.encodePacked(v.state, bytes16(uint128((degree << 120) | (NUM_VARS << 56)))));
+        uint256[] memory weights = barycentricWeights(degree);
+        point = new uint256[](NUM_VARS);
+        eval = sum;
+        ok = true;
+        for (uint256 i = 0; i < NUM_VARS; i++) {
+            uint256 start = offset + i * (degree + 1);
+            appendMessage(v, proof, start, degree + 1);
+            point[i] = challenge(v);
+            if (addmod(proof[start], proof[start + 1], R_MOD) != eval) {
+                ok = false;
+            }
+            eval = interpolate(proof[start:start + degree + 1], weights, point[i]);
+        }
+    }
+
+    /// Evaluate at `x` the polynomial whose evaluations at `0, ..., degree`
+    /// are `evals`, with the Lagrange basis `weights[i] * prod_{j != i} (x - j)`.
+    function interpolate(uint256[] calldata evals, uint256[] memory weights, uint256 x)
+        internal
+        pure
+        returns (uint256 res)
+    {
+        uint256 len = weights.length;
+        // suffix[i] = prod_{j > i} (x - j)
+        uint256[] memory suffix = new uint256[](len);
+        uint256 prefix = 1;
+        for (uint256 i = len; i > 0; i--) {
+            suffix[i - 1] = prefix;
+            prefix = mulmod(prefix, addmod(x, R_MOD - (i - 1), R_MOD), R_MOD);
+        }
+        prefix = 1;
+        for (uint256 i = 0; i < len; i++) {
+            uint256 basis = mulmod(mulmod(prefix, suffix[i], R_MOD), weights[i], R_MOD);
+            res = addmod(res, mulmod(basis, evals[i], R_MOD), R_MOD);
+            prefix = mulmod(prefix, addmod(x, R_MOD - i, R_MOD), R_MOD);
+        }
+    }
+
+    /// The barycentric weights `1 / prod_{j != i} (i - j)` of the points
+    /// `0, ..., degree`.
+    function barycentricWeights(uint256 degree) internal pure returns (uint256[] memory w) {
+        w = new uint256[](degree + 1);
+{{BARYCENTRIC_WEIGHTS}}
+    }
+
+    // =======================================================================
+    // openings
+    // =======================================================================
+
+    /// Deduplicate the openings in the same way as the native verifier, by
+    /// commitment value and point, and check the number of evaluations.
+    function collectOpenings(Verifier memory v, uint256[] calldata proof) internal pure returns (bool) {
+        uint256[] memory slots = new uint256[](2 * NUM_SLOTS);
+        slots[0] = proof[PROD_OFFSET];
+        slots[1] = proof[PROD_OFFSET + 1];
+        slots[2] = proof[FRAC_OFFSET];
+        slots[3] = proof[FRAC_OFFSET + 1];
+        for (uint256 i = 0; i < NUM_WITNESSES; i++) {
+            slots[2 * (WITNESS_SLOTS + i)] = proof[2 * i];
+            slots[2 * (WITNESS_SLOTS + i) + 1] = proof[2 * i + 1];
+        }
+        vkCommitments(slots);
+        v.slots = slots;
+        v.canon = canonicalSlots(slots);
+        v.numOpenings = dedupOpenings(v);
+        return proof.length == EVALS_OFFSET + v.numOpenings;
+    }
+
+    /// The first slot holding the same commitment as each slot.
+    function canonicalSlots(uint256[] memory slots) internal pure returns (uint256[] memory canon) {
+        canon = new uint256[](NUM_SLOTS);
+        for (uint256 s = 0; s < NUM_SLOTS; s++) {
+            canon[s] = s;
+            for (uint256 t = 0; t < s; t++) {
+                if (slots[2 * t] == slots[2 * s] && slots[2 * t + 1] == slots[2 * s + 1]) {
+                    canon[s] = t;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Collect the distinct pairs of a canonical slot and a point in the
+    /// order of the insertions, and return their number.
+    function dedupOpenings(Verifier memory v) internal pure returns (uint256 n) {
+        bytes memory insertions = INSERTIONS;
+        v.index = new uint256[](NUM_INSERTIONS);
+        v.openingSlot = new uint256[](NUM_INSERTIONS);
+        v.openingPoint = new uint256[](NUM_INSERTIONS);
+        for (uint256 e = 0; e < NUM_INSERTIONS; e++) {
+            uint256 slot = v.canon[uint8(insertions[2 * e])];
+            uint256 pointId = uint8(insertions[2 * e + 1]);
+            uint256 j = 0;
+            while (j < n && (v.openingSlot[j] != slot || v.openingPoint[j] != pointId)) {
+                j++;
+            }
+            if (j == n) {
+                v.openingSlot[n] = slot;
+                v.openingPoint[n] = pointId;
+                n++;
+            }
+            v.index[e] = j;
+        }
+    }
+
+    /// Write the commitments of the verifying key into their slots.
+    function vkCommitments(uint256[] memory slots) internal pure {
+{{VK_COMMITMENTS}}
+    }
+
+    /// The evaluation of insertion `e`.
+    function evaluation(Verifier memory v, uint256[] calldata proof, uint256 e) internal pure returns (uint256) {
+        return proof[EVALS_OFFSET + v.index[e]];
+    }
+
+    /// eq(a, p) for the point `pointId`, whose coordinates are coded as
+    /// pairs of bytes: 0 or 1 for the constants, and 2, 3, 4 or 5 followed
+    /// by an index for the coordinates of the zero check point, the
+    /// permutation check point, the public input point and one minus the
+    /// coordinates of the zero check point.
+    function eqPoint(Verifier memory v, bytes memory points, uint256[] memory a, uint256 pointId)
+        internal
+        pure
+        returns (uint256 res)
+    {
+        res = 1;
+        for (uint256 j = 0; j < NUM_VARS; j++) {
+            uint256 k = 2 * (pointId * NUM_VARS + j);
+            uint256 kind = uint8(points[k]);
+            uint256 i = uint8(points[k + 1]);
+            uint256 p;
+            if (kind == 1) {
+                p = 1;
+            } else if (kind == 2) {
+                p = v.zeroPoint[i];
+            } else if (kind == 3) {
+                p = v.permPoint[i];
+            } else if (kind == 4) {
+                p = v.rPi[i];
+            } else if (kind == 5) {
+                p = addmod(1, R_MOD - v.zeroPoint[i], R_MOD);
+            }
+            res = mulmod(res, eqTerm(a[j], p), R_MOD);
+        }
+    }
+
+    // =======================================================================
+    // subclaims
+    // =======================================================================
+
+    /// Check the zero check subclaim against the gate evaluated on the
+    /// openings.
+    function checkGate(Verifier memory v, uint256[] calldata proof) internal pure returns (bool) {
+        uint256[] memory s = new uint256[](NUM_SELECTORS);
+        for (uint256 i = 0; i < NUM_SELECTORS; i++) {
+            s[i] = evaluation(v, proof, SELECTOR_INSERTIONS + i);
+        }
+        uint256[] memory w = new uint256[](NUM_WITNESSES);
+        for (uint256 i = 0; i < NUM_WITNESSES; i++) {
+            w[i] = evaluation(v, proof, WITNESS_GATE_INSERTIONS + i);
+        }
+        uint256[] memory wn = nextRowEvals(v, proof);
+        return mulmod(gate(s, w, wn), v.zeroEq, R_MOD) == v.zeroEval;
+    }
+
+    /// The evaluations w_i(next(zeroPoint)) of the witnesses read at the next
+    /// row, as `(1 - z_{n-1}) * w_i(p_0) + z_{n-1} * w_i(p_1)`.
+    function nextRowEvals(Verifier memory v, uint256[] calldata proof) internal pure returns (uint256[] memory wn) {
+        wn = new uint256[](NUM_WITNESSES);
+        bytes memory columns = NEXT_ROW_WITNESSES;
+        uint256 z = v.zeroPoint[NUM_VARS - 1];
+        for (uint256 c = 0; c < columns.length; c++) {
+            uint256 base = NEXT_ROW_INSERTIONS + 2 * c;
+            wn[uint8(columns[c])] = addmod(
+                mulmod(addmod(1, R_MOD - z, R_MOD), evaluation(v, proof, base), R_MOD),
+                mulmod(z, evaluation(v, proof, base + 1), R_MOD),
+                R_MOD
+            );
+        }
+    }
+
+    /// The gate evaluated on the selectors `s`, the witnesses `w` and the
+    /// witnesses at the next row `wn`.
+    function gate(uint256[] memory s, uint256[] memory w, uint256[] memory wn) internal pure returns (uint256 res) {
+{{GATE}}
+    }
+
+    /// Check the permutation check subclaim
+    /// `prod - p1 * p2 + alpha * (frac * g_1 ... g_k - f_1 ... f_k)` and the
+    /// final query `prod(0, 1, ..., 1) = 1`.
+    function checkPerm(Verifier memory v, uint256[] calldata proof) internal pure returns (bool) {
+        (uint256 fProd, uint256 gProd) = permProducts(v, proof);
+        uint256 p = mulmod(permP(v, proof, 1), permP(v, proof, 2), R_MOD);
+        uint256 res = addmod(mulmod(evaluation(v, proof, FRAC_INSERTIONS), gProd, R_MOD), R_MOD - fProd, R_MOD);
+        res = addmod(
+            addmod(evaluation(v, proof, PROD_INSERTIONS), R_MOD - p, R_MOD), mulmod(v.alpha, res, R_MOD), R_MOD
+        );
+        return mulmod(res, v.permEq, R_MOD) == v.permEval && evaluation(v, proof, PROD_INSERTIONS + 3) == 1;
+    }
+
+    /// p_k = frac(x_k) + x1 * (prod(x_k) - frac(x_k)) for the points x_1 and
+    /// x_2 of the permutation check with a first coordinate 0 and 1.
+    function permP(Verifier memory v, uint256[] calldata proof, uint256 k) internal pure returns (uint256) {
+        uint256 frac = evaluation(v, proof, FRAC_INSERTIONS + k);
+        uint256 prod = evaluation(v, proof, PROD_INSERTIONS + k);
+        uint256 x1 = v.permPoint[NUM_VARS - 1];
+        return addmod(frac, mulmod(x1, addmod(prod, R_MOD - frac, R_MOD), R_MOD), R_MOD);
+    }
+
+    /// The products `f_i = w_i + beta * id_i + gamma` and
+    /// `g_i = w_i + beta * perm_i + gamma` over the witnesses.
+    function permProducts(Verifier memory v, uint256[] calldata proof)
+        internal
+        pure
+        returns (uint256 fProd, uint256 gProd)
+    {
+        // id_i(x) = sum_j 2^j x_j + i * 2^NUM_VARS
+        uint256 id = 0;
+        for (uint256 j = NUM_VARS; j > 0; j--) {
+            id = addmod(addmod(id, id, R_MOD), v.permPoint[j - 1], R_MOD);
+        }
+        fProd = 1;
+        gProd = 1;
+        for (uint256 i = 0; i < NUM_WITNESSES; i++) {
+            uint256 wg = addmod(evaluation(v, proof, WITNESS_PERM_INSERTIONS + i), v.gamma, R_MOD);
+            uint256 idi = addmod(id, (i << NUM_VARS) % R_MOD, R_MOD);
+            fProd = mulmod(fProd, addmod(wg, mulmod(v.beta, idi, R_MOD), R_MOD), R_MOD);
+            uint256 perm = evaluation(v, proof, PERM_INSERTIONS + i);
+            gProd = mulmod(gProd, addmod(wg, mulmod(v.beta, perm, R_MOD), R_MOD), R_MOD);
+        }
+    }
+
+    /// Check the opening of w_0 at (rPi, 0, ..., 0) against the multilinear
+    /// extension of the public inputs.
+    function checkPublicInputs(Verifier memory v, uint256[] calldata publicInputs, uint256[] calldata proof)
+        internal
+        pure
+        returns (bool)
+    {
+        return evaluateMle(publicInputs, v.rPi) == evaluation(v, proof, PI_INSERTION);
+    }
+
+    /// Evaluate at `r` the multilinear extension of `evals`, whose first
+    /// variable is the lowest bit of the index.
+    function evaluateMle(uint256[] memory evals, uint256[] memory r) internal pure returns (uint256) {
+        uint256 len = evals.length;
+        for (uint256 j = 0; j < r.length; j++) {
+            len >>= 1;
+            for (uint256 i = 0; i < len; i++) {
+                uint256 lo = evals[2 * i];
+                evals[i] = addmod(lo, mulmod(r[j], addmod(evals[2 * i + 1], R_MOD - lo, R_MOD), R_MOD), R_MOD);
+            }
+        }
+        return evals[0];
+    }
+
+    // =======================================================================
+    // batch opening
+    // =======================================================================
+
+    /// Reduce the openings to an opening of g' = sum_i eq(t, i) * eq(a, p_i)
+    /// * f_i at the point a of a sum check, and check it with a pairing.
+    function checkOpenings(Verifier memory v, uint256[] calldata proof) internal view returns (bool) {
+        (uint256[] memory eqT, uint256 sum) = batchSum(v, proof);
+        (uint256[] memory a, uint256 gEval, bool ok) = sumCheck(v, proof, BATCH_OFFSET, 2, sum);
+        if (!ok) {
+            return false;
+        }
+        return checkPairing(v, proof, a, gEval, mergeScalars(v, a, eqT));
+    }
+
+    /// The coefficients eq(t, i) of the openings for a challenge t, and the
+    /// sum of the evaluations with these coefficients.
+    function batchSum(Verifier memory v, uint256[] calldata proof)
+        internal
+        pure
+        returns (uint256[] memory eqT, uint256 sum)
+    {
+        uint256 n = v.numOpenings;
+        uint256 ell = 0;
+        while ((1 << ell) < n) {
+            ell++;
+        }
+        uint256[] memory t = challenges(v, ell);
+        eqT = new uint256[](n);
+        for (uint256 i = 0; i < n; i++) {
+            eqT[i] = 1;
+            for (uint256 j = 0; j < ell; j++) {
+                eqT[i] = mulmod(eqT[i], ((i >> j) & 1) == 1 ? t[j] : addmod(1, R_MOD - t[j], R_MOD), R_MOD);
+            }
+            sum = addmod(sum, mulmod(eqT[i], proof[EVALS_OFFSET + i], R_MOD), R_MOD);
+        }
+    }
+
+    /// The scalars eq(t, i) * eq(a, p_i) of g', merged by commitment.
+    function mergeScalars(Verifier memory v, uint256[] memory a, uint256[] memory eqT)
+        internal
+        pure
+        returns (uint256[] memory scalars)
+    {
+        bytes memory points = POINTS;
+        scalars = new uint256[](NUM_SLOTS);
+        for (uint256 i = 0; i < v.numOpenings; i++) {
+            uint256 slot = v.openingSlot[i];
+            uint256 scalar = mulmod(eqPoint(v, points, a, v.openingPoint[i]), eqT[i], R_MOD);
+            scalars[slot] = addmod(scalars[slot], scalar, R_MOD);
+        }
+    }
+
+    /// Check e(g^gEval / g' / prod_j pi_j^{a_j}, h) * prod_j e(pi_j, h^{t_j}) = 1
+    /// for the quotients pi_j of the opening of g' at a.
+    function checkPairing(
+        Verifier memory v,
+        uint256[] calldata proof,
+        uint256[] memory a,
+        uint256 gEval,
+        uint256[] memory scalars
+    ) internal view returns (bool) {
+        uint256[2] memory acc;
+        if (!ecMul(acc, G_X, G_Y, gEval) || !subCommitments(acc, v, scalars) || !subQuotients(acc, proof, a)) {
+            return false;
+        }
+
+        uint256[] memory input = new uint256[](6 * (NUM_VARS + 1));
+        input[0] = acc[0];
+        input[1] = acc[1];
+        input[2] = H_X_1;
+        input[3] = H_X_0;
+        input[4] = H_Y_1;
+        input[5] = H_Y_0;
+        for (uint256 j = 0; j < NUM_VARS; j++) {
+            input[6 * (j + 1)] = proof[QUOTIENT_OFFSET + 2 * j];
+            input[6 * (j + 1) + 1] = proof[QUOTIENT_OFFSET + 2 * j + 1];
+        }
+        hMask(input);
+        uint256[1] memory out;
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 0x08, add(input, 0x20), mul(mload(input), 0x20), out, 0x20)
+        }
+        return ok && out[0] == 1;
+    }
+
+    /// acc -= sum_s scalars[s] * C_s over the distinct commitments C_s.
+    function subCommitments(uint256[2] memory acc, Verifier memory v, uint256[] memory scalars)
+        internal
+        view
+        returns (bool)
+    {
+        for (uint256 s = 0; s < NUM_SLOTS; s++) {
+            if (v.canon[s] == s && scalars[s] != 0) {
+                if (!ecMulAdd(acc, v.slots[2 * s], v.slots[2 * s + 1], R_MOD - scalars[s])) {
+                    return false;
+                }
+            }
+        }
+        return true;
+    }
+
+    /// acc -= sum_j a_j * pi_j over the quotients pi_j.
+    function subQuotients(uint256[2] memory acc, uint256[] calldata proof, uint256[] memory a)
+        internal
+        view
+        returns (bool)
+    {
+        for (uint256 j = 0; j < NUM_VARS; j++) {
+            uint256 q = QUOTIENT_OFFSET + 2 * j;
+            if (!ecMulAdd(acc, proof[q], proof[q + 1], R_MOD - a[j])) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    /// Write the elements h^{t_j} of the verifying key paired with the
+    /// quotients into the pairing input.
+    function hMask(uint256[] memory input) internal pure {
+{{H_MASK}}
+    }
+
+    /// acc = s * (x, y)
+    function ecMul(uint256[2] memory acc, uint256 x, uint256 y, uint256 s) internal view returns (bool ok) {
+        uint256[3] memory input;
+        input[0] = x;
+        input[1] = y;
+        input[2] = s;
+        assembly {
+            ok := staticcall(gas(), 0x07, input, 0x60, acc, 0x40)
+        }
+    }
+
+    /// acc += s * (x, y)
+    function ecMulAdd(uint256[2] memory acc, uint256 x, uint256 y, uint256 s) internal view returns (bool ok) {
+        uint256[4] memory input;
+        input[0] = x;
+        input[1] = y;
+        input[2] = s;
+        assembly {
+            ok := staticcall(gas(), 0x07, input, 0x60, input, 0x40)
+        }
+        if (!ok) {
+            return false;
+        }
+        input[2] = acc[0];
+        input[3] = acc[1];
+        assembly {
+            ok := staticcall(gas(), 0x06, input, 0x80, acc, 0x40)
+        }
+    }
+
+    // =======================================================================
+    // field and transcript
+    // =======================================================================
+
+    /// x * y + (1 - x) * (1 - y)
+    function eqTerm(uint256 x, uint256 y) internal pure returns (uint256) {
+        uint256 xy = mulmod(x, y, R_MOD);
+        return addmod(addmod(xy, xy, R_MOD), addmod(R_MOD - x, R_MOD + 1 - y, R_MOD), R_MOD);
+    }
+
+    /// eq(x, y) = prod_i (x_i * y_i + (1 - x_i) * (1 - y_i))
+    function eq(uint256[] memory x, uint256[] memory y) internal pure returns (uint256 res) {
+        res = 1;
+        for (uint256 i = 0; i < x.length; i++) {
+            res = mulmod(res, eqTerm(x[i], y[i]), R_MOD);
+        }
+    }
+
+    /// The challenge `state || keccak256(state)` read in little endian mod r,
+    /// which is then appended in big endian.
+    function challenge(Verifier memory v) internal pure returns (uint256 c) {
+        uint256 hi = reverse(uint256(keccak256(abi.encodePacked(v.state))));
+        c = addmod(mulmod(hi, R_2_256, R_MOD), reverse(uint256(v.state)), R_MOD);
+        v.state = keccak256(abi.encodePacked(v.state, c));
+    }
+
+    function challenges(Verifier memory v, uint256 n) internal pure returns (uint256[] memory cs) {
+        cs = new uint256[](n);
+        for (uint256 i = 0; i < n; i++) {
+            cs[i] = challenge(v);
+        }
+    }
+
+    /// Append a point of G1 in the uncompressed serialization of arkworks:
+    /// the coordinates in little endian, with flags in the top bits of the
+    /// last byte for a negative y and for the point at infinity.
+    function appendG1(Verifier memory v, uint256 x, uint256 y) internal pure {
+        uint256 yLe;
+        if (x == 0 && y == 0) {
+            yLe = 0x40;
+        } else {
+            yLe = reverse(y);
+            if (y > Q_HALF) {
+                yLe |= 0x80;
+            }
+        }
+        v.state = keccak256(abi.encodePacked(v.state, reverse(x), yLe));
+    }
+
+    /// Append the round message `proof[start..start + len]` of a sum check,
+    /// serialized as its u64 length followed by its elements in little
+    /// endian.
+    function appendMessage(Verifier memory v, uint256[] calldata proof, uint256 start, uint256 len) internal pure {
+        bytes memory buf = new bytes(40 + 32 * len);
+        bytes32 state = v.state;
+        assembly {
+            mstore(add(buf, 0x20), state)
+            mstore(add(buf, 0x40), shl(248, len))
+        }
+        for (uint256 i = 0; i < len; i++) {
+            uint256 word = reverse(proof[start + i]);
+            assembly {
+                mstore(add(buf, add(0x48, mul(i, 0x20))), word)
+            }
+        }
+        v.state = keccak256(buf);
+    }
+
+    /// Reverse the bytes of a word.
+    function reverse(uint256 x) internal pure returns (uint256) {
+        x = ((x & MASK_8) >> 8) | ((x << 8) & MASK_8);
+        x = ((x & MASK_16) >> 16) | ((x << 16) & MASK_16);
+        x = ((x & MASK_32) >> 32) | ((x << 32) & MASK_32);
+        x = ((x & MASK_64) >> 64) | ((x << 64) & MASK_64);
+        return (x >> 128) | (x << 128);
+    }
+}
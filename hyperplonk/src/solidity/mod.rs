@@ -0,0 +1,742 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Solidity verifier of HyperPlonk proofs over BN254.
+//!
+//! [`gen_verifier`] generates, from a verifying key of the multilinear KZG
+//! scheme, a contract whose `verify(uint256[] publicInputs, uint256[] proof)`
+//! replays the verifier of the SNARK on the EVM:
+//! - the Keccak transcript, byte for byte
+//! - the sum checks of the zero check of the gate and of the permutation
+//!   check
+//! - the evaluation of the gate and of the permutation check polynomial on
+//!   the openings, and of the public input
+//! - the batch opening of the commitments, with the precompiles of BN254
+//!
+//! It accepts the proofs of `PolyIOP<Fr, KeccakTranscript<Fr>>` for a single
+//! instance, encoded by [`encode_calldata`].
+//!
+//! The proof is a list of words:
+//! - the witness commitments, as `(x, y)` pairs
+//! - the rounds of the zero check
+//! - the commitments of `prod(x)` and `frac(x)`
+//! - the rounds of the zero check of the permutation check
+//! - the rounds of the sum check of the batch opening
+//! - the quotients of the opening of g'
+//! - the evaluations of the batch opening
+//!
+//! The openings are deduplicated at run time as in the native verifier, so
+//! the number of evaluations depends on which commitments of the proof
+//! coincide with each other or with the verifying key.
+
+use crate::{
+    custom_gate::{CustomizedGateSet, Row},
+    errors::HyperPlonkErrors,
+    structs::{HyperPlonkProof, HyperPlonkVerifyingKey},
+};
+use arithmetic::next_row_polynomial;
+use ark_bn254::{Bn254, Fq, Fr, G1Affine};
+use ark_ff::{BigInteger, Field, One, PrimeField, Zero};
+use ark_std::log2;
+use subroutines::{
+    pcs::prelude::{Commitment, MultilinearKzgPCS},
+    poly_iop::{prelude::IOPProof, PolyIOP},
+};
+use transcript::{keccak256, KeccakTranscript};
+
+/// The contract, with placeholders for the parts generated from the
+/// verifying key.
+const TEMPLATE: &str = include_str!("HyperPlonkVerifier.sol");
+
+/// A coordinate of an opening point, as known when generating the verifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Coordinate {
+    Zero,
+    One,
+    /// a coordinate of the zero check point
+    ZeroCheck(usize),
+    /// one minus a coordinate of the zero check point
+    ZeroCheckComplement(usize),
+    /// a coordinate of the permutation check point
+    PermCheck(usize),
+    /// a coordinate of the public input point
+    PublicInput(usize),
+}
+
+impl Coordinate {
+    /// The two bytes decoded by `eqPoint` in the contract.
+    fn code(&self) -> Result<[u8; 2], HyperPlonkErrors> {
+        Ok(match *self {
+            Self::Zero => [0, 0],
+            Self::One => [1, 0],
+            Self::ZeroCheck(i) => [2, to_byte(i)?],
+            Self::PermCheck(i) => [3, to_byte(i)?],
+            Self::PublicInput(i) => [4, to_byte(i)?],
+            Self::ZeroCheckComplement(i) => [5, to_byte(i)?],
+        })
+    }
+}
+
+/// The openings of the verifier, inserted in the same order as in
+/// `verify_iop`, as a commitment slot and a point each. The points are
+/// deduplicated as they are known in advance, while the commitments of the
+/// proof are only compared by the contract.
+#[derive(Default)]
+struct Openings {
+    insertions: Vec<(usize, usize)>,
+    points: Vec<Vec<Coordinate>>,
+}
+
+impl Openings {
+    fn insert(&mut self, slot: usize, point: &[Coordinate]) {
+        let point_id = match self.points.iter().position(|p| p == point) {
+            Some(i) => i,
+            None => {
+                self.points.push(point.to_vec());
+                self.points.len() - 1
+            },
+        };
+        self.insertions.push((slot, point_id));
+    }
+
+    fn len(&self) -> usize {
+        self.insertions.len()
+    }
+}
+
+/// Generate the Solidity verifier of the proofs for the verifying key
+/// `vk`, whose circuit is given by `vk.params.gate_func`.
+pub fn gen_verifier(
+    vk: &HyperPlonkVerifyingKey<Bn254, MultilinearKzgPCS<Bn254>>,
+) -> Result<String, HyperPlonkErrors> {
+    let params = &vk.params;
+    params.gate_func.validate()?;
+    let num_vars = params.num_variables();
+    let num_witnesses = params.num_witness_columns();
+    let num_selectors = params.num_selector_columns();
+    let gate_degree = params.gate_func.degree();
+    let next_row_witnesses = params.gate_func.next_row_witnesses();
+    if num_vars == 0 || num_vars > vk.pcs_param.num_vars {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "number of variables ({}) is not supported by the verifier param ({})",
+            num_vars, vk.pcs_param.num_vars
+        )));
+    }
+    if !params.num_pub_input.is_power_of_two() || params.num_pub_input > params.num_constraints {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "public input length ({}) is not a power of two of at most {}",
+            params.num_pub_input, params.num_constraints
+        )));
+    }
+    if vk.perm_commitments.len() != num_witnesses || vk.selector_commitments.len() != num_selectors
+    {
+        return Err(HyperPlonkErrors::InvalidParameters(
+            "number of commitments does not match the gate".to_string(),
+        ));
+    }
+    let ell = log2(params.num_pub_input) as usize;
+
+    // the commitment slots: prod(x), frac(x), the permutations, the
+    // witnesses and the selectors
+    let perm_slots = 2;
+    let witness_slots = perm_slots + num_witnesses;
+    let selector_slots = witness_slots + num_witnesses;
+    let num_slots = selector_slots + num_selectors;
+    // the byte codes of the contract only index up to 256 slots and points
+    if num_slots > 256 || num_vars >= 256 {
+        return Err(HyperPlonkErrors::InvalidParameters(
+            "circuit is too large for the Solidity verifier".to_string(),
+        ));
+    }
+
+    // the openings, as in `verify_iop`
+    let zero_check_point: Vec<_> = (0..num_vars).map(Coordinate::ZeroCheck).collect();
+    let perm_check_point: Vec<_> = (0..num_vars).map(Coordinate::PermCheck).collect();
+    let perm_check_point_0 = [&[Coordinate::Zero], &perm_check_point[..num_vars - 1]].concat();
+    let perm_check_point_1 = [&[Coordinate::One], &perm_check_point[..num_vars - 1]].concat();
+    let prod_final_query_point =
+        [vec![Coordinate::Zero], vec![Coordinate::One; num_vars - 1]].concat();
+    let mut openings = Openings::default();
+
+    let prod_insertions = openings.len();
+    for point in [
+        &perm_check_point,
+        &perm_check_point_0,
+        &perm_check_point_1,
+        &prod_final_query_point,
+    ] {
+        openings.insert(0, point);
+    }
+    let frac_insertions = openings.len();
+    for point in [&perm_check_point, &perm_check_point_0, &perm_check_point_1] {
+        openings.insert(1, point);
+    }
+    let perm_insertions = openings.len();
+    for i in 0..num_witnesses {
+        openings.insert(perm_slots + i, &perm_check_point);
+    }
+    let witness_perm_insertions = openings.len();
+    for i in 0..num_witnesses {
+        openings.insert(witness_slots + i, &perm_check_point);
+    }
+    let witness_gate_insertions = openings.len();
+    for i in 0..num_witnesses {
+        openings.insert(witness_slots + i, &zero_check_point);
+    }
+    let selector_insertions = openings.len();
+    for i in 0..num_selectors {
+        openings.insert(selector_slots + i, &zero_check_point);
+    }
+    let next_row_insertions = openings.len();
+    for &i in next_row_witnesses.iter() {
+        for point in next_row_points(num_vars) {
+            openings.insert(witness_slots + i, &point);
+        }
+    }
+    let pi_insertion = openings.len();
+    let r_pi_padded: Vec<_> = (0..num_vars)
+        .map(|i| match i < ell {
+            true => Coordinate::PublicInput(i),
+            false => Coordinate::Zero,
+        })
+        .collect();
+    openings.insert(witness_slots, &r_pi_padded);
+    if openings.points.len() > 256 {
+        return Err(HyperPlonkErrors::InvalidParameters(
+            "circuit is too large for the Solidity verifier".to_string(),
+        ));
+    }
+
+    // the positions in the proof
+    let zero_check_offset = 2 * num_witnesses;
+    let prod_offset = zero_check_offset + num_vars * (gate_degree + 2);
+    let frac_offset = prod_offset + 2;
+    let perm_check_offset = frac_offset + 2;
+    let batch_offset = perm_check_offset + num_vars * (num_witnesses + 3);
+    let quotient_offset = batch_offset + num_vars * 3;
+    let evals_offset = quotient_offset + 2 * num_vars;
+
+    let g = g1_words(&vk.pcs_param.g);
+    let h = &vk.pcs_param.h;
+    let constants = [
+        ("NUM_VARS", num_vars.to_string()),
+        ("NUM_WITNESSES", num_witnesses.to_string()),
+        ("NUM_SELECTORS", num_selectors.to_string()),
+        ("NUM_PUB_INPUTS", params.num_pub_input.to_string()),
+        ("LOG_NUM_PUB_INPUTS", ell.to_string()),
+        ("GATE_DEGREE", gate_degree.to_string()),
+        ("NUM_SLOTS", num_slots.to_string()),
+        ("WITNESS_SLOTS", witness_slots.to_string()),
+        ("ZERO_CHECK_OFFSET", zero_check_offset.to_string()),
+        ("PROD_OFFSET", prod_offset.to_string()),
+        ("FRAC_OFFSET", frac_offset.to_string()),
+        ("PERM_CHECK_OFFSET", perm_check_offset.to_string()),
+        ("BATCH_OFFSET", batch_offset.to_string()),
+        ("QUOTIENT_OFFSET", quotient_offset.to_string()),
+        ("EVALS_OFFSET", evals_offset.to_string()),
+        ("NUM_INSERTIONS", openings.len().to_string()),
+        ("PROD_INSERTIONS", prod_insertions.to_string()),
+        ("FRAC_INSERTIONS", frac_insertions.to_string()),
+        ("PERM_INSERTIONS", perm_insertions.to_string()),
+        (
+            "WITNESS_PERM_INSERTIONS",
+            witness_perm_insertions.to_string(),
+        ),
+        (
+            "WITNESS_GATE_INSERTIONS",
+            witness_gate_insertions.to_string(),
+        ),
+        ("SELECTOR_INSERTIONS", selector_insertions.to_string()),
+        ("NEXT_ROW_INSERTIONS", next_row_insertions.to_string()),
+        ("PI_INSERTION", pi_insertion.to_string()),
+        ("G_X", to_hex(&g[0])),
+        ("G_Y", to_hex(&g[1])),
+        ("H_X_0", to_hex(&h.x.c0)),
+        ("H_X_1", to_hex(&h.x.c1)),
+        ("H_Y_0", to_hex(&h.y.c0)),
+        ("H_Y_1", to_hex(&h.y.c1)),
+    ];
+    let mut constants: String = constants
+        .into_iter()
+        .map(|(name, value)| format!("    uint256 internal constant {} = {};\n", name, value))
+        .collect();
+    let insertions: Vec<u8> = openings
+        .insertions
+        .iter()
+        .map(|&(slot, point_id)| Ok([to_byte(slot)?, to_byte(point_id)?]))
+        .collect::<Result<Vec<_>, HyperPlonkErrors>>()?
+        .concat();
+    let points: Vec<u8> = openings
+        .points
+        .iter()
+        .flatten()
+        .map(Coordinate::code)
+        .collect::<Result<Vec<_>, _>>()?
+        .concat();
+    let next_row_witnesses: Vec<u8> = next_row_witnesses
+        .iter()
+        .map(|&i| to_byte(i))
+        .collect::<Result<_, _>>()?;
+    for (name, bytes) in [
+        ("INSERTIONS", insertions),
+        ("POINTS", points),
+        ("NEXT_ROW_WITNESSES", next_row_witnesses),
+    ] {
+        constants += &format!(
+            "    bytes internal constant {} = hex\"{}\";\n",
+            name,
+            bytes_to_hex(&bytes)
+        );
+    }
+
+    constants += "\n";
+
+    let mut vk_commitments = String::new();
+    for (slots, commitments) in [
+        (perm_slots, &vk.perm_commitments),
+        (selector_slots, &vk.selector_commitments),
+    ] {
+        for (i, commitment) in commitments.iter().enumerate() {
+            let [x, y] = g1_words(&commitment.0);
+            let slot = slots + i;
+            vk_commitments += &format!(
+                "        slots[{}] = {};\n        slots[{}] = {};\n",
+                2 * slot,
+                to_hex(&x),
+                2 * slot + 1,
+                to_hex(&y)
+            );
+        }
+    }
+
+    // the quotients are paired with the last elements of h_mask
+    let ignored = vk.pcs_param.num_vars - num_vars;
+    let mut h_mask = String::new();
+    for (j, h) in vk.pcs_param.h_mask[ignored..].iter().enumerate() {
+        for (k, coordinate) in [h.x.c1, h.x.c0, h.y.c1, h.y.c0].iter().enumerate() {
+            h_mask += &format!(
+                "        input[{}] = {};\n",
+                6 * (j + 1) + 2 + k,
+                to_hex(coordinate)
+            );
+        }
+    }
+
+    let mut weights = String::new();
+    let mut degrees = vec![gate_degree + 1, num_witnesses + 2, 2];
+    degrees.sort_unstable();
+    degrees.dedup();
+    for degree in degrees {
+        weights += &format!("        if (degree == {}) {{\n", degree);
+        for (i, w) in barycentric_weights(degree).iter().enumerate() {
+            weights += &format!("            w[{}] = {};\n", i, to_hex(w));
+        }
+        weights += "            return w;\n        }\n";
+    }
+
+    Ok(TEMPLATE
+        .replace("{{CONSTANTS}}\n", &constants)
+        .replace("{{BARYCENTRIC_WEIGHTS}}\n", &weights)
+        .replace("{{VK_COMMITMENTS}}\n", &vk_commitments)
+        .replace("{{H_MASK}}\n", &h_mask)
+        .replace("{{GATE}}\n", &gen_gate(&params.gate_func)))
+}
+
+/// Encode the call of `verify` for a proof and its public input.
+pub fn encode_calldata(
+    pub_input: &[Fr],
+    proof: &HyperPlonkProof<Bn254, PolyIOP<Fr, KeccakTranscript<Fr>>, MultilinearKzgPCS<Bn254>>,
+) -> Vec<u8> {
+    let commitment_words =
+        |commitment: &Commitment<Bn254>| g1_words(&commitment.0).map(|x| to_word(&x));
+    let rounds_words = |sum_check_proof: &IOPProof<Fr>| {
+        sum_check_proof
+            .proofs
+            .iter()
+            .flat_map(|msg| msg.evaluations.iter().map(to_word))
+            .collect::<Vec<_>>()
+    };
+    let batch_openings = &proof.batch_openings;
+    let perm_check_proof = &proof.perm_check_proof;
+    let proof_words: Vec<_> = proof
+        .witness_commits
+        .iter()
+        .flat_map(commitment_words)
+        .chain(rounds_words(&proof.zero_check_proof))
+        .chain(commitment_words(&perm_check_proof.prod_x_comm))
+        .chain(commitment_words(&perm_check_proof.frac_comm))
+        .chain(rounds_words(&perm_check_proof.zero_check_proof))
+        .chain(rounds_words(&batch_openings.sum_check_proof))
+        .chain(
+            batch_openings
+                .g_prime_proof
+                .proofs
+                .iter()
+                .flat_map(|pi| g1_words(pi).map(|x| to_word(&x))),
+        )
+        .chain(batch_openings.f_i_eval_at_point_i.iter().map(to_word))
+        .collect();
+
+    // the selector, the offsets of the two arrays, then the arrays with
+    // their lengths
+    let mut calldata = keccak256(b"verify(uint256[],uint256[])")[..4].to_vec();
+    let pub_input_offset = 64;
+    let proof_offset = pub_input_offset + 32 * (pub_input.len() + 1);
+    for word in [
+        to_word(&Fr::from(pub_input_offset as u64)),
+        to_word(&Fr::from(proof_offset as u64)),
+        to_word(&Fr::from(pub_input.len() as u64)),
+    ]
+    .into_iter()
+    .chain(pub_input.iter().map(to_word))
+    .chain([to_word(&Fr::from(proof_words.len() as u64))])
+    .chain(proof_words)
+    {
+        calldata.extend(word);
+    }
+    calldata
+}
+
+/// The points `(0, z_0, ..., z_{n-2})` and `(1, z'_0, ..., z'_{n-2})` of
+/// `next_row_points` for the zero check point `z`.
+fn next_row_points(num_vars: usize) -> Vec<Vec<Coordinate>> {
+    let low_terms = next_row_polynomial(num_vars);
+    let p0 = (0..num_vars)
+        .map(|i| match i {
+            0 => Coordinate::Zero,
+            _ => Coordinate::ZeroCheck(i - 1),
+        })
+        .collect();
+    let p1 = (0..num_vars)
+        .map(|i| match (i, (low_terms >> i) & 1) {
+            (0, _) => Coordinate::One,
+            (_, 1) => Coordinate::ZeroCheckComplement(i - 1),
+            (_, _) => Coordinate::ZeroCheck(i - 1),
+        })
+        .collect();
+    vec![p0, p1]
+}
+
+/// The body of the `gate` function of the contract, which evaluates the
+/// gate as `eval_f`.
+fn gen_gate(gates: &CustomizedGateSet) -> String {
+    let mut res = "        uint256 g;\n".to_string();
+    for (activation, gate) in gates.gates.iter() {
+        res += "        g = 0;\n";
+        for (coeff, selector, witnesses) in gate.gates.iter() {
+            let coeff = if *coeff < 0 {
+                -Fr::from(coeff.unsigned_abs())
+            } else {
+                Fr::from(*coeff as u64)
+            };
+            let mut factors: Vec<String> = selector.iter().map(|s| format!("s[{}]", s)).collect();
+            for witness in witnesses.iter() {
+                factors.push(match witness.row {
+                    Row::Current => format!("w[{}]", witness.column),
+                    Row::Next => format!("wn[{}]", witness.column),
+                });
+            }
+            if !coeff.is_one() || factors.is_empty() {
+                factors.insert(0, to_hex(&coeff));
+            }
+            let monomial = factors
+                .into_iter()
+                .reduce(|acc, factor| format!("mulmod({}, {}, R_MOD)", acc, factor))
+                .unwrap_or_default();
+            res += &format!("        g = addmod(g, {}, R_MOD);\n", monomial);
+        }
+        res += &match activation {
+            Some(a) => format!(
+                "        res = addmod(res, mulmod(s[{}], g, R_MOD), R_MOD);\n",
+                a
+            ),
+            None => "        res = addmod(res, g, R_MOD);\n".to_string(),
+        };
+    }
+    res
+}
+
+/// The weights `1 / prod_{j != i} (i - j)` of the interpolation at
+/// `0, ..., degree`.
+fn barycentric_weights(degree: usize) -> Vec<Fr> {
+    (0..=degree)
+        .map(|i| {
+            let prod: Fr = (0..=degree)
+                .filter(|&j| j != i)
+                .map(|j| Fr::from(i as u64) - Fr::from(j as u64))
+                .product();
+            prod.inverse().unwrap()
+        })
+        .collect()
+}
+
+/// The coordinates of a point of G1 for the precompiles, where the point at
+/// infinity is `(0, 0)`.
+fn g1_words(p: &G1Affine) -> [Fq; 2] {
+    match p.infinity {
+        true => [Fq::zero(), Fq::zero()],
+        false => [p.x, p.y],
+    }
+}
+
+/// A field element as a big endian word.
+fn to_word<F: PrimeField>(x: &F) -> Vec<u8> {
+    let bytes = x.into_bigint().to_bytes_be();
+    [vec![0; 32 - bytes.len()], bytes].concat()
+}
+
+/// A field element as a Solidity hex literal.
+fn to_hex<F: PrimeField>(x: &F) -> String {
+    format!("0x{}", bytes_to_hex(&to_word(x)))
+}
+
+/// An entry of the byte tables of the contract, which only index up to 256
+/// slots, points, coordinates or witnesses.
+fn to_byte(x: usize) -> Result<u8, HyperPlonkErrors> {
+    u8::try_from(x).map_err(|_| {
+        HyperPlonkErrors::InvalidParameters(format!(
+            "index {} does not fit in a byte of the verifier tables",
+            x
+        ))
+    })
+}
+
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        custom_gate::CustomizedGates,
+        mock::MockCircuit,
+        selectors::SelectorColumn,
+        structs::{HyperPlonkIndex, HyperPlonkParams},
+        witness::WitnessColumn,
+        HyperPlonkSNARK,
+    };
+    use arithmetic::{identity_permutation, next_row};
+    use ark_std::{test_rng, UniformRand};
+    use revm::{
+        primitives::{Address, Bytes, ExecutionResult, Output, TxKind},
+        Evm, InMemoryDB,
+    };
+    use std::{
+        io::Write,
+        path::PathBuf,
+        process::{Command, Stdio},
+    };
+    use subroutines::pcs::{prelude::MultilinearUniversalParams, PolynomialCommitmentScheme};
+
+    type Kzg = MultilinearKzgPCS<Bn254>;
+    type KeccakIOP = PolyIOP<Fr, KeccakTranscript<Fr>>;
+
+    const SUPPORTED_SIZE: usize = 6;
+
+    /// The version of solc the verifier is tested with.
+    const SOLC_VERSION: &str = "0.8.26";
+
+    /// The path of solc `SOLC_VERSION`, which is downloaded by svm on the
+    /// first run.
+    fn solc() -> PathBuf {
+        let path = svm_lib::version_path(SOLC_VERSION).join(format!("solc-{}", SOLC_VERSION));
+        if path.exists() {
+            return path;
+        }
+        let version = semver::Version::parse(SOLC_VERSION).unwrap();
+        svm_lib::blocking_install(&version).expect("failed to install solc")
+    }
+
+    /// Compile the contract with the pinned solc.
+    fn compile(source: &str) -> Vec<u8> {
+        let mut solc = Command::new(solc())
+            .args(["--bin", "--optimize", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        solc.stdin
+            .take()
+            .unwrap()
+            .write_all(source.as_bytes())
+            .unwrap();
+        let output = solc.wait_with_output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let bytecode = stdout
+            .lines()
+            .skip_while(|line| !line.starts_with("Binary"))
+            .nth(1)
+            .expect("no bytecode in the output of solc");
+        (0..bytecode.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&bytecode[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn deploy(evm: &mut Evm<(), InMemoryDB>, bytecode: Vec<u8>) -> Address {
+        evm.tx_mut().transact_to = TxKind::Create;
+        evm.tx_mut().data = Bytes::from(bytecode);
+        match evm.transact_commit().unwrap() {
+            ExecutionResult::Success {
+                output: Output::Create(_, Some(address)),
+                ..
+            } => address,
+            result => panic!("deployment failed: {:?}", result),
+        }
+    }
+
+    fn call_verify(evm: &mut Evm<(), InMemoryDB>, address: Address, calldata: Vec<u8>) -> bool {
+        evm.tx_mut().transact_to = TxKind::Call(address);
+        evm.tx_mut().data = Bytes::from(calldata);
+        match evm.transact_commit().unwrap() {
+            ExecutionResult::Success {
+                output: Output::Call(output),
+                ..
+            } => output.len() == 32 && output[31] == 1,
+            result => panic!("call failed: {:?}", result),
+        }
+    }
+
+    fn test_solidity_verifier_helper(
+        circuit: MockCircuit<Fr>,
+        pcs_srs: &MultilinearUniversalParams<Bn254>,
+    ) -> Result<(), HyperPlonkErrors> {
+        let (pk, vk) =
+            <KeccakIOP as HyperPlonkSNARK<Bn254, Kzg>>::preprocess(&circuit.index, pcs_srs)?;
+        let proof = <KeccakIOP as HyperPlonkSNARK<Bn254, Kzg>>::prove(
+            &pk,
+            &circuit.public_inputs,
+            &circuit.witnesses,
+        )?;
+        assert!(<KeccakIOP as HyperPlonkSNARK<Bn254, Kzg>>::verify(
+            &vk,
+            &circuit.public_inputs,
+            &proof,
+        )?);
+
+        let mut evm = Evm::builder().with_db(InMemoryDB::default()).build();
+        let address = deploy(&mut evm, compile(&gen_verifier(&vk)?));
+        let calldata = encode_calldata(&circuit.public_inputs, &proof);
+        assert!(call_verify(&mut evm, address, calldata.clone()));
+
+        // a wrong public input is rejected
+        let mut bad_public_inputs = circuit.public_inputs.clone();
+        bad_public_inputs[0] += Fr::one();
+        let bad_calldata = encode_calldata(&bad_public_inputs, &proof);
+        assert!(!call_verify(&mut evm, address, bad_calldata));
+
+        // a wrong evaluation, the last word of the proof, is rejected
+        let mut bad_calldata = calldata.clone();
+        *bad_calldata.last_mut().unwrap() ^= 1;
+        assert!(!call_verify(&mut evm, address, bad_calldata));
+
+        // a missing evaluation is rejected
+        let mut bad_calldata = calldata;
+        bad_calldata.truncate(bad_calldata.len() - 32);
+        let len_position = 4 + 64 + 32 * (circuit.public_inputs.len() + 1);
+        let len = (bad_calldata.len() - len_position - 32) / 32;
+        bad_calldata[len_position..len_position + 32]
+            .copy_from_slice(&to_word(&Fr::from(len as u64)));
+        assert!(!call_verify(&mut evm, address, bad_calldata));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_row_points() {
+        let mut rng = test_rng();
+        for num_vars in 1..8 {
+            let z: Vec<Fr> = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+            let points: Vec<Vec<Fr>> = next_row_points(num_vars)
+                .iter()
+                .map(|point| {
+                    point
+                        .iter()
+                        .map(|coordinate| match *coordinate {
+                            Coordinate::Zero => Fr::zero(),
+                            Coordinate::One => Fr::one(),
+                            Coordinate::ZeroCheck(i) => z[i],
+                            Coordinate::ZeroCheckComplement(i) => Fr::one() - z[i],
+                            _ => unreachable!(),
+                        })
+                        .collect()
+                })
+                .collect();
+            let expected: Vec<Vec<Fr>> = arithmetic::next_row_points(&z, 0)
+                .into_iter()
+                .map(|(point, _coeff)| point)
+                .collect();
+            assert_eq!(points, expected);
+        }
+    }
+
+    #[test]
+    fn test_solidity_verifier() -> Result<(), HyperPlonkErrors> {
+        let mut rng = test_rng();
+        let pcs_srs = Kzg::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
+
+        for nv in [2, 4] {
+            let circuit = MockCircuit::<Fr>::new(1 << nv, &CustomizedGates::vanilla_plonk_gate());
+            test_solidity_verifier_helper(circuit, &pcs_srs)?;
+        }
+        let circuit =
+            MockCircuit::<Fr>::new(1 << 3, &CustomizedGates::jellyfish_turbo_plonk_gate());
+        test_solidity_verifier_helper(circuit, &pcs_srs)?;
+
+        // several gate types with activation selectors
+        let gate_set: CustomizedGateSet = "q2*(w2 - q0*w0 - q1*w1) \
+             + q5*(w0^5 + w3 - q3*w1 + q4)"
+            .parse()?;
+        let circuit = MockCircuit::<Fr>::new_with_gate_set(1 << 3, &gate_set);
+        test_solidity_verifier_helper(circuit, &pcs_srs)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solidity_verifier_next_row() -> Result<(), HyperPlonkErrors> {
+        let mut rng = test_rng();
+        let pcs_srs = Kzg::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
+
+        // running sum: w1(next(x)) = w1(x) + w0(x) along the cycle of the
+        // non-zero rows from row 1, but on its last row and on row 0
+        let gate_set: CustomizedGateSet = "q0*(w1' - w1 - w0)".parse()?;
+        let nv = 4;
+        let num_constraints = 1 << nv;
+        let w0: Vec<Fr> = (0..num_constraints).map(|_| Fr::rand(&mut rng)).collect();
+        let mut w1 = vec![Fr::zero(); num_constraints];
+        let mut q0 = vec![Fr::zero(); num_constraints];
+        let mut row = 1;
+        w1[row] = Fr::rand(&mut rng);
+        for _ in 0..num_constraints - 2 {
+            q0[row] = Fr::one();
+            w1[next_row(row, nv)] = w1[row] + w0[row];
+            row = next_row(row, nv);
+        }
+
+        let circuit = MockCircuit {
+            public_inputs: w0[..4].to_vec(),
+            witnesses: vec![WitnessColumn(w0), WitnessColumn(w1)],
+            index: HyperPlonkIndex {
+                params: HyperPlonkParams {
+                    num_constraints,
+                    num_pub_input: 4,
+                    gate_func: gate_set,
+                },
+                permutation: identity_permutation(nv + 1, 1),
+                selectors: vec![SelectorColumn(q0)],
+            },
+        };
+        test_solidity_verifier_helper(circuit, &pcs_srs)
+    }
+}
@@ -6,7 +6,11 @@
 
 //! Main module for the HyperPlonk PolyIOP.
 
-use crate::{custom_gate::CustomizedGates, prelude::HyperPlonkErrors, selectors::SelectorColumn};
+use crate::{
+    custom_gate::CustomizedGateSet, prelude::HyperPlonkErrors, selectors::SelectorColumn,
+    utils::eval_f, witness::WitnessColumn,
+};
+use arithmetic::next_row;
 use ark_ec::pairing::Pairing;
 use ark_ff::PrimeField;
 use ark_poly::DenseMultilinearExtension;
@@ -44,7 +48,7 @@ where
 /// The HyperPlonk instance parameters, consists of the following:
 ///   - the number of constraints
 ///   - number of public input columns
-///   - the customized gate function
+///   - the customized gate function, possibly mixing several gate types
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct HyperPlonkParams {
     /// the number of constraints
@@ -54,7 +58,7 @@ pub struct HyperPlonkParams {
     // this size must not exceed number of constraints.
     pub num_pub_input: usize,
     /// customized gate function
-    pub gate_func: CustomizedGates,
+    pub gate_func: CustomizedGateSet,
 }
 
 impl HyperPlonkParams {
@@ -73,6 +77,17 @@ impl HyperPlonkParams {
         self.gate_func.num_witness_columns()
     }
 
+    /// The parameters of the circuit that interleaves
+    /// `2^log_num_instances` instances of this circuit: row `x` of instance
+    /// `y` is row `x * 2^log_num_instances + y`.
+    pub fn stack(&self, log_num_instances: usize) -> Self {
+        Self {
+            num_constraints: self.num_constraints << log_num_instances,
+            num_pub_input: self.num_pub_input << log_num_instances,
+            gate_func: self.gate_func.clone(),
+        }
+    }
+
     /// evaluate the identical polynomial
     pub fn eval_id_oracle<F: PrimeField>(&self, point: &[F]) -> Result<F, HyperPlonkErrors> {
         let len = self.num_variables() + (log2(self.num_witness_columns()) as usize);
@@ -120,6 +135,155 @@ impl<F: PrimeField> HyperPlonkIndex<F> {
     pub fn num_witness_columns(&self) -> usize {
         self.params.num_witness_columns()
     }
+
+    /// Check a witness assignment against the index and list every
+    /// constraint it fails to satisfy.
+    ///
+    /// An empty list means the witness satisfies both the gate equation on
+    /// every row and all copy constraints. Malformed inputs (wrong number of
+    /// columns, wrong column lengths, an invalid gate or permutation) are
+    /// reported as errors instead.
+    pub fn check_witness(
+        &self,
+        witnesses: &[WitnessColumn<F>],
+    ) -> Result<Vec<ConstraintViolation>, HyperPlonkErrors> {
+        self.params.gate_func.validate()?;
+
+        let num_rows = self.params.num_constraints;
+        let num_witnesses = self.num_witness_columns();
+        if witnesses.len() != num_witnesses {
+            return Err(HyperPlonkErrors::InvalidParameters(format!(
+                "number of witness columns is not correct: got {}, expect {}",
+                witnesses.len(),
+                num_witnesses
+            )));
+        }
+        if self.selectors.len() != self.num_selector_columns() {
+            return Err(HyperPlonkErrors::InvalidParameters(format!(
+                "number of selector columns is not correct: got {}, expect {}",
+                self.selectors.len(),
+                self.num_selector_columns()
+            )));
+        }
+        for (i, w) in witnesses.iter().enumerate() {
+            if w.0.len() != num_rows {
+                return Err(HyperPlonkErrors::InvalidParameters(format!(
+                    "{}-th witness length is not correct: got {}, expect {}",
+                    i,
+                    w.0.len(),
+                    num_rows
+                )));
+            }
+        }
+        for (i, s) in self.selectors.iter().enumerate() {
+            if s.0.len() != num_rows {
+                return Err(HyperPlonkErrors::InvalidParameters(format!(
+                    "{}-th selector length is not correct: got {}, expect {}",
+                    i,
+                    s.0.len(),
+                    num_rows
+                )));
+            }
+        }
+        // the permutation may be padded to a power of two number of columns,
+        // only the first `num_witnesses` columns are used
+        let perm_len = num_witnesses * num_rows;
+        if self.permutation.len() < perm_len {
+            return Err(HyperPlonkErrors::InvalidParameters(format!(
+                "permutation length is too short: got {}, expect at least {}",
+                self.permutation.len(),
+                perm_len
+            )));
+        }
+
+        // the next row is only defined on a power of two number of rows
+        let num_vars = log2(num_rows) as usize;
+        let next_row_witnesses = self.params.gate_func.next_row_witnesses();
+        if !next_row_witnesses.is_empty() && num_rows != 1 << num_vars {
+            return Err(HyperPlonkErrors::InvalidParameters(format!(
+                "the gate reads the next row, but the number of rows ({}) is not a power of two",
+                num_rows
+            )));
+        }
+
+        let mut res = vec![];
+
+        // gate equation
+        let mut selector_evals = vec![F::zero(); self.selectors.len()];
+        let mut witness_evals = vec![F::zero(); num_witnesses];
+        let mut witness_next_row_evals = vec![F::zero(); num_witnesses];
+        for row in 0..num_rows {
+            for (eval, s) in selector_evals.iter_mut().zip(self.selectors.iter()) {
+                *eval = s.0[row];
+            }
+            for (eval, w) in witness_evals.iter_mut().zip(witnesses.iter()) {
+                *eval = w.0[row];
+            }
+            for &i in next_row_witnesses.iter() {
+                witness_next_row_evals[i] = witnesses[i].0[next_row(row, num_vars)];
+            }
+            let f_eval = eval_f(
+                &self.params.gate_func,
+                &selector_evals,
+                &witness_evals,
+                &witness_next_row_evals,
+            )?;
+            if !f_eval.is_zero() {
+                res.push(ConstraintViolation::Gate { row });
+            }
+        }
+
+        // copy constraints
+        for (i, sigma) in self.permutation[..perm_len].iter().enumerate() {
+            let target = match field_to_index(sigma) {
+                Some(t) if t < perm_len => t,
+                _ => {
+                    return Err(HyperPlonkErrors::InvalidParameters(format!(
+                        "permutation maps position {} to {}, which is not a witness position",
+                        i, sigma
+                    )))
+                },
+            };
+            let (column, row) = (i / num_rows, i % num_rows);
+            let (target_column, target_row) = (target / num_rows, target % num_rows);
+            if witnesses[column].0[row] != witnesses[target_column].0[target_row] {
+                res.push(ConstraintViolation::Permutation {
+                    column,
+                    row,
+                    target_column,
+                    target_row,
+                });
+            }
+        }
+
+        Ok(res)
+    }
+}
+
+/// A constraint violated by a witness assignment, as reported by
+/// [`HyperPlonkIndex::check_witness`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstraintViolation {
+    /// The gate equation does not evaluate to zero at `row`.
+    Gate { row: usize },
+    /// The permutation links `witness[column][row]` to
+    /// `witness[target_column][target_row]`, but the two values differ.
+    Permutation {
+        column: usize,
+        row: usize,
+        target_column: usize,
+        target_row: usize,
+    },
+}
+
+// Interpret a field element as a small integer index.
+fn field_to_index<F: PrimeField>(x: &F) -> Option<usize> {
+    let bigint = x.into_bigint();
+    let limbs = bigint.as_ref();
+    if limbs[1..].iter().any(|&l| l != 0) {
+        return None;
+    }
+    usize::try_from(limbs[0]).ok()
 }
 
 /// The HyperPlonk proving key, consists of the following:
@@ -139,8 +303,19 @@ pub struct HyperPlonkProvingKey<E: Pairing, PCS: PolynomialCommitmentScheme<E>>
     pub selector_commitments: Vec<PCS::Commitment>,
     /// Commitments to the preprocessed permutation polynomials
     pub permutation_commitments: Vec<PCS::Commitment>,
+    /// Commitments to the selector polynomials of a batch of `2^m`
+    /// instances, at index `m - 1`
+    pub batch_selector_commitments: Vec<Vec<PCS::Commitment>>,
+    /// Commitments to the permutation polynomials `perm(x)` replicated to a
+    /// batch of `2^m` instances, at index `m - 1`
+    pub batch_permutation_commitments: Vec<Vec<PCS::Commitment>>,
     /// The parameters for PCS commitment
     pub pcs_param: PCS::ProverParam,
+    /// Number of rounds of the zero check on the constraint polynomial whose
+    /// prover streams its evaluations, see `StreamingZeroCheck`. With 0, the
+    /// default, the in-memory prover runs all the rounds. The proof is the
+    /// same either way.
+    pub num_streamed_rounds: usize,
 }
 
 /// The HyperPlonk verifying key, consists of the following:
@@ -157,4 +332,10 @@ pub struct HyperPlonkVerifyingKey<E: Pairing, PCS: PolynomialCommitmentScheme<E>
     pub selector_commitments: Vec<PCS::Commitment>,
     /// Permutation oracles' commitments
     pub perm_commitments: Vec<PCS::Commitment>,
+    /// Commitments to the selector polynomials of a batch of `2^m`
+    /// instances, at index `m - 1`
+    pub batch_selector_commitments: Vec<Vec<PCS::Commitment>>,
+    /// Commitments to the permutation polynomials `perm(x)` replicated to a
+    /// batch of `2^m` instances, at index `m - 1`
+    pub batch_perm_commitments: Vec<Vec<PCS::Commitment>>,
 }
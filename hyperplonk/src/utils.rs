@@ -5,31 +5,102 @@
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
 use crate::{
-    custom_gate::CustomizedGates, errors::HyperPlonkErrors, structs::HyperPlonkParams,
+    custom_gate::{CustomizedGateSet, Row, Wire},
+    errors::HyperPlonkErrors,
+    structs::HyperPlonkParams,
     witness::WitnessColumn,
 };
-use arithmetic::{evaluate_opt, VirtualPolynomial};
+use arithmetic::{
+    evaluate_opt, next_row_index, shift_to_next_row, MultilinearPolynomial,
+    SmallMultilinearPolynomial, VirtualPolynomial,
+};
 use ark_ec::pairing::Pairing;
 use ark_ff::PrimeField;
 use ark_poly::DenseMultilinearExtension;
 use std::{borrow::Borrow, sync::Arc};
-use subroutines::pcs::{prelude::Commitment, PolynomialCommitmentScheme};
-use transcript::IOPTranscript;
+use subroutines::{
+    pcs::PolynomialCommitmentScheme,
+    poly_iop::prelude::{FnMultilinearStream, MultilinearStream, StreamingPolynomial},
+};
+use transcript::Transcript;
 
-/// An accumulator structure that holds a polynomial and
-/// its opening points
+/// The distinct openings of commitments at points.
+///
+/// The prover's `PcsAccumulator` and the verifier insert the same openings in
+/// the same order, so they agree on the index of each evaluation.
+#[derive(Debug)]
+pub(super) struct Openings<C, P> {
+    /// the distinct commitments
+    pub(crate) commitments: Vec<C>,
+    /// the distinct points
+    pub(crate) points: Vec<P>,
+    /// the indices of the commitment and of the point of each opening
+    pub(crate) indices: Vec<(usize, usize)>,
+}
+
+impl<C: Clone + PartialEq, P: Clone + PartialEq> Openings<C, P> {
+    /// Create an empty set of openings.
+    pub(super) fn new() -> Self {
+        Self {
+            commitments: vec![],
+            points: vec![],
+            indices: vec![],
+        }
+    }
+
+    /// Insert the opening of `commitment` at `point` if it is not there yet,
+    /// and return its index.
+    pub(super) fn insert(&mut self, commitment: &C, point: &P) -> usize {
+        let index = (
+            position_or_push(&mut self.commitments, commitment),
+            position_or_push(&mut self.points, point),
+        );
+        position_or_push(&mut self.indices, &index)
+    }
+
+    /// The number of distinct openings
+    pub(super) fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// The commitment and the point of each opening
+    pub(super) fn expand(&self) -> (Vec<C>, Vec<P>) {
+        self.indices
+            .iter()
+            .map(|&(c, p)| (self.commitments[c].clone(), self.points[p].clone()))
+            .unzip()
+    }
+}
+
+fn position_or_push<T: Clone + PartialEq>(items: &mut Vec<T>, item: &T) -> usize {
+    match items.iter().position(|x| x == item) {
+        Some(i) => i,
+        None => {
+            items.push(item.clone());
+            items.len() - 1
+        },
+    }
+}
+
+/// An accumulator structure that holds the polynomials and their opening
+/// points, without duplicated openings.
 #[derive(Debug)]
 pub(super) struct PcsAccumulator<E: Pairing, PCS: PolynomialCommitmentScheme<E>> {
     // sequence:
-    // - prod(x) at 5 points
-    // - w_merged at perm check point
-    // - w_merged at zero check points (#witness points)
-    // - selector_merged at zero check points (#selector points)
+    // - prod(x) at 4 points
+    // - frac(x) at 3 points
+    // - perm(x) at perm check point
+    // - w at perm check point
+    // - w at zero check point
+    // - selector at zero check point
+    // - w at the next row points, for the witnesses read at the next row
     // - w[0] at r_pi
     pub(crate) num_var: usize,
+    /// the distinct openings
+    pub(crate) openings: Openings<PCS::Commitment, PCS::Point>,
+    /// the polynomial of each distinct commitment
     pub(crate) polynomials: Vec<PCS::Polynomial>,
-    pub(crate) commitments: Vec<PCS::Commitment>,
-    pub(crate) points: Vec<PCS::Point>,
+    /// the evaluation of each distinct opening
     pub(crate) evals: Vec<PCS::Evaluation>,
 }
 
@@ -41,49 +112,57 @@ where
         Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
         Point = Vec<E::ScalarField>,
         Evaluation = E::ScalarField,
-        Commitment = Commitment<E>,
     >,
 {
     /// Create an empty accumulator.
     pub(super) fn new(num_var: usize) -> Self {
         Self {
             num_var,
+            openings: Openings::new(),
             polynomials: vec![],
-            commitments: vec![],
-            points: vec![],
             evals: vec![],
         }
     }
 
-    /// Push a new evaluation point into the accumulator
+    /// Push a new evaluation point into the accumulator, unless the
+    /// polynomial is already opened at this point, and return the index of
+    /// its evaluation.
     pub(super) fn insert_poly_and_points(
         &mut self,
         poly: &PCS::Polynomial,
         commit: &PCS::Commitment,
         point: &PCS::Point,
-    ) {
+    ) -> usize {
         assert!(poly.num_vars == point.len());
         assert!(poly.num_vars == self.num_var);
 
-        let eval = evaluate_opt(poly, point);
-
-        self.evals.push(eval);
-        self.polynomials.push(poly.clone());
-        self.points.push(point.clone());
-        self.commitments.push(*commit);
+        let index = self.openings.insert(commit, point);
+        if self.polynomials.len() < self.openings.commitments.len() {
+            self.polynomials.push(poly.clone());
+        }
+        if self.evals.len() < self.openings.len() {
+            self.evals.push(evaluate_opt(poly, point));
+        }
+        index
     }
 
     /// Batch open all the points over a merged polynomial.
     /// A simple wrapper of PCS::multi_open
-    pub(super) fn multi_open(
+    pub(super) fn multi_open<T: Transcript<E::ScalarField>>(
         &self,
         prover_param: impl Borrow<PCS::ProverParam>,
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut T,
     ) -> Result<PCS::BatchProof, HyperPlonkErrors> {
+        let (polynomials, points): (Vec<_>, Vec<_>) = self
+            .openings
+            .indices
+            .iter()
+            .map(|&(c, p)| (self.polynomials[c].clone(), self.openings.points[p].clone()))
+            .unzip();
         Ok(PCS::multi_open(
             prover_param.borrow(),
-            self.polynomials.as_ref(),
-            self.points.as_ref(),
+            &polynomials,
+            &points,
             self.evals.as_ref(),
             transcript,
         )?)
@@ -178,17 +257,37 @@ pub(crate) fn prover_sanity_check<F: PrimeField>(
     Ok(())
 }
 
-/// build `f(w_0(x),...w_d(x))` where `f` is the constraint polynomial
-/// i.e., `f(a, b, c) = q_l a(x) + q_r b(x) + q_m a(x)b(x) - q_o c(x)` in
-/// vanilla plonk
-pub(crate) fn build_f<F: PrimeField>(
-    gates: &CustomizedGates,
+/// A multiplicand of a product of the constraint polynomial `f`.
+#[derive(Clone, Copy, Debug)]
+enum Multiplicand {
+    Selector(usize),
+    Witness(Wire),
+}
+
+/// Check the MLEs `f` is built from, and return the products of `f` as
+/// coefficients and multiplicands.
+#[allow(clippy::type_complexity)]
+fn f_products<F: PrimeField>(
+    gates: &CustomizedGateSet,
     num_vars: usize,
     selector_mles: &[Arc<DenseMultilinearExtension<F>>],
     witness_mles: &[Arc<DenseMultilinearExtension<F>>],
-) -> Result<VirtualPolynomial<F>, HyperPlonkErrors> {
-    // TODO: check that selector and witness lengths match what is in
-    // the gate definition
+) -> Result<Vec<(F, Vec<Multiplicand>)>, HyperPlonkErrors> {
+    gates.validate()?;
+    if selector_mles.len() != gates.num_selector_columns() {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "number of selectors does not match the gate: {} vs {}",
+            selector_mles.len(),
+            gates.num_selector_columns()
+        )));
+    }
+    if witness_mles.len() != gates.num_witness_columns() {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "number of witnesses does not match the gate: {} vs {}",
+            witness_mles.len(),
+            gates.num_witness_columns()
+        )));
+    }
 
     for selector_mle in selector_mles.iter() {
         if selector_mle.num_vars != num_vars {
@@ -202,57 +301,232 @@ pub(crate) fn build_f<F: PrimeField>(
     for witness_mle in witness_mles.iter() {
         if witness_mle.num_vars != num_vars {
             return Err(HyperPlonkErrors::InvalidParameters(format!(
-                "selector has different number of vars: {} vs {}",
+                "witness has different number of vars: {} vs {}",
                 witness_mle.num_vars, num_vars
             )));
         }
     }
 
-    let mut res = VirtualPolynomial::<F>::new(num_vars);
-
-    for (coeff, selector, witnesses) in gates.gates.iter() {
-        let coeff_fr = if *coeff < 0 {
-            -F::from(-*coeff as u64)
-        } else {
-            F::from(*coeff as u64)
-        };
-        let mut mle_list = vec![];
-        if let Some(s) = *selector {
-            mle_list.push(selector_mles[s].clone())
+    let mut res = vec![];
+    for (activation, gate) in gates.gates.iter() {
+        for (coeff, selector, witnesses) in gate.gates.iter() {
+            let coeff_fr = if *coeff < 0 {
+                -F::from(-*coeff as u64)
+            } else {
+                F::from(*coeff as u64)
+            };
+            let multiplicands = activation
+                .iter()
+                .chain(selector.iter())
+                .map(|&s| Multiplicand::Selector(s))
+                .chain(witnesses.iter().map(|&w| Multiplicand::Witness(w)))
+                .collect();
+            res.push((coeff_fr, multiplicands));
         }
-        for &witness in witnesses.iter() {
-            mle_list.push(witness_mles[witness].clone())
+    }
+    Ok(res)
+}
+
+/// build `f(w_0(x),...w_d(x))` where `f` is the constraint polynomial
+/// i.e., `f(a, b, c) = q_l a(x) + q_r b(x) + q_m a(x)b(x) - q_o c(x)` in
+/// vanilla plonk
+///
+/// With several gate types, `f` is the sum of each gate multiplied by its
+/// activation selector. The MLEs interleave `2^log_num_instances` instances
+/// of the circuit.
+pub(crate) fn build_f<F: PrimeField>(
+    gates: &CustomizedGateSet,
+    num_vars: usize,
+    selector_mles: &[Arc<DenseMultilinearExtension<F>>],
+    witness_mles: &[Arc<DenseMultilinearExtension<F>>],
+    log_num_instances: usize,
+) -> Result<VirtualPolynomial<F>, HyperPlonkErrors> {
+    let products = f_products(gates, num_vars, selector_mles, witness_mles)?;
+
+    // columns of small integers, such as booleans, are kept as such so that
+    // the first sumcheck round multiplies them as integers
+    let as_small = |mle: &Arc<DenseMultilinearExtension<F>>| -> Arc<dyn MultilinearPolynomial<F>> {
+        match SmallMultilinearPolynomial::try_from_field_evaluations(mle.num_vars, &mle.evaluations)
+        {
+            Some(small) => Arc::new(small),
+            None => mle.clone(),
         }
-        res.add_mle_list(mle_list, coeff_fr)?;
+    };
+    let selector_tables: Vec<_> = selector_mles.iter().map(as_small).collect();
+    let witness_tables: Vec<_> = witness_mles.iter().map(as_small).collect();
+
+    // w_i(next(x)) for the witnesses that are read at the next row
+    let mut next_row_mles = vec![None; witness_mles.len()];
+    for i in gates.next_row_witnesses() {
+        next_row_mles[i] = Some(as_small(&shift_to_next_row(
+            &witness_mles[i],
+            log_num_instances,
+        )));
+    }
+
+    let mut res = VirtualPolynomial::<F>::new(num_vars);
+    for (coeff, multiplicands) in products {
+        let mle_list = multiplicands.iter().map(|m| match *m {
+            Multiplicand::Selector(s) => selector_tables[s].clone(),
+            Multiplicand::Witness(w) => match w.row {
+                Row::Current => witness_tables[w.column].clone(),
+                // safe unwrap: built above for every next row witness
+                Row::Next => next_row_mles[w.column].clone().unwrap(),
+            },
+        });
+        res.add_mle_list(mle_list, coeff)?;
     }
 
     Ok(res)
 }
 
+/// build `f` as in `build_f`, over streams of the MLEs for the streaming
+/// zero check. The witnesses at the next row are read from the witness
+/// MLEs, without building `w_i(next(x))`.
+pub(crate) fn build_streaming_f<F: PrimeField>(
+    gates: &CustomizedGateSet,
+    num_vars: usize,
+    selector_mles: &[Arc<DenseMultilinearExtension<F>>],
+    witness_mles: &[Arc<DenseMultilinearExtension<F>>],
+    log_num_instances: usize,
+) -> Result<StreamingPolynomial<F>, HyperPlonkErrors> {
+    let products = f_products(gates, num_vars, selector_mles, witness_mles)?;
+
+    let as_stream =
+        |mle: &Arc<DenseMultilinearExtension<F>>| -> Arc<dyn MultilinearStream<F>> { mle.clone() };
+    let selector_streams: Vec<_> = selector_mles.iter().map(as_stream).collect();
+    let witness_streams: Vec<_> = witness_mles.iter().map(as_stream).collect();
+    let next_row_streams: Vec<Arc<dyn MultilinearStream<F>>> = witness_mles
+        .iter()
+        .map(|mle| {
+            let mle = mle.clone();
+            Arc::new(FnMultilinearStream::new(num_vars, move |i| {
+                mle.evaluations[next_row_index(i, num_vars, log_num_instances)]
+            })) as Arc<dyn MultilinearStream<F>>
+        })
+        .collect();
+
+    let mut res = StreamingPolynomial::<F>::new(num_vars);
+    for (coeff, multiplicands) in products {
+        let stream_list = multiplicands.iter().map(|m| match *m {
+            Multiplicand::Selector(s) => selector_streams[s].clone(),
+            Multiplicand::Witness(w) => match w.row {
+                Row::Current => witness_streams[w.column].clone(),
+                Row::Next => next_row_streams[w.column].clone(),
+            },
+        });
+        res.add_stream_list(stream_list, coeff)?;
+    }
+
+    Ok(res)
+}
+
+/// evaluate the constraint polynomial `f` from the evaluations of the
+/// selectors and witnesses, and of the witnesses at the next row (only the
+/// ones read at the next row are used)
 pub(crate) fn eval_f<F: PrimeField>(
-    gates: &CustomizedGates,
+    gates: &CustomizedGateSet,
     selector_evals: &[F],
     witness_evals: &[F],
+    witness_next_row_evals: &[F],
 ) -> Result<F, HyperPlonkErrors> {
     let mut res = F::zero();
-    for (coeff, selector, witnesses) in gates.gates.iter() {
-        let mut cur_value = if *coeff < 0 {
-            -F::from(-*coeff as u64)
-        } else {
-            F::from(*coeff as u64)
-        };
-        cur_value *= match selector {
-            Some(s) => selector_evals[*s],
-            None => F::one(),
-        };
-        for &witness in witnesses.iter() {
-            cur_value *= witness_evals[witness]
+    for (activation, gate) in gates.gates.iter() {
+        let mut gate_value = F::zero();
+        for (coeff, selector, witnesses) in gate.gates.iter() {
+            let mut cur_value = if *coeff < 0 {
+                -F::from(-*coeff as u64)
+            } else {
+                F::from(*coeff as u64)
+            };
+            cur_value *= match selector {
+                Some(s) => selector_evals[*s],
+                None => F::one(),
+            };
+            for witness in witnesses.iter() {
+                cur_value *= match witness.row {
+                    Row::Current => witness_evals[witness.column],
+                    Row::Next => witness_next_row_evals[witness.column],
+                }
+            }
+            gate_value += cur_value;
         }
-        res += cur_value;
+        res += match activation {
+            Some(a) => selector_evals[*a] * gate_value,
+            None => gate_value,
+        };
     }
     Ok(res)
 }
 
+/// Interleave the evaluations of `2^log_num_instances` instances: entry
+/// `x * 2^log_num_instances + y` is entry `x` of instance `y`. Missing
+/// instances are filled with the last one.
+pub(crate) fn interleave_instances<F: Copy>(
+    instances: &[&[F]],
+    log_num_instances: usize,
+) -> Vec<F> {
+    let num_instances = 1 << log_num_instances;
+    let len = instances[0].len();
+    (0..len)
+        .flat_map(|x| (0..num_instances).map(move |y| instances[y.min(instances.len() - 1)][x]))
+        .collect()
+}
+
+/// Build `s(y, x) = s(x)` for `2^log_num_instances` interleaved instances.
+pub(crate) fn replicate_instances<F: PrimeField>(
+    mle: &Arc<DenseMultilinearExtension<F>>,
+    log_num_instances: usize,
+) -> Arc<DenseMultilinearExtension<F>> {
+    if log_num_instances == 0 {
+        return mle.clone();
+    }
+    let instances = vec![mle.evaluations.as_slice(); 1 << log_num_instances];
+    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        mle.num_vars + log_num_instances,
+        interleave_instances(&instances, log_num_instances),
+    ))
+}
+
+/// Build the permutation `perm(y, x) = y + 2^log_num_instances * perm(x)` of
+/// `2^log_num_instances` interleaved instances, which maps a wire of instance
+/// `y` to a wire of the same instance.
+pub(crate) fn stack_permutation<F: PrimeField>(
+    mle: &Arc<DenseMultilinearExtension<F>>,
+    log_num_instances: usize,
+) -> Arc<DenseMultilinearExtension<F>> {
+    if log_num_instances == 0 {
+        return mle.clone();
+    }
+    let num_instances = 1 << log_num_instances;
+    let shift = F::from(num_instances as u64);
+    let evals = mle
+        .evaluations
+        .iter()
+        .flat_map(|&v| (0..num_instances).map(move |y| F::from(y as u64) + shift * v))
+        .collect();
+    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        mle.num_vars + log_num_instances,
+        evals,
+    ))
+}
+
+/// Evaluate `perm(y, x) = y + 2^log_num_instances * perm(x)` at `point`
+/// given `perm_eval = perm(point[log_num_instances..])`.
+pub(crate) fn eval_stacked_permutation<F: PrimeField>(
+    perm_eval: F,
+    point: &[F],
+    log_num_instances: usize,
+) -> F {
+    let mut res = F::zero();
+    let mut base = F::one();
+    for &v in point[..log_num_instances].iter() {
+        res += base * v;
+        base += base;
+    }
+    res + base * perm_eval
+}
+
 // check perm check subclaim:
 // proof.witness_perm_check_eval ?= perm_check_sub_claim.expected_eval
 // Q(x) := prod(x) - p1(x) * p2(x)
@@ -295,9 +569,11 @@ pub(crate) fn eval_perm_gate<F: PrimeField>(
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::custom_gate::CustomizedGates;
     use ark_bls12_381::Fr;
     use ark_ff::PrimeField;
     use ark_poly::MultilinearExtension;
+    use ark_std::{test_rng, UniformRand};
     #[test]
     fn test_build_gate() -> Result<(), HyperPlonkErrors> {
         test_build_gate_helper::<Fr>()
@@ -337,10 +613,18 @@ mod test {
         //     ( 1,    Some(id_qL),    vec![id_W1, id_W1, id_W1, id_W1, id_W1]),
         //     (-1,    None,           vec![id_W2])
         // ]
-        let gates = CustomizedGates {
-            gates: vec![(1, Some(0), vec![0, 0, 0, 0, 0]), (-1, None, vec![1])],
-        };
-        let f = build_f(&gates, num_vars, &[ql.clone()], &[w1.clone(), w2.clone()])?;
+        let gates = CustomizedGates::from_current_row(vec![
+            (1, Some(0), vec![0, 0, 0, 0, 0]),
+            (-1, None, vec![1]),
+        ]);
+        let gates: CustomizedGateSet = gates.into();
+        let f = build_f(
+            &gates,
+            num_vars,
+            &[ql.clone()],
+            &[w1.clone(), w2.clone()],
+            0,
+        )?;
 
         // Sanity check on build_f
         // f(0, 0) = 0
@@ -381,10 +665,89 @@ mod test {
             let point = [F::one(), F::one()];
             let selector_evals = ql.evaluate(&point).unwrap();
             let witness_evals = [w1.evaluate(&point).unwrap(), w2.evaluate(&point).unwrap()];
-            let eval_f = eval_f(&gates, &[selector_evals], &witness_evals)?;
+            let eval_f = eval_f(&gates, &[selector_evals], &witness_evals, &[])?;
             // f(1, 1) = 5 * 2^5 + (-1) * 2 = 158
             assert_eq!(eval_f, F::from(158u64));
         }
         Ok(())
     }
+
+    #[test]
+    fn test_build_gate_set() -> Result<(), HyperPlonkErrors> {
+        let mut rng = test_rng();
+        let num_vars = 3;
+        let gates: CustomizedGateSet =
+            "q5*(q0*w0 + q1*w1 + q2*w2 + q3*w0*w1 + q4) + q9*(q6*w0^5 + q7*w1 - w2) + q8*w2"
+                .parse()?;
+        gates.validate()?;
+        assert_eq!(gates.degree(), 7);
+
+        let selectors: Vec<_> = (0..gates.num_selector_columns())
+            .map(|_| Arc::new(DenseMultilinearExtension::<Fr>::rand(num_vars, &mut rng)))
+            .collect();
+        let witnesses: Vec<_> = (0..gates.num_witness_columns())
+            .map(|_| Arc::new(DenseMultilinearExtension::<Fr>::rand(num_vars, &mut rng)))
+            .collect();
+        let f = build_f(&gates, num_vars, &selectors, &witnesses, 0)?;
+        assert_eq!(f.aux_info.max_degree, gates.degree());
+
+        let point: Vec<Fr> = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+        let selector_evals: Vec<Fr> = selectors.iter().map(|s| evaluate_opt(s, &point)).collect();
+        let witness_evals: Vec<Fr> = witnesses.iter().map(|w| evaluate_opt(w, &point)).collect();
+        assert_eq!(
+            eval_f(&gates, &selector_evals, &witness_evals, &[])?,
+            f.evaluate(&point)?
+        );
+
+        // the counts must match the gate set
+        assert!(build_f(&gates, num_vars, &selectors[1..], &witnesses, 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_stack_instances() {
+        let mut rng = test_rng();
+        let num_vars = 3;
+        let log_num_instances = 2;
+        let s = Arc::new(DenseMultilinearExtension::<Fr>::rand(num_vars, &mut rng));
+
+        // instances are interleaved and the last one is repeated
+        let a = [Fr::from(1u64), Fr::from(2u64)];
+        let b = [Fr::from(3u64), Fr::from(4u64)];
+        let c = [Fr::from(5u64), Fr::from(6u64)];
+        assert_eq!(
+            interleave_instances(&[&a[..], &b[..], &c[..]], log_num_instances),
+            [1u64, 3, 5, 5, 2, 4, 6, 6].map(Fr::from).to_vec()
+        );
+
+        let point: Vec<Fr> = (0..num_vars + log_num_instances)
+            .map(|_| Fr::rand(&mut rng))
+            .collect();
+        let s_eval = evaluate_opt(&s, &point[log_num_instances..]);
+        assert_eq!(
+            evaluate_opt(&replicate_instances(&s, log_num_instances), &point),
+            s_eval
+        );
+        assert_eq!(
+            evaluate_opt(&stack_permutation(&s, log_num_instances), &point),
+            eval_stacked_permutation(s_eval, &point, log_num_instances)
+        );
+    }
+
+    #[test]
+    fn test_openings() {
+        let mut openings = Openings::<u64, Vec<u64>>::new();
+        assert_eq!(openings.insert(&10, &vec![0, 1]), 0);
+        assert_eq!(openings.insert(&20, &vec![0, 1]), 1);
+        assert_eq!(openings.insert(&10, &vec![1, 1]), 2);
+        // the same commitment at the same point is opened once
+        assert_eq!(openings.insert(&20, &vec![0, 1]), 1);
+        assert_eq!(openings.len(), 3);
+        assert_eq!(openings.commitments, vec![10, 20]);
+        assert_eq!(openings.points.len(), 2);
+        assert_eq!(
+            openings.expand(),
+            (vec![10, 20, 10], vec![vec![0, 1], vec![0, 1], vec![1, 1]])
+        );
+    }
 }
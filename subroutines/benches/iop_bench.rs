@@ -4,10 +4,14 @@
 // You should have received a copy of the MIT License
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
-use arithmetic::{identity_permutation_mles, VPAuxInfo, VirtualPolynomial};
+use arithmetic::{
+    fix_first_variable, fix_first_variable_in_place, fix_variables, identity_permutation_mles,
+    MultilinearPolynomial, VPAuxInfo, VirtualPolynomial,
+};
 use ark_bls12_381::{Bls12_381, Fr};
 use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
-use ark_std::test_rng;
+use ark_std::{test_rng, UniformRand};
+use rayon::prelude::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator};
 use std::{marker::PhantomData, sync::Arc, time::Instant};
 use subroutines::{
     pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
@@ -15,14 +19,21 @@ use subroutines::{
         PermutationCheck, PolyIOP, PolyIOPErrors, ProductCheck, SumCheck, ZeroCheck,
     },
 };
+use transcript::Transcript;
 
 type Kzg = MultilinearKzgPCS<Bls12_381>;
 
 fn main() -> Result<(), PolyIOPErrors> {
     bench_permutation_check()?;
     println!("\n\n");
+    bench_gkr_permutation_check()?;
+    println!("\n\n");
     bench_sum_check()?;
     println!("\n\n");
+    bench_sum_check_verifier()?;
+    println!("\n\n");
+    bench_sum_check_folding()?;
+    println!("\n\n");
     bench_prod_check()?;
     println!("\n\n");
     bench_zero_check()
@@ -86,6 +97,103 @@ fn bench_sum_check() -> Result<(), PolyIOPErrors> {
     Ok(())
 }
 
+fn bench_sum_check_verifier() -> Result<(), PolyIOPErrors> {
+    let mut rng = test_rng();
+    let nv = 10;
+    let repetition = 100;
+    for degree in 2..=32 {
+        let (poly, asserted_sum) = VirtualPolynomial::rand(nv, (degree, degree + 1), 2, &mut rng)?;
+        let poly_info = poly.aux_info.clone();
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let proof = <PolyIOP<Fr> as SumCheck<Fr>>::prove(&poly, &mut transcript)?;
+
+        let start = Instant::now();
+        for _ in 0..repetition {
+            let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+            let _subclaim = <PolyIOP<Fr> as SumCheck<Fr>>::verify(
+                asserted_sum,
+                &proof,
+                &poly_info,
+                &mut transcript,
+            )?;
+        }
+        println!(
+            "sum check verification time for {} variables and {} degree: {} ns",
+            nv,
+            degree,
+            start.elapsed().as_nanos() / repetition as u128
+        );
+    }
+    Ok(())
+}
+
+fn bench_sum_check_folding() -> Result<(), PolyIOPErrors> {
+    let mut rng = test_rng();
+    for nv in 4..25 {
+        let repetition = if nv < 10 {
+            100
+        } else if nv < 20 {
+            50
+        } else {
+            10
+        };
+
+        let (poly, _asserted_sum) = VirtualPolynomial::<Fr>::rand(nv, (3, 4), 2, &mut rng)?;
+        let challenges: Vec<Fr> = (0..nv - 1).map(|_| Fr::rand(&mut rng)).collect();
+
+        // folding as the prover did before: every round copies the tables,
+        // folds the copies and wraps the results in new `Arc`s
+        {
+            let start = Instant::now();
+            for _ in 0..repetition {
+                let mut mles = poly.flattened_ml_extensions.clone();
+                for r in challenges.iter() {
+                    let mut tables: Vec<DenseMultilinearExtension<Fr>> =
+                        mles.par_iter().map(|x| x.to_dense()).collect();
+                    tables
+                        .par_iter_mut()
+                        .for_each(|mle| *mle = fix_variables(mle, &[*r]));
+                    mles = tables
+                        .par_iter()
+                        .map(|x| Arc::new(x.clone()) as Arc<dyn MultilinearPolynomial<Fr>>)
+                        .collect();
+                }
+            }
+            println!(
+                "sum check folding time with copies for {} variables: {} ns",
+                nv,
+                start.elapsed().as_nanos() / repetition as u128
+            );
+        }
+
+        // folding as the prover does now: the first round reads the input
+        // tables, and the folded tables are then updated in place
+        {
+            let start = Instant::now();
+            for _ in 0..repetition {
+                let mut tables: Vec<Vec<Fr>> = poly
+                    .flattened_ml_extensions
+                    .iter()
+                    .map(|mle| fix_first_variable(mle.dense_evaluations().unwrap(), &challenges[0]))
+                    .collect();
+                for r in challenges[1..].iter() {
+                    tables
+                        .par_iter_mut()
+                        .for_each(|evals| fix_first_variable_in_place(evals, r));
+                }
+            }
+            println!(
+                "sum check folding time in place for {} variables: {} ns",
+                nv,
+                start.elapsed().as_nanos() / repetition as u128
+            );
+        }
+
+        println!("====================================");
+    }
+    Ok(())
+}
+
 fn bench_zero_check() -> Result<(), PolyIOPErrors> {
     let mut rng = test_rng();
     for degree in 2..4 {
@@ -206,6 +314,66 @@ fn bench_permutation_check() -> Result<(), PolyIOPErrors> {
     Ok(())
 }
 
+fn bench_gkr_permutation_check() -> Result<(), PolyIOPErrors> {
+    let mut rng = test_rng();
+
+    for nv in 4..20 {
+        let ws = vec![Arc::new(DenseMultilinearExtension::rand(nv, &mut rng))];
+
+        // identity map
+        let perms = identity_permutation_mles(nv, 1);
+
+        let proof = {
+            let start = Instant::now();
+            let mut transcript =
+                <PolyIOP<Fr> as PermutationCheck<Bls12_381, Kzg>>::init_transcript();
+            transcript.append_message(b"testing", b"initializing transcript for testing")?;
+
+            let proof = <PolyIOP<Fr> as PermutationCheck<Bls12_381, Kzg>>::prove_with_gkr(
+                &ws,
+                &ws,
+                &perms,
+                &mut transcript,
+            )?;
+
+            println!(
+                "gkr permutation check proving time for {} variables: {} ns",
+                nv,
+                start.elapsed().as_nanos()
+            );
+            proof
+        };
+
+        {
+            let poly_info = VPAuxInfo {
+                max_degree: 3,
+                num_variables: nv,
+                phantom: PhantomData,
+            };
+
+            let start = Instant::now();
+            let mut transcript =
+                <PolyIOP<Fr> as PermutationCheck<Bls12_381, Kzg>>::init_transcript();
+            transcript.append_message(b"testing", b"initializing transcript for testing")?;
+            let _perm_check_sum_claim =
+                <PolyIOP<Fr> as PermutationCheck<Bls12_381, Kzg>>::verify_with_gkr(
+                    &proof,
+                    &poly_info,
+                    &mut transcript,
+                )?;
+            println!(
+                "gkr permutation check verification time for {} variables: {} ns",
+                nv,
+                start.elapsed().as_nanos()
+            );
+        }
+
+        println!("====================================");
+    }
+
+    Ok(())
+}
+
 fn bench_prod_check() -> Result<(), PolyIOPErrors> {
     let mut rng = test_rng();
 
@@ -4,18 +4,22 @@
 // You should have received a copy of the MIT License
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
-use ark_bls12_381::{Bls12_381, Fr};
+use arithmetic::SmallMultilinearPolynomial;
+use ark_bls12_381::{Bls12_381, Fr, G1Projective};
 use ark_ff::UniformRand;
 use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
 use ark_std::{sync::Arc, test_rng};
 use std::time::Instant;
 use subroutines::pcs::{
-    prelude::{MultilinearKzgPCS, PCSError, PolynomialCommitmentScheme},
+    prelude::{HyraxPCS, MultilinearKzgPCS, PCSError, PolynomialCommitmentScheme},
     StructuredReferenceString,
 };
 
+type Hyrax = HyraxPCS<G1Projective>;
+
 fn main() -> Result<(), PCSError> {
-    bench_pcs()
+    bench_pcs()?;
+    bench_hyrax()
 }
 
 fn bench_pcs() -> Result<(), PCSError> {
@@ -54,6 +58,22 @@ fn bench_pcs() -> Result<(), PCSError> {
             MultilinearKzgPCS::commit(&ck, &poly)?
         };
 
+        // commit to small integers
+        for num_bits in [1, 32] {
+            let small_poly = SmallMultilinearPolynomial::<Fr>::rand(nv, num_bits, &mut rng);
+            let start = Instant::now();
+            for _ in 0..repetition {
+                let _commit = MultilinearKzgPCS::commit_multilinear(&ck, &small_poly)?;
+            }
+
+            println!(
+                "KZG commit for {} variables of {} bits: {} ns",
+                nv,
+                num_bits,
+                start.elapsed().as_nanos() / repetition as u128
+            );
+        }
+
         // open
         let (proof, value) = {
             let start = Instant::now();
@@ -89,3 +109,77 @@ fn bench_pcs() -> Result<(), PCSError> {
 
     Ok(())
 }
+
+fn bench_hyrax() -> Result<(), PCSError> {
+    let mut rng = test_rng();
+
+    let params =
+        <Hyrax as PolynomialCommitmentScheme<Bls12_381>>::gen_srs_for_testing(&mut rng, 24)?;
+
+    for nv in 4..25 {
+        let repetition = if nv < 10 {
+            10
+        } else if nv < 20 {
+            5
+        } else {
+            2
+        };
+
+        let poly = Arc::new(DenseMultilinearExtension::rand(nv, &mut rng));
+        let (ck, vk) =
+            <Hyrax as PolynomialCommitmentScheme<Bls12_381>>::trim(&params, None, Some(nv))?;
+
+        let point: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+
+        // commit
+        let com = {
+            let start = Instant::now();
+            for _ in 0..repetition {
+                let _commit = <Hyrax as PolynomialCommitmentScheme<Bls12_381>>::commit(&ck, &poly)?;
+            }
+
+            println!(
+                "Hyrax commit for {} variables: {} ns",
+                nv,
+                start.elapsed().as_nanos() / repetition as u128
+            );
+
+            <Hyrax as PolynomialCommitmentScheme<Bls12_381>>::commit(&ck, &poly)?
+        };
+
+        // open
+        let (proof, value) = {
+            let start = Instant::now();
+            for _ in 0..repetition {
+                let _open =
+                    <Hyrax as PolynomialCommitmentScheme<Bls12_381>>::open(&ck, &poly, &point)?;
+            }
+
+            println!(
+                "Hyrax open for {} variables: {} ns",
+                nv,
+                start.elapsed().as_nanos() / repetition as u128
+            );
+            <Hyrax as PolynomialCommitmentScheme<Bls12_381>>::open(&ck, &poly, &point)?
+        };
+
+        // verify
+        {
+            let start = Instant::now();
+            for _ in 0..repetition {
+                assert!(<Hyrax as PolynomialCommitmentScheme<Bls12_381>>::verify(
+                    &vk, &com, &point, &value, &proof
+                )?);
+            }
+            println!(
+                "Hyrax verify for {} variables: {} ns",
+                nv,
+                start.elapsed().as_nanos() / repetition as u128
+            );
+        }
+
+        println!("====================================");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,246 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! The linear-time encodable code of Brakedown
+//! [GLSTW21](https://eprint.iacr.org/2021/1043.pdf).
+//!
+//! A message `x` of length `n` is encoded recursively as
+//!
+//! `Enc(x) = (x, Enc(x A), Enc(x A) B)`
+//!
+//! where `A` is a sparse random `n x ceil(alpha n)` matrix, and `B` a sparse
+//! random matrix completing the codeword to `ceil(r n)` symbols. Messages of
+//! at most `BASE_LEN` symbols are encoded with the Reed-Solomon code of the
+//! same rate. Each level costs a number of field operations linear in its
+//! length, and the lengths decrease geometrically, so encoding takes `O(n)`
+//! field operations.
+//!
+//! The matrices are sampled from a fixed seed, so that the prover and the
+//! verifier build the same code. The parameters are the set of Figure 2 of
+//! the paper with the largest distance: the code has relative distance
+//! `beta / r` except with probability `2^{-100}` over the matrices.
+
+use ark_ff::PrimeField;
+use ark_std::rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+/// Seed of the sparse matrices of the code
+const CODE_SEED: [u8; 32] = *b"HyperPlonk Brakedown matrix seed";
+
+/// Ratio of the length of `x A` to the length of `x`
+const ALPHA: f64 = 0.2380;
+/// Relative distance of `x -> x A` as a code, up to a factor of `r`
+const BETA: f64 = 0.1205;
+/// Inverse rate of the code
+const R: f64 = 1.720;
+
+/// Maximum length of the messages encoded with the Reed-Solomon code
+const BASE_LEN: usize = 30;
+
+/// A Brakedown code for messages of a given length.
+pub(crate) struct BrakedownCode<F: PrimeField> {
+    message_len: usize,
+    codeword_len: usize,
+    /// The matrices `(A, B)` of each level of the recursion, from the
+    /// outermost one
+    levels: Vec<(SparseMatrix<F>, SparseMatrix<F>)>,
+}
+
+impl<F: PrimeField> BrakedownCode<F> {
+    /// Sample the code for messages of length `message_len`.
+    pub(crate) fn new(message_len: usize) -> Self {
+        let log2_q = F::MODULUS_BIT_SIZE as usize;
+        let mut rng = ChaCha20Rng::from_seed(CODE_SEED);
+        let mut levels = vec![];
+        let mut n = message_len;
+        while n > BASE_LEN {
+            let m = (ALPHA * n as f64).ceil() as usize;
+            let a = SparseMatrix::rand(n, m, c_n(n).min(m), &mut rng);
+            let inner_len = codeword_len(m);
+            let tail_len = codeword_len(n) - n - inner_len;
+            let b = SparseMatrix::rand(inner_len, tail_len, d_n(log2_q, n).min(tail_len), &mut rng);
+            levels.push((a, b));
+            n = m;
+        }
+        Self {
+            message_len,
+            codeword_len: codeword_len(message_len),
+            levels,
+        }
+    }
+
+    pub(crate) fn codeword_len(&self) -> usize {
+        self.codeword_len
+    }
+
+    /// Encode `message`, of the length the code was built for.
+    pub(crate) fn encode(&self, message: &[F]) -> Vec<F> {
+        assert_eq!(message.len(), self.message_len);
+        self.encode_level(0, message)
+    }
+
+    fn encode_level(&self, level: usize, message: &[F]) -> Vec<F> {
+        match self.levels.get(level) {
+            Some((a, b)) => {
+                let inner = self.encode_level(level + 1, &a.mul_vec(message));
+                let tail = b.mul_vec(&inner);
+                [message, &inner, &tail].concat()
+            },
+            None => reed_solomon(message),
+        }
+    }
+}
+
+/// Length of the encoding of a message of length `message_len`
+fn codeword_len(message_len: usize) -> usize {
+    (R * message_len as f64).ceil() as usize
+}
+
+/// The Reed-Solomon encoding of `message`: the evaluations at `1, ...,
+/// codeword_len(message.len())` of the polynomial whose coefficients are
+/// `message`.
+fn reed_solomon<F: PrimeField>(message: &[F]) -> Vec<F> {
+    (1..=codeword_len(message.len()) as u64)
+        .map(|point| {
+            let point = F::from(point);
+            message
+                .iter()
+                .rev()
+                .fold(F::zero(), |acc, coeff| acc * point + coeff)
+        })
+        .collect()
+}
+
+/// The binary entropy function
+fn entropy(p: f64) -> f64 {
+    -p * p.log2() - (1.0 - p) * (1.0 - p).log2()
+}
+
+/// Number of non-zero entries in each row of `A` for messages of length `n`
+fn c_n(n: usize) -> usize {
+    let n = n as f64;
+    let lower = (1.28 * BETA * n).ceil().max((BETA * n).ceil() + 4.0);
+    let bound = (110.0 / n + entropy(BETA) + ALPHA * entropy(1.28 * BETA / ALPHA))
+        / (BETA * (ALPHA / (1.28 * BETA)).log2());
+    lower.min(bound.ceil()) as usize
+}
+
+/// Number of non-zero entries in each row of `B` for messages of length `n`
+/// over a field of `log2_q` bits
+fn d_n(log2_q: usize, n: usize) -> usize {
+    let n = n as f64;
+    let mu = R - 1.0 - R * ALPHA;
+    let nu = BETA + ALPHA * BETA + 0.03;
+    let lower = ((2.0 * BETA + ((R - 1.0) + 110.0 / n) / log2_q as f64) * n).ceil();
+    let bound = (R * ALPHA * entropy(BETA / R) + mu * entropy(nu / mu) + 110.0 / n)
+        / (ALPHA * BETA * (mu / nu).log2());
+    lower.min(bound.ceil()) as usize
+}
+
+/// A sparse matrix with the same number of non-zero entries in each row
+struct SparseMatrix<F: PrimeField> {
+    num_cols: usize,
+    /// The column and the value of the non-zero entries of each row
+    rows: Vec<Vec<(usize, F)>>,
+}
+
+impl<F: PrimeField> SparseMatrix<F> {
+    /// A matrix whose rows have `degree` random entries at distinct random
+    /// columns.
+    fn rand(num_rows: usize, num_cols: usize, degree: usize, rng: &mut impl Rng) -> Self {
+        let rows = (0..num_rows)
+            .map(|_| {
+                // Floyd's sampling of `degree` distinct columns
+                let mut cols = Vec::with_capacity(degree);
+                for j in num_cols - degree..num_cols {
+                    let col = rng.gen_range(0..=j);
+                    cols.push(if cols.contains(&col) { j } else { col });
+                }
+                cols.into_iter().map(|col| (col, F::rand(rng))).collect()
+            })
+            .collect();
+        Self { num_cols, rows }
+    }
+
+    /// `x M`
+    fn mul_vec(&self, x: &[F]) -> Vec<F> {
+        let mut res = vec![F::zero(); self.num_cols];
+        for (x, row) in x.iter().zip(self.rows.iter()) {
+            for (col, value) in row.iter() {
+                res[*col] += *x * value;
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bls12_381::Fr;
+    use ark_std::{test_rng, One, UniformRand, Zero};
+
+    #[test]
+    fn test_brakedown_code() {
+        let mut rng = test_rng();
+        for message_len in [1, 2, 16, 31, 100, 1000] {
+            let code = BrakedownCode::<Fr>::new(message_len);
+            assert_eq!(code.codeword_len(), codeword_len(message_len));
+            assert_eq!(code.levels.is_empty(), message_len <= BASE_LEN);
+            if message_len == 1000 {
+                assert_eq!(code.levels.len(), 3);
+            }
+
+            // the code is linear, and systematic above the base case
+            let x: Vec<_> = (0..message_len).map(|_| Fr::rand(&mut rng)).collect();
+            let y: Vec<_> = (0..message_len).map(|_| Fr::rand(&mut rng)).collect();
+            let s = Fr::rand(&mut rng);
+            let combined: Vec<_> = x.iter().zip(y.iter()).map(|(x, y)| s * x + y).collect();
+            let (enc_x, enc_y) = (code.encode(&x), code.encode(&y));
+            assert_eq!(enc_x.len(), code.codeword_len());
+            let expected: Vec<_> = enc_x
+                .iter()
+                .zip(enc_y.iter())
+                .map(|(x, y)| s * x + y)
+                .collect();
+            assert_eq!(code.encode(&combined), expected);
+            if message_len > BASE_LEN {
+                assert_eq!(enc_x[..message_len], x[..]);
+            }
+
+            // the same code is sampled again
+            assert_eq!(BrakedownCode::<Fr>::new(message_len).encode(&x), enc_x);
+        }
+    }
+
+    #[test]
+    fn test_num_queries() {
+        // a query rejects a row `e`-far from the code, for `e` less than a
+        // third of the distance, with probability at least `distance / 3`
+        let num_queries = (-128.0 / (1.0 - BETA / R / 3.0).log2()).ceil() as usize;
+        assert_eq!(num_queries, crate::pcs::brakedown::DEFAULT_NUM_QUERIES);
+    }
+
+    #[test]
+    fn test_unit_vector_weight() {
+        // the encodings of the unit vectors are at least as far from zero as
+        // the distance of the code
+        for message_len in [64, 256] {
+            let code = BrakedownCode::<Fr>::new(message_len);
+            let min_weight = (BETA / R * code.codeword_len() as f64).ceil() as usize;
+            for i in 0..message_len {
+                let mut message = vec![Fr::zero(); message_len];
+                message[i] = Fr::one();
+                let weight = code
+                    .encode(&message)
+                    .iter()
+                    .filter(|symbol| !symbol.is_zero())
+                    .count();
+                assert!(weight >= min_weight);
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Keccak256 based Merkle trees.
+
+use ark_std::{cfg_into_iter, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use transcript::{keccak256, Digest};
+
+fn hash_pair(left: &Digest, right: &Digest) -> Digest {
+    let mut data = [0u8; 64];
+    data[..32].copy_from_slice(left);
+    data[32..].copy_from_slice(right);
+    keccak256(&data)
+}
+
+/// A Merkle tree, whose leaves are padded with zero digests to a power of
+/// two.
+pub(crate) struct MerkleTree {
+    /// The nodes, with the root at index 1, the children of node `i` at
+    /// `2i` and `2i + 1`, and the leaves at the second half.
+    nodes: Vec<Digest>,
+}
+
+impl MerkleTree {
+    /// Build the tree over the hashes of the leaves.
+    pub(crate) fn new(leaves: Vec<Digest>) -> Self {
+        let num_leaves = leaves.len().next_power_of_two();
+        let mut nodes = vec![Digest::default(); num_leaves];
+        nodes.extend(leaves);
+        nodes.resize(num_leaves << 1, Digest::default());
+        let mut width = num_leaves >> 1;
+        while width > 0 {
+            let level: Vec<_> = cfg_into_iter!(width..width << 1)
+                .map(|i| hash_pair(&nodes[i << 1], &nodes[(i << 1) + 1]))
+                .collect();
+            nodes[width..width << 1].copy_from_slice(&level);
+            width >>= 1;
+        }
+        Self { nodes }
+    }
+
+    pub(crate) fn root(&self) -> Digest {
+        self.nodes[1]
+    }
+
+    /// The siblings of the path from leaf `index` to the root, bottom up.
+    pub(crate) fn path(&self, index: usize) -> Vec<Digest> {
+        let mut node = index + (self.nodes.len() >> 1);
+        let mut path = vec![];
+        while node > 1 {
+            path.push(self.nodes[node ^ 1]);
+            node >>= 1;
+        }
+        path
+    }
+}
+
+/// Check that `path` is a path from a leaf `leaf` at `index` to `root`.
+pub(crate) fn verify_path(root: &Digest, leaf: Digest, index: usize, path: &[Digest]) -> bool {
+    if path.len() >= usize::BITS as usize || index >> path.len() != 0 {
+        return false;
+    }
+    let node = path
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (height, sibling)| {
+            if (index >> height) & 1 == 0 {
+                hash_pair(&node, sibling)
+            } else {
+                hash_pair(sibling, &node)
+            }
+        });
+    node == *root
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_merkle_tree() {
+        for num_leaves in [1, 2, 5, 8, 64] {
+            let leaves: Vec<_> = (0..num_leaves as u64)
+                .map(|i| keccak256(&i.to_le_bytes()))
+                .collect();
+            let tree = MerkleTree::new(leaves.clone());
+            for (index, leaf) in leaves.iter().enumerate() {
+                let path = tree.path(index);
+                assert!(verify_path(&tree.root(), *leaf, index, &path));
+                assert!(!verify_path(&tree.root(), *leaf, index ^ 1, &path));
+                assert!(!verify_path(
+                    &tree.root(),
+                    keccak256(b"other"),
+                    index,
+                    &path
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,745 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Brakedown polynomial commitment scheme, a transparent
+//! multilinear commitment based on a linear-time encodable code and Merkle
+//! trees [GLSTW21](https://eprint.iacr.org/2021/1043.pdf).
+//!
+//! The `2^n` evaluations of a polynomial are arranged in a matrix `M` of
+//! `2^{n - m}` rows and `2^m` columns, with `m = ceil(n / 2)`, so that
+//!
+//! `f(z) = eq(z_hi)^T M eq(z_lo)`
+//!
+//! where `eq(z_lo)` (resp. `eq(z_hi)`) is the table of `eq(x, z_lo)` over the
+//! first `m` (resp. last `n - m`) variables. The commitment is the Merkle root
+//! over the columns of the matrix whose rows are the encodings of the rows of
+//! `M`. The code is the expander-based code of Brakedown, see `code`, of
+//! rate `1 / 1.72`, so committing takes `O(2^n)` field operations, against
+//! `O(2^n log(2^n))` for the FFT of the Reed-Solomon code of Ligero
+//! [AHIV17](https://eprint.iacr.org/2022/1608.pdf).
+//!
+//! To open at `z`, the prover sends a random combination of the rows of `M`
+//! for the proximity test, and the combination `eq(z_hi)^T M`. The verifier
+//! encodes both, and checks them against the same combinations of columns
+//! opened at random positions. Since the encoding is linear, the openings of
+//! several committed polynomials at the same point share their column
+//! positions, which `multi_open` uses after the sum check reducing all the
+//! openings to one point.
+//!
+//! The scheme needs no trusted setup nor pairings. Proofs are of size
+//! `O(sqrt(2^n))` field elements plus `num_queries` Merkle paths; the code
+//! has a smaller distance than the Reed-Solomon code, so it needs more
+//! queries.
+
+mod code;
+mod merkle;
+
+use crate::pcs::{
+    multilinear_kzg::batching::{batch_sum_check_prove, batch_sum_check_verify, merge_scalars},
+    prelude::{BatchProof, PCSError},
+    PolynomialCommitmentScheme,
+};
+use arithmetic::{build_eq_x_r_vec, evaluate_opt};
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+    borrow::Borrow, cfg_chunks, cfg_into_iter, end_timer, log2, marker::PhantomData, rand::Rng,
+    start_timer, One,
+};
+use code::BrakedownCode;
+use merkle::{verify_path, MerkleTree};
+#[cfg(feature = "parallel")]
+use rayon::prelude::{IntoParallelIterator, ParallelIterator, ParallelSlice};
+use std::sync::Arc;
+use transcript::{keccak256, Digest, IOPTranscript, Transcript};
+
+/// Default number of columns opened by a proof.
+///
+/// A proximity row `e`-far from the code, for `e` less than a third of the
+/// relative distance `delta = 0.07` of the code, passes a query with
+/// probability at most `1 - delta / 3`, so `3755` queries give 128 bits of
+/// security.
+pub const DEFAULT_NUM_QUERIES: usize = 3755;
+
+/// Brakedown Polynomial Commitment Scheme on multilinear polynomials.
+pub struct BrakedownPCS<E: Pairing> {
+    #[doc(hidden)]
+    phantom: PhantomData<E>,
+}
+
+/// Public parameters of the Brakedown commitment scheme. There is no
+/// trapdoor: they only bound the number of variables and set the number of
+/// queries.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BrakedownParams {
+    /// Maximum number of variables of the committed polynomials
+    pub num_vars: usize,
+    /// Number of columns opened by a proof
+    pub num_queries: usize,
+}
+
+impl BrakedownParams {
+    /// Parameters for polynomials of up to `num_vars` variables, with
+    /// `DEFAULT_NUM_QUERIES` queries.
+    pub fn new(num_vars: usize) -> Self {
+        Self {
+            num_vars,
+            num_queries: DEFAULT_NUM_QUERIES,
+        }
+    }
+}
+
+/// A commitment: the Merkle root over the columns of the encoded matrix
+#[derive(
+    CanonicalSerialize, CanonicalDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash,
+)]
+pub struct BrakedownCommitment(pub Digest);
+
+/// An opening proof of one or several committed polynomials at the same
+/// point
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BrakedownProof<F: PrimeField> {
+    /// A random combination of the rows of the committed matrices
+    pub proximity_row: Vec<F>,
+    /// The combination of the rows of the committed matrices with `eq(z_hi)`
+    pub evaluation_row: Vec<F>,
+    /// The opened columns, for each query and each committed matrix
+    pub columns: Vec<Vec<Vec<F>>>,
+    /// The Merkle paths of the opened columns
+    pub paths: Vec<Vec<Vec<Digest>>>,
+}
+
+impl<E: Pairing> PolynomialCommitmentScheme<E> for BrakedownPCS<E> {
+    // Parameters
+    type ProverParam = BrakedownParams;
+    type VerifierParam = BrakedownParams;
+    type SRS = BrakedownParams;
+    // Polynomial and its associated types
+    type Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>;
+    type Point = Vec<E::ScalarField>;
+    type Evaluation = E::ScalarField;
+    // Commitments and proofs
+    type Commitment = BrakedownCommitment;
+    type Proof = BrakedownProof<E::ScalarField>;
+    type BatchProof = BatchProof<E, Self>;
+
+    /// Build the parameters for polynomials of up to `log_size` variables.
+    ///
+    /// The scheme is transparent: the parameters sample no secret and are
+    /// safe to use in production.
+    fn gen_srs_for_testing<R: Rng>(_rng: &mut R, log_size: usize) -> Result<Self::SRS, PCSError> {
+        Ok(BrakedownParams::new(log_size))
+    }
+
+    /// Trim the parameters to `supported_num_vars` variables.
+    fn trim(
+        srs: impl Borrow<Self::SRS>,
+        supported_degree: Option<usize>,
+        supported_num_vars: Option<usize>,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), PCSError> {
+        assert!(supported_degree.is_none());
+
+        let supported_num_vars = match supported_num_vars {
+            Some(p) => p,
+            None => {
+                return Err(PCSError::InvalidParameters(
+                    "multilinear should receive a num_var param".to_string(),
+                ))
+            },
+        };
+        let srs = srs.borrow();
+        if supported_num_vars > srs.num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "SRS does not support target number of vars {}",
+                supported_num_vars
+            )));
+        }
+        let params = BrakedownParams {
+            num_vars: supported_num_vars,
+            num_queries: srs.num_queries,
+        };
+        Ok((params, params))
+    }
+
+    /// Generate a commitment for a polynomial.
+    ///
+    /// This function encodes the `2^{n - m}` rows into codewords of
+    /// `ceil(1.72 * 2^m)` symbols and hashes the columns.
+    fn commit(
+        prover_param: impl Borrow<Self::ProverParam>,
+        poly: &Self::Polynomial,
+    ) -> Result<Self::Commitment, PCSError> {
+        check_num_vars(prover_param.borrow(), poly.num_vars)?;
+        let commit_timer = start_timer!(|| "commit");
+        let (col_vars, _) = matrix_dims(poly.num_vars);
+        let code = BrakedownCode::new(1 << col_vars);
+        let root = EncodedMatrix::new(&code, poly)?.tree.root();
+        end_timer!(commit_timer);
+        Ok(BrakedownCommitment(root))
+    }
+
+    /// On input a polynomial `p` and a point `point`, outputs a proof for the
+    /// same.
+    ///
+    /// The prover encodes the polynomial again, as in `commit`.
+    fn open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomial: &Self::Polynomial,
+        point: &Self::Point,
+    ) -> Result<(Self::Proof, Self::Evaluation), PCSError> {
+        let prover_param = prover_param.borrow();
+        check_num_vars(prover_param, polynomial.num_vars)?;
+        let mut transcript = IOPTranscript::new(b"Brakedown open");
+        let proof = open_internal(
+            prover_param,
+            &[polynomial.as_ref()],
+            polynomial,
+            point,
+            &mut transcript,
+        )?;
+        Ok((proof, evaluate_opt(polynomial.as_ref(), point)))
+    }
+
+    /// Input a list of multilinear extensions, and a same number of points, and
+    /// a transcript, compute a multi-opening for all the polynomials.
+    ///
+    /// A sum check reduces the openings to the opening of `g'(X) = \sum_i
+    /// scalar_i * f_i(X)` at one point, which is proven with columns of each
+    /// distinct committed polynomial.
+    fn multi_open<T: Transcript<E::ScalarField>>(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomials: &[Self::Polynomial],
+        points: &[Self::Point],
+        evals: &[Self::Evaluation],
+        transcript: &mut T,
+    ) -> Result<BatchProof<E, Self>, PCSError> {
+        let prover_param = prover_param.borrow();
+        let open_timer = start_timer!(|| format!("multi open {} points", points.len()));
+        for poly in polynomials.iter() {
+            check_num_vars(prover_param, poly.num_vars)?;
+        }
+
+        let (sum_check_proof, _scalars, g_prime) =
+            batch_sum_check_prove(polynomials, points, transcript)?;
+
+        // each distinct polynomial is opened once; the verifier groups the
+        // scalars of the same commitments
+        let mut distinct_polys: Vec<&DenseMultilinearExtension<E::ScalarField>> = vec![];
+        for poly in polynomials.iter() {
+            if !distinct_polys
+                .iter()
+                .any(|other| std::ptr::eq(*other, poly.as_ref()) || *other == poly.as_ref())
+            {
+                distinct_polys.push(poly.as_ref());
+            }
+        }
+        let g_prime_proof = open_internal(
+            prover_param,
+            &distinct_polys,
+            &g_prime,
+            &sum_check_proof.point,
+            transcript,
+        )?;
+
+        end_timer!(open_timer);
+        Ok(BatchProof {
+            sum_check_proof,
+            f_i_eval_at_point_i: evals.to_vec(),
+            g_prime_proof,
+        })
+    }
+
+    /// Verifies that `value` is the evaluation at `x` of the polynomial
+    /// committed inside `comm`.
+    fn verify(
+        verifier_param: &Self::VerifierParam,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &E::ScalarField,
+        proof: &Self::Proof,
+    ) -> Result<bool, PCSError> {
+        check_num_vars(verifier_param, point.len())?;
+        let mut transcript = IOPTranscript::new(b"Brakedown open");
+        verify_internal(
+            verifier_param,
+            &[*commitment],
+            &[E::ScalarField::one()],
+            point,
+            value,
+            proof,
+            &mut transcript,
+        )
+    }
+
+    /// Verifies that `value_i` is the evaluation at `x_i` of the polynomial
+    /// `poly_i` committed inside `comm`.
+    fn batch_verify<T: Transcript<E::ScalarField>>(
+        verifier_param: &Self::VerifierParam,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        batch_proof: &Self::BatchProof,
+        transcript: &mut T,
+    ) -> Result<bool, PCSError> {
+        if commitments.len() != points.len() {
+            return Err(PCSError::InvalidParameters(format!(
+                "number of commitments ({}) does not match the number of points ({})",
+                commitments.len(),
+                points.len()
+            )));
+        }
+        for point in points.iter() {
+            check_num_vars(verifier_param, point.len())?;
+        }
+        let verify_timer = start_timer!(|| "batch verification");
+
+        let res = batch_sum_check_verify(
+            points,
+            &batch_proof.sum_check_proof,
+            &batch_proof.f_i_eval_at_point_i,
+            transcript,
+        )
+        .and_then(|(scalars, g_prime_eval)| {
+            // group the scalars of the same commitments, as the prover did for
+            // the same polynomials
+            let (distinct_commitments, distinct_scalars) = merge_scalars(commitments, &scalars);
+            verify_internal(
+                verifier_param,
+                &distinct_commitments,
+                &distinct_scalars,
+                &batch_proof.sum_check_proof.point,
+                &g_prime_eval,
+                &batch_proof.g_prime_proof,
+                transcript,
+            )
+        });
+
+        end_timer!(verify_timer);
+        res
+    }
+}
+
+fn check_num_vars(params: &BrakedownParams, num_vars: usize) -> Result<(), PCSError> {
+    if num_vars > params.num_vars {
+        return Err(PCSError::InvalidParameters(format!(
+            "number of variables {} exceeds the supported {}",
+            num_vars, params.num_vars
+        )));
+    }
+    Ok(())
+}
+
+/// Number of variables indexing the columns and the rows of the matrix of a
+/// polynomial of `num_vars` variables
+fn matrix_dims(num_vars: usize) -> (usize, usize) {
+    let col_vars = num_vars.div_ceil(2);
+    (col_vars, num_vars - col_vars)
+}
+
+/// The Merkle leaf of a column of an encoded matrix
+fn hash_column<F: PrimeField>(column: &[F]) -> Result<Digest, PCSError> {
+    let mut bytes = vec![];
+    column.serialize_uncompressed(&mut bytes)?;
+    Ok(keccak256(&bytes))
+}
+
+/// The encoded rows of a polynomial and the Merkle tree over their columns
+struct EncodedMatrix<F: PrimeField> {
+    /// The encoded rows, each of length `codeword_len`
+    rows: Vec<Vec<F>>,
+    codeword_len: usize,
+    tree: MerkleTree,
+}
+
+impl<F: PrimeField> EncodedMatrix<F> {
+    fn new(code: &BrakedownCode<F>, poly: &DenseMultilinearExtension<F>) -> Result<Self, PCSError> {
+        let (col_vars, _) = matrix_dims(poly.num_vars);
+        let rows: Vec<_> = cfg_chunks!(poly.evaluations, 1 << col_vars)
+            .map(|row| code.encode(row))
+            .collect();
+        let codeword_len = code.codeword_len();
+        let leaves = cfg_into_iter!(0..codeword_len)
+            .map(|j| hash_column(&column(&rows, j)))
+            .collect::<Result<Vec<_>, PCSError>>()?;
+        Ok(Self {
+            rows,
+            codeword_len,
+            tree: MerkleTree::new(leaves),
+        })
+    }
+}
+
+fn column<F: PrimeField>(rows: &[Vec<F>], j: usize) -> Vec<F> {
+    rows.iter().map(|row| row[j]).collect()
+}
+
+/// `eq(x, point)` for all `x`, with `[1]` for an empty point
+fn eq_table<F: PrimeField>(point: &[F]) -> Result<Vec<F>, PCSError> {
+    if point.is_empty() {
+        Ok(vec![F::one()])
+    } else {
+        Ok(build_eq_x_r_vec(point)?)
+    }
+}
+
+/// The challenges of the proximity test, one per row of each matrix
+fn proximity_challenges<F: PrimeField, T: Transcript<F>>(
+    roots: &[Digest],
+    point: &[F],
+    num_rows: usize,
+    transcript: &mut T,
+) -> Result<Vec<F>, PCSError> {
+    for root in roots.iter() {
+        transcript.append_message(b"root", root)?;
+    }
+    transcript.append_serializable_element(b"point", &point.to_vec())?;
+    Ok(transcript.get_and_append_challenge_vectors(b"proximity", roots.len() * num_rows)?)
+}
+
+/// The positions of the opened columns
+fn query_indices<F: PrimeField, T: Transcript<F>>(
+    params: &BrakedownParams,
+    proximity_row: &[F],
+    evaluation_row: &[F],
+    codeword_len: usize,
+    transcript: &mut T,
+) -> Result<Vec<usize>, PCSError> {
+    transcript.append_serializable_element(b"proximity row", &proximity_row.to_vec())?;
+    transcript.append_serializable_element(b"evaluation row", &evaluation_row.to_vec())?;
+    (0..params.num_queries)
+        .map(|_| {
+            let challenge = transcript.get_and_append_challenge(b"query")?;
+            Ok(challenge.into_bigint().as_ref()[0] as usize % codeword_len)
+        })
+        .collect()
+}
+
+/// Prove the evaluation at `point` of `combined`, a linear combination of the
+/// `polys`.
+fn open_internal<F: PrimeField, T: Transcript<F>>(
+    params: &BrakedownParams,
+    polys: &[&DenseMultilinearExtension<F>],
+    combined: &DenseMultilinearExtension<F>,
+    point: &[F],
+    transcript: &mut T,
+) -> Result<BrakedownProof<F>, PCSError> {
+    let open_timer = start_timer!(|| format!("open {} polynomials", polys.len()));
+    let num_vars = point.len();
+    if polys
+        .iter()
+        .chain([&combined])
+        .any(|poly| poly.num_vars != num_vars)
+    {
+        return Err(PCSError::InvalidParameters(
+            "polynomial and point have different number of variables".to_string(),
+        ));
+    }
+    let (col_vars, row_vars) = matrix_dims(num_vars);
+    let num_cols = 1 << col_vars;
+
+    let step = start_timer!(|| "encode");
+    let code = BrakedownCode::new(num_cols);
+    let matrices = polys
+        .iter()
+        .map(|poly| EncodedMatrix::new(&code, poly))
+        .collect::<Result<Vec<_>, PCSError>>()?;
+    end_timer!(step);
+    let roots: Vec<_> = matrices.iter().map(|matrix| matrix.tree.root()).collect();
+
+    let gamma = proximity_challenges(&roots, point, 1 << row_vars, transcript)?;
+    let proximity_row = combine_rows(
+        &gamma,
+        polys
+            .iter()
+            .flat_map(|poly| poly.evaluations.chunks(num_cols)),
+        num_cols,
+    );
+    let eq_hi = eq_table(&point[col_vars..])?;
+    let evaluation_row = combine_rows(&eq_hi, combined.evaluations.chunks(num_cols), num_cols);
+
+    let codeword_len = matrices[0].codeword_len;
+    let queries = query_indices(
+        params,
+        &proximity_row,
+        &evaluation_row,
+        codeword_len,
+        transcript,
+    )?;
+    let (columns, paths) = queries
+        .iter()
+        .map(|&j| {
+            matrices
+                .iter()
+                .map(|matrix| (column(&matrix.rows, j), matrix.tree.path(j)))
+                .unzip()
+        })
+        .unzip();
+
+    end_timer!(open_timer);
+    Ok(BrakedownProof {
+        proximity_row,
+        evaluation_row,
+        columns,
+        paths,
+    })
+}
+
+/// Verify the evaluation `value` at `point` of `\sum_i scalars_i * poly_i`,
+/// where `poly_i` is committed inside `commitments_i`.
+fn verify_internal<F: PrimeField, T: Transcript<F>>(
+    params: &BrakedownParams,
+    commitments: &[BrakedownCommitment],
+    scalars: &[F],
+    point: &[F],
+    value: &F,
+    proof: &BrakedownProof<F>,
+    transcript: &mut T,
+) -> Result<bool, PCSError> {
+    let (col_vars, row_vars) = matrix_dims(point.len());
+    let num_cols = 1 << col_vars;
+    let num_rows = 1 << row_vars;
+    let code = BrakedownCode::new(num_cols);
+    let codeword_len = code.codeword_len();
+
+    // sanity checks on the shape of the proof
+    if proof.proximity_row.len() != num_cols
+        || proof.evaluation_row.len() != num_cols
+        || proof.columns.len() != params.num_queries
+        || proof.paths.len() != params.num_queries
+        || proof
+            .columns
+            .iter()
+            .zip(proof.paths.iter())
+            .any(|(columns, paths)| {
+                columns.len() != commitments.len()
+                    || paths.len() != commitments.len()
+                    || columns.iter().any(|column| column.len() != num_rows)
+            })
+    {
+        return Ok(false);
+    }
+    let verify_timer = start_timer!(|| "verify");
+
+    let roots: Vec<_> = commitments.iter().map(|comm| comm.0).collect();
+    let gamma = proximity_challenges(&roots, point, num_rows, transcript)?;
+    let queries = query_indices(
+        params,
+        &proof.proximity_row,
+        &proof.evaluation_row,
+        codeword_len,
+        transcript,
+    )?;
+
+    let encoded_proximity_row = code.encode(&proof.proximity_row);
+    let encoded_evaluation_row = code.encode(&proof.evaluation_row);
+    let eq_hi = eq_table(&point[col_vars..])?;
+    let mut res = true;
+    'queries: for ((&j, columns), paths) in queries
+        .iter()
+        .zip(proof.columns.iter())
+        .zip(proof.paths.iter())
+    {
+        let mut proximity_eval = F::zero();
+        let mut evaluation_eval = F::zero();
+        for (i, (column, path)) in columns.iter().zip(paths.iter()).enumerate() {
+            if path.len() != log2(codeword_len) as usize
+                || !verify_path(&roots[i], hash_column(column)?, j, path)
+            {
+                res = false;
+                break 'queries;
+            }
+            let gamma_i = &gamma[i * num_rows..(i + 1) * num_rows];
+            proximity_eval += inner_product(gamma_i, column);
+            evaluation_eval += scalars[i] * inner_product(&eq_hi, column);
+        }
+        if proximity_eval != encoded_proximity_row[j]
+            || evaluation_eval != encoded_evaluation_row[j]
+        {
+            res = false;
+            break;
+        }
+    }
+
+    if res {
+        let eq_lo = eq_table(&point[..col_vars])?;
+        res = inner_product(&proof.evaluation_row, &eq_lo) == *value;
+    }
+    end_timer!(verify_timer);
+    Ok(res)
+}
+
+/// `\sum_i coeffs_i * rows_i`
+fn combine_rows<'a, F: PrimeField>(
+    coeffs: &[F],
+    rows: impl Iterator<Item = &'a [F]>,
+    num_cols: usize,
+) -> Vec<F> {
+    let mut res = vec![F::zero(); num_cols];
+    for (coeff, row) in coeffs.iter().zip(rows) {
+        res.iter_mut()
+            .zip(row.iter())
+            .for_each(|(res, eval)| *res += *coeff * eval);
+    }
+    res
+}
+
+fn inner_product<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b.iter()).map(|(a, b)| *a * b).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_poly::MultilinearExtension;
+    use ark_std::{test_rng, vec::Vec, UniformRand, Zero};
+
+    type E = Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+
+    fn test_single_helper<R: Rng>(
+        params: &BrakedownParams,
+        poly: &Arc<DenseMultilinearExtension<Fr>>,
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        let nv = poly.num_vars();
+        let (ck, vk) = BrakedownPCS::<E>::trim(params, None, Some(nv))?;
+        let point: Vec<_> = (0..nv).map(|_| Fr::rand(rng)).collect();
+        let com = BrakedownPCS::<E>::commit(ck, poly)?;
+        let (proof, value) = BrakedownPCS::<E>::open(ck, poly, &point)?;
+        assert_eq!(value, poly.evaluate(&point).unwrap());
+
+        assert!(BrakedownPCS::<E>::verify(
+            &vk, &com, &point, &value, &proof
+        )?);
+
+        let value = Fr::rand(rng);
+        assert!(!BrakedownPCS::<E>::verify(
+            &vk, &com, &point, &value, &proof
+        )?);
+
+        // a proof for another polynomial is rejected
+        let other = Arc::new(DenseMultilinearExtension::rand(nv, rng));
+        let (other_proof, other_value) = BrakedownPCS::<E>::open(ck, &other, &point)?;
+        assert!(!BrakedownPCS::<E>::verify(
+            &vk,
+            &com,
+            &point,
+            &other_value,
+            &other_proof
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_commit() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+
+        let params = BrakedownPCS::<E>::gen_srs_for_testing(&mut rng, 10)?;
+
+        for nv in [1, 2, 5, 8] {
+            let poly = Arc::new(DenseMultilinearExtension::rand(nv, &mut rng));
+            test_single_helper(&params, &poly, &mut rng)?;
+        }
+
+        // a tampered column is rejected
+        let (ck, vk) = BrakedownPCS::<E>::trim(params, None, Some(4))?;
+        let poly = Arc::new(DenseMultilinearExtension::rand(4, &mut rng));
+        let point: Vec<_> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+        let com = BrakedownPCS::<E>::commit(ck, &poly)?;
+        let (mut proof, value) = BrakedownPCS::<E>::open(ck, &poly, &point)?;
+        proof.columns[0][0][0] += Fr::one();
+        assert!(!BrakedownPCS::<E>::verify(
+            &vk, &com, &point, &value, &proof
+        )?);
+
+        // too many variables
+        let poly = Arc::new(DenseMultilinearExtension::rand(5, &mut rng));
+        assert!(BrakedownPCS::<E>::commit(ck, &poly).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_open() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+
+        let params = BrakedownPCS::<E>::gen_srs_for_testing(&mut rng, 10)?;
+        let nv = 7;
+        let (ck, vk) = BrakedownPCS::<E>::trim(params, None, Some(nv))?;
+        let polys: Vec<_> = (0..3)
+            .map(|_| Arc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng)))
+            .collect();
+        let points: Vec<Vec<_>> = (0..3)
+            .map(|_| (0..nv).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        // the same polynomials at several points, and several polynomials at
+        // the same point
+        let opened_polys = vec![
+            polys[0].clone(),
+            polys[0].clone(),
+            polys[1].clone(),
+            polys[2].clone(),
+            polys[2].clone(),
+        ];
+        let opened_points = vec![
+            points[0].clone(),
+            points[1].clone(),
+            points[1].clone(),
+            points[1].clone(),
+            points[2].clone(),
+        ];
+        let evals: Vec<_> = opened_polys
+            .iter()
+            .zip(opened_points.iter())
+            .map(|(poly, point)| poly.evaluate(point).unwrap())
+            .collect();
+        let commitments = opened_polys
+            .iter()
+            .map(|poly| BrakedownPCS::<E>::commit(ck, poly))
+            .collect::<Result<Vec<_>, PCSError>>()?;
+
+        let mut transcript = IOPTranscript::new("test transcript".as_ref());
+        transcript.append_field_element("init".as_ref(), &Fr::zero())?;
+        let batch_proof = BrakedownPCS::<E>::multi_open(
+            ck,
+            &opened_polys,
+            &opened_points,
+            &evals,
+            &mut transcript,
+        )?;
+        // one column per distinct polynomial
+        assert_eq!(batch_proof.g_prime_proof.columns[0].len(), 3);
+
+        let mut transcript = IOPTranscript::new("test transcript".as_ref());
+        transcript.append_field_element("init".as_ref(), &Fr::zero())?;
+        assert!(BrakedownPCS::<E>::batch_verify(
+            &vk,
+            &commitments,
+            &opened_points,
+            &batch_proof,
+            &mut transcript
+        )?);
+
+        // a wrong evaluation is rejected
+        let mut bad_proof = batch_proof;
+        bad_proof.f_i_eval_at_point_i[3] += Fr::one();
+        let mut transcript = IOPTranscript::new("test transcript".as_ref());
+        transcript.append_field_element("init".as_ref(), &Fr::zero())?;
+        assert!(!BrakedownPCS::<E>::batch_verify(
+            &vk,
+            &commitments,
+            &opened_points,
+            &bad_proof,
+            &mut transcript
+        )
+        .unwrap_or(false));
+
+        Ok(())
+    }
+}
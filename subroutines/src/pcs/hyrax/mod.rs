@@ -0,0 +1,639 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Hyrax polynomial commitment scheme, a transparent
+//! multilinear commitment with square-root size commitments
+//! [WTSTW18](https://eprint.iacr.org/2017/1132.pdf).
+//!
+//! The `2^n` evaluations of a polynomial are arranged in a matrix `M` of
+//! `2^{n - m}` rows and `2^m` columns, with `m = ceil(n / 2)`, so that
+//!
+//! `f(z) = eq(z_hi)^T M eq(z_lo)`
+//!
+//! where `eq(z_lo)` (resp. `eq(z_hi)`) is the table of `eq(x, z_lo)` over the
+//! first `m` (resp. last `n - m`) variables. The commitment is the list of the
+//! Pedersen commitments to the rows of `M`. To open at `z`, the verifier
+//! combines the row commitments with `eq(z_hi)` into a commitment to the row
+//! `t = eq(z_hi)^T M`, and the prover shows `<t, eq(z_lo)> = f(z)` with a
+//! Bulletproofs inner product argument.
+//!
+//! The scheme works over any curve, needs neither a trusted setup nor
+//! pairings, and has commitments of `2^{n - m}` group elements and proofs of
+//! `2m` group elements.
+
+use crate::pcs::{
+    multilinear_kzg::batching::{batch_sum_check_prove, batch_sum_check_verify, merge_scalars},
+    prelude::{BatchProof, PCSError},
+    PolynomialCommitmentScheme,
+};
+use arithmetic::build_eq_x_r_vec;
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::{Field, PrimeField};
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+    borrow::Borrow,
+    cfg_chunks, end_timer, format,
+    marker::PhantomData,
+    rand::{Rng, SeedableRng},
+    start_timer,
+    string::ToString,
+    vec,
+    vec::Vec,
+    One, Zero,
+};
+use rand_chacha::ChaCha20Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::{ParallelIterator, ParallelSlice};
+use std::sync::Arc;
+use transcript::{IOPTranscript, Transcript};
+
+/// Seed of the generators of the Pedersen commitments
+const GENERATORS_SEED: [u8; 32] = *b"HyperPlonk Hyrax generators seed";
+
+/// Hyrax Polynomial Commitment Scheme on multilinear polynomials, over the
+/// curve `G`.
+pub struct HyraxPCS<G: CurveGroup> {
+    #[doc(hidden)]
+    phantom: PhantomData<G>,
+}
+
+/// Public parameters of the Hyrax commitment scheme: generators with unknown
+/// discrete logarithms, derived from a fixed seed.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct HyraxParams<G: CurveGroup> {
+    /// Maximum number of variables of the committed polynomials
+    pub num_vars: usize,
+    /// The generators of the row commitments
+    pub generators: Vec<G::Affine>,
+    /// The generator of the inner product in the inner product argument
+    pub u: G::Affine,
+}
+
+impl<G: CurveGroup> HyraxParams<G> {
+    /// Parameters for polynomials of up to `num_vars` variables.
+    pub fn new(num_vars: usize) -> Self {
+        let (col_vars, _) = matrix_dims(num_vars);
+        let mut rng = ChaCha20Rng::from_seed(GENERATORS_SEED);
+        // random points are sampled from their coordinates, with no known
+        // discrete logarithm
+        let u = G::rand(&mut rng);
+        let generators: Vec<_> = (0..1 << col_vars).map(|_| G::rand(&mut rng)).collect();
+        Self {
+            num_vars,
+            generators: G::normalize_batch(&generators),
+            u: u.into_affine(),
+        }
+    }
+}
+
+/// A commitment: the Pedersen commitments to the rows of the matrix of
+/// evaluations
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct HyraxCommitment<G: CurveGroup> {
+    /// The row commitments
+    pub rows: Vec<G::Affine>,
+}
+
+/// An inner product argument proving the evaluation of a committed polynomial
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct HyraxProof<G: CurveGroup> {
+    /// The cross terms `L_j` of each round
+    pub l_vec: Vec<G::Affine>,
+    /// The cross terms `R_j` of each round
+    pub r_vec: Vec<G::Affine>,
+    /// The folded row
+    pub a: G::ScalarField,
+}
+
+impl<E, G> PolynomialCommitmentScheme<E> for HyraxPCS<G>
+where
+    E: Pairing,
+    G: CurveGroup<ScalarField = E::ScalarField>,
+{
+    // Parameters
+    type ProverParam = HyraxParams<G>;
+    type VerifierParam = HyraxParams<G>;
+    type SRS = HyraxParams<G>;
+    // Polynomial and its associated types
+    type Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>;
+    type Point = Vec<E::ScalarField>;
+    type Evaluation = E::ScalarField;
+    // Commitments and proofs
+    type Commitment = HyraxCommitment<G>;
+    type Proof = HyraxProof<G>;
+    type BatchProof = BatchProof<E, Self>;
+
+    /// Build the parameters for polynomials of up to `log_size` variables.
+    ///
+    /// The scheme is transparent: the parameters are derived from a fixed
+    /// seed, sample no secret and are safe to use in production.
+    fn gen_srs_for_testing<R: Rng>(_rng: &mut R, log_size: usize) -> Result<Self::SRS, PCSError> {
+        Ok(HyraxParams::new(log_size))
+    }
+
+    /// Trim the parameters to `supported_num_vars` variables.
+    fn trim(
+        srs: impl Borrow<Self::SRS>,
+        supported_degree: Option<usize>,
+        supported_num_vars: Option<usize>,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), PCSError> {
+        assert!(supported_degree.is_none());
+
+        let supported_num_vars = match supported_num_vars {
+            Some(p) => p,
+            None => {
+                return Err(PCSError::InvalidParameters(
+                    "multilinear should receive a num_var param".to_string(),
+                ))
+            },
+        };
+        let srs = srs.borrow();
+        if supported_num_vars > srs.num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "SRS does not support target number of vars {}",
+                supported_num_vars
+            )));
+        }
+        let (col_vars, _) = matrix_dims(supported_num_vars);
+        let params = HyraxParams {
+            num_vars: supported_num_vars,
+            generators: srs.generators[..1 << col_vars].to_vec(),
+            u: srs.u,
+        };
+        Ok((params.clone(), params))
+    }
+
+    /// Generate a commitment for a polynomial.
+    ///
+    /// This function takes `2^num_vars` number of scalar multiplications over
+    /// G, in `2^{n - m}` MSMs of size `2^m`.
+    fn commit(
+        prover_param: impl Borrow<Self::ProverParam>,
+        poly: &Self::Polynomial,
+    ) -> Result<Self::Commitment, PCSError> {
+        let prover_param = prover_param.borrow();
+        check_num_vars(prover_param, poly.num_vars)?;
+        let commit_timer = start_timer!(|| "commit");
+        let (col_vars, _) = matrix_dims(poly.num_vars);
+        let generators = &prover_param.generators[..1 << col_vars];
+        let rows: Vec<_> = cfg_chunks!(poly.evaluations, 1 << col_vars)
+            .map(|row| G::msm_unchecked(generators, row))
+            .collect();
+        end_timer!(commit_timer);
+        Ok(HyraxCommitment {
+            rows: G::normalize_batch(&rows),
+        })
+    }
+
+    /// On input a polynomial `p` and a point `point`, outputs a proof for the
+    /// same.
+    fn open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomial: &Self::Polynomial,
+        point: &Self::Point,
+    ) -> Result<(Self::Proof, Self::Evaluation), PCSError> {
+        let mut transcript = IOPTranscript::new(b"Hyrax open");
+        open_internal(prover_param.borrow(), polynomial, point, &mut transcript)
+    }
+
+    /// Input a list of multilinear extensions, and a same number of points, and
+    /// a transcript, compute a multi-opening for all the polynomials.
+    ///
+    /// A sum check reduces the openings to the opening of `g'(X) = \sum_i
+    /// scalar_i * f_i(X)` at one point, whose row commitments the verifier
+    /// derives from the ones of the `f_i`.
+    fn multi_open<T: Transcript<E::ScalarField>>(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomials: &[Self::Polynomial],
+        points: &[Self::Point],
+        evals: &[Self::Evaluation],
+        transcript: &mut T,
+    ) -> Result<BatchProof<E, Self>, PCSError> {
+        let prover_param = prover_param.borrow();
+        let open_timer = start_timer!(|| format!("multi open {} points", points.len()));
+
+        let (sum_check_proof, _scalars, g_prime) =
+            batch_sum_check_prove(polynomials, points, transcript)?;
+        let (g_prime_proof, _g_prime_eval) =
+            open_internal(prover_param, &g_prime, &sum_check_proof.point, transcript)?;
+
+        end_timer!(open_timer);
+        Ok(BatchProof {
+            sum_check_proof,
+            f_i_eval_at_point_i: evals.to_vec(),
+            g_prime_proof,
+        })
+    }
+
+    /// Verifies that `value` is the evaluation at `x` of the polynomial
+    /// committed inside `comm`.
+    ///
+    /// This function takes an MSM of size `2^{n - m}` and one of size `2^m +
+    /// 2m + 2`.
+    fn verify(
+        verifier_param: &Self::VerifierParam,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &E::ScalarField,
+        proof: &Self::Proof,
+    ) -> Result<bool, PCSError> {
+        let mut transcript = IOPTranscript::new(b"Hyrax open");
+        verify_internal(
+            verifier_param,
+            &[commitment],
+            &[E::ScalarField::one()],
+            point,
+            value,
+            proof,
+            &mut transcript,
+        )
+    }
+
+    /// Verifies that `value_i` is the evaluation at `x_i` of the polynomial
+    /// `poly_i` committed inside `comm`.
+    fn batch_verify<T: Transcript<E::ScalarField>>(
+        verifier_param: &Self::VerifierParam,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        batch_proof: &Self::BatchProof,
+        transcript: &mut T,
+    ) -> Result<bool, PCSError> {
+        if commitments.len() != points.len() {
+            return Err(PCSError::InvalidParameters(format!(
+                "number of commitments ({}) does not match the number of points ({})",
+                commitments.len(),
+                points.len()
+            )));
+        }
+        let verify_timer = start_timer!(|| "batch verification");
+
+        let res = batch_sum_check_verify(
+            points,
+            &batch_proof.sum_check_proof,
+            &batch_proof.f_i_eval_at_point_i,
+            transcript,
+        )
+        .and_then(|(scalars, g_prime_eval)| {
+            // group the scalars of the same commitments
+            let commitments: Vec<_> = commitments.iter().collect();
+            let (commitments, scalars) = merge_scalars(&commitments, &scalars);
+            verify_internal(
+                verifier_param,
+                &commitments,
+                &scalars,
+                &batch_proof.sum_check_proof.point,
+                &g_prime_eval,
+                &batch_proof.g_prime_proof,
+                transcript,
+            )
+        });
+
+        end_timer!(verify_timer);
+        res
+    }
+}
+
+fn check_num_vars<G: CurveGroup>(params: &HyraxParams<G>, num_vars: usize) -> Result<(), PCSError> {
+    if num_vars > params.num_vars {
+        return Err(PCSError::InvalidParameters(format!(
+            "number of variables {} exceeds the supported {}",
+            num_vars, params.num_vars
+        )));
+    }
+    Ok(())
+}
+
+/// Number of variables indexing the columns and the rows of the matrix of a
+/// polynomial of `num_vars` variables
+fn matrix_dims(num_vars: usize) -> (usize, usize) {
+    let col_vars = num_vars.div_ceil(2);
+    (col_vars, num_vars - col_vars)
+}
+
+/// `eq(x, point)` for all `x`, with `[1]` for an empty point
+fn eq_table<F: PrimeField>(point: &[F]) -> Result<Vec<F>, PCSError> {
+    if point.is_empty() {
+        Ok(vec![F::one()])
+    } else {
+        Ok(build_eq_x_r_vec(point)?)
+    }
+}
+
+fn inner_product<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b.iter()).map(|(a, b)| *a * b).sum()
+}
+
+/// The commitment `P = <t, G> + <t, b> U` to the row `t` and its inner
+/// product with `b`, where `U` is the generator `u` scaled by a challenge.
+fn ipa_challenge_generator<G: CurveGroup, T: Transcript<G::ScalarField>>(
+    params: &HyraxParams<G>,
+    row_commitment: &G,
+    point: &[G::ScalarField],
+    value: &G::ScalarField,
+    transcript: &mut T,
+) -> Result<G, PCSError> {
+    transcript.append_serializable_element(b"row commitment", &row_commitment.into_affine())?;
+    transcript.append_serializable_element(b"point", &point.to_vec())?;
+    transcript.append_field_element(b"value", value)?;
+    let c = transcript.get_and_append_challenge(b"u")?;
+    Ok(params.u * c)
+}
+
+/// Prove the evaluation of `polynomial` at `point`.
+///
+/// This function takes `2^{n - m + 1}` field multiplications for the row, and
+/// about `2^{m + 2}` scalar multiplications over G for the inner product
+/// argument.
+fn open_internal<G: CurveGroup, T: Transcript<G::ScalarField>>(
+    params: &HyraxParams<G>,
+    polynomial: &DenseMultilinearExtension<G::ScalarField>,
+    point: &[G::ScalarField],
+    transcript: &mut T,
+) -> Result<(HyraxProof<G>, G::ScalarField), PCSError> {
+    let open_timer = start_timer!(|| format!("open mle with {} variable", polynomial.num_vars));
+    check_num_vars(params, polynomial.num_vars)?;
+    if polynomial.num_vars != point.len() {
+        return Err(PCSError::InvalidParameters(format!(
+            "Polynomial num_vars {} does not match point len {}",
+            polynomial.num_vars,
+            point.len()
+        )));
+    }
+    let (col_vars, _) = matrix_dims(point.len());
+    let num_cols = 1 << col_vars;
+
+    // t = eq(z_hi)^T M
+    let eq_hi = eq_table(&point[col_vars..])?;
+    let mut t = vec![G::ScalarField::zero(); num_cols];
+    for (coeff, row) in eq_hi.iter().zip(polynomial.evaluations.chunks(num_cols)) {
+        t.iter_mut()
+            .zip(row.iter())
+            .for_each(|(t, eval)| *t += *coeff * eval);
+    }
+    let mut b = eq_table(&point[..col_vars])?;
+    let value = inner_product(&t, &b);
+
+    let mut generators: Vec<G> = params.generators[..num_cols]
+        .iter()
+        .map(|g| (*g).into())
+        .collect();
+    let row_commitment = G::msm_unchecked(&params.generators[..num_cols], &t);
+    let u = ipa_challenge_generator(params, &row_commitment, point, &value, transcript)?;
+
+    let mut l_vec = Vec::with_capacity(col_vars);
+    let mut r_vec = Vec::with_capacity(col_vars);
+    while t.len() > 1 {
+        let half = t.len() >> 1;
+        let (t_l, t_r) = t.split_at(half);
+        let (b_l, b_r) = b.split_at(half);
+        let (g_l, g_r) = generators.split_at(half);
+        let g_l = G::normalize_batch(g_l);
+        let g_r = G::normalize_batch(g_r);
+
+        // L = <t_L, G_R> + <t_L, b_R> U, R = <t_R, G_L> + <t_R, b_L> U
+        let l = G::msm_unchecked(&g_r, t_l) + u * inner_product(t_l, b_r);
+        let r = G::msm_unchecked(&g_l, t_r) + u * inner_product(t_r, b_l);
+        transcript.append_serializable_element(b"L", &l.into_affine())?;
+        transcript.append_serializable_element(b"R", &r.into_affine())?;
+        let x = transcript.get_and_append_challenge(b"x")?;
+        let x_inv = x.inverse().ok_or_else(|| {
+            PCSError::InvalidProver("inner product challenge is zero".to_string())
+        })?;
+        l_vec.push(l);
+        r_vec.push(r);
+
+        // t' = x t_L + x^{-1} t_R, b' = x^{-1} b_L + x b_R, G' = x^{-1} G_L + x G_R
+        t = t_l
+            .iter()
+            .zip(t_r.iter())
+            .map(|(t_l, t_r)| x * t_l + x_inv * t_r)
+            .collect();
+        b = b_l
+            .iter()
+            .zip(b_r.iter())
+            .map(|(b_l, b_r)| x_inv * b_l + x * b_r)
+            .collect();
+        generators = g_l
+            .iter()
+            .zip(g_r.iter())
+            .map(|(g_l, g_r)| *g_l * x_inv + *g_r * x)
+            .collect();
+    }
+
+    end_timer!(open_timer);
+    Ok((
+        HyraxProof {
+            l_vec: G::normalize_batch(&l_vec),
+            r_vec: G::normalize_batch(&r_vec),
+            a: t[0],
+        },
+        value,
+    ))
+}
+
+/// Verify the evaluation `value` at `point` of `\sum_i scalars_i * poly_i`,
+/// where `poly_i` is committed inside `commitments_i`.
+fn verify_internal<G: CurveGroup, T: Transcript<G::ScalarField>>(
+    params: &HyraxParams<G>,
+    commitments: &[&HyraxCommitment<G>],
+    scalars: &[G::ScalarField],
+    point: &[G::ScalarField],
+    value: &G::ScalarField,
+    proof: &HyraxProof<G>,
+    transcript: &mut T,
+) -> Result<bool, PCSError> {
+    check_num_vars(params, point.len())?;
+    let (col_vars, row_vars) = matrix_dims(point.len());
+    let num_cols = 1 << col_vars;
+
+    // sanity checks on the shape of the commitments and of the proof
+    if commitments
+        .iter()
+        .any(|commitment| commitment.rows.len() != 1 << row_vars)
+        || proof.l_vec.len() != col_vars
+        || proof.r_vec.len() != col_vars
+    {
+        return Ok(false);
+    }
+    let verify_timer = start_timer!(|| "verify");
+
+    // the commitment to t = eq(z_hi)^T M
+    let eq_hi = eq_table(&point[col_vars..])?;
+    let (bases, row_scalars): (Vec<_>, Vec<_>) = commitments
+        .iter()
+        .zip(scalars.iter())
+        .flat_map(|(commitment, scalar)| {
+            commitment
+                .rows
+                .iter()
+                .zip(eq_hi.iter())
+                .map(move |(row, eq)| (*row, *scalar * eq))
+        })
+        .unzip();
+    let row_commitment = G::msm_unchecked(&bases, &row_scalars);
+    let u = ipa_challenge_generator(params, &row_commitment, point, value, transcript)?;
+
+    let mut challenges = Vec::with_capacity(col_vars);
+    for (l, r) in proof.l_vec.iter().zip(proof.r_vec.iter()) {
+        transcript.append_serializable_element(b"L", l)?;
+        transcript.append_serializable_element(b"R", r)?;
+        let x = transcript.get_and_append_challenge(b"x")?;
+        let x_inv = match x.inverse() {
+            Some(x_inv) => x_inv,
+            None => return Ok(false),
+        };
+        challenges.push((x, x_inv));
+    }
+
+    // s_i = \prod_j x_j^{+-1}, the coefficients of the folded generator, where
+    // the first round folds the top bit of i
+    let mut s = vec![G::ScalarField::one()];
+    for (x, x_inv) in challenges.iter().rev() {
+        s = s
+            .iter()
+            .map(|s| *s * x_inv)
+            .chain(s.iter().map(|s| *s * x))
+            .collect();
+    }
+    let b = inner_product(&s, &eq_table(&point[..col_vars])?);
+
+    // P + \sum_j (x_j^2 L_j + x_j^{-2} R_j) = a <s, G> + a b U
+    let mut bases: Vec<_> = proof
+        .l_vec
+        .iter()
+        .chain(proof.r_vec.iter())
+        .copied()
+        .collect();
+    let mut final_scalars: Vec<_> = challenges
+        .iter()
+        .map(|(x, _)| x.square())
+        .chain(challenges.iter().map(|(_, x_inv)| x_inv.square()))
+        .collect();
+    bases.extend_from_slice(&params.generators[..num_cols]);
+    final_scalars.extend(s.iter().map(|s| -proof.a * s));
+    let res =
+        G::msm_unchecked(&bases, &final_scalars) + row_commitment + u * *value - u * (proof.a * b);
+
+    end_timer!(verify_timer);
+    Ok(res.is_zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, G1Projective};
+    use ark_poly::MultilinearExtension;
+    use ark_std::{test_rng, UniformRand};
+
+    type E = Bls12_381;
+    type G = G1Projective;
+    type Fr = <E as Pairing>::ScalarField;
+    type Hyrax = HyraxPCS<G>;
+
+    fn test_single_helper<R: Rng>(
+        params: &HyraxParams<G>,
+        poly: &Arc<DenseMultilinearExtension<Fr>>,
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        let nv = poly.num_vars();
+        let (ck, vk) = <Hyrax as PolynomialCommitmentScheme<E>>::trim(params, None, Some(nv))?;
+        let point: Vec<_> = (0..nv).map(|_| Fr::rand(rng)).collect();
+        let com = <Hyrax as PolynomialCommitmentScheme<E>>::commit(&ck, poly)?;
+        let (proof, value) = <Hyrax as PolynomialCommitmentScheme<E>>::open(&ck, poly, &point)?;
+        assert_eq!(value, poly.evaluate(&point).unwrap());
+
+        assert!(<Hyrax as PolynomialCommitmentScheme<E>>::verify(
+            &vk, &com, &point, &value, &proof
+        )?);
+
+        let value = Fr::rand(rng);
+        assert!(!<Hyrax as PolynomialCommitmentScheme<E>>::verify(
+            &vk, &com, &point, &value, &proof
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_commit() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+
+        let params = <Hyrax as PolynomialCommitmentScheme<E>>::gen_srs_for_testing(&mut rng, 10)?;
+
+        for nv in [0, 1, 2, 5, 8, 10] {
+            let poly = Arc::new(DenseMultilinearExtension::rand(nv, &mut rng));
+            test_single_helper(&params, &poly, &mut rng)?;
+        }
+
+        // too many variables
+        let (ck, _vk) = <Hyrax as PolynomialCommitmentScheme<E>>::trim(&params, None, Some(4))?;
+        let poly = Arc::new(DenseMultilinearExtension::rand(5, &mut rng));
+        assert!(<Hyrax as PolynomialCommitmentScheme<E>>::commit(&ck, &poly).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_open() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+
+        let params = <Hyrax as PolynomialCommitmentScheme<E>>::gen_srs_for_testing(&mut rng, 10)?;
+        let nv = 7;
+        let (ck, vk) = <Hyrax as PolynomialCommitmentScheme<E>>::trim(&params, None, Some(nv))?;
+        let polys: Vec<_> = (0..3)
+            .map(|_| Arc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng)))
+            .collect();
+        let points: Vec<Vec<_>> = (0..3)
+            .map(|_| (0..nv).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        let evals: Vec<_> = polys
+            .iter()
+            .zip(points.iter())
+            .map(|(poly, point)| poly.evaluate(point).unwrap())
+            .collect();
+        let commitments = polys
+            .iter()
+            .map(|poly| <Hyrax as PolynomialCommitmentScheme<E>>::commit(&ck, poly))
+            .collect::<Result<Vec<_>, PCSError>>()?;
+
+        let mut transcript = IOPTranscript::new("test transcript".as_ref());
+        transcript.append_field_element("init".as_ref(), &Fr::zero())?;
+        let batch_proof = <Hyrax as PolynomialCommitmentScheme<E>>::multi_open(
+            &ck,
+            &polys,
+            &points,
+            &evals,
+            &mut transcript,
+        )?;
+
+        let mut transcript = IOPTranscript::new("test transcript".as_ref());
+        transcript.append_field_element("init".as_ref(), &Fr::zero())?;
+        assert!(<Hyrax as PolynomialCommitmentScheme<E>>::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &batch_proof,
+            &mut transcript
+        )?);
+
+        // a wrong evaluation is rejected
+        let mut bad_proof = batch_proof;
+        bad_proof.f_i_eval_at_point_i[1] += Fr::one();
+        let mut transcript = IOPTranscript::new("test transcript".as_ref());
+        transcript.append_field_element("init".as_ref(), &Fr::zero())?;
+        assert!(!<Hyrax as PolynomialCommitmentScheme<E>>::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &bad_proof,
+            &mut transcript
+        )
+        .unwrap_or(false));
+
+        Ok(())
+    }
+}
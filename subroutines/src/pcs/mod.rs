@@ -9,8 +9,11 @@ pub mod errors;
 mod structs;
 mod univariate_kzg;
 
+pub mod brakedown;
+pub mod hyrax;
 pub mod multilinear_kzg;
 pub mod prelude;
+pub mod zeromorph;
 
 use ark_ec::pairing::Pairing;
 use ark_ff::Field;
@@ -18,7 +21,7 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::rand::Rng;
 use errors::PCSError;
 use std::{borrow::Borrow, fmt::Debug, hash::Hash};
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
 /// This trait defines APIs for polynomial commitment schemes.
 /// Note that for our usage of PCS, we do not require the hiding property.
@@ -36,7 +39,14 @@ pub trait PolynomialCommitmentScheme<E: Pairing> {
     /// Polynomial Evaluation
     type Evaluation: Field;
     /// Commitments
-    type Commitment: Clone + CanonicalSerialize + CanonicalDeserialize + Debug + PartialEq + Eq;
+    type Commitment: Clone
+        + CanonicalSerialize
+        + CanonicalDeserialize
+        + Debug
+        + PartialEq
+        + Eq
+        + Send
+        + Sync;
     /// Proofs
     type Proof: Clone + CanonicalSerialize + CanonicalDeserialize + Debug + PartialEq + Eq;
     /// Batch proofs
@@ -95,12 +105,12 @@ pub trait PolynomialCommitmentScheme<E: Pairing> {
 
     /// Input a list of multilinear extensions, and a same number of points, and
     /// a transcript, compute a multi-opening for all the polynomials.
-    fn multi_open(
+    fn multi_open<T: Transcript<E::ScalarField>>(
         _prover_param: impl Borrow<Self::ProverParam>,
         _polynomials: &[Self::Polynomial],
         _points: &[Self::Point],
         _evals: &[Self::Evaluation],
-        _transcript: &mut IOPTranscript<E::ScalarField>,
+        _transcript: &mut T,
     ) -> Result<Self::BatchProof, PCSError> {
         // the reason we use unimplemented!() is to enable developers to implement the
         // trait without always implementing the batching APIs.
@@ -119,12 +129,12 @@ pub trait PolynomialCommitmentScheme<E: Pairing> {
 
     /// Verifies that `value_i` is the evaluation at `x_i` of the polynomial
     /// `poly_i` committed inside `comm`.
-    fn batch_verify(
+    fn batch_verify<T: Transcript<E::ScalarField>>(
         _verifier_param: &Self::VerifierParam,
         _commitments: &[Self::Commitment],
         _points: &[Self::Point],
         _batch_proof: &Self::BatchProof,
-        _transcript: &mut IOPTranscript<E::ScalarField>,
+        _transcript: &mut T,
     ) -> Result<bool, PCSError> {
         // the reason we use unimplemented!() is to enable developers to implement the
         // trait without always implementing the batching APIs.
@@ -132,6 +142,48 @@ pub trait PolynomialCommitmentScheme<E: Pairing> {
     }
 }
 
+/// A polynomial commitment scheme whose batch verification can be deferred
+/// into an accumulator, so that the batch openings of many proofs are checked
+/// together.
+///
+/// The accumulator is a plain value: it can be combined with others, and
+/// checked later, e.g. by a recursive circuit or an on-chain verifier.
+pub trait AccumulationScheme<E: Pairing>: PolynomialCommitmentScheme<E> {
+    /// The deferred check of one or several batch openings
+    type Accumulator: Clone
+        + CanonicalSerialize
+        + CanonicalDeserialize
+        + Debug
+        + PartialEq
+        + Eq
+        + Send
+        + Sync;
+
+    /// Run all the checks of `batch_verify` but the final one, and return
+    /// it as an accumulator.
+    fn batch_verify_deferred<T: Transcript<E::ScalarField>>(
+        verifier_param: &Self::VerifierParam,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        batch_proof: &Self::BatchProof,
+        transcript: &mut T,
+    ) -> Result<Self::Accumulator, PCSError>;
+
+    /// Combine the `accumulators` with a random linear combination, whose
+    /// coefficient is drawn from the `transcript` after absorbing all of
+    /// them.
+    fn accumulate<T: Transcript<E::ScalarField>>(
+        accumulators: &[Self::Accumulator],
+        transcript: &mut T,
+    ) -> Result<Self::Accumulator, PCSError>;
+
+    /// Run the deferred check of the `accumulator`.
+    fn decide(
+        verifier_param: &Self::VerifierParam,
+        accumulator: &Self::Accumulator,
+    ) -> Result<bool, PCSError>;
+}
+
 /// API definitions for structured reference string
 pub trait StructuredReferenceString<E: Pairing>: Sized {
     /// Prover parameters
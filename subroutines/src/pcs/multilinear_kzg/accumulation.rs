@@ -0,0 +1,237 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Accumulation of multilinear KZG openings.
+//!
+//! An opening proof `pi_1, ..., pi_n` of `value` at `x` for the commitment
+//! `C` is accepted when
+//!
+//! `e(g^value / C, h) * \prod_i e(pi_i, h^{t_i} / h^{x_i}) = 1`,
+//!
+//! which only involves the fixed elements `h` and `h^{t_i}` of G2 once
+//! rewritten as
+//!
+//! `e(g^value / C / \prod_i pi_i^{x_i}, h) * \prod_i e(pi_i, h^{t_i}) = 1`.
+//!
+//! The G1 side of this equation is a [`KzgAccumulator`]. A random linear
+//! combination of accumulators is again an accumulator, so any number of
+//! openings is checked with a single multi-pairing of `num_vars + 1` pairs.
+
+use crate::pcs::{
+    multilinear_kzg::{srs::MultilinearVerifierParam, MultilinearKzgProof},
+    prelude::Commitment,
+    PCSError,
+};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    scalar_mul::variable_base::VariableBaseMSM,
+    CurveGroup,
+};
+use ark_ff::{One, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{end_timer, format, start_timer, string::ToString, vec, vec::Vec};
+use std::ops::Mul;
+use transcript::Transcript;
+
+/// The deferred pairing check of one or several multilinear KZG openings:
+///
+/// `e(g_term, h) * \prod_i e(quotients[i], h^{t_i}) = 1`,
+///
+/// where the quotients are paired with the last `quotients.len()` elements of
+/// `h_mask`.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KzgAccumulator<E: Pairing> {
+    /// the G1 element paired with `h`
+    pub g_term: E::G1Affine,
+    /// the G1 elements paired with `h^{t_i}`
+    pub quotients: Vec<E::G1Affine>,
+}
+
+impl<E: Pairing> KzgAccumulator<E> {
+    /// The accumulator of the opening of `commitment` at `point` to `value`.
+    pub fn from_opening(
+        verifier_param: &MultilinearVerifierParam<E>,
+        commitment: &Commitment<E>,
+        point: &[E::ScalarField],
+        value: &E::ScalarField,
+        proof: &MultilinearKzgProof<E>,
+    ) -> Result<Self, PCSError> {
+        if point.len() > verifier_param.num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "point length ({}) exceeds param limit ({})",
+                point.len(),
+                verifier_param.num_vars
+            )));
+        }
+        if proof.proofs.len() != point.len() {
+            return Err(PCSError::InvalidProof(format!(
+                "number of quotients ({}) does not match the point length ({})",
+                proof.proofs.len(),
+                point.len()
+            )));
+        }
+
+        let g_term = verifier_param.g.mul(*value)
+            - commitment.0
+            - E::G1::msm_unchecked(&proof.proofs, point);
+        Ok(Self {
+            g_term: g_term.into_affine(),
+            quotients: proof.proofs.clone(),
+        })
+    }
+
+    /// Combine the `accumulators` with the powers of a challenge drawn from
+    /// the `transcript` after absorbing all of them.
+    pub fn combine<T: Transcript<E::ScalarField>>(
+        accumulators: &[Self],
+        transcript: &mut T,
+    ) -> Result<Self, PCSError> {
+        let combine_timer = start_timer!(|| "combine accumulators");
+        if accumulators.is_empty() {
+            return Err(PCSError::InvalidParameters(
+                "no accumulator to combine".to_string(),
+            ));
+        }
+        for accumulator in accumulators.iter() {
+            transcript.append_serializable_element(b"accumulator", accumulator)?;
+        }
+        let r = transcript.get_and_append_challenge(b"r")?;
+
+        // the quotients are aligned on the last variables
+        let len = accumulators
+            .iter()
+            .map(|accumulator| accumulator.quotients.len())
+            .max()
+            .unwrap_or_default();
+        let mut g_term = E::G1::zero();
+        let mut quotients = vec![E::G1::zero(); len];
+        let mut coeff = E::ScalarField::one();
+        for accumulator in accumulators.iter() {
+            g_term += accumulator.g_term.mul(coeff);
+            let offset = len - accumulator.quotients.len();
+            for (quotient, pi) in quotients[offset..]
+                .iter_mut()
+                .zip(accumulator.quotients.iter())
+            {
+                *quotient += pi.mul(coeff);
+            }
+            coeff *= r;
+        }
+
+        end_timer!(combine_timer);
+        Ok(Self {
+            g_term: g_term.into_affine(),
+            quotients: E::G1::normalize_batch(&quotients),
+        })
+    }
+
+    /// Run the pairing check of the accumulator.
+    pub fn decide(&self, verifier_param: &MultilinearVerifierParam<E>) -> Result<bool, PCSError> {
+        let decide_timer = start_timer!(|| "decide accumulator");
+        if self.quotients.len() > verifier_param.num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "number of quotients ({}) exceeds param limit ({})",
+                self.quotients.len(),
+                verifier_param.num_vars
+            )));
+        }
+
+        let offset = verifier_param.num_vars - self.quotients.len();
+        let ps = [self.g_term]
+            .into_iter()
+            .chain(self.quotients.iter().copied())
+            .map(E::G1Prepared::from);
+        let hs = [verifier_param.h]
+            .into_iter()
+            .chain(verifier_param.h_mask[offset..].iter().copied())
+            .map(E::G2Prepared::from);
+        let res = E::multi_pairing(ps, hs) == PairingOutput(E::TargetField::one());
+
+        end_timer!(decide_timer);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcs::{
+        prelude::{MultilinearKzgPCS, MultilinearUniversalParams},
+        AccumulationScheme, PolynomialCommitmentScheme, StructuredReferenceString,
+    };
+    use ark_bls12_381::Bls12_381;
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::{sync::Arc, test_rng, UniformRand};
+    use transcript::IOPTranscript;
+
+    type E = Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+
+    #[test]
+    fn test_accumulation() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+        let params = MultilinearUniversalParams::<E>::gen_srs_for_testing(&mut rng, 10)?;
+        let (ck, vk) = MultilinearKzgPCS::trim(&params, None, Some(10))?;
+
+        // single openings with fewer variables, and batch openings
+        let mut accumulators = vec![];
+        for nv in [4, 7, 10] {
+            let (ck, vk) = params.trim(nv)?;
+            let poly = Arc::new(DenseMultilinearExtension::rand(nv, &mut rng));
+            let point: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+            let com = MultilinearKzgPCS::commit(&ck, &poly)?;
+            let (proof, value) = MultilinearKzgPCS::open(&ck, &poly, &point)?;
+            let accumulator = KzgAccumulator::from_opening(&vk, &com, &point, &value, &proof)?;
+            assert!(accumulator.decide(&vk)?);
+            accumulators.push(accumulator);
+        }
+        for _ in 0..2 {
+            let polys: Vec<_> = (0..3)
+                .map(|_| Arc::new(DenseMultilinearExtension::rand(8, &mut rng)))
+                .collect();
+            let points: Vec<Vec<_>> = (0..3)
+                .map(|_| (0..8).map(|_| Fr::rand(&mut rng)).collect())
+                .collect();
+            let evals: Vec<_> = polys
+                .iter()
+                .zip(points.iter())
+                .map(|(poly, point)| poly.evaluate(point).unwrap())
+                .collect();
+            let coms = polys
+                .iter()
+                .map(|poly| MultilinearKzgPCS::commit(&ck, poly))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut transcript = IOPTranscript::new(b"test transcript");
+            transcript.append_field_element(b"init", &Fr::zero())?;
+            let batch_proof =
+                MultilinearKzgPCS::multi_open(&ck, &polys, &points, &evals, &mut transcript)?;
+            let mut transcript = IOPTranscript::new(b"test transcript");
+            transcript.append_field_element(b"init", &Fr::zero())?;
+            accumulators.push(MultilinearKzgPCS::batch_verify_deferred(
+                &vk,
+                &coms,
+                &points,
+                &batch_proof,
+                &mut transcript,
+            )?);
+        }
+
+        let mut transcript = IOPTranscript::new(b"test accumulation");
+        let accumulator = MultilinearKzgPCS::accumulate(&accumulators, &mut transcript)?;
+        assert_eq!(accumulator.quotients.len(), 10);
+        assert!(MultilinearKzgPCS::decide(&vk, &accumulator)?);
+
+        // a single wrong opening fails the combined check
+        let mut bad = accumulators.clone();
+        bad[1].g_term = (bad[1].g_term + vk.g).into_affine();
+        let mut transcript = IOPTranscript::new(b"test accumulation");
+        let accumulator = MultilinearKzgPCS::accumulate(&bad, &mut transcript)?;
+        assert!(!MultilinearKzgPCS::decide(&vk, &accumulator)?);
+
+        Ok(())
+    }
+}
@@ -21,10 +21,11 @@ use crate::{
 };
 use arithmetic::{build_eq_x_r_vec, DenseMultilinearExtension, VPAuxInfo, VirtualPolynomial};
 use ark_ec::{pairing::Pairing, scalar_mul::variable_base::VariableBaseMSM, CurveGroup};
+use ark_ff::PrimeField;
 
-use ark_std::{end_timer, log2, start_timer, One, Zero};
+use ark_std::{end_timer, log2, start_timer, Zero};
 use std::{collections::BTreeMap, iter, marker::PhantomData, ops::Deref, sync::Arc};
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct BatchProof<E, PCS>
@@ -33,11 +34,11 @@ where
     PCS: PolynomialCommitmentScheme<E>,
 {
     /// A sum check proof proving tilde g's sum
-    pub(crate) sum_check_proof: IOPProof<E::ScalarField>,
+    pub sum_check_proof: IOPProof<E::ScalarField>,
     /// f_i(point_i)
     pub f_i_eval_at_point_i: Vec<E::ScalarField>,
     /// proof for g'(a_2)
-    pub(crate) g_prime_proof: PCS::Proof,
+    pub g_prime_proof: PCS::Proof,
 }
 
 /// Steps:
@@ -48,15 +49,16 @@ where
 /// 5. run sumcheck on \sum_i=1..k \tilde eq_i * \tilde g_i
 /// 6. build g'(X) = \sum_i=1..k \tilde eq_i(a2) * \tilde g_i(X) where (a2) is
 /// the sumcheck's point 7. open g'(X) at point (a2)
-pub(crate) fn multi_open_internal<E, PCS>(
+pub(crate) fn multi_open_internal<E, PCS, T>(
     prover_param: &PCS::ProverParam,
     polynomials: &[PCS::Polynomial],
     points: &[PCS::Point],
     evals: &[PCS::Evaluation],
-    transcript: &mut IOPTranscript<E::ScalarField>,
+    transcript: &mut T,
 ) -> Result<BatchProof<E, PCS>, PCSError>
 where
     E: Pairing,
+    T: Transcript<E::ScalarField>,
     PCS: PolynomialCommitmentScheme<
         E,
         Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
@@ -66,6 +68,35 @@ where
 {
     let open_timer = start_timer!(|| format!("multi open {} points", points.len()));
 
+    let (proof, _scalars, g_prime) = batch_sum_check_prove(polynomials, points, transcript)?;
+    let a2 = &proof.point;
+
+    let step = start_timer!(|| "pcs open");
+    let (g_prime_proof, _g_prime_eval) = PCS::open(prover_param, &g_prime, a2)?;
+    // assert_eq!(g_prime_eval, tilde_g_eval);
+    end_timer!(step);
+
+    end_timer!(open_timer);
+
+    Ok(BatchProof {
+        sum_check_proof: proof,
+        f_i_eval_at_point_i: evals.to_vec(),
+        g_prime_proof,
+    })
+}
+
+/// Steps 1 to 6 of `multi_open_internal`, shared by the commitment schemes:
+/// reduce the openings of the `polynomials` at the `points` to an opening of
+/// g'(X) at the sum check point (a2).
+///
+/// Returns the sum check proof, whose point is (a2), the `scalar_i = eq(a2,
+/// point_i) * eq(t, <i>)`, and g'(X) = \sum_i scalar_i * f_i(X).
+#[allow(clippy::type_complexity)]
+pub(crate) fn batch_sum_check_prove<F: PrimeField, T: Transcript<F>>(
+    polynomials: &[Arc<DenseMultilinearExtension<F>>],
+    points: &[Vec<F>],
+    transcript: &mut T,
+) -> Result<(IOPProof<F>, Vec<F>, Arc<DenseMultilinearExtension<F>>), PCSError> {
     // TODO: sanity checks
     let num_var = polynomials[0].num_vars;
     let k = polynomials.len();
@@ -127,14 +158,11 @@ where
     let step = start_timer!(|| "add mle");
     let mut sum_check_vp = VirtualPolynomial::new(num_var);
     for (merged_tilde_g, tilde_eq) in merged_tilde_gs.iter().zip(tilde_eqs.into_iter()) {
-        sum_check_vp.add_mle_list([merged_tilde_g.clone(), tilde_eq], E::ScalarField::one())?;
+        sum_check_vp.add_mle_list([merged_tilde_g.clone(), tilde_eq], F::one())?;
     }
     end_timer!(step);
 
-    let proof = match <PolyIOP<E::ScalarField> as SumCheck<E::ScalarField>>::prove(
-        &sum_check_vp,
-        transcript,
-    ) {
+    let proof = match <PolyIOP<F, T> as SumCheck<F>>::prove(&sum_check_vp, transcript) {
         Ok(p) => p,
         Err(_e) => {
             // cannot wrap IOPError with PCSError due to cyclic dependency
@@ -157,22 +185,14 @@ where
         let eq_i_a2 = eq_eval(a2, point)?;
         *Arc::make_mut(&mut g_prime) += (eq_i_a2, merged_tilde_g.deref());
     }
+    let scalars = points
+        .iter()
+        .zip(eq_t_i_list.iter())
+        .map(|(point, eq_t_i)| Ok(eq_eval(a2, point)? * eq_t_i))
+        .collect::<Result<Vec<_>, PCSError>>()?;
     end_timer!(step);
 
-    let step = start_timer!(|| "pcs open");
-    let (g_prime_proof, _g_prime_eval) = PCS::open(prover_param, &g_prime, a2.to_vec().as_ref())?;
-    // assert_eq!(g_prime_eval, tilde_g_eval);
-    end_timer!(step);
-
-    let step = start_timer!(|| "evaluate fi(pi)");
-    end_timer!(step);
-    end_timer!(open_timer);
-
-    Ok(BatchProof {
-        sum_check_proof: proof,
-        f_i_eval_at_point_i: evals.to_vec(),
-        g_prime_proof,
-    })
+    Ok((proof, scalars, g_prime))
 }
 
 /// Steps:
@@ -180,15 +200,16 @@ where
 /// 2. build g' commitment
 /// 3. ensure \sum_i eq(a2, point_i) * eq(t, <i>) * f_i_evals matches the sum
 /// via SumCheck verification 4. verify commitment
-pub(crate) fn batch_verify_internal<E, PCS>(
+pub(crate) fn batch_verify_internal<E, PCS, T>(
     verifier_param: &PCS::VerifierParam,
     f_i_commitments: &[Commitment<E>],
     points: &[PCS::Point],
     proof: &BatchProof<E, PCS>,
-    transcript: &mut IOPTranscript<E::ScalarField>,
+    transcript: &mut T,
 ) -> Result<bool, PCSError>
 where
     E: Pairing,
+    T: Transcript<E::ScalarField>,
     PCS: PolynomialCommitmentScheme<
         E,
         Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
@@ -199,70 +220,137 @@ where
 {
     let open_timer = start_timer!(|| "batch verification");
 
+    let (g_prime_commit, tilde_g_eval) = batch_reduce(f_i_commitments, points, proof, transcript)?;
+
+    // verify commitment
+    let res = PCS::verify(
+        verifier_param,
+        &g_prime_commit,
+        &proof.sum_check_proof.point,
+        &tilde_g_eval,
+        &proof.g_prime_proof,
+    )?;
+
+    end_timer!(open_timer);
+    Ok(res)
+}
+
+/// Steps 1 to 3 of `batch_verify_internal`: reduce the openings of the
+/// `f_i_commitments` at the `points` to an opening of g' at the sum check
+/// point.
+///
+/// Returns the commitment of g' and its expected evaluation.
+pub(crate) fn batch_reduce<E, PCS, T>(
+    f_i_commitments: &[Commitment<E>],
+    points: &[PCS::Point],
+    proof: &BatchProof<E, PCS>,
+    transcript: &mut T,
+) -> Result<(Commitment<E>, E::ScalarField), PCSError>
+where
+    E: Pairing,
+    T: Transcript<E::ScalarField>,
+    PCS: PolynomialCommitmentScheme<E, Point = Vec<E::ScalarField>>,
+{
+    let (scalars, tilde_g_eval) = batch_sum_check_verify(
+        points,
+        &proof.sum_check_proof,
+        &proof.f_i_eval_at_point_i,
+        transcript,
+    )?;
+
+    // build g' commitment, with one base per distinct commitment
+    let step = start_timer!(|| "build homomorphic commitment");
+    let (f_i_commitments, scalars) = merge_scalars(f_i_commitments, &scalars);
+    let bases: Vec<_> = f_i_commitments.iter().map(|comm| comm.0).collect();
+    let g_prime_commit = E::G1::msm_unchecked(&bases, &scalars);
+    end_timer!(step);
+
+    Ok((Commitment(g_prime_commit.into_affine()), tilde_g_eval))
+}
+
+/// Steps 1 and 3 of `batch_verify_internal`, shared by the commitment
+/// schemes: check the sum check reducing the openings at the `points` to an
+/// opening of g'(X) = \sum_i scalar_i * f_i(X) at the sum check point.
+///
+/// Returns the `scalar_i = eq(a2, point_i) * eq(t, <i>)` and the expected
+/// g'(a2).
+pub(crate) fn batch_sum_check_verify<F: PrimeField, T: Transcript<F>>(
+    points: &[Vec<F>],
+    sum_check_proof: &IOPProof<F>,
+    f_i_evals: &[F],
+    transcript: &mut T,
+) -> Result<(Vec<F>, F), PCSError> {
     // TODO: sanity checks
 
-    let k = f_i_commitments.len();
+    let k = points.len();
+    if f_i_evals.len() != k {
+        return Err(PCSError::InvalidProof(format!(
+            "number of evaluations ({}) does not match the number of points ({})",
+            f_i_evals.len(),
+            k
+        )));
+    }
     let ell = log2(k) as usize;
-    let num_var = proof.sum_check_proof.point.len();
+    let num_var = sum_check_proof.point.len();
 
     // challenge point t
     let t = transcript.get_and_append_challenge_vectors("t".as_ref(), ell)?;
 
     // sum check point (a2)
-    let a2 = &proof.sum_check_proof.point[..num_var];
+    let a2 = &sum_check_proof.point[..num_var];
 
-    // build g' commitment
-    let step = start_timer!(|| "build homomorphic commitment");
     let eq_t_list = build_eq_x_r_vec(t.as_ref())?;
-
-    let mut scalars = vec![];
-    let mut bases = vec![];
-
-    for (i, point) in points.iter().enumerate() {
-        let eq_i_a2 = eq_eval(a2, point)?;
-        scalars.push(eq_i_a2 * eq_t_list[i]);
-        bases.push(f_i_commitments[i].0);
-    }
-    let g_prime_commit = E::G1::msm_unchecked(&bases, &scalars);
-    end_timer!(step);
+    let scalars = points
+        .iter()
+        .zip(eq_t_list.iter())
+        .map(|(point, eq_t_i)| Ok(eq_eval(a2, point)? * eq_t_i))
+        .collect::<Result<Vec<_>, PCSError>>()?;
 
     // ensure \sum_i eq(t, <i>) * f_i_evals matches the sum via SumCheck
-    let mut sum = E::ScalarField::zero();
+    let mut sum = F::zero();
     for (i, &e) in eq_t_list.iter().enumerate().take(k) {
-        sum += e * proof.f_i_eval_at_point_i[i];
+        sum += e * f_i_evals[i];
     }
     let aux_info = VPAuxInfo {
         max_degree: 2,
         num_variables: num_var,
         phantom: PhantomData,
     };
-    let subclaim = match <PolyIOP<E::ScalarField> as SumCheck<E::ScalarField>>::verify(
-        sum,
-        &proof.sum_check_proof,
-        &aux_info,
-        transcript,
-    ) {
-        Ok(p) => p,
-        Err(_e) => {
-            // cannot wrap IOPError with PCSError due to cyclic dependency
-            return Err(PCSError::InvalidProver(
-                "Sumcheck in batch verification failed".to_string(),
-            ));
-        },
-    };
-    let tilde_g_eval = subclaim.expected_evaluation;
+    let subclaim =
+        match <PolyIOP<F, T> as SumCheck<F>>::verify(sum, sum_check_proof, &aux_info, transcript) {
+            Ok(p) => p,
+            Err(_e) => {
+                // cannot wrap IOPError with PCSError due to cyclic dependency
+                return Err(PCSError::InvalidProver(
+                    "Sumcheck in batch verification failed".to_string(),
+                ));
+            },
+        };
 
-    // verify commitment
-    let res = PCS::verify(
-        verifier_param,
-        &Commitment(g_prime_commit.into_affine()),
-        a2.to_vec().as_ref(),
-        &tilde_g_eval,
-        &proof.g_prime_proof,
-    )?;
+    Ok((scalars, subclaim.expected_evaluation))
+}
 
-    end_timer!(open_timer);
-    Ok(res)
+/// Group the `scalars` of equal `commitments`, so that a polynomial opened
+/// at several points is only combined once into the commitment of g'.
+pub(crate) fn merge_scalars<C: Clone + PartialEq, F: PrimeField>(
+    commitments: &[C],
+    scalars: &[F],
+) -> (Vec<C>, Vec<F>) {
+    let mut distinct_commitments: Vec<C> = vec![];
+    let mut distinct_scalars: Vec<F> = vec![];
+    for (commitment, scalar) in commitments.iter().zip(scalars.iter()) {
+        match distinct_commitments
+            .iter()
+            .position(|other| other == commitment)
+        {
+            Some(i) => distinct_scalars[i] += scalar,
+            None => {
+                distinct_commitments.push(commitment.clone());
+                distinct_scalars.push(*scalar);
+            },
+        }
+    }
+    (distinct_commitments, distinct_scalars)
 }
 
 #[cfg(test)]
@@ -277,6 +365,7 @@ mod tests {
     use ark_ec::pairing::Pairing;
     use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
     use ark_std::{rand::Rng, test_rng, vec::Vec, UniformRand};
+    use transcript::IOPTranscript;
 
     type Fr = <E as Pairing>::ScalarField;
 
@@ -310,7 +399,7 @@ mod tests {
         let mut transcript = IOPTranscript::new("test transcript".as_ref());
         transcript.append_field_element("init".as_ref(), &Fr::zero())?;
 
-        let batch_proof = multi_open_internal::<E, MultilinearKzgPCS<E>>(
+        let batch_proof = multi_open_internal::<E, MultilinearKzgPCS<E>, _>(
             &ml_ck,
             polys,
             &points,
@@ -321,7 +410,7 @@ mod tests {
         // good path
         let mut transcript = IOPTranscript::new("test transcript".as_ref());
         transcript.append_field_element("init".as_ref(), &Fr::zero())?;
-        assert!(batch_verify_internal::<E, MultilinearKzgPCS<E>>(
+        assert!(batch_verify_internal::<E, MultilinearKzgPCS<E>, _>(
             &ml_vk,
             &commitments,
             &points,
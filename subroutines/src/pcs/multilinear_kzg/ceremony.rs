@@ -0,0 +1,431 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! An updatable ceremony for the multilinear KZG parameters.
+//!
+//! The level `i` of `powers_of_g` holds `g^{\prod_{j >= i} eq(t_j, x_j)}`
+//! over the boolean hypercube, and `h_mask` holds `h^{t_i}`. A participant
+//! multiplies each trapdoor `t_i` by a fresh secret `s_i`: as
+//! `eq(s t, 0) = eq(t, 0) + (1 - s) eq(t, 1)` and `eq(s t, 1) = s eq(t, 1)`,
+//! the top level is updated one variable at a time, and every other level is
+//! obtained by summing out the first variable of the level above.
+//!
+//! Each participant publishes a [`Contribution`]: `g^{s_i}`, a proof of
+//! knowledge of `s_i`, and the updated `h_mask`. Anyone can verify the whole
+//! chain with [`verify_ceremony`], and the final parameters are secure as
+//! long as one participant discarded their secrets.
+
+use crate::pcs::{
+    multilinear_kzg::srs::{Evaluations, MultilinearProverParam, MultilinearUniversalParams},
+    PCSError,
+};
+use ark_ec::{
+    pairing::Pairing, scalar_mul::variable_base::VariableBaseMSM, AffineRepr, CurveGroup, Group,
+};
+use ark_ff::{One, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+    cfg_chunks_mut, end_timer, format, rand::Rng, start_timer, string::ToString, vec, vec::Vec,
+    UniformRand,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::{ParallelIterator, ParallelSliceMut};
+use transcript::{IOPTranscript, Transcript};
+
+/// The public record of one participant of the ceremony.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Contribution<E: Pairing> {
+    /// `g^{s_i}` for the secret `s_i` of each variable
+    pub secret_g: Vec<E::G1Affine>,
+    /// Schnorr proofs of knowledge of the secrets: `g^{k_i}` for random `k_i`
+    pub pok_commitments: Vec<E::G1Affine>,
+    /// Schnorr proofs of knowledge of the secrets: `k_i + c s_i`
+    pub pok_responses: Vec<E::ScalarField>,
+    /// `h^{t_i s_i}`, the `h_mask` after the contribution
+    pub h_mask: Vec<E::G2Affine>,
+}
+
+impl<E: Pairing> MultilinearUniversalParams<E> {
+    /// The parameters a ceremony for `num_vars` variables starts from: the
+    /// generators of G1 and G2, with all trapdoors equal to one.
+    pub fn ceremony_genesis(num_vars: usize) -> Result<Self, PCSError> {
+        if num_vars == 0 {
+            return Err(PCSError::InvalidParameters(
+                "constant polynomial not supported".to_string(),
+            ));
+        }
+        Self::from_trapdoors(
+            E::G1::generator(),
+            E::G2::generator(),
+            &vec![E::ScalarField::one(); num_vars],
+        )
+    }
+
+    /// Rerandomize the parameters with fresh secrets, and return the new
+    /// parameters with the public record of the contribution. The secrets are
+    /// dropped on return.
+    pub fn contribute<R: Rng>(&self, rng: &mut R) -> Result<(Self, Contribution<E>), PCSError> {
+        let contribute_timer = start_timer!(|| "ceremony contribution");
+        let num_vars = self.prover_param.num_vars;
+        let mut secrets = Vec::with_capacity(num_vars);
+        while secrets.len() < num_vars {
+            let s = E::ScalarField::rand(rng);
+            if !s.is_zero() {
+                secrets.push(s);
+            }
+        }
+
+        // update the top level, one variable at a time
+        let mut top: Vec<E::G1> = self.prover_param.powers_of_g[0]
+            .evals
+            .iter()
+            .map(|p| p.into_group())
+            .collect();
+        for (i, s) in secrets.iter().enumerate() {
+            let one_minus_s = E::ScalarField::one() - s;
+            cfg_chunks_mut!(top, 2 << i).for_each(|chunk| {
+                let (zeros, ones) = chunk.split_at_mut(1 << i);
+                for (zero, one) in zeros.iter_mut().zip(ones.iter_mut()) {
+                    *zero += *one * one_minus_s;
+                    *one *= s;
+                }
+            });
+        }
+
+        // every other level sums out the first variable of the level above
+        let mut levels = vec![top];
+        for _ in 1..num_vars {
+            let next = levels
+                .last()
+                .expect("at least one level")
+                .chunks(2)
+                .map(|pair| pair[0] + pair[1])
+                .collect();
+            levels.push(next);
+        }
+        let mut powers_of_g: Vec<_> = levels
+            .iter()
+            .map(|level| Evaluations {
+                evals: E::G1::normalize_batch(level),
+            })
+            .collect();
+        powers_of_g.push(Evaluations {
+            evals: vec![self.prover_param.g],
+        });
+
+        let h_mask = E::G2::normalize_batch(
+            &self
+                .h_mask
+                .iter()
+                .zip(secrets.iter())
+                .map(|(h, s)| *h * s)
+                .collect::<Vec<_>>(),
+        );
+        let secret_g = E::G1::normalize_batch(
+            &secrets
+                .iter()
+                .map(|s| self.prover_param.g * s)
+                .collect::<Vec<_>>(),
+        );
+
+        // Schnorr proofs of knowledge of the secrets
+        let nonces: Vec<_> = (0..num_vars).map(|_| E::ScalarField::rand(rng)).collect();
+        let pok_commitments = E::G1::normalize_batch(
+            &nonces
+                .iter()
+                .map(|k| self.prover_param.g * k)
+                .collect::<Vec<_>>(),
+        );
+        let challenge = pok_challenge::<E>(&self.h_mask, &secret_g, &pok_commitments, &h_mask)?;
+        let pok_responses = nonces
+            .iter()
+            .zip(secrets.iter())
+            .map(|(k, s)| *k + challenge * s)
+            .collect();
+
+        let params = Self {
+            prover_param: MultilinearProverParam {
+                num_vars,
+                powers_of_g,
+                g: self.prover_param.g,
+                h: self.prover_param.h,
+            },
+            h_mask: h_mask.clone(),
+        };
+        end_timer!(contribute_timer);
+        Ok((
+            params,
+            Contribution {
+                secret_g,
+                pok_commitments,
+                pok_responses,
+                h_mask,
+            },
+        ))
+    }
+
+    /// Check that the parameters are derived from the trapdoors of `h_mask`:
+    /// the levels fold into each other, and `g^{eq(t_i, 1) ...} = (g^{...})^{t_i}`
+    /// in every level, with a random linear combination per level and a
+    /// single multi-pairing.
+    pub fn check_well_formed<R: Rng>(&self, rng: &mut R) -> Result<(), PCSError> {
+        let check_timer = start_timer!(|| "check multilinear parameters");
+        let res = self.check_levels(rng);
+        end_timer!(check_timer);
+        res
+    }
+
+    fn check_levels<R: Rng>(&self, rng: &mut R) -> Result<(), PCSError> {
+        let num_vars = self.prover_param.num_vars;
+        let powers_of_g = &self.prover_param.powers_of_g;
+        let g = self.prover_param.g;
+        let h = self.prover_param.h;
+        if powers_of_g.len() != num_vars + 1
+            || self.h_mask.len() != num_vars
+            || powers_of_g
+                .iter()
+                .enumerate()
+                .any(|(i, level)| level.evals.len() != 1 << (num_vars - i))
+            || powers_of_g[num_vars].evals[0] != g
+            || g.is_zero()
+            || h.is_zero()
+            || self.h_mask.iter().any(|t| t.is_zero())
+        {
+            return Err(PCSError::InvalidParameters(
+                "malformed multilinear parameters".to_string(),
+            ));
+        }
+
+        let mut g1_elems = Vec::with_capacity(num_vars + 1);
+        let mut g2_elems = Vec::with_capacity(num_vars + 1);
+        let mut ones_sum = E::G1::zero();
+        for i in 0..num_vars {
+            let level = &powers_of_g[i].evals;
+            let next = &powers_of_g[i + 1].evals;
+            if level
+                .chunks(2)
+                .zip(next.iter())
+                .any(|(pair, p)| (pair[0].into_group() + pair[1]).into_affine() != *p)
+            {
+                return Err(PCSError::InvalidParameters(format!(
+                    "level {} does not fold into level {}",
+                    i,
+                    i + 1
+                )));
+            }
+
+            // e(\sum_x r_x g^{eq(t_i, 1) f(x)}, h) = e(\sum_x r_x g^{f(x)}, h^{t_i})
+            let r: Vec<_> = next.iter().map(|_| E::ScalarField::rand(rng)).collect();
+            let ones: Vec<_> = level.iter().skip(1).step_by(2).copied().collect();
+            ones_sum += E::G1::msm_unchecked(&ones, &r);
+            g1_elems.push((-E::G1::msm_unchecked(next, &r)).into_affine());
+            g2_elems.push(self.h_mask[i]);
+        }
+        g1_elems.push(ones_sum.into_affine());
+        g2_elems.push(h);
+        if E::multi_pairing(g1_elems, g2_elems).0.is_one() {
+            Ok(())
+        } else {
+            Err(PCSError::InvalidParameters(
+                "the levels do not match h_mask".to_string(),
+            ))
+        }
+    }
+}
+
+/// Verify the chain of contributions of a ceremony from
+/// [`MultilinearUniversalParams::ceremony_genesis`] to `params`: each proof
+/// of knowledge, each update of `h_mask` with a pairing check, and that
+/// `params` is well formed for the final `h_mask`.
+pub fn verify_ceremony<E: Pairing, R: Rng>(
+    params: &MultilinearUniversalParams<E>,
+    contributions: &[Contribution<E>],
+    rng: &mut R,
+) -> Result<(), PCSError> {
+    let verify_timer = start_timer!(|| "verify ceremony");
+    let res = verify_contributions(params, contributions, rng);
+    end_timer!(verify_timer);
+    res
+}
+
+fn verify_contributions<E: Pairing, R: Rng>(
+    params: &MultilinearUniversalParams<E>,
+    contributions: &[Contribution<E>],
+    rng: &mut R,
+) -> Result<(), PCSError> {
+    let num_vars = params.prover_param.num_vars;
+    let g = params.prover_param.g;
+    let h = params.prover_param.h;
+    if g != E::G1Affine::generator() || h != E::G2Affine::generator() {
+        return Err(PCSError::InvalidParameters(
+            "the ceremony does not start from the generators".to_string(),
+        ));
+    }
+
+    let mut h_mask = vec![h; num_vars];
+    for (index, contribution) in contributions.iter().enumerate() {
+        let invalid = || PCSError::InvalidParameters(format!("invalid contribution {}", index));
+        if contribution.secret_g.len() != num_vars
+            || contribution.pok_commitments.len() != num_vars
+            || contribution.pok_responses.len() != num_vars
+            || contribution.h_mask.len() != num_vars
+            || contribution.secret_g.iter().any(|s| s.is_zero())
+        {
+            return Err(invalid());
+        }
+
+        // g^{k_i + c s_i} = g^{k_i} (g^{s_i})^c, batched with random coefficients
+        let challenge = pok_challenge::<E>(
+            &h_mask,
+            &contribution.secret_g,
+            &contribution.pok_commitments,
+            &contribution.h_mask,
+        )?;
+        let r: Vec<_> = (0..num_vars).map(|_| E::ScalarField::rand(rng)).collect();
+        let response: E::ScalarField = r
+            .iter()
+            .zip(contribution.pok_responses.iter())
+            .map(|(r, z)| *r * z)
+            .sum();
+        let r_challenge: Vec<_> = r.iter().map(|r| *r * challenge).collect();
+        if g * response
+            != E::G1::msm_unchecked(&contribution.pok_commitments, &r)
+                + E::G1::msm_unchecked(&contribution.secret_g, &r_challenge)
+        {
+            return Err(invalid());
+        }
+
+        // e(g^{s_i}, h^{t_i}) = e(g, h^{t_i s_i}), batched with random coefficients
+        let r: Vec<_> = (0..num_vars).map(|_| E::ScalarField::rand(rng)).collect();
+        let mut g1_elems: Vec<_> = contribution
+            .secret_g
+            .iter()
+            .zip(r.iter())
+            .map(|(s, r)| (*s * r).into_affine())
+            .collect();
+        let mut g2_elems = h_mask.clone();
+        g1_elems.push((-g.into_group()).into_affine());
+        g2_elems.push(E::G2::msm_unchecked(&contribution.h_mask, &r).into_affine());
+        if !E::multi_pairing(g1_elems, g2_elems).0.is_one() {
+            return Err(invalid());
+        }
+
+        h_mask = contribution.h_mask.clone();
+    }
+
+    if params.h_mask != h_mask {
+        return Err(PCSError::InvalidParameters(
+            "the parameters do not match the last contribution".to_string(),
+        ));
+    }
+    params.check_well_formed(rng)
+}
+
+/// The Fiat-Shamir challenge of the proofs of knowledge, bound to the
+/// `h_mask` before and after the contribution.
+fn pok_challenge<E: Pairing>(
+    previous_h_mask: &[E::G2Affine],
+    secret_g: &[E::G1Affine],
+    pok_commitments: &[E::G1Affine],
+    h_mask: &[E::G2Affine],
+) -> Result<E::ScalarField, PCSError> {
+    let mut transcript = IOPTranscript::<E::ScalarField>::new(b"multilinear KZG ceremony");
+    transcript.append_serializable_element(b"previous h_mask", &previous_h_mask.to_vec())?;
+    transcript.append_serializable_element(b"secret g", &secret_g.to_vec())?;
+    transcript.append_serializable_element(b"pok commitments", &pok_commitments.to_vec())?;
+    transcript.append_serializable_element(b"h_mask", &h_mask.to_vec())?;
+    Ok(transcript.get_and_append_challenge(b"pok challenge")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme};
+    use ark_bls12_381::Bls12_381;
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::{
+        rand::{rngs::StdRng, SeedableRng},
+        sync::Arc,
+        test_rng,
+    };
+
+    type E = Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+
+    #[test]
+    fn test_ceremony() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+        let num_vars = 4;
+        let genesis = MultilinearUniversalParams::<E>::ceremony_genesis(num_vars)?;
+        genesis.check_well_formed(&mut rng)?;
+
+        let mut params = genesis;
+        let mut contributions = vec![];
+        for _ in 0..3 {
+            let (next, contribution) = params.contribute(&mut rng)?;
+            params = next;
+            contributions.push(contribution);
+        }
+        verify_ceremony(&params, &contributions, &mut rng)?;
+
+        // the parameters open polynomials
+        let (ck, vk) = MultilinearKzgPCS::<E>::trim(&params, None, Some(num_vars))?;
+        let poly = Arc::new(DenseMultilinearExtension::rand(num_vars, &mut rng));
+        let point: Vec<_> = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+        let com = MultilinearKzgPCS::<E>::commit(&ck, &poly)?;
+        let (proof, value) = MultilinearKzgPCS::<E>::open(&ck, &poly, &point)?;
+        assert_eq!(value, poly.evaluate(&point).unwrap());
+        assert!(MultilinearKzgPCS::<E>::verify(
+            &vk, &com, &point, &value, &proof
+        )?);
+
+        // a missing contribution
+        assert!(verify_ceremony(&params, &contributions[1..], &mut rng).is_err());
+        // a tampered level
+        let mut tampered = params.clone();
+        tampered.prover_param.powers_of_g[1].evals[0] = tampered.prover_param.g;
+        assert!(tampered.check_well_formed(&mut rng).is_err());
+        // a proof of knowledge for another secret
+        let mut forged = contributions.clone();
+        forged[2].pok_responses[0] += Fr::one();
+        assert!(verify_ceremony(&params, &forged, &mut rng).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_contribution_matches_trapdoors() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+        let t: Vec<_> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+        let params = MultilinearUniversalParams::<E>::from_trapdoors(
+            <E as Pairing>::G1::generator(),
+            <E as Pairing>::G2::generator(),
+            &t,
+        )?;
+
+        // the secrets are the first scalars sampled by the contribution
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut secrets_rng = StdRng::seed_from_u64(0);
+        let (next, _) = params.contribute(&mut rng)?;
+        let updated_t: Vec<_> = t.iter().map(|t| *t * Fr::rand(&mut secrets_rng)).collect();
+        let expected = MultilinearUniversalParams::<E>::from_trapdoors(
+            <E as Pairing>::G1::generator(),
+            <E as Pairing>::G2::generator(),
+            &updated_t,
+        )?;
+        assert_eq!(next.h_mask, expected.h_mask);
+        for (level, expected_level) in next
+            .prover_param
+            .powers_of_g
+            .iter()
+            .zip(expected.prover_param.powers_of_g.iter())
+        {
+            assert_eq!(level.evals, expected_level.evals);
+        }
+
+        Ok(())
+    }
+}
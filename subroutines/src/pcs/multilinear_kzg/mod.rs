@@ -6,15 +6,21 @@
 
 //! Main module for multilinear KZG commitment scheme
 
+pub mod accumulation;
 pub(crate) mod batching;
+pub mod ceremony;
 pub mod srs;
+pub mod srs_file;
 pub mod util;
 
 use crate::{
-    pcs::{prelude::Commitment, PCSError, PolynomialCommitmentScheme, StructuredReferenceString},
+    pcs::{
+        prelude::Commitment, AccumulationScheme, PCSError, PolynomialCommitmentScheme,
+        StructuredReferenceString,
+    },
     BatchProof,
 };
-use arithmetic::evaluate_opt;
+use arithmetic::{evaluate_opt, MultilinearPolynomial, SmallMultilinearPolynomial};
 use ark_ec::{
     pairing::Pairing,
     scalar_mul::{fixed_base::FixedBase, variable_base::VariableBaseMSM},
@@ -30,9 +36,13 @@ use ark_std::{
 use std::ops::Mul;
 // use batching::{batch_verify_internal, multi_open_internal};
 use srs::{MultilinearProverParam, MultilinearUniversalParams, MultilinearVerifierParam};
-use transcript::IOPTranscript;
+use transcript::Transcript;
+use util::small_scalar_msm;
 
-use self::batching::{batch_verify_internal, multi_open_internal};
+use self::{
+    accumulation::KzgAccumulator,
+    batching::{batch_reduce, batch_verify_internal, multi_open_internal},
+};
 
 /// KZG Polynomial Commitment Scheme on multilinear polynomials.
 pub struct MultilinearKzgPCS<E: Pairing> {
@@ -98,32 +108,13 @@ impl<E: Pairing> PolynomialCommitmentScheme<E> for MultilinearKzgPCS<E> {
     /// Generate a commitment for a polynomial.
     ///
     /// This function takes `2^num_vars` number of scalar multiplications over
-    /// G1.
+    /// G1. See [`MultilinearKzgPCS::commit_multilinear`] for other
+    /// representations.
     fn commit(
         prover_param: impl Borrow<Self::ProverParam>,
         poly: &Self::Polynomial,
     ) -> Result<Self::Commitment, PCSError> {
-        let prover_param = prover_param.borrow();
-        let commit_timer = start_timer!(|| "commit");
-        if prover_param.num_vars < poly.num_vars {
-            return Err(PCSError::InvalidParameters(format!(
-                "MlE length ({}) exceeds param limit ({})",
-                poly.num_vars, prover_param.num_vars
-            )));
-        }
-        let ignored = prover_param.num_vars - poly.num_vars;
-        let scalars: Vec<_> = poly.to_evaluations();
-        let msm_timer = start_timer!(|| format!(
-            "msm of size {}",
-            prover_param.powers_of_g[ignored].evals.len()
-        ));
-        let commitment =
-            E::G1::msm_unchecked(&prover_param.powers_of_g[ignored].evals, scalars.as_slice())
-                .into_affine();
-        end_timer!(msm_timer);
-
-        end_timer!(commit_timer);
-        Ok(Commitment(commitment))
+        Self::commit_multilinear(prover_param, poly)
     }
 
     /// On input a polynomial `p` and a point `point`, outputs a proof for the
@@ -145,12 +136,12 @@ impl<E: Pairing> PolynomialCommitmentScheme<E> for MultilinearKzgPCS<E> {
 
     /// Input a list of multilinear extensions, and a same number of points, and
     /// a transcript, compute a multi-opening for all the polynomials.
-    fn multi_open(
+    fn multi_open<T: Transcript<E::ScalarField>>(
         prover_param: impl Borrow<Self::ProverParam>,
         polynomials: &[Self::Polynomial],
         points: &[Self::Point],
         evals: &[Self::Evaluation],
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut T,
     ) -> Result<BatchProof<E, Self>, PCSError> {
         multi_open_internal(
             prover_param.borrow(),
@@ -179,17 +170,130 @@ impl<E: Pairing> PolynomialCommitmentScheme<E> for MultilinearKzgPCS<E> {
 
     /// Verifies that `value_i` is the evaluation at `x_i` of the polynomial
     /// `poly_i` committed inside `comm`.
-    fn batch_verify(
+    fn batch_verify<T: Transcript<E::ScalarField>>(
         verifier_param: &Self::VerifierParam,
         commitments: &[Self::Commitment],
         points: &[Self::Point],
         batch_proof: &Self::BatchProof,
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut T,
     ) -> Result<bool, PCSError> {
         batch_verify_internal(verifier_param, commitments, points, batch_proof, transcript)
     }
 }
 
+impl<E: Pairing> AccumulationScheme<E> for MultilinearKzgPCS<E> {
+    type Accumulator = KzgAccumulator<E>;
+
+    /// Reduce the openings to the opening of g' as in `batch_verify`, and
+    /// defer its pairing check.
+    fn batch_verify_deferred<T: Transcript<E::ScalarField>>(
+        verifier_param: &Self::VerifierParam,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        batch_proof: &Self::BatchProof,
+        transcript: &mut T,
+    ) -> Result<Self::Accumulator, PCSError> {
+        let (g_prime_commit, g_prime_eval) =
+            batch_reduce(commitments, points, batch_proof, transcript)?;
+        KzgAccumulator::from_opening(
+            verifier_param,
+            &g_prime_commit,
+            &batch_proof.sum_check_proof.point,
+            &g_prime_eval,
+            &batch_proof.g_prime_proof,
+        )
+    }
+
+    fn accumulate<T: Transcript<E::ScalarField>>(
+        accumulators: &[Self::Accumulator],
+        transcript: &mut T,
+    ) -> Result<Self::Accumulator, PCSError> {
+        KzgAccumulator::combine(accumulators, transcript)
+    }
+
+    fn decide(
+        verifier_param: &Self::VerifierParam,
+        accumulator: &Self::Accumulator,
+    ) -> Result<bool, PCSError> {
+        accumulator.decide(verifier_param)
+    }
+}
+
+impl<E: Pairing> MultilinearKzgPCS<E> {
+    /// Generate a commitment for a polynomial in any multilinear
+    /// representation.
+    ///
+    /// This function takes one scalar multiplication over G1 per stored
+    /// evaluation, i.e. `2^num_vars` of them for a dense polynomial and one
+    /// per non-zero evaluation for a sparse one. If the evaluations are all
+    /// integers less than `2^k`, the scalar multiplications only run over `k`
+    /// bits.
+    pub fn commit_multilinear<M: MultilinearPolynomial<E::ScalarField> + ?Sized>(
+        prover_param: impl Borrow<MultilinearProverParam<E>>,
+        poly: &M,
+    ) -> Result<Commitment<E>, PCSError> {
+        let prover_param = prover_param.borrow();
+        let commit_timer = start_timer!(|| "commit");
+        let num_vars = poly.num_variables();
+        if prover_param.num_vars < num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "MlE length ({}) exceeds param limit ({})",
+                num_vars, prover_param.num_vars
+            )));
+        }
+        let ignored = prover_param.num_vars - num_vars;
+        let bases = &prover_param.powers_of_g[ignored].evals;
+        // dense tables of small integers, such as boolean witnesses, are
+        // committed to as such
+        let small_table = poly.dense_evaluations().and_then(|evals| {
+            SmallMultilinearPolynomial::try_from_field_evaluations(num_vars, evals)
+        });
+        let small = match small_table.as_ref() {
+            Some(small_table) => small_table.small_evaluations(),
+            None => poly.small_evaluations(),
+        };
+        let commitment = if let Some((scalars, num_bits)) = small {
+            let msm_timer = start_timer!(|| format!(
+                "msm of size {} with {}-bit scalars",
+                scalars.len(),
+                num_bits
+            ));
+            let commitment = small_scalar_msm::<E::G1>(bases, scalars, num_bits);
+            end_timer!(msm_timer);
+            commitment
+        } else {
+            match (poly.dense_evaluations(), poly.sparse_evaluations()) {
+                (Some(scalars), _) => {
+                    let msm_timer = start_timer!(|| format!("msm of size {}", bases.len()));
+                    let commitment = E::G1::msm_unchecked(bases, scalars);
+                    end_timer!(msm_timer);
+                    commitment
+                },
+                (None, Some(evaluations)) => {
+                    let msm_timer = start_timer!(|| format!("msm of size {}", evaluations.len()));
+                    let (bases, scalars): (Vec<_>, Vec<_>) = evaluations
+                        .iter()
+                        .map(|(index, scalar)| (bases[*index], *scalar))
+                        .unzip();
+                    let commitment = E::G1::msm_unchecked(&bases, &scalars);
+                    end_timer!(msm_timer);
+                    commitment
+                },
+                (None, None) => {
+                    let msm_timer = start_timer!(|| format!("msm of size {}", bases.len()));
+                    let commitment = E::G1::msm_unchecked(bases, &poly.to_dense().evaluations);
+                    end_timer!(msm_timer);
+                    commitment
+                },
+            }
+        }
+        .into_affine();
+
+        end_timer!(commit_timer);
+        Ok(Commitment(commitment))
+    }
+}
+
 /// On input a polynomial `p` and a point `point`, outputs a proof for the
 /// same. This function does not need to take the evaluation value as an
 /// input.
@@ -331,6 +435,7 @@ fn verify_internal<E: Pairing>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use arithmetic::{SmallMultilinearPolynomial, SparseMultilinearPolynomial};
     use ark_bls12_381::Bls12_381;
     use ark_ec::pairing::Pairing;
     use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
@@ -380,6 +485,49 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_sparse_commit() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+
+        let params = MultilinearKzgPCS::<E>::gen_srs_for_testing(&mut rng, 10)?;
+        let (ck, _) = MultilinearKzgPCS::trim(&params, None, Some(10))?;
+
+        for num_nonzero in [0, 1, 100] {
+            let poly = SparseMultilinearPolynomial::<Fr>::rand(8, num_nonzero, &mut rng);
+            let dense = Arc::new(poly.to_dense());
+            assert_eq!(
+                MultilinearKzgPCS::commit_multilinear(&ck, &poly)?,
+                MultilinearKzgPCS::commit(&ck, &dense)?
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_small_commit() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+
+        let params = MultilinearKzgPCS::<E>::gen_srs_for_testing(&mut rng, 10)?;
+        let (ck, _) = MultilinearKzgPCS::trim(&params, None, Some(10))?;
+
+        for num_vars in [1, 8] {
+            for num_bits in [0, 1, 8, 64] {
+                let poly = SmallMultilinearPolynomial::<Fr>::rand(num_vars, num_bits, &mut rng);
+                let dense = Arc::new(poly.to_dense());
+                let com = MultilinearKzgPCS::commit_multilinear(&ck, &poly)?;
+                assert_eq!(com, MultilinearKzgPCS::commit(&ck, &dense)?);
+                let bases = &ck.powers_of_g[10 - num_vars].evals;
+                assert_eq!(
+                    com.0,
+                    <E as Pairing>::G1::msm_unchecked(bases, &dense.evaluations).into_affine()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn setup_commit_verify_constant_polynomial() {
         let mut rng = test_rng();
@@ -28,6 +28,11 @@ pub struct Evaluations<C: AffineRepr> {
 }
 
 /// Universal Parameter
+///
+/// The trapdoors of the variables must be independent, so these parameters
+/// can not be derived from a powers-of-tau ceremony. See
+/// `ZeromorphUniversalParams::read_ptau` for a multilinear commitment over a
+/// powers-of-tau.
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
 pub struct MultilinearUniversalParams<E: Pairing> {
     /// prover parameters
@@ -52,11 +57,10 @@ pub struct MultilinearProverParam<E: Pairing> {
 }
 
 impl<E: Pairing> MultilinearProverParam<E> {
-    /// Number of variables in a multilinear system
-    pub fn get_power_g(&self) -> Vec<E::G1Affine> {
-        self.powers_of_g[0].clone().evals
+    /// The top level of the powers of `g`, over all the variables
+    pub fn get_power_g(&self) -> &[E::G1Affine] {
+        &self.powers_of_g[0].evals
     }
-
 }
 
 /// Verifier Parameters
@@ -141,19 +145,32 @@ impl<E: Pairing> StructuredReferenceString<E> for MultilinearUniversalParams<E>
         }
 
         let total_timer = start_timer!(|| "SRS generation");
-
-        let pp_generation_timer = start_timer!(|| "Prover Param generation");
-
         let g = E::G1::rand(rng);
         let h = E::G2::rand(rng);
+        let t: Vec<_> = (0..num_vars).map(|_| E::ScalarField::rand(rng)).collect();
+        let pp = Self::from_trapdoors(g, h, &t)?;
+        end_timer!(total_timer);
+        Ok(pp)
+    }
+}
+
+impl<E: Pairing> MultilinearUniversalParams<E> {
+    /// Build the parameters from the generators `g` and `h` and the
+    /// trapdoors `t_1, ..., t_nv`.
+    pub(crate) fn from_trapdoors(
+        g: E::G1,
+        h: E::G2,
+        t: &[E::ScalarField],
+    ) -> Result<Self, PCSError> {
+        let num_vars = t.len();
+        let pp_generation_timer = start_timer!(|| "Prover Param generation");
 
         let mut powers_of_g = Vec::new();
 
-        let t: Vec<_> = (0..num_vars).map(|_| E::ScalarField::rand(rng)).collect();
         let scalar_bits = E::ScalarField::MODULUS_BIT_SIZE as usize;
 
         let mut eq: LinkedList<DenseMultilinearExtension<E::ScalarField>> =
-            LinkedList::from_iter(eq_extension(&t).into_iter());
+            LinkedList::from_iter(eq_extension(t).into_iter());
         let mut eq_arr = LinkedList::new();
         let mut base = eq.pop_back().unwrap().evaluations;
 
@@ -204,7 +221,7 @@ impl<E: Pairing> StructuredReferenceString<E> for MultilinearUniversalParams<E>
         };
         powers_of_g.push(gg);
 
-        let pp = Self::ProverParam {
+        let pp = MultilinearProverParam {
             num_vars,
             g: g.into_affine(),
             h: h.into_affine(),
@@ -217,10 +234,9 @@ impl<E: Pairing> StructuredReferenceString<E> for MultilinearUniversalParams<E>
         let h_mask = {
             let window_size = FixedBase::get_mul_window_size(num_vars);
             let h_table = FixedBase::get_window_table(scalar_bits, window_size, h);
-            E::G2::normalize_batch(&FixedBase::msm(scalar_bits, window_size, &h_table, &t))
+            E::G2::normalize_batch(&FixedBase::msm(scalar_bits, window_size, &h_table, t))
         };
         end_timer!(vp_generation_timer);
-        end_timer!(total_timer);
         Ok(Self {
             prover_param: pp,
             h_mask,
@@ -0,0 +1,375 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! A versioned file format for the multilinear KZG parameters, which is
+//! memory mapped so that only the levels a circuit needs are read.
+//!
+//! All integers are little endian and all points are serialized
+//! uncompressed.
+//!
+//! | field        | size                   | content                                   |
+//! |--------------|------------------------|-------------------------------------------|
+//! | magic        | 8                      | `HPMLKZG\0`                               |
+//! | version      | 4                      | `1`                                       |
+//! | curve        | 32                     | Keccak256 of the generators of G1 and G2  |
+//! | num_vars     | 4                      | `nv`                                      |
+//! | point sizes  | 4 + 4                  | sizes of a G1 and of a G2 point           |
+//! | levels       | (nv + 1) * (8 + 32)    | offset and checksum of each level         |
+//! | g, h, h_mask | G1 + (nv + 1) * G2     | the generators and `h^{t_i}`              |
+//! | checksum     | 32                     | Keccak256 of all the above                |
+//! | payload      |                        | the levels of `powers_of_g`, in order     |
+//!
+//! The level `i` holds `2^{nv - i}` points. Its checksum is the Keccak256 of
+//! the Keccak256 of each chunk of [`CHECKSUM_CHUNK`] points, so that it is
+//! computed in parallel and while streaming.
+
+use crate::pcs::{
+    multilinear_kzg::srs::{
+        Evaluations, MultilinearProverParam, MultilinearUniversalParams, MultilinearVerifierParam,
+    },
+    PCSError,
+};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{cfg_chunks, end_timer, format, start_timer, string::ToString, vec::Vec};
+use memmap2::Mmap;
+#[cfg(feature = "parallel")]
+use rayon::prelude::{ParallelIterator, ParallelSlice};
+use std::{
+    fs::File,
+    io::{BufWriter, Seek, SeekFrom, Write},
+    path::Path,
+};
+use transcript::keccak256;
+
+/// Magic bytes of the file
+const MAGIC: &[u8; 8] = b"HPMLKZG\0";
+/// Current version of the format
+const VERSION: u32 = 1;
+/// Number of points hashed together for the checksum of a level
+pub const CHECKSUM_CHUNK: usize = 1 << 14;
+
+/// The multilinear KZG parameters, memory mapped from a file.
+pub struct MultilinearSrsFile<E: Pairing> {
+    mmap: Mmap,
+    num_vars: usize,
+    g1_size: usize,
+    /// offset and checksum of each level
+    levels: Vec<(usize, [u8; 32])>,
+    g: E::G1Affine,
+    h: E::G2Affine,
+    h_mask: Vec<E::G2Affine>,
+}
+
+impl<E: Pairing> MultilinearUniversalParams<E> {
+    /// Write the parameters to `path`, in the format of [`MultilinearSrsFile`].
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), PCSError> {
+        let write_timer = start_timer!(|| "write multilinear SRS");
+        let num_vars = self.prover_param.num_vars;
+        let g1_size = self.prover_param.g.uncompressed_size();
+        let g2_size = self.prover_param.h.uncompressed_size();
+        let mut writer = BufWriter::new(File::create(path).map_err(io_error)?);
+
+        // the header, with the checksums of the levels filled in afterwards
+        let mut offsets = Vec::with_capacity(num_vars + 1);
+        let mut offset = header_size(num_vars, g1_size, g2_size);
+        for level in self.prover_param.powers_of_g.iter() {
+            offsets.push(offset);
+            offset += level.evals.len() * g1_size;
+        }
+        let placeholder: Vec<_> = offsets.iter().map(|offset| (*offset, [0u8; 32])).collect();
+        writer
+            .write_all(&self.header(&placeholder, g1_size, g2_size)?)
+            .map_err(io_error)?;
+
+        let mut levels = Vec::with_capacity(num_vars + 1);
+        let mut buf = Vec::with_capacity(CHECKSUM_CHUNK * g1_size);
+        for (level, offset) in self.prover_param.powers_of_g.iter().zip(offsets) {
+            let mut chunk_digests = Vec::new();
+            for chunk in level.evals.chunks(CHECKSUM_CHUNK) {
+                buf.clear();
+                for point in chunk {
+                    point.serialize_uncompressed(&mut buf)?;
+                }
+                chunk_digests.extend(keccak256(&buf));
+                writer.write_all(&buf).map_err(io_error)?;
+            }
+            levels.push((offset, keccak256(&chunk_digests)));
+        }
+
+        writer.seek(SeekFrom::Start(0)).map_err(io_error)?;
+        writer
+            .write_all(&self.header(&levels, g1_size, g2_size)?)
+            .map_err(io_error)?;
+        writer.flush().map_err(io_error)?;
+        end_timer!(write_timer);
+        Ok(())
+    }
+
+    fn header(
+        &self,
+        levels: &[(usize, [u8; 32])],
+        g1_size: usize,
+        g2_size: usize,
+    ) -> Result<Vec<u8>, PCSError> {
+        let mut header = MAGIC.to_vec();
+        header.extend(VERSION.to_le_bytes());
+        header.extend(curve_id::<E>()?);
+        header.extend((self.prover_param.num_vars as u32).to_le_bytes());
+        header.extend((g1_size as u32).to_le_bytes());
+        header.extend((g2_size as u32).to_le_bytes());
+        for (offset, checksum) in levels {
+            header.extend((*offset as u64).to_le_bytes());
+            header.extend(checksum);
+        }
+        self.prover_param.g.serialize_uncompressed(&mut header)?;
+        self.prover_param.h.serialize_uncompressed(&mut header)?;
+        for h in self.h_mask.iter() {
+            h.serialize_uncompressed(&mut header)?;
+        }
+        let checksum = keccak256(&header);
+        header.extend(checksum);
+        Ok(header)
+    }
+}
+
+impl<E: Pairing> MultilinearSrsFile<E> {
+    /// Memory map the parameters at `path`, and check the header. The levels
+    /// are only read by [`Self::trim`] and [`Self::load`].
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PCSError> {
+        let file = File::open(path).map_err(io_error)?;
+        // the file is expected not to be modified while mapped
+        let mmap = unsafe { Mmap::map(&file) }.map_err(io_error)?;
+
+        let mut reader = HeaderReader {
+            bytes: &mmap,
+            pos: 0,
+        };
+        if reader.read(MAGIC.len())? != MAGIC {
+            return Err(invalid_file("not a multilinear SRS file"));
+        }
+        let version = reader.read_u32()?;
+        if version != VERSION {
+            return Err(PCSError::InvalidParameters(format!(
+                "unsupported SRS file version {}",
+                version
+            )));
+        }
+        if reader.read(32)? != curve_id::<E>()? {
+            return Err(invalid_file("the SRS file is over another curve"));
+        }
+        let num_vars = reader.read_u32()? as usize;
+        let g1_size = reader.read_u32()? as usize;
+        let g2_size = reader.read_u32()? as usize;
+        if num_vars >= usize::BITS as usize
+            || g1_size != E::G1Affine::generator().uncompressed_size()
+            || g2_size != E::G2Affine::generator().uncompressed_size()
+        {
+            return Err(invalid_file("invalid SRS file header"));
+        }
+
+        let mut levels = Vec::with_capacity(num_vars + 1);
+        for i in 0..=num_vars {
+            let offset = reader.read_u64()? as usize;
+            let mut checksum = [0u8; 32];
+            checksum.copy_from_slice(reader.read(32)?);
+            let len = (1usize << (num_vars - i)) * g1_size;
+            if !matches!(offset.checked_add(len), Some(end) if end <= mmap.len()) {
+                return Err(invalid_file("truncated SRS file"));
+            }
+            levels.push((offset, checksum));
+        }
+        let g = E::G1Affine::deserialize_uncompressed(reader.read(g1_size)?)?;
+        let h = E::G2Affine::deserialize_uncompressed(reader.read(g2_size)?)?;
+        let h_mask = (0..num_vars)
+            .map(|_| {
+                Ok(E::G2Affine::deserialize_uncompressed(
+                    reader.read(g2_size)?,
+                )?)
+            })
+            .collect::<Result<Vec<_>, PCSError>>()?;
+        let header_len = reader.pos;
+        if reader.read(32)? != keccak256(&mmap[..header_len]) {
+            return Err(invalid_file("corrupted SRS file header"));
+        }
+
+        Ok(Self {
+            mmap,
+            num_vars,
+            g1_size,
+            levels,
+            g,
+            h,
+            h_mask,
+        })
+    }
+
+    /// The maximum number of variables of the parameters
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// Read the prover and verifier parameters for `supported_num_vars`,
+    /// that is the last `supported_num_vars + 1` levels, as
+    /// `MultilinearUniversalParams::trim` does. The checksums of the levels
+    /// are checked, and the points are not validated otherwise.
+    pub fn trim(
+        &self,
+        supported_num_vars: usize,
+    ) -> Result<(MultilinearProverParam<E>, MultilinearVerifierParam<E>), PCSError> {
+        if supported_num_vars > self.num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "SRS does not support target number of vars {}",
+                supported_num_vars
+            )));
+        }
+        let trim_timer = start_timer!(|| format!("trim SRS file to {} vars", supported_num_vars));
+        let to_reduce = self.num_vars - supported_num_vars;
+        let powers_of_g = (to_reduce..=self.num_vars)
+            .map(|i| self.read_level(i))
+            .collect::<Result<Vec<_>, PCSError>>();
+        end_timer!(trim_timer);
+        let powers_of_g = powers_of_g?;
+
+        Ok((
+            MultilinearProverParam {
+                num_vars: supported_num_vars,
+                powers_of_g,
+                g: self.g,
+                h: self.h,
+            },
+            MultilinearVerifierParam {
+                num_vars: supported_num_vars,
+                g: self.g,
+                h: self.h,
+                h_mask: self.h_mask[to_reduce..].to_vec(),
+            },
+        ))
+    }
+
+    /// Read all the parameters.
+    pub fn load(&self) -> Result<MultilinearUniversalParams<E>, PCSError> {
+        let (prover_param, verifier_param) = self.trim(self.num_vars)?;
+        Ok(MultilinearUniversalParams {
+            prover_param,
+            h_mask: verifier_param.h_mask,
+        })
+    }
+
+    fn read_level(&self, i: usize) -> Result<Evaluations<E::G1Affine>, PCSError> {
+        let (offset, checksum) = self.levels[i];
+        let bytes = &self.mmap[offset..offset + (1 << (self.num_vars - i)) * self.g1_size];
+        let chunk_digests = cfg_chunks!(bytes, CHECKSUM_CHUNK * self.g1_size)
+            .map(keccak256)
+            .collect::<Vec<_>>()
+            .concat();
+        if keccak256(&chunk_digests) != checksum {
+            return Err(PCSError::InvalidParameters(format!(
+                "corrupted level {} of the SRS file",
+                i
+            )));
+        }
+        let evals = cfg_chunks!(bytes, self.g1_size)
+            .map(E::G1Affine::deserialize_uncompressed_unchecked)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Evaluations { evals })
+    }
+}
+
+/// Reads the fields of the header, failing on truncated files.
+struct HeaderReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> HeaderReader<'a> {
+    fn read(&mut self, len: usize) -> Result<&'a [u8], PCSError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| invalid_file("truncated SRS file"))?;
+        let res = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(res)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, PCSError> {
+        Ok(u32::from_le_bytes(self.read(4)?.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, PCSError> {
+        Ok(u64::from_le_bytes(self.read(8)?.try_into().unwrap()))
+    }
+}
+
+fn header_size(num_vars: usize, g1_size: usize, g2_size: usize) -> usize {
+    MAGIC.len() + 4 + 32 + 4 + 8 + (num_vars + 1) * 40 + g1_size + (num_vars + 1) * g2_size + 32
+}
+
+/// The identifier of the curve: the Keccak256 of its generators.
+fn curve_id<E: Pairing>() -> Result<[u8; 32], PCSError> {
+    let mut bytes = Vec::new();
+    E::G1Affine::generator().serialize_uncompressed(&mut bytes)?;
+    E::G2Affine::generator().serialize_uncompressed(&mut bytes)?;
+    Ok(keccak256(&bytes))
+}
+
+fn invalid_file(msg: &str) -> PCSError {
+    PCSError::InvalidParameters(msg.to_string())
+}
+
+fn io_error(e: std::io::Error) -> PCSError {
+    PCSError::InvalidParameters(format!("failed to access the SRS file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcs::StructuredReferenceString;
+    use ark_bls12_381::Bls12_381;
+    use ark_std::test_rng;
+
+    type E = Bls12_381;
+
+    #[test]
+    fn test_srs_file() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("hyperplonk_srs_test_{}.params", std::process::id()));
+        let srs = MultilinearUniversalParams::<E>::gen_srs_for_testing(&mut rng, 6)?;
+        srs.write_to_file(&path)?;
+
+        let file = MultilinearSrsFile::<E>::open(&path)?;
+        assert_eq!(file.num_vars(), 6);
+        for nv in 0..=6 {
+            let (ck, vk) = file.trim(nv)?;
+            let (expected_ck, expected_vk) = srs.trim(nv)?;
+            assert_eq!(ck.powers_of_g.len(), expected_ck.powers_of_g.len());
+            for (level, expected) in ck.powers_of_g.iter().zip(expected_ck.powers_of_g.iter()) {
+                assert_eq!(level.evals, expected.evals);
+            }
+            assert_eq!(vk.h_mask, expected_vk.h_mask);
+        }
+        assert!(file.trim(7).is_err());
+        let loaded = file.load()?;
+        assert_eq!(loaded.h_mask, srs.h_mask);
+
+        // a corrupted level is only detected when read
+        let top_level = file.levels[0].0;
+        drop(file);
+        let mut bytes = std::fs::read(&path).map_err(io_error)?;
+        bytes[top_level] ^= 1;
+        std::fs::write(&path, &bytes).map_err(io_error)?;
+        let file = MultilinearSrsFile::<E>::open(&path)?;
+        assert!(file.trim(5).is_ok());
+        assert!(file.trim(6).is_err());
+        drop(file);
+
+        std::fs::remove_file(&path).map_err(io_error)?;
+        Ok(())
+    }
+}
@@ -5,9 +5,12 @@
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
 //! Useful utilities for KZG PCS
+use ark_ec::CurveGroup;
 use ark_ff::PrimeField;
 use ark_poly::DenseMultilinearExtension;
-use ark_std::{end_timer, start_timer, vec::Vec};
+use ark_std::{cfg_into_iter, end_timer, log2, start_timer, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 
 use crate::PCSError;
 
@@ -49,3 +52,72 @@ pub fn eq_eval<F: PrimeField>(x: &[F], y: &[F]) -> Result<F, PCSError> {
     end_timer!(start);
     Ok(res)
 }
+
+/// Multi-scalar multiplication with scalars less than `2^num_bits`.
+///
+/// This runs Pippenger's algorithm over the `num_bits` low bits only, rather
+/// than over the bit size of the scalar field, so boolean scalars only take
+/// one addition each.
+pub fn small_scalar_msm<G: CurveGroup>(bases: &[G::Affine], scalars: &[u64], num_bits: u32) -> G {
+    let size = ark_std::cmp::min(bases.len(), scalars.len());
+    #[cfg(feature = "parallel")]
+    let num_chunks = rayon::current_num_threads();
+    #[cfg(not(feature = "parallel"))]
+    let num_chunks = 1;
+    let chunk_size = ark_std::cmp::max(size.div_ceil(num_chunks), 1);
+
+    cfg_into_iter!(0..size.div_ceil(chunk_size))
+        .map(|i| {
+            let range = i * chunk_size..ark_std::cmp::min((i + 1) * chunk_size, size);
+            small_scalar_msm_serial::<G>(&bases[range.clone()], &scalars[range], num_bits)
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .fold(G::zero(), |acc, chunk| acc + chunk)
+}
+
+fn small_scalar_msm_serial<G: CurveGroup>(
+    bases: &[G::Affine],
+    scalars: &[u64],
+    num_bits: u32,
+) -> G {
+    if num_bits <= 1 {
+        return bases
+            .iter()
+            .zip(scalars.iter())
+            .filter(|(_, scalar)| **scalar != 0)
+            .fold(G::zero(), |acc, (base, _)| acc + base);
+    }
+
+    // window size as in arkworks, about ln(size) + 2 bits
+    let c = if bases.len() < 32 {
+        3
+    } else {
+        log2(bases.len()) * 69 / 100 + 2
+    };
+    let c = ark_std::cmp::min(c, num_bits);
+    let mask = (1 << c) - 1;
+
+    let mut res = G::zero();
+    for (k, window_start) in (0..num_bits).step_by(c as usize).rev().enumerate() {
+        if k > 0 {
+            for _ in 0..c {
+                res.double_in_place();
+            }
+        }
+        let mut buckets = vec![G::zero(); mask as usize];
+        for (base, scalar) in bases.iter().zip(scalars.iter()) {
+            let digit = (scalar >> window_start) & mask;
+            if digit != 0 {
+                buckets[digit as usize - 1] += base;
+            }
+        }
+        // \sum_i i * buckets[i - 1] as a sum of running sums
+        let mut running_sum = G::zero();
+        for bucket in buckets.into_iter().rev() {
+            running_sum += bucket;
+            res += running_sum;
+        }
+    }
+    res
+}
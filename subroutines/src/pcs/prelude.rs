@@ -6,10 +6,14 @@
 
 //! Prelude
 pub use crate::pcs::{
+    brakedown::{BrakedownCommitment, BrakedownPCS, BrakedownParams, BrakedownProof},
     errors::PCSError,
+    hyrax::{HyraxCommitment, HyraxPCS, HyraxParams, HyraxProof},
     multilinear_kzg::{
+        accumulation::KzgAccumulator,
         batching::BatchProof,
         srs::{MultilinearProverParam, MultilinearUniversalParams, MultilinearVerifierParam},
+        srs_file::MultilinearSrsFile,
         MultilinearKzgPCS, MultilinearKzgProof,
     },
     structs::Commitment,
@@ -17,5 +21,9 @@ pub use crate::pcs::{
         srs::{UnivariateProverParam, UnivariateUniversalParams, UnivariateVerifierParam},
         UnivariateKzgBatchProof, UnivariateKzgPCS, UnivariateKzgProof,
     },
-    PolynomialCommitmentScheme, StructuredReferenceString,
+    zeromorph::{
+        srs::{ZeromorphProverParam, ZeromorphUniversalParams, ZeromorphVerifierParam},
+        ZeromorphPCS, ZeromorphProof,
+    },
+    AccumulationScheme, PolynomialCommitmentScheme, StructuredReferenceString,
 };
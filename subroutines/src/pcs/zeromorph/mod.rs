@@ -0,0 +1,637 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Zeromorph polynomial commitment scheme, a multilinear
+//! commitment built on univariate KZG
+//! [KT23](https://eprint.iacr.org/2023/917.pdf).
+//!
+//! A multilinear polynomial `f` of `n` variables is committed to as the
+//! univariate polynomial `U_n(f)(X) = \sum_i f_i X^i` whose coefficients are
+//! its evaluations over the boolean hypercube, so that any powers-of-tau SRS
+//! can be used. The evaluation `v = f(u)` is proven by the quotients `q_k`
+//! of `f(X) - v = \sum_k (X_k - u_k) q_k(X_0, ..., X_{k-1})`, from the
+//! univariate identity
+//!
+//! `U_n(f) - v Phi_n(X) = \sum_k (X^{2^k} Phi_{n-k-1}(X^{2^{k+1}}) - u_k
+//! Phi_{n-k}(X^{2^k})) U_k(q_k)`
+//!
+//! where `Phi_n(X) = \sum_{i < 2^n} X^i`. The degrees of the `U_k(q_k)` are
+//! checked in a batch, through the shift of `\sum_k y^k X^{2^n - 2^k}
+//! U_k(q_k)` to the maximum degree of the SRS. The verifier key holds three
+//! G2 elements whatever the number of variables.
+
+pub mod ptau;
+pub mod srs;
+
+use crate::pcs::{
+    multilinear_kzg::batching::{batch_sum_check_prove, batch_sum_check_verify, merge_scalars},
+    prelude::{BatchProof, Commitment, UnivariateKzgPCS, UnivariateKzgProof},
+    PCSError, PolynomialCommitmentScheme, StructuredReferenceString,
+};
+use ark_ec::{
+    pairing::Pairing, scalar_mul::variable_base::VariableBaseMSM, AffineRepr, CurveGroup,
+};
+use ark_ff::{Field, PrimeField};
+use ark_poly::{univariate::DensePolynomial, DenseMultilinearExtension, DenseUVPolynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+    borrow::Borrow, end_timer, format, marker::PhantomData, rand::Rng, start_timer,
+    string::ToString, sync::Arc, vec, vec::Vec, One, Zero,
+};
+use srs::{ZeromorphProverParam, ZeromorphUniversalParams, ZeromorphVerifierParam};
+use transcript::{IOPTranscript, Transcript};
+
+/// Zeromorph Polynomial Commitment Scheme on multilinear polynomials.
+pub struct ZeromorphPCS<E: Pairing> {
+    #[doc(hidden)]
+    phantom: PhantomData<E>,
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+/// proof of opening
+pub struct ZeromorphProof<E: Pairing> {
+    /// Commitments to the quotients `U_k(q_k)`
+    pub quotient_commitments: Vec<Commitment<E>>,
+    /// Commitment to the batched quotient `\sum_k y^k X^{2^n - 2^k}
+    /// U_k(q_k)`
+    pub batched_quotient_commitment: Commitment<E>,
+    /// Commitment to the batched quotient shifted to the maximum degree of
+    /// the SRS
+    pub shifted_quotient_commitment: Commitment<E>,
+    /// KZG proof that the linear combination of the identities vanishes at
+    /// the challenge `x`
+    pub proof: UnivariateKzgProof<E>,
+}
+
+impl<E: Pairing> PolynomialCommitmentScheme<E> for ZeromorphPCS<E> {
+    // Parameters
+    type ProverParam = ZeromorphProverParam<E>;
+    type VerifierParam = ZeromorphVerifierParam<E>;
+    type SRS = ZeromorphUniversalParams<E>;
+    // Polynomial and its associated types
+    type Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>;
+    type Point = Vec<E::ScalarField>;
+    type Evaluation = E::ScalarField;
+    // Commitments and proofs
+    type Commitment = Commitment<E>;
+    type Proof = ZeromorphProof<E>;
+    type BatchProof = BatchProof<E, Self>;
+
+    /// Build SRS for testing.
+    ///
+    /// - For multilinear polynomials, `log_size` is the number of variables.
+    ///
+    /// WARNING: THIS FUNCTION IS FOR TESTING PURPOSE ONLY.
+    /// THE OUTPUT SRS SHOULD NOT BE USED IN PRODUCTION.
+    fn gen_srs_for_testing<R: Rng>(rng: &mut R, log_size: usize) -> Result<Self::SRS, PCSError> {
+        ZeromorphUniversalParams::<E>::gen_srs_for_testing(rng, log_size)
+    }
+
+    /// Trim the universal parameters to specialize the public parameters.
+    /// Input `supported_num_vars` for multilinear.
+    fn trim(
+        srs: impl Borrow<Self::SRS>,
+        supported_degree: Option<usize>,
+        supported_num_vars: Option<usize>,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), PCSError> {
+        assert!(supported_degree.is_none());
+
+        let supported_num_vars = match supported_num_vars {
+            Some(p) => p,
+            None => {
+                return Err(PCSError::InvalidParameters(
+                    "multilinear should receive a num_var param".to_string(),
+                ))
+            },
+        };
+        srs.borrow().trim(supported_num_vars)
+    }
+
+    /// Generate a commitment for a polynomial.
+    ///
+    /// This function takes `2^num_vars` number of scalar multiplications over
+    /// G1.
+    fn commit(
+        prover_param: impl Borrow<Self::ProverParam>,
+        poly: &Self::Polynomial,
+    ) -> Result<Self::Commitment, PCSError> {
+        let prover_param = prover_param.borrow();
+        if poly.num_vars > prover_param.num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "MlE length ({}) exceeds param limit ({})",
+                poly.num_vars, prover_param.num_vars
+            )));
+        }
+        UnivariateKzgPCS::<E>::commit(
+            &prover_param.powers_of_g,
+            &DensePolynomial::from_coefficients_slice(&poly.evaluations),
+        )
+    }
+
+    /// On input a polynomial `p` and a point `point`, outputs a proof for the
+    /// same.
+    ///
+    /// The proof is bound to the commitment of `p`, which this function
+    /// computes again.
+    fn open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomial: &Self::Polynomial,
+        point: &Self::Point,
+    ) -> Result<(Self::Proof, Self::Evaluation), PCSError> {
+        let prover_param = prover_param.borrow();
+        let commitment = Self::commit(prover_param, polynomial)?;
+        let mut transcript = IOPTranscript::new(b"Zeromorph open");
+        open_internal(
+            prover_param,
+            polynomial,
+            &commitment,
+            point,
+            &mut transcript,
+        )
+    }
+
+    /// Input a list of multilinear extensions, and a same number of points, and
+    /// a transcript, compute a multi-opening for all the polynomials.
+    ///
+    /// A sum check reduces the openings to the opening of `g'(X) = \sum_i
+    /// scalar_i * f_i(X)` at one point, which is proven as in `open`.
+    fn multi_open<T: Transcript<E::ScalarField>>(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomials: &[Self::Polynomial],
+        points: &[Self::Point],
+        evals: &[Self::Evaluation],
+        transcript: &mut T,
+    ) -> Result<BatchProof<E, Self>, PCSError> {
+        let prover_param = prover_param.borrow();
+        let open_timer = start_timer!(|| format!("multi open {} points", points.len()));
+
+        let (sum_check_proof, _scalars, g_prime) =
+            batch_sum_check_prove(polynomials, points, transcript)?;
+        let g_prime_commitment = Self::commit(prover_param, &g_prime)?;
+        let (g_prime_proof, _g_prime_eval) = open_internal(
+            prover_param,
+            &g_prime,
+            &g_prime_commitment,
+            &sum_check_proof.point,
+            transcript,
+        )?;
+
+        end_timer!(open_timer);
+        Ok(BatchProof {
+            sum_check_proof,
+            f_i_eval_at_point_i: evals.to_vec(),
+            g_prime_proof,
+        })
+    }
+
+    /// Verifies that `value` is the evaluation at `x` of the polynomial
+    /// committed inside `comm`.
+    ///
+    /// This function takes
+    /// - an MSM of size `num_var + 3`,
+    /// - 4 pairing products.
+    fn verify(
+        verifier_param: &Self::VerifierParam,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &E::ScalarField,
+        proof: &Self::Proof,
+    ) -> Result<bool, PCSError> {
+        let mut transcript = IOPTranscript::new(b"Zeromorph open");
+        verify_internal(
+            verifier_param,
+            commitment,
+            point,
+            value,
+            proof,
+            &mut transcript,
+        )
+    }
+
+    /// Verifies that `value_i` is the evaluation at `x_i` of the polynomial
+    /// `poly_i` committed inside `comm`.
+    fn batch_verify<T: Transcript<E::ScalarField>>(
+        verifier_param: &Self::VerifierParam,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        batch_proof: &Self::BatchProof,
+        transcript: &mut T,
+    ) -> Result<bool, PCSError> {
+        if commitments.len() != points.len() {
+            return Err(PCSError::InvalidParameters(format!(
+                "number of commitments ({}) does not match the number of points ({})",
+                commitments.len(),
+                points.len()
+            )));
+        }
+        let verify_timer = start_timer!(|| "batch verification");
+
+        let res = batch_sum_check_verify(
+            points,
+            &batch_proof.sum_check_proof,
+            &batch_proof.f_i_eval_at_point_i,
+            transcript,
+        )
+        .and_then(|(scalars, g_prime_eval)| {
+            // build g' commitment, with one base per distinct commitment
+            let step = start_timer!(|| "build homomorphic commitment");
+            let (commitments, scalars) = merge_scalars(commitments, &scalars);
+            let bases: Vec<_> = commitments.iter().map(|comm| comm.0).collect();
+            let g_prime_commitment =
+                Commitment(E::G1::msm_unchecked(&bases, &scalars).into_affine());
+            end_timer!(step);
+
+            verify_internal(
+                verifier_param,
+                &g_prime_commitment,
+                &batch_proof.sum_check_proof.point,
+                &g_prime_eval,
+                &batch_proof.g_prime_proof,
+                transcript,
+            )
+        });
+
+        end_timer!(verify_timer);
+        res
+    }
+}
+
+/// The quotients `q_k`, of `2^k` evaluations each, such that `f(X) - f(point)
+/// = \sum_k (X_k - point_k) q_k(X_0, ..., X_{k-1})`, and `f(point)`.
+fn compute_quotients<F: PrimeField>(evaluations: &[F], point: &[F]) -> (Vec<Vec<F>>, F) {
+    let mut f = evaluations.to_vec();
+    let mut quotients = vec![vec![]; point.len()];
+    for (k, &point_at_k) in point.iter().enumerate().rev() {
+        let cur_dim = 1 << k;
+        // q[b] = f[b, 1] - f[b, 0]
+        let q: Vec<_> = (0..cur_dim).map(|b| f[b + cur_dim] - f[b]).collect();
+        // r[b] = f[b, 0] + q[b] * p
+        f.truncate(cur_dim);
+        f.iter_mut()
+            .zip(q.iter())
+            .for_each(|(r, q)| *r += *q * point_at_k);
+        quotients[k] = q;
+    }
+    (quotients, f[0])
+}
+
+/// `Phi_num_vars(x) = \sum_{i < 2^num_vars} x^i`
+fn phi<F: Field>(num_vars: usize, x: F) -> F {
+    let mut res = F::one();
+    let mut x_power = x;
+    for _ in 0..num_vars {
+        res *= F::one() + x_power;
+        x_power.square_in_place();
+    }
+    res
+}
+
+/// The scalars of the quotients `U_k(q_k)` in `zeta_x + z * Z_x`, the
+/// combination of the degree check and the evaluation identity at `x` which
+/// vanishes at `x`:
+///
+/// `- y^k x^{2^max_num_vars - 2^k} - z (x^{2^k} Phi_{n-k-1}(x^{2^{k+1}}) -
+/// point_k Phi_{n-k}(x^{2^k}))`
+fn quotient_scalars<F: PrimeField>(max_num_vars: usize, point: &[F], x: F, y: F, z: F) -> Vec<F> {
+    let num_vars = point.len();
+    let mut x_power = x; // x^{2^k}
+    let mut y_power = F::one(); // y^k
+    let mut scalars = Vec::with_capacity(num_vars);
+    for (k, point_at_k) in point.iter().enumerate() {
+        let x_next_power = x_power.square();
+        let degree_check = y_power * x.pow([(1u64 << max_num_vars) - (1u64 << k)]);
+        let identity = x_power * phi(num_vars - k - 1, x_next_power)
+            - *point_at_k * phi(num_vars - k, x_power);
+        scalars.push(-degree_check - z * identity);
+        x_power = x_next_power;
+        y_power *= y;
+    }
+    scalars
+}
+
+/// Prove the evaluation at `point` of `polynomial`, committed inside
+/// `commitment`.
+///
+/// This function takes about `2^{max_num_vars + 2}` scalar multiplications
+/// over G1: `2^num_vars` for the quotients, `2^{max_num_vars + 1}` for the
+/// batched quotient and its shift, and `2^max_num_vars` for the KZG proof.
+fn open_internal<E: Pairing, T: Transcript<E::ScalarField>>(
+    prover_param: &ZeromorphProverParam<E>,
+    polynomial: &DenseMultilinearExtension<E::ScalarField>,
+    commitment: &Commitment<E>,
+    point: &[E::ScalarField],
+    transcript: &mut T,
+) -> Result<(ZeromorphProof<E>, E::ScalarField), PCSError> {
+    let open_timer = start_timer!(|| format!("open mle with {} variable", polynomial.num_vars));
+
+    if polynomial.num_vars > prover_param.num_vars {
+        return Err(PCSError::InvalidParameters(format!(
+            "Polynomial num_vars {} exceed the limit {}",
+            polynomial.num_vars, prover_param.num_vars
+        )));
+    }
+    if polynomial.num_vars != point.len() {
+        return Err(PCSError::InvalidParameters(format!(
+            "Polynomial num_vars {} does not match point len {}",
+            polynomial.num_vars,
+            point.len()
+        )));
+    }
+    let max_size = 1 << prover_param.num_vars;
+
+    let step = start_timer!(|| "commit quotients");
+    let (quotients, eval) = compute_quotients(&polynomial.evaluations, point);
+    let quotient_commitments = quotients
+        .iter()
+        .map(|q| {
+            UnivariateKzgPCS::<E>::commit(
+                &prover_param.powers_of_g,
+                &DensePolynomial::from_coefficients_slice(q),
+            )
+        })
+        .collect::<Result<Vec<_>, PCSError>>()?;
+    end_timer!(step);
+
+    transcript.append_serializable_element(b"commitment", commitment)?;
+    transcript.append_serializable_element(b"point", &point.to_vec())?;
+    transcript.append_field_element(b"eval", &eval)?;
+    for quotient_commitment in quotient_commitments.iter() {
+        transcript.append_serializable_element(b"quotient", quotient_commitment)?;
+    }
+    let y = transcript.get_and_append_challenge(b"y")?;
+
+    // \sum_k y^k X^{2^max_num_vars - 2^k} U_k(q_k)
+    let step = start_timer!(|| "commit batched quotient");
+    let mut batched_quotient = vec![E::ScalarField::zero(); max_size];
+    let mut y_power = E::ScalarField::one();
+    for q in quotients.iter() {
+        batched_quotient[max_size - q.len()..]
+            .iter_mut()
+            .zip(q.iter())
+            .for_each(|(coeff, q)| *coeff += y_power * q);
+        y_power *= y;
+    }
+    let batched_quotient = DensePolynomial::from_coefficients_vec(batched_quotient);
+    let batched_quotient_commitment =
+        UnivariateKzgPCS::<E>::commit(&prover_param.powers_of_g, &batched_quotient)?;
+    let shifted_quotient_commitment =
+        UnivariateKzgPCS::<E>::commit(&prover_param.shifted_powers_of_g, &batched_quotient)?;
+    end_timer!(step);
+
+    transcript.append_serializable_element(b"batched quotient", &batched_quotient_commitment)?;
+    transcript.append_serializable_element(b"shifted quotient", &shifted_quotient_commitment)?;
+    let x = transcript.get_and_append_challenge(b"x")?;
+    let z = transcript.get_and_append_challenge(b"z")?;
+
+    // zeta_x + z * Z_x = batched quotient + z * (U_n(f) - eval * Phi_n(x)) +
+    // \sum_k scalar_k * U_k(q_k)
+    let step = start_timer!(|| "open the identities");
+    let mut coeffs = batched_quotient.coeffs;
+    coeffs.resize(max_size, E::ScalarField::zero());
+    coeffs
+        .iter_mut()
+        .zip(polynomial.evaluations.iter())
+        .for_each(|(coeff, f)| *coeff += z * f);
+    coeffs[0] -= z * eval * phi(point.len(), x);
+    for (q, scalar) in quotients
+        .iter()
+        .zip(quotient_scalars(prover_param.num_vars, point, x, y, z))
+    {
+        coeffs
+            .iter_mut()
+            .zip(q.iter())
+            .for_each(|(coeff, q)| *coeff += scalar * q);
+    }
+    let (proof, _zero) = UnivariateKzgPCS::<E>::open(
+        &prover_param.powers_of_g,
+        &DensePolynomial::from_coefficients_vec(coeffs),
+        &x,
+    )?;
+    end_timer!(step);
+
+    end_timer!(open_timer);
+    Ok((
+        ZeromorphProof {
+            quotient_commitments,
+            batched_quotient_commitment,
+            shifted_quotient_commitment,
+            proof,
+        },
+        eval,
+    ))
+}
+
+/// Verifies that `value` is the evaluation at `point` of the polynomial
+/// committed inside `commitment`.
+fn verify_internal<E: Pairing, T: Transcript<E::ScalarField>>(
+    verifier_param: &ZeromorphVerifierParam<E>,
+    commitment: &Commitment<E>,
+    point: &[E::ScalarField],
+    value: &E::ScalarField,
+    proof: &ZeromorphProof<E>,
+    transcript: &mut T,
+) -> Result<bool, PCSError> {
+    if point.len() > verifier_param.num_vars {
+        return Err(PCSError::InvalidParameters(format!(
+            "point length ({}) exceeds param limit ({})",
+            point.len(),
+            verifier_param.num_vars
+        )));
+    }
+    if proof.quotient_commitments.len() != point.len() {
+        return Ok(false);
+    }
+    let verify_timer = start_timer!(|| "verify");
+
+    transcript.append_serializable_element(b"commitment", commitment)?;
+    transcript.append_serializable_element(b"point", &point.to_vec())?;
+    transcript.append_field_element(b"eval", value)?;
+    for quotient_commitment in proof.quotient_commitments.iter() {
+        transcript.append_serializable_element(b"quotient", quotient_commitment)?;
+    }
+    let y = transcript.get_and_append_challenge(b"y")?;
+    transcript
+        .append_serializable_element(b"batched quotient", &proof.batched_quotient_commitment)?;
+    transcript
+        .append_serializable_element(b"shifted quotient", &proof.shifted_quotient_commitment)?;
+    let x = transcript.get_and_append_challenge(b"x")?;
+    let z = transcript.get_and_append_challenge(b"z")?;
+
+    // the batched quotient is of degree less than 2^max_num_vars
+    let degree_check = E::multi_pairing(
+        [
+            proof.batched_quotient_commitment.0,
+            (-proof.shifted_quotient_commitment.0.into_group()).into_affine(),
+        ],
+        [verifier_param.shifted_h, verifier_param.univariate.h],
+    )
+    .0
+    .is_one();
+    if !degree_check {
+        end_timer!(verify_timer);
+        return Ok(false);
+    }
+
+    // commitment to zeta_x + z * Z_x, which vanishes at x
+    let mut bases = vec![
+        proof.batched_quotient_commitment.0,
+        commitment.0,
+        verifier_param.univariate.g,
+    ];
+    let mut scalars = vec![E::ScalarField::one(), z, -z * value * phi(point.len(), x)];
+    bases.extend(proof.quotient_commitments.iter().map(|comm| comm.0));
+    scalars.extend(quotient_scalars(verifier_param.num_vars, point, x, y, z));
+    let identity_commitment = Commitment(E::G1::msm_unchecked(&bases, &scalars).into_affine());
+
+    let res = UnivariateKzgPCS::<E>::verify(
+        &verifier_param.univariate,
+        &identity_commitment,
+        &x,
+        &E::ScalarField::zero(),
+        &proof.proof,
+    )?;
+    end_timer!(verify_timer);
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_poly::MultilinearExtension;
+    use ark_std::{test_rng, UniformRand};
+
+    type E = Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+
+    fn test_single_helper<R: Rng>(
+        params: &ZeromorphUniversalParams<E>,
+        poly: &Arc<DenseMultilinearExtension<Fr>>,
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        let nv = poly.num_vars();
+        let (ck, vk) = ZeromorphPCS::<E>::trim(params, None, Some(nv))?;
+        let point: Vec<_> = (0..nv).map(|_| Fr::rand(rng)).collect();
+        let com = ZeromorphPCS::<E>::commit(&ck, poly)?;
+        let (proof, value) = ZeromorphPCS::<E>::open(&ck, poly, &point)?;
+        assert_eq!(value, poly.evaluate(&point).unwrap());
+
+        assert!(ZeromorphPCS::<E>::verify(
+            &vk, &com, &point, &value, &proof
+        )?);
+
+        let value = Fr::rand(rng);
+        assert!(!ZeromorphPCS::<E>::verify(
+            &vk, &com, &point, &value, &proof
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_commit() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+
+        let params = ZeromorphPCS::<E>::gen_srs_for_testing(&mut rng, 10)?;
+
+        // normal polynomials
+        let poly1 = Arc::new(DenseMultilinearExtension::rand(8, &mut rng));
+        test_single_helper(&params, &poly1, &mut rng)?;
+
+        // single-variate polynomials
+        let poly2 = Arc::new(DenseMultilinearExtension::rand(1, &mut rng));
+        test_single_helper(&params, &poly2, &mut rng)?;
+
+        // the whole SRS
+        let poly3 = Arc::new(DenseMultilinearExtension::rand(10, &mut rng));
+        test_single_helper(&params, &poly3, &mut rng)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_degree_check() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+
+        let params = ZeromorphPCS::<E>::gen_srs_for_testing(&mut rng, 6)?;
+        let (ck, vk) = ZeromorphPCS::<E>::trim(&params, None, Some(4))?;
+        let poly = Arc::new(DenseMultilinearExtension::rand(4, &mut rng));
+        let point: Vec<_> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+        let com = ZeromorphPCS::<E>::commit(&ck, &poly)?;
+        let (proof, value) = ZeromorphPCS::<E>::open(&ck, &poly, &point)?;
+
+        // a batched quotient which is not shifted is rejected
+        let mut bad_proof = proof.clone();
+        bad_proof.shifted_quotient_commitment = bad_proof.batched_quotient_commitment;
+        assert!(!ZeromorphPCS::<E>::verify(
+            &vk, &com, &point, &value, &bad_proof
+        )?);
+
+        // a missing quotient is rejected
+        let mut bad_proof = proof;
+        bad_proof.quotient_commitments.pop();
+        assert!(!ZeromorphPCS::<E>::verify(
+            &vk, &com, &point, &value, &bad_proof
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_open() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+
+        let params = ZeromorphPCS::<E>::gen_srs_for_testing(&mut rng, 8)?;
+        let nv = 6;
+        let (ck, vk) = ZeromorphPCS::<E>::trim(&params, None, Some(nv))?;
+        let polys: Vec<_> = (0..3)
+            .map(|_| Arc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng)))
+            .collect();
+        let points: Vec<Vec<_>> = (0..3)
+            .map(|_| (0..nv).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        let evals: Vec<_> = polys
+            .iter()
+            .zip(points.iter())
+            .map(|(poly, point)| poly.evaluate(point).unwrap())
+            .collect();
+        let commitments = polys
+            .iter()
+            .map(|poly| ZeromorphPCS::<E>::commit(&ck, poly))
+            .collect::<Result<Vec<_>, PCSError>>()?;
+
+        let mut transcript = IOPTranscript::new("test transcript".as_ref());
+        transcript.append_field_element("init".as_ref(), &Fr::zero())?;
+        let batch_proof =
+            ZeromorphPCS::<E>::multi_open(&ck, &polys, &points, &evals, &mut transcript)?;
+
+        let mut transcript = IOPTranscript::new("test transcript".as_ref());
+        transcript.append_field_element("init".as_ref(), &Fr::zero())?;
+        assert!(ZeromorphPCS::<E>::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &batch_proof,
+            &mut transcript
+        )?);
+
+        // a wrong evaluation is rejected
+        let mut bad_proof = batch_proof;
+        bad_proof.f_i_eval_at_point_i[1] += Fr::one();
+        let mut transcript = IOPTranscript::new("test transcript".as_ref());
+        transcript.append_field_element("init".as_ref(), &Fr::zero())?;
+        assert!(!ZeromorphPCS::<E>::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &bad_proof,
+            &mut transcript
+        )
+        .unwrap_or(false));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,583 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Importing the output of a powers-of-tau ceremony as Zeromorph parameters.
+//!
+//! The ceremony outputs `{ \tau^i G }` and `{ \tau^i H }`, either in the
+//! monomial basis or, for `G`, in the Lagrange basis of a multiplicative
+//! subgroup. The `.ptau` files of snarkjs and of the perpetual powers-of-tau
+//! are read directly.
+//!
+//! Note that `MultilinearUniversalParams` can not be derived from a
+//! powers-of-tau: its trapdoors `t_1, ..., t_n` must be independent. With
+//! `t_i = \tau^{2^{i-1}}`, the check of `MultilinearKzgPCS` becomes a
+//! univariate identity in `\tau`, which a prover satisfies for any value with
+//! the public powers of `\tau`. Zeromorph is sound over a powers-of-tau, as it
+//! checks the degrees of the quotients.
+
+use crate::pcs::{
+    prelude::UnivariateUniversalParams, zeromorph::srs::ZeromorphUniversalParams, PCSError,
+};
+use ark_ec::{
+    pairing::Pairing,
+    scalar_mul::variable_base::VariableBaseMSM,
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{BigInteger, Field, One, PrimeField};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{
+    collections::BTreeMap, end_timer, format, log2, rand::Rng, start_timer, string::ToString, vec,
+    vec::Vec, UniformRand,
+};
+use std::io::Read;
+
+/// Magic bytes of a `.ptau` file
+const PTAU_MAGIC: &[u8; 4] = b"ptau";
+/// Section of the header: field size, modulus and power
+const HEADER_SECTION: u32 = 1;
+/// Section of `{ \tau^i G }` for `i` below `2^{power + 1} - 1`
+const TAU_G1_SECTION: u32 = 2;
+/// Section of `{ \tau^i H }` for `i` below `2^power`
+const TAU_G2_SECTION: u32 = 3;
+
+impl<E: Pairing> ZeromorphUniversalParams<E> {
+    /// Build the parameters for `num_vars` variables from the monomial basis
+    /// `{ \tau^i G }` for `i` below `2^num_vars` and `{ \tau^i H }` for `i`
+    /// below `max(2, 2^num_vars)`.
+    ///
+    /// The consistency of the input is checked with random pairings.
+    pub fn from_monomial_basis<R: Rng>(
+        powers_of_g: Vec<E::G1Affine>,
+        powers_of_h: &[E::G2Affine],
+        rng: &mut R,
+    ) -> Result<Self, PCSError> {
+        let num_vars = num_vars_of(powers_of_g.len())?;
+        if powers_of_h.len() < powers_of_g.len().max(2) {
+            return Err(PCSError::InvalidParameters(format!(
+                "{} powers of H is not enough for {} variables",
+                powers_of_h.len(),
+                num_vars
+            )));
+        }
+        let size = powers_of_g.len();
+        let pp = Self {
+            univariate: UnivariateUniversalParams {
+                powers_of_g,
+                h: powers_of_h[0],
+                beta_h: powers_of_h[1],
+            },
+            shifted_h: (0..=num_vars)
+                .map(|n| powers_of_h[size - (1 << n)])
+                .collect(),
+        };
+        pp.check_consistency(rng)?;
+        Ok(pp)
+    }
+
+    /// Build the parameters for `num_vars` variables from the Lagrange basis
+    /// `{ L_i(\tau) G }` of the subgroup of size `2^num_vars` of
+    /// `Radix2EvaluationDomain`, and `{ \tau^i H }` for `i` below `max(2,
+    /// 2^num_vars)`.
+    ///
+    /// The basis is converted with an FFT over G1, as `\tau^k = \sum_i
+    /// \omega^{ik} L_i(\tau)`, and the consistency of the input is checked
+    /// with random pairings.
+    pub fn from_lagrange_basis<R: Rng>(
+        lagrange_powers_of_g: &[E::G1Affine],
+        powers_of_h: &[E::G2Affine],
+        rng: &mut R,
+    ) -> Result<Self, PCSError> {
+        let convert_timer = start_timer!(|| "convert the Lagrange basis");
+        num_vars_of(lagrange_powers_of_g.len())?;
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(lagrange_powers_of_g.len())
+            .ok_or_else(|| {
+                PCSError::InvalidParameters(format!(
+                    "no FFT domain of size {}",
+                    lagrange_powers_of_g.len()
+                ))
+            })?;
+        let mut powers: Vec<E::G1> = lagrange_powers_of_g
+            .iter()
+            .map(|p| p.into_group())
+            .collect();
+        group_fft(&mut powers, domain.group_gen);
+        end_timer!(convert_timer);
+        Self::from_monomial_basis(E::G1::normalize_batch(&powers), powers_of_h, rng)
+    }
+
+    /// Read the parameters for `num_vars` variables from a `.ptau` file of a
+    /// ceremony of at least `2^num_vars` powers, as output by snarkjs.
+    ///
+    /// Only the beginning of the powers of `G` and `H` are read, and the
+    /// consistency of the input is checked with random pairings.
+    pub fn read_ptau<P1, P2, Rd, R>(
+        reader: Rd,
+        num_vars: usize,
+        rng: &mut R,
+    ) -> Result<Self, PCSError>
+    where
+        P1: SWCurveConfig,
+        P2: SWCurveConfig,
+        E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+        Rd: Read,
+        R: Rng,
+    {
+        let size = 1usize
+            .checked_shl(num_vars as u32)
+            .ok_or_else(|| PCSError::InvalidParameters("too many variables".to_string()))?;
+        let read_timer = start_timer!(|| format!("read ptau for {} variables", num_vars));
+        let powers = Self::read_ptau_powers::<P1, P2, Rd>(reader, num_vars, size);
+        end_timer!(read_timer);
+        let (powers_of_g, powers_of_h) = powers?;
+
+        let pp = Self {
+            univariate: UnivariateUniversalParams {
+                powers_of_g,
+                h: powers_of_h[&0],
+                beta_h: powers_of_h[&1],
+            },
+            shifted_h: (0..=num_vars)
+                .map(|n| powers_of_h[&(size - (1 << n))])
+                .collect(),
+        };
+        pp.check_consistency(rng)?;
+        Ok(pp)
+    }
+
+    /// Read the first `size` powers of `G`, and the powers of `H` needed for
+    /// `num_vars` variables, from a `.ptau` file.
+    #[allow(clippy::type_complexity)]
+    fn read_ptau_powers<P1, P2, Rd>(
+        mut reader: Rd,
+        num_vars: usize,
+        size: usize,
+    ) -> Result<(Vec<E::G1Affine>, BTreeMap<usize, E::G2Affine>), PCSError>
+    where
+        P1: SWCurveConfig,
+        P2: SWCurveConfig,
+        E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+        Rd: Read,
+    {
+        let mut magic = [0u8; 4];
+        read_bytes(&mut reader, &mut magic)?;
+        if &magic != PTAU_MAGIC {
+            return Err(PCSError::InvalidParameters("not a ptau file".to_string()));
+        }
+        let _version = read_u32(&mut reader)?;
+        let num_sections = read_u32(&mut reader)?;
+
+        // bytes per base field element
+        let mut n8: Option<usize> = None;
+        let mut powers_of_g: Option<Vec<E::G1Affine>> = None;
+        let mut powers_of_h: Option<BTreeMap<usize, E::G2Affine>> = None;
+        for _ in 0..num_sections {
+            if powers_of_g.is_some() && powers_of_h.is_some() {
+                break;
+            }
+            let section = read_u32(&mut reader)?;
+            let section_size = read_u64(&mut reader)?;
+            let mut section_reader = (&mut reader).take(section_size);
+            match section {
+                HEADER_SECTION => {
+                    let size_of_elem = read_u32(&mut section_reader)? as usize;
+                    let mut modulus = vec![0u8; size_of_elem];
+                    read_bytes(&mut section_reader, &mut modulus)?;
+                    let mut expected =
+                        <P1::BaseField as Field>::BasePrimeField::MODULUS.to_bytes_le();
+                    expected.resize(size_of_elem, 0);
+                    if modulus != expected {
+                        return Err(PCSError::InvalidParameters(
+                            "the ptau file is over another curve".to_string(),
+                        ));
+                    }
+                    let power = read_u32(&mut section_reader)? as usize;
+                    if num_vars > power {
+                        return Err(PCSError::InvalidParameters(format!(
+                            "the ptau file supports {} variables, not {}",
+                            power, num_vars
+                        )));
+                    }
+                    n8 = Some(size_of_elem);
+                },
+                TAU_G1_SECTION => {
+                    let n8 = n8.ok_or_else(missing_header)?;
+                    let r_inv = montgomery_r_inv(n8);
+                    powers_of_g = Some(
+                        (0..size)
+                            .map(|_| read_point::<P1, _>(&mut section_reader, n8, &r_inv))
+                            .collect::<Result<Vec<_>, PCSError>>()?,
+                    );
+                },
+                TAU_G2_SECTION => {
+                    let n8 = n8.ok_or_else(missing_header)?;
+                    let r_inv = montgomery_r_inv(n8);
+                    let point_size = point_size::<P2>(n8);
+                    // h, \tau h and the shifts \tau^{2^num_vars - 2^n} h
+                    let mut needed: Vec<_> = (0..=num_vars).map(|n| size - (1 << n)).collect();
+                    needed.extend([0, 1]);
+                    let mut points = BTreeMap::new();
+                    for i in 0..size.max(2) {
+                        if needed.contains(&i) {
+                            points.insert(i, read_point::<P2, _>(&mut section_reader, n8, &r_inv)?);
+                        } else {
+                            skip_bytes(&mut section_reader, point_size as u64)?;
+                        }
+                    }
+                    powers_of_h = Some(points);
+                },
+                _ => {},
+            }
+            // skip the rest of the section
+            let remaining = section_reader.limit();
+            skip_bytes(&mut section_reader, remaining)?;
+        }
+        match (powers_of_g, powers_of_h) {
+            (Some(g), Some(h)) => Ok((g, h)),
+            _ => Err(PCSError::InvalidParameters(
+                "the ptau file misses the powers of tau".to_string(),
+            )),
+        }
+    }
+
+    /// Check that the parameters are powers of the same `\tau`, with random
+    /// linear combinations of the powers of G and two multi-pairings.
+    pub fn check_consistency<R: Rng>(&self, rng: &mut R) -> Result<(), PCSError> {
+        let powers_of_g = &self.univariate.powers_of_g;
+        let size = powers_of_g.len();
+        if self.shifted_h.is_empty()
+            || size != 1 << self.max_num_vars()
+            || powers_of_g[0].is_zero()
+            || self.univariate.h.is_zero()
+        {
+            return Err(PCSError::InvalidParameters(
+                "degenerate powers of tau".to_string(),
+            ));
+        }
+        let check_timer = start_timer!(|| "check the consistency of the powers of tau");
+
+        // e(\sum_i r_i \tau^{i+1} G, H) = e(\sum_i r_i \tau^i G, \tau H)
+        let r: Vec<_> = (1..size).map(|_| E::ScalarField::rand(rng)).collect();
+        let shifted = E::G1::msm_unchecked(&powers_of_g[1..], &r);
+        let unshifted = E::G1::msm_unchecked(&powers_of_g[..size - 1], &r);
+        let powers_check = E::multi_pairing(
+            [shifted.into_affine(), (-unshifted).into_affine()],
+            [self.univariate.h, self.univariate.beta_h],
+        )
+        .0
+        .is_one();
+
+        // e(\sum_n s_n \tau^{size - 2^n} G, H) = e(G, \sum_n s_n \tau^{size - 2^n} H)
+        let s: Vec<_> = self
+            .shifted_h
+            .iter()
+            .map(|_| E::ScalarField::rand(rng))
+            .collect();
+        let shifted_g: Vec<_> = (0..self.shifted_h.len())
+            .map(|n| powers_of_g[size - (1 << n)])
+            .collect();
+        let shifts_check = E::multi_pairing(
+            [
+                E::G1::msm_unchecked(&shifted_g, &s).into_affine(),
+                (-powers_of_g[0].into_group()).into_affine(),
+            ],
+            [
+                self.univariate.h,
+                E::G2::msm_unchecked(&self.shifted_h, &s).into_affine(),
+            ],
+        )
+        .0
+        .is_one();
+
+        end_timer!(check_timer);
+        if powers_check && shifts_check {
+            Ok(())
+        } else {
+            Err(PCSError::InvalidParameters(
+                "inconsistent powers of tau".to_string(),
+            ))
+        }
+    }
+}
+
+fn num_vars_of(size: usize) -> Result<usize, PCSError> {
+    if !size.is_power_of_two() {
+        return Err(PCSError::InvalidParameters(format!(
+            "the number of powers {} is not a power of two",
+            size
+        )));
+    }
+    Ok(log2(size) as usize)
+}
+
+fn missing_header() -> PCSError {
+    PCSError::InvalidParameters("the ptau header should come first".to_string())
+}
+
+fn io_error(e: std::io::Error) -> PCSError {
+    PCSError::InvalidParameters(format!("failed to read the ptau file: {}", e))
+}
+
+fn read_bytes<Rd: Read>(reader: &mut Rd, buf: &mut [u8]) -> Result<(), PCSError> {
+    reader.read_exact(buf).map_err(io_error)
+}
+
+fn read_u32<Rd: Read>(reader: &mut Rd) -> Result<u32, PCSError> {
+    let mut buf = [0u8; 4];
+    read_bytes(reader, &mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<Rd: Read>(reader: &mut Rd) -> Result<u64, PCSError> {
+    let mut buf = [0u8; 8];
+    read_bytes(reader, &mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn skip_bytes<Rd: Read>(reader: &mut Rd, len: u64) -> Result<(), PCSError> {
+    let skipped = std::io::copy(&mut reader.take(len), &mut std::io::sink()).map_err(io_error)?;
+    if skipped != len {
+        return Err(PCSError::InvalidParameters(
+            "truncated ptau file".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// `R^{-1}` for the Montgomery form of elements of `n8` bytes, `R = 2^{8 n8}`
+fn montgomery_r_inv<F: PrimeField>(n8: usize) -> F {
+    F::from(2u64)
+        .pow([8 * n8 as u64])
+        .inverse()
+        .expect("2 is invertible")
+}
+
+/// Size in bytes of an affine point whose coordinates are `n8`-byte base
+/// field elements
+fn point_size<P: SWCurveConfig>(n8: usize) -> usize {
+    2 * P::BaseField::extension_degree() as usize * n8
+}
+
+/// Read an affine point as the two coordinates, each as its base prime field
+/// elements in little-endian Montgomery form, with zeros for the point at
+/// infinity. The point is checked to be on the curve and in the subgroup.
+fn read_point<P: SWCurveConfig, Rd: Read>(
+    reader: &mut Rd,
+    n8: usize,
+    r_inv: &<P::BaseField as Field>::BasePrimeField,
+) -> Result<Affine<P>, PCSError> {
+    let mut bytes = vec![0u8; point_size::<P>(n8)];
+    read_bytes(reader, &mut bytes)?;
+    if bytes.iter().all(|b| *b == 0) {
+        return Ok(Affine::identity());
+    }
+    let mut coordinates = bytes.chunks(bytes.len() / 2).map(|coordinate| {
+        let elems: Vec<_> = coordinate
+            .chunks(n8)
+            .map(|elem| {
+                <P::BaseField as Field>::BasePrimeField::from_le_bytes_mod_order(elem) * r_inv
+            })
+            .collect();
+        P::BaseField::from_base_prime_field_elems(&elems)
+            .ok_or_else(|| PCSError::InvalidParameters("invalid coordinate".to_string()))
+    });
+    let x = coordinates.next().expect("two coordinates")?;
+    let y = coordinates.next().expect("two coordinates")?;
+    let point = Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(PCSError::InvalidParameters(
+            "the ptau file has an invalid point".to_string(),
+        ));
+    }
+    Ok(point)
+}
+
+/// In place FFT over a group: `points_k <- \sum_i \omega^{ik} points_i`.
+fn group_fft<G: CurveGroup>(points: &mut [G], omega: G::ScalarField) {
+    let n = points.len();
+    if n <= 1 {
+        return;
+    }
+    let log_n = log2(n);
+    for k in 0..n {
+        let rk = k.reverse_bits() >> (usize::BITS - log_n);
+        if k < rk {
+            points.swap(k, rk);
+        }
+    }
+    let mut m = 1;
+    while m < n {
+        let omega_m = omega.pow([(n / (2 * m)) as u64]);
+        for k in (0..n).step_by(2 * m) {
+            let mut w = G::ScalarField::one();
+            for j in 0..m {
+                let t = points[k + j + m] * w;
+                let u = points[k + j];
+                points[k + j] = u + t;
+                points[k + j + m] = u - t;
+                w *= omega_m;
+            }
+        }
+        m *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, G1Projective, G2Projective};
+    use ark_ec::Group;
+    use ark_std::test_rng;
+    use std::ops::Mul;
+
+    type E = Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+
+    fn write_point<P: SWCurveConfig>(bytes: &mut Vec<u8>, point: &Affine<P>, n8: usize) {
+        let r = <P::BaseField as Field>::BasePrimeField::from(2u64).pow([8 * n8 as u64]);
+        for coordinate in [point.x, point.y] {
+            for elem in coordinate.to_base_prime_field_elements() {
+                let mut le = (elem * r).into_bigint().to_bytes_le();
+                le.resize(n8, 0);
+                bytes.extend(le);
+            }
+        }
+    }
+
+    /// A ptau file of `2^power` powers of `tau`, with an extra section
+    fn write_ptau(tau: Fr, power: usize) -> Vec<u8> {
+        let n8 = 48;
+        let g = G1Projective::generator();
+        let h = G2Projective::generator();
+        let mut tau_powers = vec![Fr::one()];
+        for i in 1..(2 << power) - 1 {
+            tau_powers.push(tau_powers[i - 1] * tau);
+        }
+
+        let mut header = Vec::new();
+        header.extend((n8 as u32).to_le_bytes());
+        let mut modulus = ark_bls12_381::Fq::MODULUS.to_bytes_le();
+        modulus.resize(n8, 0);
+        header.extend(modulus);
+        header.extend((power as u32).to_le_bytes());
+        header.extend((power as u32).to_le_bytes());
+
+        let mut tau_g1 = Vec::new();
+        for tau_i in tau_powers.iter() {
+            write_point(&mut tau_g1, &g.mul(tau_i).into_affine(), n8);
+        }
+        let mut tau_g2 = Vec::new();
+        for tau_i in tau_powers[..1 << power].iter() {
+            write_point(&mut tau_g2, &h.mul(tau_i).into_affine(), n8);
+        }
+
+        let sections = [
+            (HEADER_SECTION, header),
+            (7, vec![1, 2, 3]),
+            (TAU_G1_SECTION, tau_g1),
+            (TAU_G2_SECTION, tau_g2),
+        ];
+        let mut bytes = PTAU_MAGIC.to_vec();
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend((sections.len() as u32).to_le_bytes());
+        for (section, data) in sections.iter() {
+            bytes.extend(section.to_le_bytes());
+            bytes.extend((data.len() as u64).to_le_bytes());
+            bytes.extend(data);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_read_ptau() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+        let tau = Fr::rand(&mut rng);
+        let ptau = write_ptau(tau, 4);
+
+        for num_vars in [0, 2, 4] {
+            let pp = ZeromorphUniversalParams::<E>::read_ptau(&ptau[..], num_vars, &mut rng)?;
+            assert_eq!(pp.max_num_vars(), num_vars);
+            assert_eq!(pp.univariate.powers_of_g.len(), 1 << num_vars);
+            if num_vars > 0 {
+                assert_eq!(
+                    pp.univariate.powers_of_g[1],
+                    G1Projective::generator().mul(tau).into_affine()
+                );
+            }
+        }
+        // not enough powers
+        assert!(ZeromorphUniversalParams::<E>::read_ptau(&ptau[..], 5, &mut rng).is_err());
+        // truncated file
+        assert!(
+            ZeromorphUniversalParams::<E>::read_ptau(&ptau[..ptau.len() - 1], 4, &mut rng).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lagrange_basis() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+        let num_vars = 5;
+        let size = 1 << num_vars;
+        let tau = Fr::rand(&mut rng);
+        let g = G1Projective::generator();
+        let h = G2Projective::generator();
+        let domain = Radix2EvaluationDomain::<Fr>::new(size).unwrap();
+        let lagrange: Vec<_> = domain
+            .evaluate_all_lagrange_coefficients(tau)
+            .iter()
+            .map(|l| g.mul(l).into_affine())
+            .collect();
+        let mut tau_power = Fr::one();
+        let mut powers_of_h = vec![];
+        for _ in 0..size {
+            powers_of_h.push(h.mul(tau_power).into_affine());
+            tau_power *= tau;
+        }
+
+        let pp =
+            ZeromorphUniversalParams::<E>::from_lagrange_basis(&lagrange, &powers_of_h, &mut rng)?;
+        let mut tau_power = Fr::one();
+        for power in pp.univariate.powers_of_g.iter() {
+            assert_eq!(*power, g.mul(tau_power).into_affine());
+            tau_power *= tau;
+        }
+
+        // another tau in G2 is rejected
+        powers_of_h[1] = h.mul(tau + Fr::one()).into_affine();
+        assert!(ZeromorphUniversalParams::<E>::from_lagrange_basis(
+            &lagrange,
+            &powers_of_h,
+            &mut rng
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_imported_commitment() -> Result<(), PCSError> {
+        use crate::pcs::{prelude::ZeromorphPCS, PolynomialCommitmentScheme};
+        use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+        use std::sync::Arc;
+
+        let mut rng = test_rng();
+        let tau = Fr::rand(&mut rng);
+        let pp = ZeromorphUniversalParams::<E>::read_ptau(&write_ptau(tau, 4)[..], 4, &mut rng)?;
+        let (ck, vk) = ZeromorphPCS::<E>::trim(&pp, None, Some(3))?;
+        let poly = Arc::new(DenseMultilinearExtension::rand(3, &mut rng));
+        let point: Vec<_> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+        let com = ZeromorphPCS::<E>::commit(&ck, &poly)?;
+        let (proof, value) = ZeromorphPCS::<E>::open(&ck, &poly, &point)?;
+        assert_eq!(value, poly.evaluate(&point).unwrap());
+        assert!(ZeromorphPCS::<E>::verify(
+            &vk, &com, &point, &value, &proof
+        )?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Implementing Structured Reference Strings for Zeromorph
+
+use crate::pcs::{
+    prelude::{UnivariateProverParam, UnivariateUniversalParams, UnivariateVerifierParam},
+    PCSError, StructuredReferenceString,
+};
+use ark_ec::{pairing::Pairing, scalar_mul::fixed_base::FixedBase, CurveGroup};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+    end_timer, format, rand::Rng, start_timer, string::ToString, vec, vec::Vec, One, UniformRand,
+};
+use derivative::Derivative;
+use std::ops::Mul;
+
+/// Universal parameters of Zeromorph: the univariate KZG parameters of a
+/// powers-of-tau ceremony, and the G2 elements of its degree checks.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ZeromorphUniversalParams<E: Pairing> {
+    /// `{ \beta^i G }` for `i` from 0 to `max_degree`, `h` and `\beta h`.
+    pub univariate: UnivariateUniversalParams<E>,
+    /// `{ \beta^{max_degree + 1 - 2^n} H }` for `n` from 0 to the maximum
+    /// number of variables.
+    pub shifted_h: Vec<E::G2Affine>,
+}
+
+impl<E: Pairing> ZeromorphUniversalParams<E> {
+    /// Returns the maximum supported number of variables
+    pub fn max_num_vars(&self) -> usize {
+        self.shifted_h.len() - 1
+    }
+}
+
+/// Prover Parameters
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ZeromorphProverParam<E: Pairing> {
+    /// number of variables
+    pub num_vars: usize,
+    /// `{ \beta^i G }` for `i` below `2^num_vars`
+    pub powers_of_g: UnivariateProverParam<E::G1Affine>,
+    /// `{ \beta^i G }` for the last `2^num_vars` powers of the SRS, to commit
+    /// to the shifted quotient of the degree check
+    pub shifted_powers_of_g: UnivariateProverParam<E::G1Affine>,
+}
+
+/// Verifier Parameters
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Default(bound = ""),
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct ZeromorphVerifierParam<E: Pairing> {
+    /// number of variables
+    pub num_vars: usize,
+    /// The univariate KZG verifier parameters: `g`, `h` and `\beta h`.
+    pub univariate: UnivariateVerifierParam<E>,
+    /// `\beta^{max_degree + 1 - 2^num_vars} h`
+    pub shifted_h: E::G2Affine,
+}
+
+impl<E: Pairing> StructuredReferenceString<E> for ZeromorphUniversalParams<E> {
+    type ProverParam = ZeromorphProverParam<E>;
+    type VerifierParam = ZeromorphVerifierParam<E>;
+
+    /// Extract the prover parameters from the public parameters.
+    fn extract_prover_param(&self, supported_num_vars: usize) -> Self::ProverParam {
+        let powers = &self.univariate.powers_of_g;
+        Self::ProverParam {
+            num_vars: supported_num_vars,
+            powers_of_g: UnivariateProverParam {
+                powers_of_g: powers[..1 << supported_num_vars].to_vec(),
+            },
+            shifted_powers_of_g: UnivariateProverParam {
+                powers_of_g: powers[powers.len() - (1 << supported_num_vars)..].to_vec(),
+            },
+        }
+    }
+
+    /// Extract the verifier parameters from the public parameters.
+    fn extract_verifier_param(&self, supported_num_vars: usize) -> Self::VerifierParam {
+        Self::VerifierParam {
+            num_vars: supported_num_vars,
+            univariate: UnivariateVerifierParam {
+                g: self.univariate.powers_of_g[0],
+                h: self.univariate.h,
+                beta_h: self.univariate.beta_h,
+            },
+            shifted_h: self.shifted_h[supported_num_vars],
+        }
+    }
+
+    /// Trim the universal parameters to specialize the public parameters
+    /// for multilinear polynomials to the given `supported_num_vars`, and
+    /// returns committer key and verifier key. `supported_num_vars` should
+    /// be in range `0..=params.max_num_vars()`
+    fn trim(
+        &self,
+        supported_num_vars: usize,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), PCSError> {
+        if supported_num_vars > self.max_num_vars()
+            || 1 << supported_num_vars > self.univariate.powers_of_g.len()
+        {
+            return Err(PCSError::InvalidParameters(format!(
+                "SRS does not support target number of vars {}",
+                supported_num_vars
+            )));
+        }
+
+        Ok((
+            self.extract_prover_param(supported_num_vars),
+            self.extract_verifier_param(supported_num_vars),
+        ))
+    }
+
+    /// Build SRS for testing, with `2^num_vars` powers of G1.
+    /// WARNING: THIS FUNCTION IS FOR TESTING PURPOSE ONLY.
+    /// THE OUTPUT SRS SHOULD NOT BE USED IN PRODUCTION.
+    fn gen_srs_for_testing<R: Rng>(rng: &mut R, num_vars: usize) -> Result<Self, PCSError> {
+        if num_vars >= usize::BITS as usize {
+            return Err(PCSError::InvalidParameters(
+                "number of variables is too large".to_string(),
+            ));
+        }
+        let setup_time = start_timer!(|| format!("Zeromorph::Setup with {} variables", num_vars));
+        let beta = E::ScalarField::rand(rng);
+        let g = E::G1::rand(rng);
+        let h = E::G2::rand(rng);
+
+        let size = 1 << num_vars;
+        let mut powers_of_beta = vec![E::ScalarField::one()];
+        let mut cur = beta;
+        for _ in 1..size {
+            powers_of_beta.push(cur);
+            cur *= &beta;
+        }
+
+        let window_size = FixedBase::get_mul_window_size(size);
+        let scalar_bits = E::ScalarField::MODULUS_BIT_SIZE as usize;
+        let g_time = start_timer!(|| "Generating powers of G");
+        let g_table = FixedBase::get_window_table(scalar_bits, window_size, g);
+        let powers_of_g =
+            FixedBase::msm::<E::G1>(scalar_bits, window_size, &g_table, &powers_of_beta);
+        end_timer!(g_time);
+
+        // the maximum degree is `size - 1`
+        let shifted_h = (0..=num_vars)
+            .map(|n| h.mul(powers_of_beta[size - (1 << n)]))
+            .collect::<Vec<_>>();
+
+        let pp = Self {
+            univariate: UnivariateUniversalParams {
+                powers_of_g: E::G1::normalize_batch(&powers_of_g),
+                h: h.into_affine(),
+                beta_h: h.mul(beta).into_affine(),
+            },
+            shifted_h: E::G2::normalize_batch(&shifted_h),
+        };
+        end_timer!(setup_time);
+        Ok(pp)
+    }
+}
@@ -0,0 +1,560 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Memory Check protocol
+
+use self::util::{eval_padded, eval_padded_identity, pad_low_variables};
+use crate::{
+    pcs::PolynomialCommitmentScheme,
+    poly_iop::{
+        errors::PolyIOPErrors, multiset_check::MultisetCheckSubClaim, prelude::MultisetCheck,
+        prod_check::util::eval_product_check_poly, PolyIOP,
+    },
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::{One, Zero};
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{end_timer, start_timer};
+use derivative::Derivative;
+use std::{fmt::Debug, sync::Arc};
+use transcript::{IOPTranscript, Transcript};
+
+mod util;
+
+/// A trace of memory operations, and the memory before and after it.
+///
+/// Each memory cell counts the operations on it. The trace has `2^n`
+/// operations. The x-th operation reads `read_values(x)` at address
+/// `addrs(x)`, whose counter is `read_timestamps(x)`, then writes
+/// `write_values(x)` at the same address and increments its counter. A read
+/// only operation writes back the value it reads.
+///
+/// The memory has `2^m` cells. Cell `y` holds `init_values(y)` before the
+/// trace, with a counter of 0, and `final_values(y)` after the trace, with a
+/// counter of `final_timestamps(y)`.
+///
+/// `T` is either the MLEs of the trace, or their evaluations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryTrace<T> {
+    /// addresses of the operations
+    pub addrs: T,
+    /// values read by the operations
+    pub read_values: T,
+    /// counters of the cells read by the operations
+    pub read_timestamps: T,
+    /// values written by the operations
+    pub write_values: T,
+    /// values of the memory cells before the trace
+    pub init_values: T,
+    /// values of the memory cells after the trace
+    pub final_values: T,
+    /// counters of the memory cells after the trace
+    pub final_timestamps: T,
+}
+
+impl<T> MemoryTrace<T> {
+    /// The MLEs of the operations
+    pub fn operations(&self) -> [&T; 4] {
+        [
+            &self.addrs,
+            &self.read_values,
+            &self.read_timestamps,
+            &self.write_values,
+        ]
+    }
+
+    /// The MLEs of the memory cells
+    pub fn memory(&self) -> [&T; 2] {
+        [&self.init_values, &self.final_values]
+    }
+}
+
+/// A memory check subclaim consists of
+/// - the SubClaim from the MultisetCheck
+/// - the number of variables of the operations and of the memory
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "PCS: Clone"),
+    Debug(bound = "PCS: Debug"),
+    Default(bound = "PCS: Default"),
+    PartialEq(bound = "PCS: PartialEq")
+)]
+pub struct MemoryCheckSubClaim<E, PCS, T = IOPTranscript<<E as Pairing>::ScalarField>>
+where
+    E: Pairing,
+    PCS: PolynomialCommitmentScheme<E, Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>>,
+    T: Transcript<E::ScalarField>,
+{
+    /// the SubClaim from the MultisetCheck
+    pub multiset_check_sub_claim: MultisetCheckSubClaim<E, PCS, PolyIOP<E::ScalarField, T>>,
+    /// number of variables of the operations
+    pub ops_num_vars: usize,
+    /// number of variables of the memory
+    pub memory_num_vars: usize,
+}
+
+impl<E, PCS, T> MemoryCheckSubClaim<E, PCS, T>
+where
+    E: Pairing,
+    PCS: PolynomialCommitmentScheme<E, Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>>,
+    T: Transcript<E::ScalarField>,
+{
+    /// The point at which all the MLEs of the trace are queried.
+    ///
+    /// A trace MLE of `k` variables is queried at the last `k` coordinates of
+    /// the point, which is the evaluation of its replication to the number
+    /// of variables of the point at the whole point.
+    pub fn point(&self) -> &[E::ScalarField] {
+        &self
+            .multiset_check_sub_claim
+            .product_check_sub_claim
+            .zero_check_sub_claim
+            .point
+    }
+
+    /// The points at which the product polynomial `prod(x)` is queried:
+    /// `point`, `(0, point[0..n-1])`, `(1, point[0..n-1])` and the final
+    /// query. The fractional polynomial `frac(x)` is queried at the first
+    /// three.
+    pub fn prod_points(&self) -> Vec<Vec<E::ScalarField>> {
+        let point = self.point();
+        let num_vars = point.len();
+        vec![
+            point.to_vec(),
+            [&[E::ScalarField::zero()], &point[0..num_vars - 1]].concat(),
+            [&[E::ScalarField::one()], &point[0..num_vars - 1]].concat(),
+            self.multiset_check_sub_claim
+                .product_check_sub_claim
+                .final_query
+                .0
+                .clone(),
+        ]
+    }
+
+    /// Check the subclaim with
+    /// - `trace_evals`: the evaluations of the MLEs of the trace, queried as
+    ///   in `point`
+    /// - `prod_evals`: the evaluations of `prod(x)` at `prod_points`
+    /// - `frac_evals`: the evaluations of `frac(x)` at the first three
+    ///   `prod_points`
+    pub fn check_evaluations(
+        &self,
+        trace_evals: &MemoryTrace<E::ScalarField>,
+        prod_evals: &[E::ScalarField],
+        frac_evals: &[E::ScalarField],
+    ) -> Result<(), PolyIOPErrors> {
+        if prod_evals.len() != 4 || frac_evals.len() != 3 {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "prod_evals.len() = {}, frac_evals.len() = {}, expect 4 and 3",
+                prod_evals.len(),
+                frac_evals.len(),
+            )));
+        }
+        let product_check_sub_claim = &self.multiset_check_sub_claim.product_check_sub_claim;
+        let point = self.point();
+
+        // the evaluations of the padded MLEs of the operations and the memory
+        let ops = |eval: &E::ScalarField| eval_padded(*eval, point, self.ops_num_vars);
+        let memory = |eval: &E::ScalarField| eval_padded(*eval, point, self.memory_num_vars);
+        let memory_addrs = eval_padded_identity(point, self.memory_num_vars);
+
+        let fingerprint =
+            |tuple: &[E::ScalarField]| self.multiset_check_sub_claim.eval_fingerprint(tuple);
+        // init and writes, at the incremented counters
+        let f_prod_eval = fingerprint(&[
+            memory_addrs,
+            memory(&trace_evals.init_values),
+            E::ScalarField::zero(),
+        ]) * fingerprint(&[
+            ops(&trace_evals.addrs),
+            ops(&trace_evals.write_values),
+            ops(&(trace_evals.read_timestamps + E::ScalarField::one())),
+        ]);
+        // reads and final
+        let g_prod_eval = fingerprint(&[
+            ops(&trace_evals.addrs),
+            ops(&trace_evals.read_values),
+            ops(&trace_evals.read_timestamps),
+        ]) * fingerprint(&[
+            memory_addrs,
+            memory(&trace_evals.final_values),
+            memory(&trace_evals.final_timestamps),
+        ]);
+
+        let eval = eval_product_check_poly(
+            point,
+            prod_evals,
+            frac_evals,
+            f_prod_eval,
+            g_prod_eval,
+            product_check_sub_claim.alpha,
+        );
+        if eval
+            != product_check_sub_claim
+                .zero_check_sub_claim
+                .expected_evaluation
+        {
+            return Err(PolyIOPErrors::InvalidVerifier(
+                "memory check evaluations do not match the zero check".to_string(),
+            ));
+        }
+        if prod_evals[3] != product_check_sub_claim.final_query.1 {
+            return Err(PolyIOPErrors::InvalidVerifier(
+                "memory check final product is not one".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A MemoryCheck proves that a trace of memory operations is consistent
+/// with the memory before and after it, with offline memory checking as in
+/// Spice and Lasso.
+/// It is derived from MultisetCheck.
+///
+/// The elements are the tuples `(address, value, counter)`. The trace is
+/// consistent iff, as multisets,
+///   init \cup writes = reads \cup final
+/// where init = {(y, init_values(y), 0)}, writes = {(addrs(x),
+/// write_values(x), read_timestamps(x) + 1)}, reads = {(addrs(x),
+/// read_values(x), read_timestamps(x))} and final = {(y, final_values(y),
+/// final_timestamps(y))}.
+///
+/// The write counters are derived from the read ones, so every operation
+/// reads the value written by the operation before it on the same cell, in
+/// the order of the counters. A stale read repeats a counter and is
+/// rejected. The counters order the operations of each cell on their own:
+/// a caller that needs this order to be the order of the trace checks that
+/// the counters of each address increase along the trace.
+///
+/// The operations and the memory are zero padded in their low variables to
+/// the same number of variables `max(n, m)`; the padding is the same on both
+/// sides.
+pub trait MemoryCheck<E, PCS>: MultisetCheck<E, PCS>
+where
+    E: Pairing,
+    PCS: PolynomialCommitmentScheme<E>,
+{
+    type MemoryCheckSubClaim;
+    type MemoryCheckProof;
+
+    /// Initialize the system with a transcript
+    ///
+    /// This function is optional -- in the case where a MemoryCheck is
+    /// an building block for a more complex protocol, the transcript
+    /// may be initialized by this complex protocol, and passed to the
+    /// MemoryCheck prover/verifier.
+    fn init_transcript() -> Self::Transcript;
+
+    /// Inputs:
+    /// - trace: the MLEs of the memory trace, committed by the caller
+    ///
+    /// Outputs:
+    /// - a memory check proof proving that the trace is consistent
+    /// - the product polynomial built during product check
+    /// - the fractional polynomial built during product check
+    ///
+    /// Cost: O(N)
+    #[allow(clippy::type_complexity)]
+    fn prove(
+        pcs_param: &PCS::ProverParam,
+        trace: &MemoryTrace<Self::MultilinearExtension>,
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::MemoryCheckProof,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    >;
+
+    /// Verify that a trace of `2^ops_num_vars` operations on a memory of
+    /// `2^memory_num_vars` cells is consistent.
+    fn verify(
+        proof: &Self::MemoryCheckProof,
+        ops_num_vars: usize,
+        memory_num_vars: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::MemoryCheckSubClaim, PolyIOPErrors>;
+}
+
+impl<E, PCS, T> MemoryCheck<E, PCS> for PolyIOP<E::ScalarField, T>
+where
+    E: Pairing,
+    T: Transcript<E::ScalarField>,
+    PCS: PolynomialCommitmentScheme<E, Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>>,
+{
+    type MemoryCheckSubClaim = MemoryCheckSubClaim<E, PCS, T>;
+    type MemoryCheckProof = Self::MultisetCheckProof;
+
+    fn init_transcript() -> Self::Transcript {
+        T::new(b"Initializing MemoryCheck transcript")
+    }
+
+    fn prove(
+        pcs_param: &PCS::ProverParam,
+        trace: &MemoryTrace<Self::MultilinearExtension>,
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::MemoryCheckProof,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    > {
+        let start = start_timer!(|| "Memory check prove");
+
+        let ops_num_vars = trace.addrs.num_vars;
+        let memory_num_vars = trace.init_values.num_vars;
+        if trace
+            .operations()
+            .iter()
+            .any(|poly| poly.num_vars != ops_num_vars)
+            || trace
+                .memory()
+                .iter()
+                .chain([&&trace.final_timestamps])
+                .any(|poly| poly.num_vars != memory_num_vars)
+        {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "number of variables unmatched".to_string(),
+            ));
+        }
+
+        let num_vars = ops_num_vars.max(memory_num_vars);
+        let pad = |poly: &Self::MultilinearExtension| pad_low_variables(poly, num_vars);
+        let memory_addrs = pad(&Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            memory_num_vars,
+            (0..1u64 << memory_num_vars)
+                .map(E::ScalarField::from)
+                .collect(),
+        )));
+        let zeros = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            num_vars,
+            vec![E::ScalarField::zero(); 1 << num_vars],
+        ));
+        let addrs = pad(&trace.addrs);
+
+        // init \cup writes = reads \cup final
+        let write_timestamps = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            ops_num_vars,
+            trace
+                .read_timestamps
+                .evaluations
+                .iter()
+                .map(|&t| t + E::ScalarField::one())
+                .collect(),
+        ));
+        let fxs = vec![
+            vec![memory_addrs.clone(), pad(&trace.init_values), zeros],
+            vec![
+                addrs.clone(),
+                pad(&trace.write_values),
+                pad(&write_timestamps),
+            ],
+        ];
+        let gxs = vec![
+            vec![addrs, pad(&trace.read_values), pad(&trace.read_timestamps)],
+            vec![
+                memory_addrs,
+                pad(&trace.final_values),
+                pad(&trace.final_timestamps),
+            ],
+        ];
+        let (proof, prod_poly, frac_poly) =
+            <Self as MultisetCheck<E, PCS>>::prove(pcs_param, &fxs, &gxs, transcript)?;
+
+        end_timer!(start);
+        Ok((proof, prod_poly, frac_poly))
+    }
+
+    fn verify(
+        proof: &Self::MemoryCheckProof,
+        ops_num_vars: usize,
+        memory_num_vars: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::MemoryCheckSubClaim, PolyIOPErrors> {
+        let start = start_timer!(|| "Memory check verify");
+
+        let aux_info = Self::VPAuxInfo {
+            max_degree: 3,
+            num_variables: ops_num_vars.max(memory_num_vars),
+            phantom: Default::default(),
+        };
+        let multiset_check_sub_claim =
+            <Self as MultisetCheck<E, PCS>>::verify(proof, &aux_info, transcript)?;
+
+        end_timer!(start);
+        Ok(MemoryCheckSubClaim {
+            multiset_check_sub_claim,
+            ops_num_vars,
+            memory_num_vars,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MemoryCheck, MemoryTrace};
+    use crate::{
+        pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+        poly_iop::{errors::PolyIOPErrors, PolyIOP},
+    };
+    use arithmetic::evaluate_opt;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::{One, PrimeField};
+    use ark_poly::DenseMultilinearExtension;
+    use ark_std::{rand::RngCore, test_rng, UniformRand};
+    use std::sync::Arc;
+    use transcript::Transcript;
+
+    type Kzg = MultilinearKzgPCS<Bls12_381>;
+
+    // A random trace of 2^ops_num_vars operations on a memory of
+    // 2^memory_num_vars cells, half of them reads.
+    fn random_trace<R: RngCore>(
+        ops_num_vars: usize,
+        memory_num_vars: usize,
+        rng: &mut R,
+    ) -> MemoryTrace<Vec<Fr>> {
+        let init_values: Vec<Fr> = (0..1 << memory_num_vars).map(|_| Fr::rand(rng)).collect();
+        let mut values = init_values.clone();
+        let mut timestamps = vec![Fr::from(0u64); 1 << memory_num_vars];
+        let mut trace = MemoryTrace {
+            init_values,
+            ..Default::default()
+        };
+        for x in 0..1 << ops_num_vars {
+            let addr = rng.next_u64() as usize % (1 << memory_num_vars);
+            trace.addrs.push(Fr::from(addr as u64));
+            trace.read_values.push(values[addr]);
+            trace.read_timestamps.push(timestamps[addr]);
+            if x % 2 == 1 {
+                values[addr] = Fr::rand(rng);
+            }
+            timestamps[addr] += Fr::one();
+            trace.write_values.push(values[addr]);
+        }
+        trace.final_values = values;
+        trace.final_timestamps = timestamps;
+        trace
+    }
+
+    fn test_memory_check_helper(
+        pcs_param: &<Kzg as PolynomialCommitmentScheme<Bls12_381>>::ProverParam,
+        trace: &MemoryTrace<Vec<Fr>>,
+    ) -> Result<(), PolyIOPErrors> {
+        let ops_num_vars = trace.addrs.len().trailing_zeros() as usize;
+        let memory_num_vars = trace.init_values.len().trailing_zeros() as usize;
+        let mle = |evals: &Vec<Fr>| {
+            Arc::new(DenseMultilinearExtension::from_evaluations_slice(
+                evals.len().trailing_zeros() as usize,
+                evals,
+            ))
+        };
+        let polys = MemoryTrace {
+            addrs: mle(&trace.addrs),
+            read_values: mle(&trace.read_values),
+            read_timestamps: mle(&trace.read_timestamps),
+            write_values: mle(&trace.write_values),
+            init_values: mle(&trace.init_values),
+            final_values: mle(&trace.final_values),
+            final_timestamps: mle(&trace.final_timestamps),
+        };
+
+        // prover
+        let mut transcript = <PolyIOP<Fr> as MemoryCheck<Bls12_381, Kzg>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let (proof, prod_x, frac_poly) = <PolyIOP<Fr> as MemoryCheck<Bls12_381, Kzg>>::prove(
+            pcs_param,
+            &polys,
+            &mut transcript,
+        )?;
+
+        // verifier
+        let mut transcript = <PolyIOP<Fr> as MemoryCheck<Bls12_381, Kzg>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let sub_claim = <PolyIOP<Fr> as MemoryCheck<Bls12_381, Kzg>>::verify(
+            &proof,
+            ops_num_vars,
+            memory_num_vars,
+            &mut transcript,
+        )?;
+
+        // the evaluations of the trace at the last coordinates of the point
+        let point = sub_claim.point();
+        let eval = |poly: &Arc<DenseMultilinearExtension<Fr>>| {
+            evaluate_opt(poly, &point[point.len() - poly.num_vars..])
+        };
+        let trace_evals = MemoryTrace {
+            addrs: eval(&polys.addrs),
+            read_values: eval(&polys.read_values),
+            read_timestamps: eval(&polys.read_timestamps),
+            write_values: eval(&polys.write_values),
+            init_values: eval(&polys.init_values),
+            final_values: eval(&polys.final_values),
+            final_timestamps: eval(&polys.final_timestamps),
+        };
+        let prod_points = sub_claim.prod_points();
+        let prod_evals: Vec<Fr> = prod_points
+            .iter()
+            .map(|point| evaluate_opt(&prod_x, point))
+            .collect();
+        let frac_evals: Vec<Fr> = prod_points[..3]
+            .iter()
+            .map(|point| evaluate_opt(&frac_poly, point))
+            .collect();
+        sub_claim.check_evaluations(&trace_evals, &prod_evals, &frac_evals)
+    }
+
+    fn test_memory_check(ops_num_vars: usize, memory_num_vars: usize) -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+
+        let num_vars = ops_num_vars.max(memory_num_vars);
+        let srs = Kzg::gen_srs_for_testing(&mut rng, num_vars)?;
+        let (pcs_param, _) = Kzg::trim(&srs, None, Some(num_vars))?;
+
+        // good path
+        let trace = random_trace(ops_num_vars, memory_num_vars, &mut rng);
+        test_memory_check_helper(&pcs_param, &trace)?;
+
+        // bad path: a read of a wrong value
+        let mut bad_trace = trace.clone();
+        bad_trace.read_values[1] += Fr::one();
+        assert!(test_memory_check_helper(&pcs_param, &bad_trace).is_err());
+
+        // bad path: a read of a stale value, from an old timestamp
+        let mut bad_trace = trace.clone();
+        let x = (0..trace.addrs.len())
+            .find(|&x| trace.read_timestamps[x] > Fr::from(0u64))
+            .unwrap();
+        bad_trace.read_values[x] = trace.init_values[trace.addrs[x].into_bigint().0[0] as usize];
+        bad_trace.read_timestamps[x] = Fr::from(0u64);
+        assert!(test_memory_check_helper(&pcs_param, &bad_trace).is_err());
+
+        // bad path: a wrong final memory
+        let mut bad_trace = trace;
+        bad_trace.final_values[0] += Fr::one();
+        assert!(test_memory_check_helper(&pcs_param, &bad_trace).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_num_vars() -> Result<(), PolyIOPErrors> {
+        test_memory_check(5, 5)
+    }
+    #[test]
+    fn test_more_operations() -> Result<(), PolyIOPErrors> {
+        test_memory_check(6, 3)
+    }
+    #[test]
+    fn test_larger_memory() -> Result<(), PolyIOPErrors> {
+        test_memory_check(3, 6)
+    }
+}
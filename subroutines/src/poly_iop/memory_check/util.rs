@@ -0,0 +1,53 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! This module implements useful functions for the memory check protocol.
+
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use std::sync::Arc;
+
+/// Zero pad a `k` variables MLE `p` into a `num_vars` variables MLE, in its
+/// low variables: the evaluation at `x` is `p(x[num_vars-k..])` if
+/// `x[0..num_vars-k]` is zero, and zero otherwise.
+///
+/// The caller is responsible for sanity-check
+pub(super) fn pad_low_variables<F: PrimeField>(
+    poly: &Arc<DenseMultilinearExtension<F>>,
+    num_vars: usize,
+) -> Arc<DenseMultilinearExtension<F>> {
+    if poly.num_vars == num_vars {
+        return poly.clone();
+    }
+    let shift = num_vars - poly.num_vars;
+    let mut evals = vec![F::zero(); 1 << num_vars];
+    for (i, &eval) in poly.evaluations.iter().enumerate() {
+        evals[i << shift] = eval;
+    }
+    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        num_vars, evals,
+    ))
+}
+
+/// Evaluate the padding of a `num_vars` variables MLE `p` at `point` from
+/// `eval = p(point[point.len()-num_vars..])`.
+pub(super) fn eval_padded<F: PrimeField>(eval: F, point: &[F], num_vars: usize) -> F {
+    point[0..point.len() - num_vars]
+        .iter()
+        .fold(eval, |acc, &x| acc * (F::one() - x))
+}
+
+/// Evaluate the padding of the `num_vars` variables MLE `p(x) = x` at
+/// `point`.
+pub(super) fn eval_padded_identity<F: PrimeField>(point: &[F], num_vars: usize) -> F {
+    let mut eval = F::zero();
+    let mut base = F::one();
+    for &x in point[point.len() - num_vars..].iter() {
+        eval += base * x;
+        base.double_in_place();
+    }
+    eval_padded(eval, point, num_vars)
+}
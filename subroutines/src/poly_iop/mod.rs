@@ -5,32 +5,49 @@
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
 use ark_ff::PrimeField;
+use derivative::Derivative;
 use std::marker::PhantomData;
+use transcript::{IOPTranscript, Transcript};
 
 mod errors;
+mod memory_check;
+mod multiset_check;
 mod perm_check;
 pub mod prelude;
 mod prod_check;
 pub mod structs;
+mod subset_check;
 pub mod sum_check;
 mod utils;
 mod zero_check;
 
-#[derive(Clone, Debug, Default, Copy, PartialEq, Eq)]
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    Default(bound = ""),
+    Copy(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
 /// Struct for PolyIOP protocol.
 /// It has an associated type `F` that defines the prime field the multi-variate
-/// polynomial operates on.
+/// polynomial operates on, and a type `T` of Fiat-Shamir transcript, which is
+/// a Merlin transcript by default.
 ///
 /// An PolyIOP may be instantiated with one of the following:
 /// - SumCheck protocol.
 /// - ZeroCheck protocol.
 /// - PermutationCheck protocol.
+/// - MultisetCheck protocol.
+/// - SubsetCheck protocol.
+/// - MemoryCheck protocol.
 ///
 /// Those individual protocol may have similar or identical APIs.
 /// The systematic way to invoke specific protocol is, for example
 ///     `<PolyIOP<F> as SumCheck<F>>::prove()`
-pub struct PolyIOP<F: PrimeField> {
-    /// Associated field
+pub struct PolyIOP<F: PrimeField, T: Transcript<F> = IOPTranscript<F>> {
+    /// Associated field and transcript
     #[doc(hidden)]
-    phantom: PhantomData<F>,
+    phantom: PhantomData<(F, T)>,
 }
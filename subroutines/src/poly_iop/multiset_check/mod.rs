@@ -0,0 +1,386 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Multiset Check protocol
+
+use self::util::{check_tuples, compute_fingerprints};
+use crate::{
+    pcs::PolynomialCommitmentScheme,
+    poly_iop::{errors::PolyIOPErrors, prelude::ProductCheck, PolyIOP},
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::Zero;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{end_timer, start_timer};
+use derivative::Derivative;
+use std::{fmt::Debug, sync::Arc};
+use transcript::Transcript;
+
+pub(crate) mod util;
+
+/// A multiset subclaim consists of
+/// - the SubClaim from the ProductCheck
+/// - Challenges beta and gamma
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "PC::ProductCheckSubClaim: Clone"),
+    Debug(bound = "PC::ProductCheckSubClaim: Debug"),
+    Default(bound = "PC::ProductCheckSubClaim: Default"),
+    PartialEq(bound = "PC::ProductCheckSubClaim: PartialEq")
+)]
+pub struct MultisetCheckSubClaim<E, PCS, PC>
+where
+    E: Pairing,
+    PC: ProductCheck<E, PCS>,
+    PCS: PolynomialCommitmentScheme<E>,
+{
+    /// the SubClaim from the ProductCheck
+    pub product_check_sub_claim: PC::ProductCheckSubClaim,
+    /// Challenges beta and gamma
+    pub challenges: (E::ScalarField, E::ScalarField),
+}
+
+impl<E, PCS, PC> MultisetCheckSubClaim<E, PCS, PC>
+where
+    E: Pairing,
+    PC: ProductCheck<E, PCS>,
+    PCS: PolynomialCommitmentScheme<E>,
+{
+    /// Evaluate the fingerprint `\gamma + c_0 + \beta c_1 + ... + \beta^{w-1}
+    /// c_{w-1}` of a tuple from the evaluations `(c_0, ..., c_{w-1})` of its
+    /// columns.
+    pub fn eval_fingerprint(&self, column_evals: &[E::ScalarField]) -> E::ScalarField {
+        let (beta, gamma) = self.challenges;
+        column_evals
+            .iter()
+            .rev()
+            .fold(E::ScalarField::zero(), |acc, eval| acc * beta + eval)
+            + gamma
+    }
+}
+
+/// A MultisetCheck w.r.t. `(fs, gs)` proves that the multiset of all the
+/// evaluations of (f1, ..., fk) over the boolean hypercube equals the
+/// multiset of all the evaluations of (g1, ..., gk).
+/// It is derived from ProductCheck.
+///
+/// Each fi (and gi) is a tuple of `w` MLEs `(fi_0, ..., fi_{w-1})`, whose
+/// elements are the tuples `(fi_0(x), ..., fi_{w-1}(x))`. Tuples are
+/// compressed into the fingerprints `fi_0(x) + \beta fi_1(x) + ... +
+/// \beta^{w-1} fi_{w-1}(x) + \gamma`, and the ProductCheck proves that the
+/// products of the fingerprints of both sides are equal.
+///
+/// Unlike the PermutationCheck, the multisets are not related by a known
+/// permutation.
+pub trait MultisetCheck<E, PCS>: ProductCheck<E, PCS>
+where
+    E: Pairing,
+    PCS: PolynomialCommitmentScheme<E>,
+{
+    type MultisetCheckSubClaim;
+    type MultisetCheckProof;
+
+    /// Initialize the system with a transcript
+    ///
+    /// This function is optional -- in the case where a MultisetCheck is
+    /// an building block for a more complex protocol, the transcript
+    /// may be initialized by this complex protocol, and passed to the
+    /// MultisetCheck prover/verifier.
+    fn init_transcript() -> Self::Transcript;
+
+    /// Inputs:
+    /// - fs = (f1, ..., fk), each fi a tuple of MLEs
+    /// - gs = (g1, ..., gk), tuples of the same width as fs
+    ///
+    /// Outputs:
+    /// - a multiset check proof proving that gs and fs are equal as
+    ///   multisets
+    /// - the product polynomial built during product check
+    /// - the fractional polynomial built during product check
+    ///
+    /// Cost: O(N)
+    #[allow(clippy::type_complexity)]
+    fn prove(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Vec<Self::MultilinearExtension>],
+        gxs: &[Vec<Self::MultilinearExtension>],
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::MultisetCheckProof,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    >;
+
+    /// Verify that (g1, ..., gk) and (f1, ..., fk) are equal as multisets
+    fn verify(
+        proof: &Self::MultisetCheckProof,
+        aux_info: &Self::VPAuxInfo,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::MultisetCheckSubClaim, PolyIOPErrors>;
+}
+
+impl<E, PCS, T> MultisetCheck<E, PCS> for PolyIOP<E::ScalarField, T>
+where
+    E: Pairing,
+    T: Transcript<E::ScalarField>,
+    PCS: PolynomialCommitmentScheme<E, Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>>,
+{
+    type MultisetCheckSubClaim = MultisetCheckSubClaim<E, PCS, Self>;
+    type MultisetCheckProof = Self::ProductCheckProof;
+
+    fn init_transcript() -> Self::Transcript {
+        T::new(b"Initializing MultisetCheck transcript")
+    }
+
+    fn prove(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Vec<Self::MultilinearExtension>],
+        gxs: &[Vec<Self::MultilinearExtension>],
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::MultisetCheckProof,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    > {
+        let start = start_timer!(|| "Multiset check prove");
+        check_tuples(fxs, gxs)?;
+        if fxs.len() != gxs.len() {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "fxs.len() = {}, gxs.len() = {}",
+                fxs.len(),
+                gxs.len(),
+            )));
+        }
+
+        // generate challenge `beta` and `gamma` from current transcript
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        let gamma = transcript.get_and_append_challenge(b"gamma")?;
+        let numerators = compute_fingerprints(&beta, &gamma, fxs);
+        let denominators = compute_fingerprints(&beta, &gamma, gxs);
+
+        // invoke product check on numerator and denominator
+        let (proof, prod_poly, frac_poly) = <Self as ProductCheck<E, PCS>>::prove(
+            pcs_param,
+            &numerators,
+            &denominators,
+            transcript,
+        )?;
+
+        end_timer!(start);
+        Ok((proof, prod_poly, frac_poly))
+    }
+
+    fn verify(
+        proof: &Self::MultisetCheckProof,
+        aux_info: &Self::VPAuxInfo,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::MultisetCheckSubClaim, PolyIOPErrors> {
+        let start = start_timer!(|| "Multiset check verify");
+
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        let gamma = transcript.get_and_append_challenge(b"gamma")?;
+
+        // invoke the product check on the iop_proof
+        let product_check_sub_claim =
+            <Self as ProductCheck<E, PCS>>::verify(proof, aux_info, transcript)?;
+
+        end_timer!(start);
+        Ok(MultisetCheckSubClaim {
+            product_check_sub_claim,
+            challenges: (beta, gamma),
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::{MultisetCheck, MultisetCheckSubClaim};
+    use crate::{
+        pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+        poly_iop::{errors::PolyIOPErrors, PolyIOP},
+    };
+    use arithmetic::{evaluate_opt, VPAuxInfo};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::{One, Zero};
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::test_rng;
+    use std::{marker::PhantomData, sync::Arc};
+    use transcript::Transcript;
+
+    type Kzg = MultilinearKzgPCS<Bls12_381>;
+
+    /// Check the subclaim of a multiset check with the evaluations of the
+    /// actual polynomials.
+    pub(crate) fn check_multiset_sub_claim(
+        sub_claim: &MultisetCheckSubClaim<Bls12_381, Kzg, PolyIOP<Fr>>,
+        fxs: &[Vec<Arc<DenseMultilinearExtension<Fr>>>],
+        gxs: &[Vec<Arc<DenseMultilinearExtension<Fr>>>],
+        prod_x: &Arc<DenseMultilinearExtension<Fr>>,
+        frac_poly: &Arc<DenseMultilinearExtension<Fr>>,
+    ) -> Result<(), PolyIOPErrors> {
+        let product_check_sub_claim = &sub_claim.product_check_sub_claim;
+        let point = &product_check_sub_claim.zero_check_sub_claim.point;
+        let num_vars = point.len();
+
+        // prod(x) - p1(x) * p2(x) + alpha * [frac(x) * g1(x) * ... * gk(x) -
+        // f1(x) * ... * fk(x)]
+        let point_0 = [&[Fr::zero()], &point[0..num_vars - 1]].concat();
+        let point_1 = [&[Fr::one()], &point[0..num_vars - 1]].concat();
+        let x1 = point[num_vars - 1];
+        let p1 = evaluate_opt(frac_poly, &point_0)
+            + x1 * (evaluate_opt(prod_x, &point_0) - evaluate_opt(frac_poly, &point_0));
+        let p2 = evaluate_opt(frac_poly, &point_1)
+            + x1 * (evaluate_opt(prod_x, &point_1) - evaluate_opt(frac_poly, &point_1));
+        let eval_product = |tuples: &[Vec<Arc<DenseMultilinearExtension<Fr>>>]| {
+            tuples
+                .iter()
+                .map(|tuple| {
+                    let column_evals: Vec<Fr> = tuple
+                        .iter()
+                        .map(|column| evaluate_opt(column, point))
+                        .collect();
+                    sub_claim.eval_fingerprint(&column_evals)
+                })
+                .product::<Fr>()
+        };
+        let q_eval = evaluate_opt(prod_x, point) - p1 * p2
+            + product_check_sub_claim.alpha
+                * (evaluate_opt(frac_poly, point) * eval_product(gxs) - eval_product(fxs));
+
+        if q_eval
+            != product_check_sub_claim
+                .zero_check_sub_claim
+                .expected_evaluation
+        {
+            return Err(PolyIOPErrors::InvalidVerifier(
+                "wrong zero check subclaim".to_string(),
+            ));
+        }
+        if evaluate_opt(prod_x, &product_check_sub_claim.final_query.0)
+            != product_check_sub_claim.final_query.1
+        {
+            return Err(PolyIOPErrors::InvalidVerifier("wrong subclaim".to_string()));
+        }
+        Ok(())
+    }
+
+    fn test_multiset_check_helper(
+        pcs_param: &<Kzg as PolynomialCommitmentScheme<Bls12_381>>::ProverParam,
+        fxs: &[Vec<Arc<DenseMultilinearExtension<Fr>>>],
+        gxs: &[Vec<Arc<DenseMultilinearExtension<Fr>>>],
+    ) -> Result<(), PolyIOPErrors> {
+        let nv = fxs[0][0].num_vars;
+        let poly_info = VPAuxInfo {
+            max_degree: fxs.len() + 1,
+            num_variables: nv,
+            phantom: PhantomData,
+        };
+
+        // prover
+        let mut transcript = <PolyIOP<Fr> as MultisetCheck<Bls12_381, Kzg>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let (proof, prod_x, frac_poly) = <PolyIOP<Fr> as MultisetCheck<Bls12_381, Kzg>>::prove(
+            pcs_param,
+            fxs,
+            gxs,
+            &mut transcript,
+        )?;
+
+        // verifier
+        let mut transcript = <PolyIOP<Fr> as MultisetCheck<Bls12_381, Kzg>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let sub_claim = <PolyIOP<Fr> as MultisetCheck<Bls12_381, Kzg>>::verify(
+            &proof,
+            &poly_info,
+            &mut transcript,
+        )?;
+
+        check_multiset_sub_claim(&sub_claim, fxs, gxs, &prod_x, &frac_poly)
+    }
+
+    // Shuffle the evaluations of a list of tuples across the whole list.
+    fn shuffle(
+        tuples: &[Vec<Arc<DenseMultilinearExtension<Fr>>>],
+    ) -> Vec<Vec<Arc<DenseMultilinearExtension<Fr>>>> {
+        let num_vars = tuples[0][0].num_vars;
+        let len = tuples.len() << num_vars;
+        // a fixed permutation of [0, len), as len is a power of two
+        let sigma = |i: usize| (i * 5 + 3) % len;
+        tuples
+            .iter()
+            .enumerate()
+            .map(|(k, tuple)| {
+                (0..tuple.len())
+                    .map(|j| {
+                        let evals = (0..1 << num_vars)
+                            .map(|x| {
+                                let i = sigma((k << num_vars) + x);
+                                tuples[i >> num_vars][j].evaluations[i % (1 << num_vars)]
+                            })
+                            .collect();
+                        Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                            num_vars, evals,
+                        ))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn test_multiset_check(nv: usize) -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+
+        let srs = Kzg::gen_srs_for_testing(&mut rng, nv)?;
+        let (pcs_param, _) = Kzg::trim(&srs, None, Some(nv))?;
+        let rand_tuple = |width: usize, rng: &mut _| -> Vec<Arc<DenseMultilinearExtension<Fr>>> {
+            (0..width)
+                .map(|_| Arc::new(DenseMultilinearExtension::rand(nv, rng)))
+                .collect()
+        };
+
+        for width in 1..4 {
+            let fs = vec![rand_tuple(width, &mut rng), rand_tuple(width, &mut rng)];
+
+            // good path: a shuffle of fs across its tuples
+            let gs = shuffle(&fs);
+            test_multiset_check_helper(&pcs_param, &fs, &gs)?;
+
+            // bad path: random tuples
+            let hs = vec![rand_tuple(width, &mut rng), rand_tuple(width, &mut rng)];
+            assert!(test_multiset_check_helper(&pcs_param, &fs, &hs).is_err());
+
+            if width > 1 {
+                // bad path: same multisets of columns, but not of tuples
+                let mut hs = gs.clone();
+                hs[0].swap(0, 1);
+                assert!(test_multiset_check_helper(&pcs_param, &fs, &hs).is_err());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        test_multiset_check(1)
+    }
+    #[test]
+    fn test_normal_polynomial() -> Result<(), PolyIOPErrors> {
+        test_multiset_check(5)
+    }
+
+    #[test]
+    fn zero_polynomial_should_error() -> Result<(), PolyIOPErrors> {
+        assert!(test_multiset_check(0).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! This module implements useful functions for the multiset check protocol.
+
+use crate::poly_iop::errors::PolyIOPErrors;
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{end_timer, start_timer};
+use std::sync::Arc;
+
+/// Returns the fingerprints `(a1, ..., ak)` of a list of tuples of MLEs
+/// `(f1, ..., fk)`, where
+/// - `fi = (fi_0, ..., fi_{w-1})` is a tuple of `w` MLEs
+/// - ai(x) is the MLE for `fi_0(x) + \beta fi_1(x) + ... + \beta^{w-1}
+///   fi_{w-1}(x) + \gamma`
+///
+/// The caller is responsible for sanity-check
+pub(crate) fn compute_fingerprints<F: PrimeField>(
+    beta: &F,
+    gamma: &F,
+    fxs: &[Vec<Arc<DenseMultilinearExtension<F>>>],
+) -> Vec<Arc<DenseMultilinearExtension<F>>> {
+    let start = start_timer!(|| "compute fingerprints");
+
+    let num_vars = fxs[0][0].num_vars;
+    let mut fingerprints = vec![];
+    for fx in fxs.iter() {
+        let mut evals = vec![*gamma; 1 << num_vars];
+        let mut coeff = F::one();
+        for column in fx.iter() {
+            for (eval, &c) in evals.iter_mut().zip(column.iter()) {
+                *eval += coeff * c;
+            }
+            coeff *= beta;
+        }
+        fingerprints.push(Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            num_vars, evals,
+        )));
+    }
+
+    end_timer!(start);
+    fingerprints
+}
+
+/// Sanity-check that `fxs` and `gxs` are non empty lists of tuples of the
+/// same width, whose MLEs have the same number of variables.
+pub(crate) fn check_tuples<F: PrimeField>(
+    fxs: &[Vec<Arc<DenseMultilinearExtension<F>>>],
+    gxs: &[Vec<Arc<DenseMultilinearExtension<F>>>],
+) -> Result<(), PolyIOPErrors> {
+    if fxs.is_empty() || gxs.is_empty() {
+        return Err(PolyIOPErrors::InvalidParameters(
+            "fxs or gxs is empty".to_string(),
+        ));
+    }
+    let width = fxs[0].len();
+    if width == 0 {
+        return Err(PolyIOPErrors::InvalidParameters(
+            "tuples are empty".to_string(),
+        ));
+    }
+    let num_vars = fxs[0][0].num_vars;
+    for tuple in fxs.iter().chain(gxs.iter()) {
+        if tuple.len() != width {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "tuples have different widths: {} vs {}",
+                tuple.len(),
+                width
+            )));
+        }
+        if tuple.iter().any(|column| column.num_vars != num_vars) {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "number of variables unmatched".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
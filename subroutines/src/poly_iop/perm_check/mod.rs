@@ -6,16 +6,22 @@
 
 //! Main module for the Permutation Check protocol
 
-use self::util::computer_nums_and_denoms;
+use self::util::{computer_nums_and_denoms, eval_identity_permutation};
 use crate::{
     pcs::PolynomialCommitmentScheme,
-    poly_iop::{errors::PolyIOPErrors, prelude::ProductCheck, PolyIOP},
+    poly_iop::{
+        errors::PolyIOPErrors,
+        prelude::{GkrProductCheck, ProductCheck},
+        prod_check::gkr::GkrProductCheckSubClaim,
+        PolyIOP,
+    },
 };
 use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
 use ark_poly::DenseMultilinearExtension;
 use ark_std::{end_timer, start_timer};
 use std::sync::Arc;
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
 /// A permutation subclaim consists of
 /// - the SubClaim from the ProductCheck
@@ -33,6 +39,59 @@ where
     pub challenges: (E::ScalarField, E::ScalarField),
 }
 
+/// A permutation subclaim of the GKR backend consists of
+/// - the SubClaim from the GkrProductCheck
+/// - Challenges beta and gamma
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GkrPermutationCheckSubClaim<F: PrimeField> {
+    /// the SubClaim from the GkrProductCheck
+    pub product_check_sub_claim: GkrProductCheckSubClaim<F>,
+    /// Challenges beta and gamma
+    pub challenges: (F, F),
+}
+
+impl<F: PrimeField> GkrPermutationCheckSubClaim<F> {
+    /// Check the evaluations of `(f1, ..., fk)`, `(g1, ..., gk)` and
+    /// `(p1, ..., pk)` at `self.product_check_sub_claim.point` against the
+    /// evaluations of the numerators and denominators expected by the
+    /// subclaim.
+    pub fn check_evaluations(
+        &self,
+        f_evals: &[F],
+        g_evals: &[F],
+        perm_evals: &[F],
+    ) -> Result<(), PolyIOPErrors> {
+        let claim = &self.product_check_sub_claim;
+        let num_chunks = claim.numerator_evals.len();
+        if f_evals.len() != num_chunks
+            || g_evals.len() != num_chunks
+            || perm_evals.len() != num_chunks
+        {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "f_evals.len() = {}, g_evals.len() = {}, perm_evals.len() = {}, expect {}",
+                f_evals.len(),
+                g_evals.len(),
+                perm_evals.len(),
+                num_chunks
+            )));
+        }
+
+        let (beta, gamma) = self.challenges;
+        for i in 0..num_chunks {
+            let s_id_eval = eval_identity_permutation(&claim.point, i);
+            if f_evals[i] + beta * s_id_eval + gamma != claim.numerator_evals[i]
+                || g_evals[i] + beta * perm_evals[i] + gamma != claim.denominator_evals[i]
+            {
+                return Err(PolyIOPErrors::InvalidVerifier(format!(
+                    "{}-th evaluations do not match the permutation check",
+                    i
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
 pub mod util;
 
 /// A PermutationCheck w.r.t. `(fs, gs, perms)`
@@ -46,13 +105,20 @@ pub mod util;
 /// - fs = (f1, ..., fk)
 /// - gs = (g1, ..., gk)
 /// - permutation oracles = (p1, ..., pk)
-pub trait PermutationCheck<E, PCS>: ProductCheck<E, PCS>
+///
+/// The grand product is proved either by the ProductCheck, which commits to
+/// two extra polynomials, or by the GkrProductCheck, which commits to
+/// nothing but has a larger proof. Each instance picks one with `prove` and
+/// `verify`, or `prove_with_gkr` and `verify_with_gkr`.
+pub trait PermutationCheck<E, PCS>: ProductCheck<E, PCS> + GkrProductCheck<E::ScalarField>
 where
     E: Pairing,
     PCS: PolynomialCommitmentScheme<E>,
 {
     type PermutationCheckSubClaim;
     type PermutationProof;
+    type GkrPermutationCheckSubClaim;
+    type GkrPermutationProof;
 
     /// Initialize the system with a transcript
     ///
@@ -79,7 +145,7 @@ where
         fxs: &[Self::MultilinearExtension],
         gxs: &[Self::MultilinearExtension],
         perms: &[Self::MultilinearExtension],
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut Self::Transcript,
     ) -> Result<
         (
             Self::PermutationProof,
@@ -96,18 +162,50 @@ where
         aux_info: &Self::VPAuxInfo,
         transcript: &mut Self::Transcript,
     ) -> Result<Self::PermutationCheckSubClaim, PolyIOPErrors>;
+
+    /// Same as `prove`, but with the grand product proved by the
+    /// GkrProductCheck, without committing to any polynomial.
+    ///
+    /// Inputs:
+    /// - fs = (f1, ..., fk)
+    /// - gs = (g1, ..., gk)
+    /// - permutation oracles = (p1, ..., pk)
+    ///
+    /// Outputs:
+    /// - a permutation check proof proving that gs is a permutation of fs under
+    ///   permutation
+    ///
+    /// Cost: O(N)
+    fn prove_with_gkr(
+        fxs: &[Self::MultilinearExtension],
+        gxs: &[Self::MultilinearExtension],
+        perms: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::GkrPermutationProof, PolyIOPErrors>;
+
+    /// Verify a proof of `prove_with_gkr`. The caller checks the subclaim
+    /// with the evaluations of (f1, ..., fk), (g1, ..., gk) and (perm1, ...,
+    /// permk) at the point of the subclaim.
+    fn verify_with_gkr(
+        proof: &Self::GkrPermutationProof,
+        aux_info: &Self::VPAuxInfo,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::GkrPermutationCheckSubClaim, PolyIOPErrors>;
 }
 
-impl<E, PCS> PermutationCheck<E, PCS> for PolyIOP<E::ScalarField>
+impl<E, PCS, T> PermutationCheck<E, PCS> for PolyIOP<E::ScalarField, T>
 where
     E: Pairing,
+    T: Transcript<E::ScalarField>,
     PCS: PolynomialCommitmentScheme<E, Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>>,
 {
     type PermutationCheckSubClaim = PermutationCheckSubClaim<E, PCS, Self>;
     type PermutationProof = Self::ProductCheckProof;
+    type GkrPermutationCheckSubClaim = GkrPermutationCheckSubClaim<E::ScalarField>;
+    type GkrPermutationProof = Self::GkrProductCheckProof;
 
     fn init_transcript() -> Self::Transcript {
-        IOPTranscript::<E::ScalarField>::new(b"Initializing PermutationCheck transcript")
+        T::new(b"Initializing PermutationCheck transcript")
     }
 
     fn prove(
@@ -115,7 +213,7 @@ where
         fxs: &[Self::MultilinearExtension],
         gxs: &[Self::MultilinearExtension],
         perms: &[Self::MultilinearExtension],
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut Self::Transcript,
     ) -> Result<
         (
             Self::PermutationProof,
@@ -125,27 +223,7 @@ where
         PolyIOPErrors,
     > {
         let start = start_timer!(|| "Permutation check prove");
-        if fxs.is_empty() {
-            return Err(PolyIOPErrors::InvalidParameters("fxs is empty".to_string()));
-        }
-        if (fxs.len() != gxs.len()) || (fxs.len() != perms.len()) {
-            return Err(PolyIOPErrors::InvalidProof(format!(
-                "fxs.len() = {}, gxs.len() = {}, perms.len() = {}",
-                fxs.len(),
-                gxs.len(),
-                perms.len(),
-            )));
-        }
-
-        let num_vars = fxs[0].num_vars;
-        for ((fx, gx), perm) in fxs.iter().zip(gxs.iter()).zip(perms.iter()) {
-            if (fx.num_vars != num_vars) || (gx.num_vars != num_vars) || (perm.num_vars != num_vars)
-            {
-                return Err(PolyIOPErrors::InvalidParameters(
-                    "number of variables unmatched".to_string(),
-                ));
-            }
-        }
+        check_inputs(fxs, gxs, perms)?;
 
         // generate challenge `beta` and `gamma` from current transcript
         let beta = transcript.get_and_append_challenge(b"beta")?;
@@ -184,6 +262,80 @@ where
             challenges: (beta, gamma),
         })
     }
+
+    fn prove_with_gkr(
+        fxs: &[Self::MultilinearExtension],
+        gxs: &[Self::MultilinearExtension],
+        perms: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::GkrPermutationProof, PolyIOPErrors> {
+        let start = start_timer!(|| "Permutation check prove with gkr");
+        check_inputs(fxs, gxs, perms)?;
+
+        // generate challenge `beta` and `gamma` from current transcript
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        let gamma = transcript.get_and_append_challenge(b"gamma")?;
+        let (numerators, denominators) = computer_nums_and_denoms(&beta, &gamma, fxs, gxs, perms)?;
+
+        // invoke the gkr product check on numerator and denominator
+        let proof = <Self as GkrProductCheck<E::ScalarField>>::prove(
+            &numerators,
+            &denominators,
+            transcript,
+        )?;
+
+        end_timer!(start);
+        Ok(proof)
+    }
+
+    fn verify_with_gkr(
+        proof: &Self::GkrPermutationProof,
+        aux_info: &Self::VPAuxInfo,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::GkrPermutationCheckSubClaim, PolyIOPErrors> {
+        let start = start_timer!(|| "Permutation check verify with gkr");
+
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        let gamma = transcript.get_and_append_challenge(b"gamma")?;
+
+        let product_check_sub_claim =
+            <Self as GkrProductCheck<E::ScalarField>>::verify(proof, aux_info, transcript);
+
+        end_timer!(start);
+        let product_check_sub_claim = product_check_sub_claim?;
+        Ok(GkrPermutationCheckSubClaim {
+            product_check_sub_claim,
+            challenges: (beta, gamma),
+        })
+    }
+}
+
+fn check_inputs<F: PrimeField>(
+    fxs: &[Arc<DenseMultilinearExtension<F>>],
+    gxs: &[Arc<DenseMultilinearExtension<F>>],
+    perms: &[Arc<DenseMultilinearExtension<F>>],
+) -> Result<(), PolyIOPErrors> {
+    if fxs.is_empty() {
+        return Err(PolyIOPErrors::InvalidParameters("fxs is empty".to_string()));
+    }
+    if (fxs.len() != gxs.len()) || (fxs.len() != perms.len()) {
+        return Err(PolyIOPErrors::InvalidProof(format!(
+            "fxs.len() = {}, gxs.len() = {}, perms.len() = {}",
+            fxs.len(),
+            gxs.len(),
+            perms.len(),
+        )));
+    }
+
+    let num_vars = fxs[0].num_vars;
+    for ((fx, gx), perm) in fxs.iter().zip(gxs.iter()).zip(perms.iter()) {
+        if (fx.num_vars != num_vars) || (gx.num_vars != num_vars) || (perm.num_vars != num_vars) {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "number of variables unmatched".to_string(),
+            ));
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -199,6 +351,7 @@ mod test {
     use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
     use ark_std::test_rng;
     use std::{marker::PhantomData, sync::Arc};
+    use transcript::Transcript;
 
     type Kzg = MultilinearKzgPCS<Bls12_381>;
 
@@ -258,6 +411,58 @@ mod test {
         Ok(())
     }
 
+    fn test_gkr_permutation_check_helper<E, PCS>(
+        fxs: &[Arc<DenseMultilinearExtension<E::ScalarField>>],
+        gxs: &[Arc<DenseMultilinearExtension<E::ScalarField>>],
+        perms: &[Arc<DenseMultilinearExtension<E::ScalarField>>],
+    ) -> Result<(), PolyIOPErrors>
+    where
+        E: Pairing,
+        PCS: PolynomialCommitmentScheme<
+            E,
+            Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
+        >,
+    {
+        let nv = fxs[0].num_vars;
+        let poly_info = VPAuxInfo {
+            max_degree: 3,
+            num_variables: nv,
+            phantom: PhantomData,
+        };
+
+        // prover
+        let mut transcript =
+            <PolyIOP<E::ScalarField> as PermutationCheck<E, PCS>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let proof = <PolyIOP<E::ScalarField> as PermutationCheck<E, PCS>>::prove_with_gkr(
+            fxs,
+            gxs,
+            perms,
+            &mut transcript,
+        )?;
+
+        // verifier
+        let mut transcript =
+            <PolyIOP<E::ScalarField> as PermutationCheck<E, PCS>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let perm_check_sub_claim =
+            <PolyIOP<E::ScalarField> as PermutationCheck<E, PCS>>::verify_with_gkr(
+                &proof,
+                &poly_info,
+                &mut transcript,
+            )?;
+
+        // check the evaluations of the oracles
+        let point = &perm_check_sub_claim.product_check_sub_claim.point;
+        let evals = |polys: &[Arc<DenseMultilinearExtension<E::ScalarField>>]| {
+            polys
+                .iter()
+                .map(|poly| evaluate_opt(poly, point))
+                .collect::<Vec<_>>()
+        };
+        perm_check_sub_claim.check_evaluations(&evals(fxs), &evals(gxs), &evals(perms))
+    }
+
     fn test_permutation_check(nv: usize) -> Result<(), PolyIOPErrors> {
         let mut rng = test_rng();
 
@@ -274,6 +479,7 @@ mod test {
             ];
             // perms is the identity map
             test_permutation_check_helper::<Bls12_381, Kzg>(&pcs_param, &ws, &ws, &id_perms)?;
+            test_gkr_permutation_check_helper::<Bls12_381, Kzg>(&ws, &ws, &id_perms)?;
         }
 
         {
@@ -288,6 +494,7 @@ mod test {
             let mut perms = id_perms.clone();
             perms.reverse();
             test_permutation_check_helper::<Bls12_381, Kzg>(&pcs_param, &fs, &gs, &perms)?;
+            test_gkr_permutation_check_helper::<Bls12_381, Kzg>(&fs, &gs, &perms)?;
         }
 
         {
@@ -303,6 +510,7 @@ mod test {
                 test_permutation_check_helper::<Bls12_381, Kzg>(&pcs_param, &ws, &ws, &perms)
                     .is_err()
             );
+            assert!(test_gkr_permutation_check_helper::<Bls12_381, Kzg>(&ws, &ws, &perms).is_err());
         }
 
         {
@@ -321,6 +529,9 @@ mod test {
                 &pcs_param, &fs, &gs, &id_perms
             )
             .is_err());
+            assert!(
+                test_gkr_permutation_check_helper::<Bls12_381, Kzg>(&fs, &gs, &id_perms).is_err()
+            );
         }
 
         Ok(())
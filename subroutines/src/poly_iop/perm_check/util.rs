@@ -73,3 +73,15 @@ pub(super) fn computer_nums_and_denoms<F: PrimeField>(
     end_timer!(start);
     Ok((numerators, denominators))
 }
+
+/// Evaluate the i-th identity permutation MLE `s_id_i(x) = i * 2^n + \sum_j
+/// x_j 2^j` at `point`, without building it.
+pub(super) fn eval_identity_permutation<F: PrimeField>(point: &[F], i: usize) -> F {
+    let mut res = F::from((i as u64) << point.len());
+    let mut base = F::one();
+    for &x in point.iter() {
+        res += base * x;
+        base.double_in_place();
+    }
+    res
+}
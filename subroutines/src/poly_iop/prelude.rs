@@ -5,6 +5,22 @@
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
 pub use crate::poly_iop::{
-    errors::PolyIOPErrors, perm_check::PermutationCheck, prod_check::ProductCheck,
-    structs::IOPProof, sum_check::SumCheck, utils::*, zero_check::ZeroCheck, PolyIOP,
+    errors::PolyIOPErrors,
+    memory_check::{MemoryCheck, MemoryTrace},
+    multiset_check::MultisetCheck,
+    perm_check::PermutationCheck,
+    prod_check::{gkr::GkrProductCheck, ProductCheck},
+    structs::IOPProof,
+    subset_check::{SubsetCheck, SubsetCheckEvaluations},
+    sum_check::{
+        batched::BatchedSumCheck,
+        streaming::{
+            FileMultilinearStream, FnMultilinearStream, MultilinearStream, StreamingPolynomial,
+            StreamingSumCheck,
+        },
+        SumCheck,
+    },
+    utils::*,
+    zero_check::{streaming::StreamingZeroCheck, ZeroCheck},
+    PolyIOP,
 };
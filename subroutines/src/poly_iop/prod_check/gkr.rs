@@ -0,0 +1,460 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! A grand product check with a layered circuit, proved with GKR.
+//!
+//! The evaluations of each n-variate polynomial are the inputs of a binary
+//! tree of multiplication gates. Layer `d` has `2^d` gates, with
+//! `V_d(b) = V_{d+1}(b, 0) * V_{d+1}(b, 1)`, so `V_0` is the product of all
+//! evaluations and `V_n` is the polynomial itself.
+//!
+//! Starting from the outputs, a claim on `V_d(r_d)` is reduced to a claim on
+//! `V_{d+1}` with a sum check on
+//! `V_d(r_d) = \sum_b eq(r_d, b) * V_{d+1}(b, 0) * V_{d+1}(b, 1)`. Once the
+//! sum check fixed `b` to `rho`, the prover sends `V_{d+1}(rho, 0)` and
+//! `V_{d+1}(rho, 1)`, and the claim moves to `V_{d+1}(rho, tau)` for a random
+//! `tau`. All circuits share their sum checks through a random linear
+//! combination, so the last claims are on all the polynomials at one point.
+//!
+//! Unlike `ProductCheck`, nothing is committed: the proof is made of `n - 1`
+//! sum checks of degree 3, and the subclaim asks for one evaluation of each
+//! polynomial.
+
+use crate::poly_iop::{errors::PolyIOPErrors, sum_check::SumCheck, PolyIOP};
+use arithmetic::{
+    build_eq_x_r, eq_eval, fix_first_variable_in_place, VPAuxInfo, VirtualPolynomial,
+};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{cfg_iter, end_timer, start_timer};
+use std::{marker::PhantomData, sync::Arc};
+use transcript::Transcript;
+
+#[cfg(feature = "parallel")]
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+/// A GKR product check proves that two lists of n-variate multilinear
+/// polynomials `(f1, f2, ..., fk)` and `(g1, ..., gk)` satisfy:
+/// \prod_{x \in {0,1}^n} f1(x) * ... * fk(x) = \prod_{x \in {0,1}^n} g1(x) *
+/// ... * gk(x)
+///
+/// Each polynomial is the input layer of a product circuit, and the outputs
+/// are checked directly. The claims on the circuits are reduced layer by layer
+/// to claims on the evaluations of `(f1, ..., fk, g1, ..., gk)` at a single
+/// random point, which the caller needs to check against the oracles.
+pub trait GkrProductCheck<F: PrimeField>: SumCheck<F> {
+    type GkrProductCheckSubClaim;
+    type GkrProductCheckProof;
+
+    /// Initialize the system with a transcript
+    ///
+    /// This function is optional -- in the case where a GkrProductCheck is
+    /// an building block for a more complex protocol, the transcript
+    /// may be initialized by this complex protocol, and passed to the
+    /// GkrProductCheck prover/verifier.
+    fn init_transcript() -> Self::Transcript;
+
+    /// Proves that two lists of n-variate multilinear polynomials `(f1, f2,
+    /// ..., fk)` and `(g1, ..., gk)` satisfy:
+    ///   \prod_{x \in {0,1}^n} f1(x) * ... * fk(x)
+    /// = \prod_{x \in {0,1}^n} g1(x) * ... * gk(x)
+    ///
+    /// Inputs:
+    /// - fxs: the list of numerator multilinear polynomial
+    /// - gxs: the list of denominator multilinear polynomial
+    /// - transcript: the IOP transcript
+    ///
+    /// Outputs
+    /// - the GKR product check proof
+    ///
+    /// Cost: O(N)
+    fn prove(
+        fxs: &[Self::MultilinearExtension],
+        gxs: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::GkrProductCheckProof, PolyIOPErrors>;
+
+    /// Verify that the products of the outputs of the circuits match, and
+    /// reduce the circuits to the evaluations of the polynomials at a random
+    /// point.
+    fn verify(
+        proof: &Self::GkrProductCheckProof,
+        aux_info: &VPAuxInfo<F>,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::GkrProductCheckSubClaim, PolyIOPErrors>;
+}
+
+/// A GKR product check subclaim consists of
+/// - the point at which the polynomials are queried
+/// - the expected evaluations of `(f1, ..., fk)` at that point
+/// - the expected evaluations of `(g1, ..., gk)` at that point
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GkrProductCheckSubClaim<F: PrimeField> {
+    pub point: Vec<F>,
+    pub numerator_evals: Vec<F>,
+    pub denominator_evals: Vec<F>,
+}
+
+/// A GKR product check proof consists of
+/// - a sum check proof for each layer but the first one
+/// - for each layer `d + 1`, the evaluations of `V_{d+1}(rho, 0)` and
+///   `V_{d+1}(rho, 1)` of all circuits, numerators first
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GkrProductCheckProof<F: PrimeField, SC: SumCheck<F>> {
+    pub sum_check_proofs: Vec<SC::SumCheckProof>,
+    pub layer_evals: Vec<(Vec<F>, Vec<F>)>,
+}
+
+impl<F: PrimeField, T: Transcript<F>> GkrProductCheck<F> for PolyIOP<F, T> {
+    type GkrProductCheckSubClaim = GkrProductCheckSubClaim<F>;
+    type GkrProductCheckProof = GkrProductCheckProof<F, Self>;
+
+    fn init_transcript() -> Self::Transcript {
+        T::new(b"Initializing GkrProductCheck transcript")
+    }
+
+    fn prove(
+        fxs: &[Self::MultilinearExtension],
+        gxs: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::GkrProductCheckProof, PolyIOPErrors> {
+        if fxs.is_empty() {
+            return Err(PolyIOPErrors::InvalidParameters("fxs is empty".to_string()));
+        }
+        if fxs.len() != gxs.len() {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "fxs and gxs have different number of polynomials".to_string(),
+            ));
+        }
+        let num_vars = fxs[0].num_vars;
+        if num_vars == 0 {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "Attempt to prove a constant.".to_string(),
+            ));
+        }
+        for poly in fxs.iter().chain(gxs.iter()) {
+            if poly.num_vars != num_vars {
+                return Err(PolyIOPErrors::InvalidParameters(
+                    "fx and gx have different number of variables".to_string(),
+                ));
+            }
+        }
+        if gxs
+            .iter()
+            .any(|poly| poly.evaluations.iter().any(|eval| eval.is_zero()))
+        {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "gxs has zero entries in the boolean hypercube".to_string(),
+            ));
+        }
+        let start = start_timer!(|| "gkr prod_check prove");
+
+        let step = start_timer!(|| "compute layers");
+        let inputs: Vec<&[F]> = fxs
+            .iter()
+            .chain(gxs.iter())
+            .map(|poly| poly.evaluations.as_slice())
+            .collect();
+        let layers: Vec<Vec<Vec<F>>> = cfg_iter!(inputs)
+            .map(|evals| compute_layers(evals, num_vars))
+            .collect();
+        end_timer!(step);
+        // the evaluations of layer `d` of the i-th circuit
+        let layer = |i: usize, d: usize| -> &[F] {
+            if d == num_vars {
+                inputs[i]
+            } else {
+                &layers[i][d]
+            }
+        };
+
+        let mut point = vec![];
+        let mut sum_check_proofs = Vec::with_capacity(num_vars - 1);
+        let mut layer_evals = Vec::with_capacity(num_vars);
+        for d in 0..num_vars {
+            if d > 0 {
+                // sum check on \sum_i lambda^i eq(r_d, b) V^i_{d+1}(b, 0) V^i_{d+1}(b, 1)
+                let lambda = transcript.get_and_append_challenge(b"lambda")?;
+                let eq_x_r = build_eq_x_r(&point)?;
+                let mut poly = VirtualPolynomial::new(d);
+                let mut coefficient = F::one();
+                for i in 0..inputs.len() {
+                    let (left, right) = layer(i, d + 1).split_at(1 << d);
+                    poly.add_mle_list(
+                        [
+                            eq_x_r.clone(),
+                            Arc::new(DenseMultilinearExtension::from_evaluations_slice(d, left)),
+                            Arc::new(DenseMultilinearExtension::from_evaluations_slice(d, right)),
+                        ],
+                        coefficient,
+                    )?;
+                    coefficient *= lambda;
+                }
+                let sum_check_proof = <Self as SumCheck<F>>::prove(&poly, transcript)?;
+                point = sum_check_proof.point.clone();
+                sum_check_proofs.push(sum_check_proof);
+            }
+
+            let (left_evals, right_evals): (Vec<F>, Vec<F>) = (0..inputs.len())
+                .map(|i| {
+                    let (left, right) = layer(i, d + 1).split_at(1 << d);
+                    (evaluate(left, &point), evaluate(right, &point))
+                })
+                .unzip();
+            transcript.append_serializable_element(b"left evals", &left_evals)?;
+            transcript.append_serializable_element(b"right evals", &right_evals)?;
+            layer_evals.push((left_evals, right_evals));
+
+            point.push(transcript.get_and_append_challenge(b"tau")?);
+        }
+
+        end_timer!(start);
+        Ok(GkrProductCheckProof {
+            sum_check_proofs,
+            layer_evals,
+        })
+    }
+
+    fn verify(
+        proof: &Self::GkrProductCheckProof,
+        aux_info: &VPAuxInfo<F>,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::GkrProductCheckSubClaim, PolyIOPErrors> {
+        let num_vars = aux_info.num_variables;
+        if num_vars == 0
+            || proof.layer_evals.len() != num_vars
+            || proof.sum_check_proofs.len() != num_vars - 1
+        {
+            return Err(PolyIOPErrors::InvalidProof(format!(
+                "proof has {} layers, expect {}",
+                proof.layer_evals.len(),
+                num_vars
+            )));
+        }
+        let num_circuits = proof.layer_evals[0].0.len();
+        if num_circuits == 0 || num_circuits % 2 != 0 {
+            return Err(PolyIOPErrors::InvalidProof(format!(
+                "proof has {} circuits, expect a positive even number",
+                num_circuits
+            )));
+        }
+        for (left_evals, right_evals) in proof.layer_evals.iter() {
+            if left_evals.len() != num_circuits || right_evals.len() != num_circuits {
+                return Err(PolyIOPErrors::InvalidProof(
+                    "layers have different number of circuits".to_string(),
+                ));
+            }
+        }
+
+        // the outputs of the circuits
+        let (left_evals, right_evals) = &proof.layer_evals[0];
+        let outputs: Vec<F> = left_evals
+            .iter()
+            .zip(right_evals.iter())
+            .map(|(left, right)| *left * right)
+            .collect();
+        let numerator_product: F = outputs[..num_circuits / 2].iter().product();
+        let denominator_product: F = outputs[num_circuits / 2..].iter().product();
+        if denominator_product.is_zero() || numerator_product != denominator_product {
+            return Err(PolyIOPErrors::InvalidProof(
+                "products of fxs and gxs are different".to_string(),
+            ));
+        }
+
+        let start = start_timer!(|| "gkr prod_check verify");
+        let res = reduce_layers(proof, transcript);
+        end_timer!(start);
+        let (point, mut claims) = res?;
+
+        let denominator_evals = claims.split_off(num_circuits / 2);
+        Ok(GkrProductCheckSubClaim {
+            point,
+            numerator_evals: claims,
+            denominator_evals,
+        })
+    }
+}
+
+/// Reduce the claims on the outputs of the circuits, layer by layer, to
+/// claims on the inputs at a single point, returning the point and the claims.
+fn reduce_layers<F: PrimeField, T: Transcript<F>>(
+    proof: &GkrProductCheckProof<F, PolyIOP<F, T>>,
+    transcript: &mut T,
+) -> Result<(Vec<F>, Vec<F>), PolyIOPErrors> {
+    let mut point = vec![];
+    let mut claims = vec![];
+    for (d, (left_evals, right_evals)) in proof.layer_evals.iter().enumerate() {
+        if d > 0 {
+            let lambda = transcript.get_and_append_challenge(b"lambda")?;
+            let mut claimed_sum = F::zero();
+            let mut expected_evaluation = F::zero();
+            let mut coefficient = F::one();
+            for (claim, (left, right)) in
+                claims.iter().zip(left_evals.iter().zip(right_evals.iter()))
+            {
+                claimed_sum += coefficient * claim;
+                expected_evaluation += coefficient * left * right;
+                coefficient *= lambda;
+            }
+
+            let layer_aux_info = VPAuxInfo {
+                max_degree: 3,
+                num_variables: d,
+                phantom: PhantomData,
+            };
+            let sum_check_sub_claim = <PolyIOP<F, T> as SumCheck<F>>::verify(
+                claimed_sum,
+                &proof.sum_check_proofs[d - 1],
+                &layer_aux_info,
+                transcript,
+            )?;
+            expected_evaluation *= eq_eval(&point, &sum_check_sub_claim.point)?;
+            if expected_evaluation != sum_check_sub_claim.expected_evaluation {
+                return Err(PolyIOPErrors::InvalidProof(format!(
+                    "layer {} evaluations do not match the sum check",
+                    d + 1
+                )));
+            }
+            point = sum_check_sub_claim.point;
+        }
+
+        transcript.append_serializable_element(b"left evals", left_evals)?;
+        transcript.append_serializable_element(b"right evals", right_evals)?;
+        let tau = transcript.get_and_append_challenge(b"tau")?;
+        claims = left_evals
+            .iter()
+            .zip(right_evals.iter())
+            .map(|(left, right)| *left + tau * (*right - left))
+            .collect();
+        point.push(tau);
+    }
+
+    Ok((point, claims))
+}
+
+/// Compute the layers `V_0, ..., V_{n-1}` of the product circuit with
+/// inputs `evals`, where `V_d(b) = V_{d+1}(b, 0) * V_{d+1}(b, 1)`.
+fn compute_layers<F: PrimeField>(evals: &[F], num_vars: usize) -> Vec<Vec<F>> {
+    let mut layers: Vec<Vec<F>> = Vec::with_capacity(num_vars);
+    for d in (0..num_vars).rev() {
+        let (left, right) = layers.last().map_or(evals, Vec::as_slice).split_at(1 << d);
+        let layer = left
+            .iter()
+            .zip(right.iter())
+            .map(|(left, right)| *left * right)
+            .collect();
+        layers.push(layer);
+    }
+    layers.reverse();
+    layers
+}
+
+/// Evaluate the MLE with evaluations `evals` at `point`.
+fn evaluate<F: PrimeField>(evals: &[F], point: &[F]) -> F {
+    let mut evals = evals.to_vec();
+    point
+        .iter()
+        .for_each(|p| fix_first_variable_in_place(&mut evals, p));
+    evals[0]
+}
+
+#[cfg(test)]
+mod test {
+    use super::GkrProductCheck;
+    use crate::poly_iop::{errors::PolyIOPErrors, PolyIOP};
+    use arithmetic::{evaluate_opt, VPAuxInfo};
+    use ark_bls12_381::Fr;
+    use ark_ff::One;
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::test_rng;
+    use std::{marker::PhantomData, sync::Arc};
+    use transcript::Transcript;
+
+    fn test_gkr_product_check(nv: usize) -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+
+        let f1: DenseMultilinearExtension<Fr> = DenseMultilinearExtension::rand(nv, &mut rng);
+        let mut g1 = f1.clone();
+        g1.evaluations.reverse();
+        let f2: DenseMultilinearExtension<Fr> = DenseMultilinearExtension::rand(nv, &mut rng);
+        let mut g2 = f2.clone();
+        g2.evaluations.reverse();
+        let fs = vec![Arc::new(f1), Arc::new(f2)];
+        let gs = vec![Arc::new(g2), Arc::new(g1)];
+        let aux_info = VPAuxInfo {
+            max_degree: 3,
+            num_variables: nv,
+            phantom: PhantomData,
+        };
+
+        let mut transcript = <PolyIOP<Fr> as GkrProductCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let proof = <PolyIOP<Fr> as GkrProductCheck<Fr>>::prove(&fs, &gs, &mut transcript)?;
+        assert_eq!(proof.sum_check_proofs.len(), nv - 1);
+
+        let mut transcript = <PolyIOP<Fr> as GkrProductCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let subclaim =
+            <PolyIOP<Fr> as GkrProductCheck<Fr>>::verify(&proof, &aux_info, &mut transcript)?;
+        for (poly, eval) in fs.iter().chain(gs.iter()).zip(
+            subclaim
+                .numerator_evals
+                .iter()
+                .chain(subclaim.denominator_evals.iter()),
+        ) {
+            assert_eq!(evaluate_opt(poly, &subclaim.point), *eval);
+        }
+
+        // bad path: the products differ
+        let hs = vec![
+            Arc::new(DenseMultilinearExtension::rand(nv, &mut rng)),
+            Arc::new(DenseMultilinearExtension::rand(nv, &mut rng)),
+        ];
+        let mut transcript = <PolyIOP<Fr> as GkrProductCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let bad_proof = <PolyIOP<Fr> as GkrProductCheck<Fr>>::prove(&fs, &hs, &mut transcript)?;
+        let mut transcript = <PolyIOP<Fr> as GkrProductCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        assert!(<PolyIOP<Fr> as GkrProductCheck<Fr>>::verify(
+            &bad_proof,
+            &aux_info,
+            &mut transcript
+        )
+        .is_err());
+
+        // bad path: an inner layer is tampered with, keeping the outputs
+        if nv > 1 {
+            let mut bad_proof = proof.clone();
+            bad_proof.layer_evals[1].0[0] += Fr::one();
+            let mut transcript = <PolyIOP<Fr> as GkrProductCheck<Fr>>::init_transcript();
+            transcript.append_message(b"testing", b"initializing transcript for testing")?;
+            assert!(<PolyIOP<Fr> as GkrProductCheck<Fr>>::verify(
+                &bad_proof,
+                &aux_info,
+                &mut transcript
+            )
+            .is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        test_gkr_product_check(1)
+    }
+    #[test]
+    fn test_normal_polynomial() -> Result<(), PolyIOPErrors> {
+        test_gkr_product_check(10)
+    }
+    #[test]
+    fn zero_polynomial_should_error() {
+        let mut rng = test_rng();
+        let fs = vec![Arc::new(DenseMultilinearExtension::<Fr>::rand(0, &mut rng))];
+        let mut transcript = <PolyIOP<Fr> as GkrProductCheck<Fr>>::init_transcript();
+        assert!(<PolyIOP<Fr> as GkrProductCheck<Fr>>::prove(&fs, &fs, &mut transcript).is_err());
+    }
+}
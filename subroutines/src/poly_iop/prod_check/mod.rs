@@ -21,9 +21,10 @@ use ark_ff::{One, PrimeField, Zero};
 use ark_poly::DenseMultilinearExtension;
 use ark_std::{end_timer, start_timer};
 use std::sync::Arc;
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
-mod util;
+pub mod gkr;
+pub(crate) mod util;
 
 /// A product-check proves that two lists of n-variate multilinear polynomials
 /// `(f1, f2, ..., fk)` and `(g1, ..., gk)` satisfy:
@@ -90,7 +91,7 @@ where
         pcs_param: &PCS::ProverParam,
         fxs: &[Self::MultilinearExtension],
         gxs: &[Self::MultilinearExtension],
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut Self::Transcript,
     ) -> Result<
         (
             Self::ProductCheckProof,
@@ -145,23 +146,24 @@ pub struct ProductCheckProof<
     pub frac_comm: PCS::Commitment,
 }
 
-impl<E, PCS> ProductCheck<E, PCS> for PolyIOP<E::ScalarField>
+impl<E, PCS, T> ProductCheck<E, PCS> for PolyIOP<E::ScalarField, T>
 where
     E: Pairing,
+    T: Transcript<E::ScalarField>,
     PCS: PolynomialCommitmentScheme<E, Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>>,
 {
     type ProductCheckSubClaim = ProductCheckSubClaim<E::ScalarField, Self>;
     type ProductCheckProof = ProductCheckProof<E, PCS, Self>;
 
     fn init_transcript() -> Self::Transcript {
-        IOPTranscript::<E::ScalarField>::new(b"Initializing ProductCheck transcript")
+        T::new(b"Initializing ProductCheck transcript")
     }
 
     fn prove(
         pcs_param: &PCS::ProverParam,
         fxs: &[Self::MultilinearExtension],
         gxs: &[Self::MultilinearExtension],
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut Self::Transcript,
     ) -> Result<
         (
             Self::ProductCheckProof,
@@ -267,6 +269,7 @@ mod test {
     use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
     use ark_std::test_rng;
     use std::{marker::PhantomData, sync::Arc};
+    use transcript::Transcript;
 
     fn check_frac_poly<E>(
         frac_poly: &Arc<DenseMultilinearExtension<E::ScalarField>>,
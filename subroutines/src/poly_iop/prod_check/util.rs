@@ -12,7 +12,7 @@ use ark_ff::{batch_inversion, PrimeField};
 use ark_poly::DenseMultilinearExtension;
 use ark_std::{end_timer, start_timer};
 use std::sync::Arc;
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
 /// Compute multilinear fractional polynomial s.t. frac(x) = f1(x) * ... * fk(x)
 /// / (g1(x) * ... * gk(x)) for all x \in {0,1}^n
@@ -118,13 +118,13 @@ pub(super) fn compute_product_poly<F: PrimeField>(
 /// Returns proof.
 ///
 /// Cost: O(N)
-pub(super) fn prove_zero_check<F: PrimeField>(
+pub(super) fn prove_zero_check<F: PrimeField, T: Transcript<F>>(
     fxs: &[Arc<DenseMultilinearExtension<F>>],
     gxs: &[Arc<DenseMultilinearExtension<F>>],
     frac_poly: &Arc<DenseMultilinearExtension<F>>,
     prod_x: &Arc<DenseMultilinearExtension<F>>,
     alpha: &F,
-    transcript: &mut IOPTranscript<F>,
+    transcript: &mut T,
 ) -> Result<(IOPProof<F>, VirtualPolynomial<F>), PolyIOPErrors> {
     let start = start_timer!(|| "zerocheck in product check");
     let num_vars = frac_poly.num_vars;
@@ -171,8 +171,30 @@ pub(super) fn prove_zero_check<F: PrimeField>(
     // - alpha * f1(x) * ... * fk(x)]
     q_x.add_mle_list(fxs.to_vec(), -*alpha)?;
 
-    let iop_proof = <PolyIOP<F> as ZeroCheck<F>>::prove(&q_x, transcript)?;
+    let iop_proof = <PolyIOP<F, T> as ZeroCheck<F>>::prove(&q_x, transcript)?;
 
     end_timer!(start);
     Ok((iop_proof, q_x))
 }
+
+/// Evaluate the virtual polynomial of the zerocheck of a product check
+///    prod(x) - p1(x) * p2(x) + alpha * [frac(x) * g1(x) * ... * gk(x) - f1(x)
+/// * ... * fk(x)] at `point`, from
+/// - `prod_evals`: prod(x) at `point`, `(0, point[0..n-1])` and `(1,
+///   point[0..n-1])`
+/// - `frac_evals`: frac(x) at the same points
+/// - `f_prod_eval` and `g_prod_eval`: f1(x) * ... * fk(x) and g1(x) * ... *
+///   gk(x) at `point`
+pub(crate) fn eval_product_check_poly<F: PrimeField>(
+    point: &[F],
+    prod_evals: &[F],
+    frac_evals: &[F],
+    f_prod_eval: F,
+    g_prod_eval: F,
+    alpha: F,
+) -> F {
+    let x1 = point[point.len() - 1];
+    let p1_eval = frac_evals[1] + x1 * (prod_evals[1] - frac_evals[1]);
+    let p2_eval = frac_evals[2] + x1 * (prod_evals[2] - frac_evals[2]);
+    prod_evals[0] - p1_eval * p2_eval + alpha * (frac_evals[0] * g_prod_eval - f_prod_eval)
+}
@@ -6,7 +6,10 @@
 
 //! This module defines structs that are shared by all sub protocols.
 
-use arithmetic::VirtualPolynomial;
+use arithmetic::{
+    DenseMultilinearExtension, MultilinearPolynomial, SparseMultilinearPolynomial,
+    VirtualPolynomial,
+};
 use ark_ff::PrimeField;
 use ark_serialize::CanonicalSerialize;
 
@@ -24,7 +27,7 @@ pub struct IOPProof<F: PrimeField> {
 /// is a list of evaluations.
 #[derive(Clone, Debug, Default, PartialEq, Eq, CanonicalSerialize)]
 pub struct IOPProverMessage<F: PrimeField> {
-    pub(crate) evaluations: Vec<F>,
+    pub evaluations: Vec<F>,
 }
 
 /// Prover State of a PolyIOP.
@@ -35,11 +38,33 @@ pub struct IOPProverState<F: PrimeField> {
     pub round: usize,
     /// pointer to the virtual polynomial
     pub poly: VirtualPolynomial<F>,
+    /// evaluations of the multilinear extensions of `poly` with the
+    /// challenges so far fixed, folded in place every round; empty until the
+    /// first challenge, before which `poly` is read directly
+    pub folded_ml_extensions: Vec<FoldedTable<F>>,
     /// points with precomputed barycentric weights for extrapolating smaller
     /// degree uni-polys to `max_degree + 1` evaluations.
     pub extrapolation_aux: Vec<(Vec<F>, Vec<F>)>,
 }
 
+/// A multilinear extension with some variables fixed by the prover, kept in
+/// the representation of the input: sparse tables stay sparse.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FoldedTable<F: PrimeField> {
+    Dense(DenseMultilinearExtension<F>),
+    Sparse(SparseMultilinearPolynomial<F>),
+}
+
+impl<F: PrimeField> FoldedTable<F> {
+    /// The folded table as a multilinear polynomial
+    pub fn as_multilinear(&self) -> &dyn MultilinearPolynomial<F> {
+        match self {
+            Self::Dense(table) => table,
+            Self::Sparse(table) => table,
+        }
+    }
+}
+
 /// Prover State of a PolyIOP
 pub struct IOPVerifierState<F: PrimeField> {
     pub(crate) round: usize,
@@ -51,4 +76,7 @@ pub struct IOPVerifierState<F: PrimeField> {
     pub(crate) polynomials_received: Vec<Vec<F>>,
     /// a list storing the randomness sampled by the verifier at each round
     pub(crate) challenges: Vec<F>,
+    /// the points `0, ..., max_degree` with their precomputed barycentric
+    /// weights, for interpolating the uni-polys sent by the prover
+    pub(crate) interpolation_aux: (Vec<F>, Vec<F>),
 }
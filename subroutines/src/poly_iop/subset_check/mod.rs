@@ -0,0 +1,508 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Subset Check protocol
+
+use self::util::{compute_sorted, cycle_points, eval_cycle_shift, shift_along_cycle};
+use crate::{
+    pcs::PolynomialCommitmentScheme,
+    poly_iop::{
+        errors::PolyIOPErrors, multiset_check::MultisetCheckSubClaim, prelude::MultisetCheck,
+        prod_check::util::eval_product_check_poly, PolyIOP,
+    },
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::{One, Zero};
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{end_timer, start_timer};
+use derivative::Derivative;
+use std::{fmt::Debug, sync::Arc};
+use transcript::{IOPTranscript, Transcript};
+
+mod util;
+
+/// The evaluations of a column of the lookups `f`, the table `t` and the
+/// sorted lookups `(s1, s2)` of a subset check.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubsetCheckEvaluations<F> {
+    /// `f` at `point`
+    pub lookup: F,
+    /// `t` at `point`, then at the `cycle_points`
+    pub table: Vec<F>,
+    /// `s1` at `point`, then at the `cycle_points`
+    pub sorted_first: Vec<F>,
+    /// `s2` at `point`
+    pub sorted_second: F,
+}
+
+/// A subset check subclaim consists of
+/// - the SubClaim from the MultisetCheck
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "PCS: Clone"),
+    Debug(bound = "PCS: Debug"),
+    Default(bound = "PCS: Default"),
+    PartialEq(bound = "PCS: PartialEq")
+)]
+pub struct SubsetCheckSubClaim<E, PCS, T = IOPTranscript<<E as Pairing>::ScalarField>>
+where
+    E: Pairing,
+    PCS: PolynomialCommitmentScheme<E, Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>>,
+    T: Transcript<E::ScalarField>,
+{
+    /// the SubClaim from the MultisetCheck
+    pub multiset_check_sub_claim: MultisetCheckSubClaim<E, PCS, PolyIOP<E::ScalarField, T>>,
+}
+
+impl<E, PCS, T> SubsetCheckSubClaim<E, PCS, T>
+where
+    E: Pairing,
+    PCS: PolynomialCommitmentScheme<E, Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>>,
+    T: Transcript<E::ScalarField>,
+{
+    /// The point at which `f`, `t`, `s1` and `s2` are queried.
+    pub fn point(&self) -> &[E::ScalarField] {
+        &self
+            .multiset_check_sub_claim
+            .product_check_sub_claim
+            .zero_check_sub_claim
+            .point
+    }
+
+    /// The points at which `t` and `s1` are also queried, to evaluate them
+    /// at the next row of `point` on the table cycle: the two points of
+    /// `next_row_points`, row 0 and row 1.
+    pub fn cycle_points(&self) -> Vec<Vec<E::ScalarField>> {
+        cycle_points(self.point())
+    }
+
+    /// The points at which the product polynomial `prod(x)` is queried:
+    /// `point`, `(0, point[0..n-1])`, `(1, point[0..n-1])` and the final
+    /// query. The fractional polynomial `frac(x)` is queried at the first
+    /// three.
+    pub fn prod_points(&self) -> Vec<Vec<E::ScalarField>> {
+        let point = self.point();
+        let num_vars = point.len();
+        vec![
+            point.to_vec(),
+            [&[E::ScalarField::zero()], &point[0..num_vars - 1]].concat(),
+            [&[E::ScalarField::one()], &point[0..num_vars - 1]].concat(),
+            self.multiset_check_sub_claim
+                .product_check_sub_claim
+                .final_query
+                .0
+                .clone(),
+        ]
+    }
+
+    /// Check the subclaim with
+    /// - `evals`: the evaluations of each column, queried as in
+    ///   [`SubsetCheckEvaluations`]
+    /// - `prod_evals`: the evaluations of `prod(x)` at `prod_points`
+    /// - `frac_evals`: the evaluations of `frac(x)` at the first three
+    ///   `prod_points`
+    pub fn check_evaluations(
+        &self,
+        evals: &[SubsetCheckEvaluations<E::ScalarField>],
+        prod_evals: &[E::ScalarField],
+        frac_evals: &[E::ScalarField],
+    ) -> Result<(), PolyIOPErrors> {
+        if prod_evals.len() != 4 || frac_evals.len() != 3 {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "prod_evals.len() = {}, frac_evals.len() = {}, expect 4 and 3",
+                prod_evals.len(),
+                frac_evals.len(),
+            )));
+        }
+        if evals
+            .iter()
+            .any(|e| e.table.len() != 5 || e.sorted_first.len() != 5)
+        {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "table and sorted_first need 5 evaluations".to_string(),
+            ));
+        }
+        let product_check_sub_claim = &self.multiset_check_sub_claim.product_check_sub_claim;
+        let point = self.point();
+
+        let lookup: Vec<_> = evals.iter().map(|e| e.lookup).collect();
+        let table: Vec<_> = evals.iter().map(|e| e.table[0]).collect();
+        let table_next = evals
+            .iter()
+            .map(|e| eval_cycle_shift(point, &e.table[1..]))
+            .collect::<Result<Vec<_>, _>>()?;
+        let sorted_first: Vec<_> = evals.iter().map(|e| e.sorted_first[0]).collect();
+        let sorted_first_next = evals
+            .iter()
+            .map(|e| eval_cycle_shift(point, &e.sorted_first[1..]))
+            .collect::<Result<Vec<_>, _>>()?;
+        let sorted_second: Vec<_> = evals.iter().map(|e| e.sorted_second).collect();
+
+        let fingerprint = |first: &[E::ScalarField], second: &[E::ScalarField]| {
+            self.multiset_check_sub_claim
+                .eval_fingerprint(&[first, second].concat())
+        };
+        // (f, f) and (t, t(next*))
+        let f_prod_eval = fingerprint(&lookup, &lookup) * fingerprint(&table, &table_next);
+        // (s1, s2) and (s2, s1(next*))
+        let g_prod_eval = fingerprint(&sorted_first, &sorted_second)
+            * fingerprint(&sorted_second, &sorted_first_next);
+
+        let eval = eval_product_check_poly(
+            point,
+            prod_evals,
+            frac_evals,
+            f_prod_eval,
+            g_prod_eval,
+            product_check_sub_claim.alpha,
+        );
+        if eval
+            != product_check_sub_claim
+                .zero_check_sub_claim
+                .expected_evaluation
+        {
+            return Err(PolyIOPErrors::InvalidVerifier(
+                "subset check evaluations do not match the zero check".to_string(),
+            ));
+        }
+        if prod_evals[3] != product_check_sub_claim.final_query.1 {
+            return Err(PolyIOPErrors::InvalidVerifier(
+                "subset check final product is not one".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A subset check proof consists of
+/// - the commitments of the columns of `s1`, then of `s2`
+/// - the MultisetCheck proof
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "MC::MultisetCheckProof: Clone"),
+    Debug(bound = "MC::MultisetCheckProof: Debug"),
+    Default(bound = "MC::MultisetCheckProof: Default"),
+    PartialEq(bound = "MC::MultisetCheckProof: PartialEq")
+)]
+pub struct SubsetCheckProof<E, PCS, MC>
+where
+    E: Pairing,
+    PCS: PolynomialCommitmentScheme<E>,
+    MC: MultisetCheck<E, PCS>,
+{
+    /// the commitments of the sorted lookups
+    pub sorted_comms: Vec<PCS::Commitment>,
+    /// the MultisetCheck proof
+    pub multiset_check_proof: MC::MultisetCheckProof,
+}
+
+/// A SubsetCheck proves that every element of the lookups `f` is an element
+/// of the table `t`, with the sorted merge of Plookup.
+/// It is derived from MultisetCheck.
+///
+/// The rows of the table are ordered along a cycle `c_0, ..., c_{N-1}`,
+/// the cycle of `next_row` with row 0 inserted before row 1, so that
+/// `t(next*(x))` is cheap to evaluate. The prover commits to `s`, the
+/// `2N` elements of `f` and `t` where each element of `t` is followed by
+/// the lookups equal to it, split into `s1(c_i) = s_{2i}` and
+/// `s2(c_i) = s_{2i+1}`. Then, as multisets,
+///   {(f, f)} \cup {(t, t(next*))} = {(s1, s2)} \cup {(s2, s1(next*))}
+/// where the right hand side are the pairs of consecutive elements of `s`
+/// around its cycle. A pair of distinct elements of `s` is a pair of
+/// consecutive elements of `t`, so every element of `s` is in `t`, and
+/// every lookup is repeated in `s`.
+///
+/// Lookups and the table are tuples of columns, with the same number of
+/// variables `n >= 1`.
+pub trait SubsetCheck<E, PCS>: MultisetCheck<E, PCS>
+where
+    E: Pairing,
+    PCS: PolynomialCommitmentScheme<E>,
+{
+    type SubsetCheckSubClaim;
+    type SubsetCheckProof;
+
+    /// Initialize the system with a transcript
+    ///
+    /// This function is optional -- in the case where a SubsetCheck is
+    /// an building block for a more complex protocol, the transcript
+    /// may be initialized by this complex protocol, and passed to the
+    /// SubsetCheck prover/verifier.
+    fn init_transcript() -> Self::Transcript;
+
+    /// Inputs:
+    /// - fxs: the columns of the lookups, committed by the caller
+    /// - txs: the columns of the table, committed by the caller
+    ///
+    /// Outputs:
+    /// - a subset check proof proving that fs is contained in ts
+    /// - the product polynomial built during product check
+    /// - the fractional polynomial built during product check
+    /// - the columns of `s1`, then of `s2`, committed in the proof
+    ///
+    /// Cost: O(N)
+    #[allow(clippy::type_complexity)]
+    fn prove(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        txs: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::SubsetCheckProof,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+            Vec<Self::MultilinearExtension>,
+        ),
+        PolyIOPErrors,
+    >;
+
+    /// Verify that lookups of `num_vars` variables are contained in a table
+    /// of `num_vars` variables.
+    fn verify(
+        proof: &Self::SubsetCheckProof,
+        num_vars: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::SubsetCheckSubClaim, PolyIOPErrors>;
+}
+
+impl<E, PCS, T> SubsetCheck<E, PCS> for PolyIOP<E::ScalarField, T>
+where
+    E: Pairing,
+    T: Transcript<E::ScalarField>,
+    PCS: PolynomialCommitmentScheme<E, Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>>,
+{
+    type SubsetCheckSubClaim = SubsetCheckSubClaim<E, PCS, T>;
+    type SubsetCheckProof = SubsetCheckProof<E, PCS, Self>;
+
+    fn init_transcript() -> Self::Transcript {
+        T::new(b"Initializing SubsetCheck transcript")
+    }
+
+    fn prove(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        txs: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::SubsetCheckProof,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+            Vec<Self::MultilinearExtension>,
+        ),
+        PolyIOPErrors,
+    > {
+        let start = start_timer!(|| "Subset check prove");
+
+        if fxs.is_empty() || fxs.len() != txs.len() {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "fxs.len() = {}, txs.len() = {}",
+                fxs.len(),
+                txs.len(),
+            )));
+        }
+        let num_vars = fxs[0].num_vars;
+        if num_vars == 0
+            || fxs
+                .iter()
+                .chain(txs.iter())
+                .any(|poly| poly.num_vars != num_vars)
+        {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "number of variables unmatched or zero".to_string(),
+            ));
+        }
+
+        let (sorted_first, sorted_second) = compute_sorted(fxs, txs)?;
+        let sorted_polys = [sorted_first.clone(), sorted_second.clone()].concat();
+        let sorted_comms = sorted_polys
+            .iter()
+            .map(|poly| PCS::commit(pcs_param, poly))
+            .collect::<Result<Vec<_>, _>>()?;
+        for comm in sorted_comms.iter() {
+            transcript.append_serializable_element(b"sorted(x)", comm)?;
+        }
+
+        // (f, f) \cup (t, t(next*)) = (s1, s2) \cup (s2, s1(next*))
+        let table_next: Vec<_> = txs.iter().map(|t| shift_along_cycle(t)).collect();
+        let sorted_first_next: Vec<_> = sorted_first.iter().map(|s| shift_along_cycle(s)).collect();
+        let fxs = vec![[fxs, fxs].concat(), [txs, table_next.as_slice()].concat()];
+        let gxs = vec![
+            [sorted_first.as_slice(), sorted_second.as_slice()].concat(),
+            [sorted_second, sorted_first_next].concat(),
+        ];
+        let (multiset_check_proof, prod_poly, frac_poly) =
+            <Self as MultisetCheck<E, PCS>>::prove(pcs_param, &fxs, &gxs, transcript)?;
+
+        end_timer!(start);
+        Ok((
+            SubsetCheckProof {
+                sorted_comms,
+                multiset_check_proof,
+            },
+            prod_poly,
+            frac_poly,
+            sorted_polys,
+        ))
+    }
+
+    fn verify(
+        proof: &Self::SubsetCheckProof,
+        num_vars: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::SubsetCheckSubClaim, PolyIOPErrors> {
+        let start = start_timer!(|| "Subset check verify");
+
+        if num_vars == 0 {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "subset check needs at least one variable".to_string(),
+            ));
+        }
+        for comm in proof.sorted_comms.iter() {
+            transcript.append_serializable_element(b"sorted(x)", comm)?;
+        }
+        let aux_info = Self::VPAuxInfo {
+            max_degree: 3,
+            num_variables: num_vars,
+            phantom: Default::default(),
+        };
+        let multiset_check_sub_claim = <Self as MultisetCheck<E, PCS>>::verify(
+            &proof.multiset_check_proof,
+            &aux_info,
+            transcript,
+        )?;
+
+        end_timer!(start);
+        Ok(SubsetCheckSubClaim {
+            multiset_check_sub_claim,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SubsetCheck, SubsetCheckEvaluations};
+    use crate::{
+        pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+        poly_iop::{errors::PolyIOPErrors, PolyIOP},
+    };
+    use arithmetic::evaluate_opt;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::DenseMultilinearExtension;
+    use ark_std::{test_rng, UniformRand};
+    use std::sync::Arc;
+    use transcript::Transcript;
+
+    type Kzg = MultilinearKzgPCS<Bls12_381>;
+
+    fn test_subset_check_helper(
+        pcs_param: &<Kzg as PolynomialCommitmentScheme<Bls12_381>>::ProverParam,
+        fxs: &[Arc<DenseMultilinearExtension<Fr>>],
+        txs: &[Arc<DenseMultilinearExtension<Fr>>],
+        claimed_fxs: &[Arc<DenseMultilinearExtension<Fr>>],
+    ) -> Result<(), PolyIOPErrors> {
+        let nv = fxs[0].num_vars;
+
+        // prover
+        let mut transcript = <PolyIOP<Fr> as SubsetCheck<Bls12_381, Kzg>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let (proof, prod_x, frac_poly, sorted_polys) = <PolyIOP<Fr> as SubsetCheck<
+            Bls12_381,
+            Kzg,
+        >>::prove(
+            pcs_param, fxs, txs, &mut transcript
+        )?;
+
+        // verifier
+        let mut transcript = <PolyIOP<Fr> as SubsetCheck<Bls12_381, Kzg>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let sub_claim =
+            <PolyIOP<Fr> as SubsetCheck<Bls12_381, Kzg>>::verify(&proof, nv, &mut transcript)?;
+
+        let point = sub_claim.point();
+        let cycle_points = sub_claim.cycle_points();
+        let evals_at = |poly: &Arc<DenseMultilinearExtension<Fr>>| -> Vec<Fr> {
+            [point.to_vec()]
+                .iter()
+                .chain(cycle_points.iter())
+                .map(|p| evaluate_opt(poly, p))
+                .collect()
+        };
+        let width = fxs.len();
+        let evals: Vec<_> = (0..width)
+            .map(|j| SubsetCheckEvaluations {
+                lookup: evaluate_opt(&claimed_fxs[j], point),
+                table: evals_at(&txs[j]),
+                sorted_first: evals_at(&sorted_polys[j]),
+                sorted_second: evaluate_opt(&sorted_polys[width + j], point),
+            })
+            .collect();
+        let prod_points = sub_claim.prod_points();
+        let prod_evals: Vec<Fr> = prod_points
+            .iter()
+            .map(|p| evaluate_opt(&prod_x, p))
+            .collect();
+        let frac_evals: Vec<Fr> = prod_points[..3]
+            .iter()
+            .map(|p| evaluate_opt(&frac_poly, p))
+            .collect();
+        sub_claim.check_evaluations(&evals, &prod_evals, &frac_evals)
+    }
+
+    fn test_subset_check(nv: usize) -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+
+        let srs = Kzg::gen_srs_for_testing(&mut rng, nv)?;
+        let (pcs_param, _) = Kzg::trim(&srs, None, Some(nv))?;
+
+        // a table of pairs with a repeated element, and lookups of a few of
+        // its elements, some of them repeated
+        let mut table: Vec<Vec<Fr>> = (0..1 << nv)
+            .map(|_| vec![Fr::rand(&mut rng), Fr::rand(&mut rng)])
+            .collect();
+        table[0] = table[(1 << nv) - 1].clone();
+        let lookup: Vec<Vec<Fr>> = (0..1 << nv)
+            .map(|i| table[(i * 7) % (1 << nv).min(3)].clone())
+            .collect();
+        let to_columns = |elements: &[Vec<Fr>]| -> Vec<Arc<DenseMultilinearExtension<Fr>>> {
+            (0..2)
+                .map(|j| {
+                    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                        nv,
+                        elements.iter().map(|element| element[j]).collect(),
+                    ))
+                })
+                .collect()
+        };
+        let fs = to_columns(&lookup);
+        let ts = to_columns(&table);
+
+        // good path
+        test_subset_check_helper(&pcs_param, &fs, &ts, &fs)?;
+        // good path: the table looks itself up
+        test_subset_check_helper(&pcs_param, &ts, &ts, &ts)?;
+
+        // bad path: a lookup is not in the table
+        let mut bad_lookup = lookup.clone();
+        bad_lookup[1][1] += Fr::from(1u64);
+        let bad_fs = to_columns(&bad_lookup);
+        assert!(test_subset_check_helper(&pcs_param, &bad_fs, &ts, &bad_fs).is_err());
+        // bad path: the proof is for other lookups
+        assert!(test_subset_check_helper(&pcs_param, &fs, &ts, &bad_fs).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        test_subset_check(1)
+    }
+    #[test]
+    fn test_normal_polynomial() -> Result<(), PolyIOPErrors> {
+        test_subset_check(5)
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! This module implements useful functions for the subset check protocol.
+
+use crate::poly_iop::errors::PolyIOPErrors;
+use arithmetic::{eq_eval, next_row, next_row_points, prev_row};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{end_timer, start_timer};
+use std::{collections::HashMap, sync::Arc};
+
+/// The row following `row` on the cycle of the table, out of `2^num_vars`
+/// rows.
+///
+/// The cycle is `0, 1, next_row(1), ..., prev_row(1)`, i.e. the cycle of
+/// `next_row` on the non-zero rows with row 0 inserted before row 1.
+pub(crate) fn next_in_cycle(row: usize, num_vars: usize) -> usize {
+    if row == 0 {
+        1
+    } else if row == prev_row(1, num_vars) {
+        0
+    } else {
+        next_row(row, num_vars)
+    }
+}
+
+/// Build `w(next*(x))`, whose evaluation at row `b` is the evaluation of `w`
+/// at row `next_in_cycle(b)`.
+pub(crate) fn shift_along_cycle<F: PrimeField>(
+    mle: &DenseMultilinearExtension<F>,
+) -> Arc<DenseMultilinearExtension<F>> {
+    let evals = (0..1 << mle.num_vars)
+        .map(|b| mle.evaluations[next_in_cycle(b, mle.num_vars)])
+        .collect();
+    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        mle.num_vars,
+        evals,
+    ))
+}
+
+/// The points at which `w` is queried to evaluate `w(next*(x))` at `point`:
+/// the two points of `next_row_points`, row 0 and row 1.
+pub(crate) fn cycle_points<F: PrimeField>(point: &[F]) -> Vec<Vec<F>> {
+    let num_vars = point.len();
+    let mut row_0 = vec![F::zero(); num_vars];
+    let mut points: Vec<Vec<F>> = next_row_points(point, 0)
+        .into_iter()
+        .map(|(point, _coeff)| point)
+        .collect();
+    points.push(row_0.clone());
+    row_0[0] = F::one();
+    points.push(row_0);
+    points
+}
+
+/// Evaluate `w(next*(x))` at `point` from the evaluations of `w` at
+/// `cycle_points(point)`.
+///
+/// `next*` only differs from `next_row` at row 0, which moves to row 1, and
+/// at `p = prev_row(1)`, which moves to row 0. So
+///     `w(next*(x)) = w(next(x)) + (eq(x, 0) - eq(x, p)) * (w(1) - w(0))`.
+pub(crate) fn eval_cycle_shift<F: PrimeField>(
+    point: &[F],
+    evals: &[F],
+) -> Result<F, PolyIOPErrors> {
+    if evals.len() != 4 {
+        return Err(PolyIOPErrors::InvalidParameters(format!(
+            "evals.len() = {}, expect 4",
+            evals.len()
+        )));
+    }
+    let num_vars = point.len();
+    let next_eval: F = next_row_points(point, 0)
+        .iter()
+        .zip(evals.iter())
+        .map(|((_point, coeff), eval)| *coeff * eval)
+        .sum();
+    let row_point = |row: usize| -> Vec<F> {
+        (0..num_vars)
+            .map(|i| F::from(((row >> i) & 1) as u64))
+            .collect()
+    };
+    let eq_0 = eq_eval(point, &row_point(0))?;
+    let eq_p = eq_eval(point, &row_point(prev_row(1, num_vars)))?;
+    Ok(next_eval + (eq_0 - eq_p) * (evals[3] - evals[2]))
+}
+
+/// Compute the columns of `s`, the lookups `fs` merged into the table `ts`
+/// in the order of the table cycle: walking the cycle of `ts` from row 0,
+/// each element of the table is followed by the lookups equal to it, if it
+/// is its first occurrence.
+///
+/// `s` has `2^{n+1}` elements, its k-th element along the walk sits at row
+/// `c_{k/2}` of the cycle of `sorted_first` if `k` is even, and of
+/// `sorted_second` otherwise. Returns `(sorted_first, sorted_second)`, or an
+/// error if a lookup is not in the table.
+#[allow(clippy::type_complexity)]
+pub(crate) fn compute_sorted<F: PrimeField>(
+    fxs: &[Arc<DenseMultilinearExtension<F>>],
+    txs: &[Arc<DenseMultilinearExtension<F>>],
+) -> Result<
+    (
+        Vec<Arc<DenseMultilinearExtension<F>>>,
+        Vec<Arc<DenseMultilinearExtension<F>>>,
+    ),
+    PolyIOPErrors,
+> {
+    let start = start_timer!(|| "compute sorted");
+
+    let num_vars = fxs[0].num_vars;
+    let element = |columns: &[Arc<DenseMultilinearExtension<F>>], x: usize| -> Vec<F> {
+        columns.iter().map(|column| column.evaluations[x]).collect()
+    };
+
+    // the number of lookups of each element
+    let mut counts: HashMap<Vec<F>, usize> = HashMap::new();
+    for x in 0..1 << num_vars {
+        *counts.entry(element(fxs, x)).or_insert(0) += 1;
+    }
+
+    let mut walk = Vec::with_capacity(2 << num_vars);
+    let mut row = 0;
+    for _ in 0..1 << num_vars {
+        let element = element(txs, row);
+        let count = counts.remove(&element).unwrap_or(0);
+        walk.extend(std::iter::repeat_n(element, count + 1));
+        row = next_in_cycle(row, num_vars);
+    }
+    if !counts.is_empty() {
+        end_timer!(start);
+        return Err(PolyIOPErrors::InvalidProver(
+            "fxs is not contained in txs".to_string(),
+        ));
+    }
+
+    let width = fxs.len();
+    let mut first = vec![vec![F::zero(); 1 << num_vars]; width];
+    let mut second = vec![vec![F::zero(); 1 << num_vars]; width];
+    for pair in walk.chunks(2) {
+        for j in 0..width {
+            first[j][row] = pair[0][j];
+            second[j][row] = pair[1][j];
+        }
+        row = next_in_cycle(row, num_vars);
+    }
+    let to_mles = |columns: Vec<Vec<F>>| -> Vec<Arc<DenseMultilinearExtension<F>>> {
+        columns
+            .into_iter()
+            .map(|evals| {
+                Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                    num_vars, evals,
+                ))
+            })
+            .collect()
+    };
+
+    end_timer!(start);
+    Ok((to_mles(first), to_mles(second)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{cycle_points, eval_cycle_shift, next_in_cycle, shift_along_cycle};
+    use arithmetic::evaluate_opt;
+    use ark_bls12_381::Fr;
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn test_cycle_shift() {
+        let mut rng = test_rng();
+        for num_vars in 1..8 {
+            // the cycle goes through all the rows
+            let mut row = 0;
+            for i in 0..1 << num_vars {
+                assert!(i == 0 || row != 0);
+                row = next_in_cycle(row, num_vars);
+            }
+            assert_eq!(row, 0);
+
+            let w = DenseMultilinearExtension::<Fr>::rand(num_vars, &mut rng);
+            let w_next = shift_along_cycle(&w);
+            let point: Vec<Fr> = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+            let evals: Vec<Fr> = cycle_points(&point)
+                .iter()
+                .map(|p| evaluate_opt(&w, p))
+                .collect();
+            assert_eq!(
+                eval_cycle_shift(&point, &evals).unwrap(),
+                evaluate_opt(&w_next, &point)
+            );
+        }
+    }
+}
@@ -0,0 +1,336 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! A sum check over a batch of polynomials with different numbers of
+//! variables.
+//!
+//! Let `n` be the largest number of variables of the batch. A polynomial
+//! `p_i` with `n_i` variables is seen as an `n` variables polynomial that
+//! does not depend on its first `n - n_i` variables, whose sum is
+//! `2^{n - n_i}` times the sum of `p_i`. During the first `n - n_i` rounds,
+//! its round polynomials are the constants `s_i * 2^{n - n_i - j - 1}`; it
+//! joins the protocol at round `n - n_i`, where its leading variables have
+//! been fixed to the last `n_i` challenges.
+//!
+//! The claims are combined with powers of a random `lambda`, so the verifier
+//! runs a single sum check on `n` variables, and the subclaim asks for the
+//! evaluation of each `p_i` at the last `n_i` coordinates of the point.
+
+use super::{SumCheck, SumCheckProver, SumCheckVerifier};
+use crate::poly_iop::{
+    errors::PolyIOPErrors,
+    structs::{IOPProof, IOPProverMessage, IOPProverState, IOPVerifierState},
+    sum_check::prover::{barycentric_weights, extrapolate},
+    PolyIOP,
+};
+use arithmetic::VPAuxInfo;
+use ark_ff::PrimeField;
+use ark_std::{end_timer, start_timer};
+use std::marker::PhantomData;
+use transcript::Transcript;
+
+/// A batched sum check proves the sums of several polynomials with different
+/// numbers of variables with a single sum check, sharing one transcript.
+pub trait BatchedSumCheck<F: PrimeField>: SumCheck<F> {
+    type BatchedSumCheckSubClaim;
+
+    /// Generate proof of the sums of the polynomials over {0,1}^`num_vars`,
+    /// where `num_vars` is the number of variables of each polynomial.
+    ///
+    /// Outputs the proof and the sums of the polynomials.
+    fn prove(
+        polys: &[Self::VirtualPolynomial],
+        transcript: &mut Self::Transcript,
+    ) -> Result<(Self::SumCheckProof, Vec<F>), PolyIOPErrors>;
+
+    /// Verify the claimed sums using the proof
+    fn verify(
+        sums: &[F],
+        proof: &Self::SumCheckProof,
+        aux_infos: &[Self::VPAuxInfo],
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::BatchedSumCheckSubClaim, PolyIOPErrors>;
+}
+
+/// A BatchedSumCheckSubClaim is a claim generated by the verifier at the end
+/// of verification when it is convinced.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchedSumCheckSubClaim<F: PrimeField> {
+    /// the point of the largest polynomials
+    pub point: Vec<F>,
+    /// the coefficients of the polynomials in the random combination
+    pub coefficients: Vec<F>,
+    /// the expected evaluation of the random combination of the polynomials,
+    /// each at its point
+    pub expected_evaluation: F,
+}
+
+impl<F: PrimeField> BatchedSumCheckSubClaim<F> {
+    /// The point at which a polynomial with `num_vars` variables is
+    /// evaluated: the last `num_vars` coordinates of `point`.
+    pub fn point_for(&self, num_vars: usize) -> &[F] {
+        &self.point[self.point.len() - num_vars..]
+    }
+}
+
+impl<F: PrimeField, T: Transcript<F>> BatchedSumCheck<F> for PolyIOP<F, T> {
+    type BatchedSumCheckSubClaim = BatchedSumCheckSubClaim<F>;
+
+    fn prove(
+        polys: &[Self::VirtualPolynomial],
+        transcript: &mut Self::Transcript,
+    ) -> Result<(Self::SumCheckProof, Vec<F>), PolyIOPErrors> {
+        let aux_infos: Vec<_> = polys.iter().map(|poly| poly.aux_info.clone()).collect();
+        let aux_info = combined_aux_info(&aux_infos)?;
+        if aux_infos.iter().any(|info| info.num_variables == 0) {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "Attempt to prove a constant.".to_string(),
+            ));
+        }
+        let start = start_timer!(|| "batched sum check prove");
+
+        // the first messages do not depend on any challenge, and give the sums
+        let mut prover_states = polys
+            .iter()
+            .map(IOPProverState::prover_init)
+            .collect::<Result<Vec<_>, _>>()?;
+        let first_msgs = prover_states
+            .iter_mut()
+            .map(|state| state.prove_round_and_update_state(&None))
+            .collect::<Result<Vec<_>, _>>()?;
+        let sums: Vec<F> = first_msgs
+            .iter()
+            .map(|msg| msg.evaluations[0] + msg.evaluations[1])
+            .collect();
+
+        let coefficients = init_batch(&aux_infos, &sums, transcript)?;
+        let extrapolation_aux: Vec<_> = aux_infos
+            .iter()
+            .map(|info| {
+                let points = (0..1 + info.max_degree as u64)
+                    .map(F::from)
+                    .collect::<Vec<_>>();
+                let weights = barycentric_weights(&points);
+                (points, weights)
+            })
+            .collect();
+
+        let mut challenges = Vec::with_capacity(aux_info.num_variables);
+        let mut prover_msgs = Vec::with_capacity(aux_info.num_variables);
+        let mut first_msgs: Vec<_> = first_msgs.into_iter().map(Some).collect();
+        for round in 0..aux_info.num_variables {
+            let mut evaluations = vec![F::zero(); aux_info.max_degree + 1];
+            for (i, state) in prover_states.iter_mut().enumerate() {
+                let start_round = aux_info.num_variables - aux_infos[i].num_variables;
+                if round < start_round {
+                    // the round polynomial is a constant
+                    let eval = sums[i]
+                        * F::from(2u64).pow([(start_round - round - 1) as u64])
+                        * coefficients[i];
+                    evaluations.iter_mut().for_each(|sum| *sum += eval);
+                    continue;
+                }
+                let msg = if round == start_round {
+                    first_msgs[i].take().ok_or(PolyIOPErrors::ShouldNotArrive)?
+                } else {
+                    state.prove_round_and_update_state(&challenges.last().copied())?
+                };
+                let (points, weights) = &extrapolation_aux[i];
+                for (j, sum) in evaluations.iter_mut().enumerate() {
+                    let eval = match msg.evaluations.get(j) {
+                        Some(eval) => *eval,
+                        None => extrapolate(points, weights, &msg.evaluations, &F::from(j as u64)),
+                    };
+                    *sum += eval * coefficients[i];
+                }
+            }
+            let prover_msg = IOPProverMessage { evaluations };
+            transcript.append_serializable_element(b"prover msg", &prover_msg)?;
+            prover_msgs.push(prover_msg);
+            challenges.push(transcript.get_and_append_challenge(b"Internal round")?);
+        }
+
+        end_timer!(start);
+        Ok((
+            IOPProof {
+                point: challenges,
+                proofs: prover_msgs,
+            },
+            sums,
+        ))
+    }
+
+    fn verify(
+        sums: &[F],
+        proof: &Self::SumCheckProof,
+        aux_infos: &[Self::VPAuxInfo],
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::BatchedSumCheckSubClaim, PolyIOPErrors> {
+        if sums.len() != aux_infos.len() {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "sums.len() = {}, aux_infos.len() = {}",
+                sums.len(),
+                aux_infos.len()
+            )));
+        }
+        let aux_info = combined_aux_info(aux_infos)?;
+        let start = start_timer!(|| "batched sum check verify");
+
+        let coefficients = init_batch(aux_infos, sums, transcript)?;
+        let claimed_sum = sums
+            .iter()
+            .zip(aux_infos.iter())
+            .zip(coefficients.iter())
+            .map(|((sum, info), coeff)| {
+                *sum * coeff
+                    * F::from(2u64).pow([(aux_info.num_variables - info.num_variables) as u64])
+            })
+            .sum::<F>();
+
+        let mut verifier_state = IOPVerifierState::verifier_init(&aux_info);
+        for i in 0..aux_info.num_variables {
+            let prover_msg = proof
+                .proofs
+                .get(i)
+                .ok_or_else(|| PolyIOPErrors::InvalidProof("proof is incomplete".to_string()))?;
+            transcript.append_serializable_element(b"prover msg", prover_msg)?;
+            IOPVerifierState::verify_round_and_update_state(
+                &mut verifier_state,
+                prover_msg,
+                transcript,
+            )?;
+        }
+        let sub_claim =
+            IOPVerifierState::check_and_generate_subclaim(&verifier_state, &claimed_sum);
+
+        end_timer!(start);
+        let sub_claim = sub_claim?;
+        Ok(BatchedSumCheckSubClaim {
+            point: sub_claim.point,
+            coefficients,
+            expected_evaluation: sub_claim.expected_evaluation,
+        })
+    }
+}
+
+/// The auxiliary information of the sum check on the combination of the
+/// polynomials.
+fn combined_aux_info<F: PrimeField>(
+    aux_infos: &[VPAuxInfo<F>],
+) -> Result<VPAuxInfo<F>, PolyIOPErrors> {
+    if aux_infos.is_empty() {
+        return Err(PolyIOPErrors::InvalidParameters(
+            "no polynomial to prove".to_string(),
+        ));
+    }
+    Ok(VPAuxInfo {
+        max_degree: aux_infos.iter().map(|info| info.max_degree).max().unwrap(),
+        num_variables: aux_infos
+            .iter()
+            .map(|info| info.num_variables)
+            .max()
+            .unwrap(),
+        phantom: PhantomData,
+    })
+}
+
+/// Bind the batch to the transcript and sample the coefficients of the
+/// combination.
+fn init_batch<F: PrimeField, T: Transcript<F>>(
+    aux_infos: &[VPAuxInfo<F>],
+    sums: &[F],
+    transcript: &mut T,
+) -> Result<Vec<F>, PolyIOPErrors> {
+    for (aux_info, sum) in aux_infos.iter().zip(sums.iter()) {
+        transcript.append_serializable_element(b"aux info", aux_info)?;
+        transcript.append_field_element(b"sum", sum)?;
+    }
+    let lambda = transcript.get_and_append_challenge(b"lambda")?;
+    let mut coefficients = Vec::with_capacity(sums.len());
+    let mut coeff = F::one();
+    for _ in 0..sums.len() {
+        coefficients.push(coeff);
+        coeff *= lambda;
+    }
+    Ok(coefficients)
+}
+
+#[cfg(test)]
+mod test {
+    use super::BatchedSumCheck;
+    use crate::poly_iop::{errors::PolyIOPErrors, sum_check::SumCheck, PolyIOP};
+    use arithmetic::VirtualPolynomial;
+    use ark_bls12_381::Fr;
+    use ark_ff::One;
+    use ark_std::test_rng;
+
+    fn test_batched_sumcheck(
+        num_vars: &[usize],
+        num_multiplicands_range: (usize, usize),
+        num_products: usize,
+    ) -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+
+        let (polys, asserted_sums): (Vec<_>, Vec<_>) = num_vars
+            .iter()
+            .map(|&nv| {
+                VirtualPolynomial::<Fr>::rand(nv, num_multiplicands_range, num_products, &mut rng)
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
+        let aux_infos: Vec<_> = polys.iter().map(|poly| poly.aux_info.clone()).collect();
+
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let (proof, sums) = <PolyIOP<Fr> as BatchedSumCheck<Fr>>::prove(&polys, &mut transcript)?;
+        assert_eq!(sums, asserted_sums);
+
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let sub_claim = <PolyIOP<Fr> as BatchedSumCheck<Fr>>::verify(
+            &asserted_sums,
+            &proof,
+            &aux_infos,
+            &mut transcript,
+        )?;
+        let mut evaluation = Fr::from(0u64);
+        for (poly, coeff) in polys.iter().zip(sub_claim.coefficients.iter()) {
+            evaluation += poly.evaluate(sub_claim.point_for(poly.aux_info.num_variables))? * coeff;
+        }
+        assert_eq!(evaluation, sub_claim.expected_evaluation, "wrong subclaim");
+
+        // bad path: wrong sum
+        let mut bad_sums = asserted_sums;
+        bad_sums[num_vars.len() - 1] += Fr::one();
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        assert!(<PolyIOP<Fr> as BatchedSumCheck<Fr>>::verify(
+            &bad_sums,
+            &proof,
+            &aux_infos,
+            &mut transcript,
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        test_batched_sumcheck(&[1], (4, 13), 5)
+    }
+    #[test]
+    fn test_same_num_vars() -> Result<(), PolyIOPErrors> {
+        test_batched_sumcheck(&[6, 6, 6], (2, 5), 3)
+    }
+    #[test]
+    fn test_different_num_vars() -> Result<(), PolyIOPErrors> {
+        test_batched_sumcheck(&[3, 8, 1, 5], (2, 5), 3)?;
+        test_batched_sumcheck(&[10, 2], (4, 9), 5)
+    }
+    #[test]
+    fn zero_polynomial_should_error() {
+        assert!(test_batched_sumcheck(&[3, 0], (4, 13), 5).is_err());
+    }
+}
@@ -16,9 +16,11 @@ use ark_ff::PrimeField;
 use ark_poly::DenseMultilinearExtension;
 use ark_std::{end_timer, start_timer};
 use std::{fmt::Debug, sync::Arc};
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
+pub mod batched;
 pub mod prover;
+pub mod streaming;
 mod verifier;
 
 /// Trait for doing sum check protocols.
@@ -86,7 +88,6 @@ pub trait SumCheckVerifier<F: PrimeField> {
     type VPAuxInfo;
     type ProverMessage;
     type Challenge;
-    type Transcript;
     type SumCheckSubClaim;
 
     /// Initialize the verifier's state.
@@ -98,10 +99,10 @@ pub trait SumCheckVerifier<F: PrimeField> {
     /// challenges; and update the verifier's state accordingly. The actual
     /// verifications are deferred (in batch) to `check_and_generate_subclaim`
     /// at the last step.
-    fn verify_round_and_update_state(
+    fn verify_round_and_update_state<T: Transcript<F>>(
         &mut self,
         prover_msg: &Self::ProverMessage,
-        transcript: &mut Self::Transcript,
+        transcript: &mut T,
     ) -> Result<Self::Challenge, PolyIOPErrors>;
 
     /// This function verifies the deferred checks in the interactive version of
@@ -129,13 +130,13 @@ pub struct SumCheckSubClaim<F: PrimeField> {
     pub expected_evaluation: F,
 }
 
-impl<F: PrimeField> SumCheck<F> for PolyIOP<F> {
+impl<F: PrimeField, T: Transcript<F>> SumCheck<F> for PolyIOP<F, T> {
     type SumCheckProof = IOPProof<F>;
     type VirtualPolynomial = VirtualPolynomial<F>;
     type VPAuxInfo = VPAuxInfo<F>;
     type MultilinearExtension = Arc<DenseMultilinearExtension<F>>;
     type SumCheckSubClaim = SumCheckSubClaim<F>;
-    type Transcript = IOPTranscript<F>;
+    type Transcript = T;
 
     fn extract_sum(proof: &Self::SumCheckProof) -> F {
         let start = start_timer!(|| "extract sum");
@@ -146,7 +147,7 @@ impl<F: PrimeField> SumCheck<F> for PolyIOP<F> {
 
     fn init_transcript() -> Self::Transcript {
         let start = start_timer!(|| "init transcript");
-        let res = IOPTranscript::<F>::new(b"Initializing SumCheck transcript");
+        let res = T::new(b"Initializing SumCheck transcript");
         end_timer!(start);
         res
     }
@@ -193,7 +194,10 @@ impl<F: PrimeField> SumCheck<F> for PolyIOP<F> {
         transcript.append_serializable_element(b"aux info", aux_info)?;
         let mut verifier_state = IOPVerifierState::verifier_init(aux_info);
         for i in 0..aux_info.num_variables {
-            let prover_msg = proof.proofs.get(i).expect("proof is incomplete");
+            let prover_msg = proof
+                .proofs
+                .get(i)
+                .ok_or_else(|| PolyIOPErrors::InvalidProof("proof is incomplete".to_string()))?;
             transcript.append_serializable_element(b"prover msg", prover_msg)?;
             IOPVerifierState::verify_round_and_update_state(
                 &mut verifier_state,
@@ -213,26 +217,31 @@ impl<F: PrimeField> SumCheck<F> for PolyIOP<F> {
 mod test {
 
     use super::*;
+    use crate::poly_iop::structs::FoldedTable;
+    use arithmetic::{
+        MultilinearPolynomial, SmallMultilinearPolynomial, SparseMultilinearPolynomial,
+    };
     use ark_bls12_381::Fr;
     use ark_ff::UniformRand;
     use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
     use ark_std::test_rng;
     use std::sync::Arc;
+    use transcript::{IOPTranscript, KeccakTranscript, PoseidonTranscript};
 
-    fn test_sumcheck(
+    fn test_sumcheck<T: Transcript<Fr>>(
         nv: usize,
         num_multiplicands_range: (usize, usize),
         num_products: usize,
     ) -> Result<(), PolyIOPErrors> {
         let mut rng = test_rng();
-        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let mut transcript = <PolyIOP<Fr, T> as SumCheck<Fr>>::init_transcript();
 
         let (poly, asserted_sum) =
             VirtualPolynomial::rand(nv, num_multiplicands_range, num_products, &mut rng)?;
-        let proof = <PolyIOP<Fr> as SumCheck<Fr>>::prove(&poly, &mut transcript)?;
+        let proof = <PolyIOP<Fr, T> as SumCheck<Fr>>::prove(&poly, &mut transcript)?;
         let poly_info = poly.aux_info.clone();
-        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
-        let subclaim = <PolyIOP<Fr> as SumCheck<Fr>>::verify(
+        let mut transcript = <PolyIOP<Fr, T> as SumCheck<Fr>>::init_transcript();
+        let subclaim = <PolyIOP<Fr, T> as SumCheck<Fr>>::verify(
             asserted_sum,
             &proof,
             &poly_info,
@@ -275,6 +284,19 @@ mod test {
                 .unwrap(),
             );
         }
+        // the input mles are shared, not copied, and the prover folds its own
+        // tables down to the last variable
+        for (mle, input) in prover_state
+            .poly
+            .flattened_ml_extensions
+            .iter()
+            .zip(poly.flattened_ml_extensions.iter())
+        {
+            assert!(Arc::ptr_eq(mle, input));
+        }
+        for table in prover_state.folded_ml_extensions.iter() {
+            assert_eq!(table.as_multilinear().num_variables(), 1);
+        }
         let subclaim =
             IOPVerifierState::check_and_generate_subclaim(&verifier_state, &asserted_sum)
                 .expect("fail to generate subclaim");
@@ -291,7 +313,7 @@ mod test {
         let num_multiplicands_range = (4, 13);
         let num_products = 5;
 
-        test_sumcheck(nv, num_multiplicands_range, num_products)?;
+        test_sumcheck::<IOPTranscript<Fr>>(nv, num_multiplicands_range, num_products)?;
         test_sumcheck_internal(nv, num_multiplicands_range, num_products)
     }
     #[test]
@@ -300,16 +322,27 @@ mod test {
         let num_multiplicands_range = (4, 9);
         let num_products = 5;
 
-        test_sumcheck(nv, num_multiplicands_range, num_products)?;
+        test_sumcheck::<IOPTranscript<Fr>>(nv, num_multiplicands_range, num_products)?;
         test_sumcheck_internal(nv, num_multiplicands_range, num_products)
     }
     #[test]
+    fn test_transcripts() -> Result<(), PolyIOPErrors> {
+        let nv = 8;
+        let num_multiplicands_range = (4, 9);
+        let num_products = 5;
+
+        test_sumcheck::<KeccakTranscript<Fr>>(nv, num_multiplicands_range, num_products)?;
+        test_sumcheck::<PoseidonTranscript<Fr>>(nv, num_multiplicands_range, num_products)
+    }
+    #[test]
     fn zero_polynomial_should_error() {
         let nv = 0;
         let num_multiplicands_range = (4, 13);
         let num_products = 5;
 
-        assert!(test_sumcheck(nv, num_multiplicands_range, num_products).is_err());
+        assert!(
+            test_sumcheck::<IOPTranscript<Fr>>(nv, num_multiplicands_range, num_products).is_err()
+        );
         assert!(test_sumcheck_internal(nv, num_multiplicands_range, num_products).is_err());
     }
 
@@ -327,6 +360,150 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_sparse_polynomial() -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+        let nv = 10;
+
+        // sparse selectors times dense mles, and a dense product
+        let selectors: Vec<_> = [1, 20, 1 << (nv - 1)]
+            .into_iter()
+            .map(|num_nonzero| {
+                Arc::new(SparseMultilinearPolynomial::rand(nv, num_nonzero, &mut rng))
+            })
+            .collect();
+        let mles: Vec<_> = (0..3)
+            .map(|_| Arc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng)))
+            .collect();
+        let mut poly = VirtualPolynomial::new(nv);
+        poly.add_mle_list(vec![selectors[0].clone()], Fr::rand(&mut rng))?;
+        poly.add_mle_list(
+            vec![
+                selectors[1].clone() as Arc<dyn MultilinearPolynomial<Fr>>,
+                mles[0].clone(),
+                mles[1].clone(),
+            ],
+            Fr::rand(&mut rng),
+        )?;
+        poly.add_mle_list(
+            vec![
+                mles[2].clone() as Arc<dyn MultilinearPolynomial<Fr>>,
+                selectors[2].clone(),
+                selectors[1].clone(),
+            ],
+            Fr::rand(&mut rng),
+        )?;
+        poly.add_mle_list(vec![mles[1].clone(), mles[2].clone()], Fr::rand(&mut rng))?;
+
+        // the same polynomial with dense selectors
+        let mut dense_poly = VirtualPolynomial::new(nv);
+        for (coefficient, products) in poly.products.iter() {
+            dense_poly.add_mle_list(
+                products
+                    .iter()
+                    .map(|&i| Arc::new(poly.flattened_ml_extensions[i].to_dense())),
+                *coefficient,
+            )?;
+        }
+
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let proof = <PolyIOP<Fr> as SumCheck<Fr>>::prove(&poly, &mut transcript)?;
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        assert_eq!(
+            proof,
+            <PolyIOP<Fr> as SumCheck<Fr>>::prove(&dense_poly, &mut transcript)?
+        );
+
+        let asserted_sum = <PolyIOP<Fr> as SumCheck<Fr>>::extract_sum(&proof);
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let subclaim = <PolyIOP<Fr> as SumCheck<Fr>>::verify(
+            asserted_sum,
+            &proof,
+            &poly.aux_info,
+            &mut transcript,
+        )?;
+        assert!(
+            poly.evaluate(&subclaim.point)? == subclaim.expected_evaluation,
+            "wrong subclaim"
+        );
+
+        // the selectors stay sparse while the prover folds them
+        let mut prover_state = IOPProverState::prover_init(&poly)?;
+        let mut challenge = None;
+        for r in subclaim.point[..nv - 1].iter() {
+            prover_state.prove_round_and_update_state(&challenge)?;
+            challenge = Some(*r);
+        }
+        prover_state.prove_round_and_update_state(&challenge)?;
+        let num_sparse = prover_state
+            .folded_ml_extensions
+            .iter()
+            .filter(|table| matches!(table, FoldedTable::Sparse(_)))
+            .count();
+        assert_eq!(num_sparse, selectors.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_small_polynomial() -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+        let nv = 10;
+
+        // booleans, u32s, and tables whose products overflow an i128
+        let small: Vec<_> = [1, 1, 32, 58, 64, 64]
+            .into_iter()
+            .map(|num_bits| {
+                Arc::new(SmallMultilinearPolynomial::rand(nv, num_bits, &mut rng))
+                    as Arc<dyn MultilinearPolynomial<Fr>>
+            })
+            .collect();
+        let dense = Arc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng))
+            as Arc<dyn MultilinearPolynomial<Fr>>;
+        let mut poly = VirtualPolynomial::new(nv);
+        for product in [
+            vec![small[0].clone(), small[1].clone(), small[0].clone()],
+            vec![small[2].clone(), small[2].clone(), small[5].clone()],
+            vec![small[3].clone(), small[4].clone()],
+            vec![small[4].clone(), small[5].clone()],
+            vec![small[1].clone(), dense.clone()],
+        ] {
+            poly.add_mle_list(product, Fr::rand(&mut rng))?;
+        }
+
+        // the same polynomial with dense tables
+        let mut dense_poly = VirtualPolynomial::new(nv);
+        for (coefficient, products) in poly.products.iter() {
+            dense_poly.add_mle_list(
+                products
+                    .iter()
+                    .map(|&i| Arc::new(poly.flattened_ml_extensions[i].to_dense())),
+                *coefficient,
+            )?;
+        }
+
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let proof = <PolyIOP<Fr> as SumCheck<Fr>>::prove(&poly, &mut transcript)?;
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        assert_eq!(
+            proof,
+            <PolyIOP<Fr> as SumCheck<Fr>>::prove(&dense_poly, &mut transcript)?
+        );
+
+        let asserted_sum = <PolyIOP<Fr> as SumCheck<Fr>>::extract_sum(&proof);
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let subclaim = <PolyIOP<Fr> as SumCheck<Fr>>::verify(
+            asserted_sum,
+            &proof,
+            &poly.aux_info,
+            &mut transcript,
+        )?;
+        assert!(
+            poly.evaluate(&subclaim.point)? == subclaim.expected_evaluation,
+            "wrong subclaim"
+        );
+        Ok(())
+    }
+
     #[test]
     /// Test that the memory usage of shared-reference is linear to number of
     /// unique MLExtensions instead of total number of multiplicands.
@@ -9,14 +9,16 @@
 use super::SumCheckProver;
 use crate::poly_iop::{
     errors::PolyIOPErrors,
-    structs::{IOPProverMessage, IOPProverState},
+    structs::{FoldedTable, IOPProverMessage, IOPProverState},
+};
+use arithmetic::{
+    fix_first_variable, fix_first_variable_in_place, fix_first_variable_small,
+    DenseMultilinearExtension, MultilinearPolynomial, SparseMultilinearPolynomial,
+    VirtualPolynomial,
 };
-use arithmetic::{fix_variables, VirtualPolynomial};
 use ark_ff::{batch_inversion, PrimeField};
-use ark_poly::DenseMultilinearExtension;
-use ark_std::{cfg_into_iter, end_timer, start_timer, vec::Vec};
-use rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator};
-use std::sync::Arc;
+use ark_std::{cfg_into_iter, cfg_iter_mut, end_timer, start_timer, vec::Vec};
+use rayon::prelude::IntoParallelIterator;
 
 #[cfg(feature = "parallel")]
 use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
@@ -40,6 +42,7 @@ impl<F: PrimeField> SumCheckProver<F> for IOPProverState<F> {
             challenges: Vec::with_capacity(polynomial.aux_info.num_variables),
             round: 0,
             poly: polynomial.clone(),
+            folded_ml_extensions: Vec::new(),
             extrapolation_aux: (1..polynomial.aux_info.max_degree)
                 .map(|degree| {
                     let points = (0..1 + degree as u64).map(F::from).collect::<Vec<_>>();
@@ -81,13 +84,9 @@ impl<F: PrimeField> SumCheckProver<F> for IOPProverState<F> {
         //    g(r_1, ..., r_{m-1}, x_m ... x_n)
         //
         // eval g over r_m, and mutate g to g(r_1, ... r_m,, x_{m+1}... x_n)
-        let mut flattened_ml_extensions: Vec<DenseMultilinearExtension<F>> = self
-            .poly
-            .flattened_ml_extensions
-            .par_iter()
-            .map(|x| x.as_ref().clone())
-            .collect(); // worker
-
+        //
+        // the first challenge folds the input mles into half-size tables owned
+        // by the prover, which later challenges fold in place
         if let Some(chal) = challenge {
             if self.round == 0 {
                 return Err(PolyIOPErrors::InvalidProver(
@@ -97,14 +96,17 @@ impl<F: PrimeField> SumCheckProver<F> for IOPProverState<F> {
             self.challenges.push(*chal);
 
             let r = self.challenges[self.round - 1];
-            #[cfg(feature = "parallel")]
-            flattened_ml_extensions
-                .par_iter_mut()
-                .for_each(|mle| *mle = fix_variables(mle, &[r]));
-            #[cfg(not(feature = "parallel"))]
-            flattened_ml_extensions
-                .iter_mut()
-                .for_each(|mle| *mle = fix_variables(mle, &[r]));
+            if self.folded_ml_extensions.is_empty() {
+                self.folded_ml_extensions = self
+                    .poly
+                    .flattened_ml_extensions
+                    .iter()
+                    .map(|mle| FoldedTable::fix_first_variable(mle.as_ref(), &r))
+                    .collect();
+            } else {
+                cfg_iter_mut!(self.folded_ml_extensions)
+                    .for_each(|table| table.fix_first_variable_in_place(&r));
+            }
         } else if self.round > 0 {
             return Err(PolyIOPErrors::InvalidProver(
                 "verifier message is empty".to_string(),
@@ -114,6 +116,20 @@ impl<F: PrimeField> SumCheckProver<F> for IOPProverState<F> {
 
         self.round += 1;
 
+        let flattened_ml_extensions: Vec<&dyn MultilinearPolynomial<F>> =
+            if self.folded_ml_extensions.is_empty() {
+                self.poly
+                    .flattened_ml_extensions
+                    .iter()
+                    .map(|mle| mle.as_ref())
+                    .collect()
+            } else {
+                self.folded_ml_extensions
+                    .iter()
+                    .map(FoldedTable::as_multilinear)
+                    .collect()
+            };
+
         let products_list = self.poly.products.clone();
         let mut products_sum = vec![F::zero(); self.poly.aux_info.max_degree + 1];
 
@@ -121,94 +137,218 @@ impl<F: PrimeField> SumCheckProver<F> for IOPProverState<F> {
         // f(r_1, ... r_m,, x_{m+1}... x_n)
 
         products_list.iter().for_each(|(coefficient, products)| {
-            let mut sum = cfg_into_iter!(0..1 << (self.poly.aux_info.num_variables - self.round))
-                .fold(
-                    || {
-                        (
-                            vec![(F::zero(), F::zero()); products.len()],
-                            vec![F::zero(); products.len() + 1],
-                        )
-                    },
-                    |(mut buf, mut acc), b| {
-                        buf.iter_mut()
-                            .zip(products.iter())
-                            .for_each(|((eval, step), f)| {
-                                let table = &flattened_ml_extensions[*f];
-                                *eval = table[b << 1];
-                                *step = table[(b << 1) + 1] - table[b << 1];
-                            });
-                        // evaluate ...0
-                        acc[0] += buf.iter().map(|(eval, _)| eval).product::<F>();
-                        // evaluate ...1, ...11, ...111
-                        acc[1..].iter_mut().for_each(|acc| {
-                            buf.iter_mut().for_each(|(eval, step)| *eval += step as &_);
-                            *acc += buf.iter().map(|(eval, _)| eval).product::<F>();
-                        });
-                        (buf, acc)
-                    },
-                )
-                .map(|(_, partial)| partial)
-                .collect::<Vec<Vec<F>>>();
-            let mut temp = sum
-                .into_iter()
-                .reduce(|mut sum, partial| {
-                    sum.iter_mut()
-                        .zip(partial.iter())
-                        .for_each(|(sum, partial)| *sum += partial);
-                    sum
-                })
-                .unwrap();
-            temp.iter_mut().for_each(|sum| *sum *= coefficient);
+            let mut sum = product_round_evaluations(&flattened_ml_extensions, products, None);
+            sum.iter_mut().for_each(|sum| *sum *= coefficient);
             let extraploation = cfg_into_iter!(0..self.poly.aux_info.max_degree - products.len())
                 .map(|i| {
                     let (points, weights) = &self.extrapolation_aux[products.len() - 1];
                     let at = F::from((products.len() + 1 + i) as u64);
-                    extrapolate(points, weights, &temp, &at)
+                    extrapolate(points, weights, &sum, &at)
                 })
                 .collect::<Vec<_>>();
             products_sum
                 .iter_mut()
-                .zip(temp.iter().chain(extraploation.iter()))
+                .zip(sum.iter().chain(extraploation.iter()))
                 .for_each(|(products_sum, sum)| *products_sum += sum);
-            //     .map(|(_, partial)| partial)
-            //     .reduce(
-            //         || vec![F::zero(); products.len() + 1],
-            //         |mut sum, partial| {
-            //             sum.iter_mut()
-            //                 .zip(partial.iter())
-            //                 .for_each(|(sum, partial)| *sum += partial);
-            //             sum
-            //         },
-            //     );
-            // sum.iter_mut().for_each(|sum| *sum *= coefficient);
-            // let extraploation =
-            // cfg_into_iter!(0..self.poly.aux_info.max_degree - products.len())
-            //     .map(|i| {
-            //         let (points, weights) =
-            // &self.extrapolation_aux[products.len() - 1];
-            //         let at = F::from((products.len() + 1 + i) as u64);
-            //         extrapolate(points, weights, &sum, &at)
-            //     })
-            //     .collect::<Vec<_>>();
-            // products_sum
-            //     .iter_mut()
-            //     .zip(sum.iter().chain(extraploation.iter()))
-            //     .for_each(|(products_sum, sum)| *products_sum += sum);
         });
 
-        // update prover's state to the partial evaluated polynomial
-        self.poly.flattened_ml_extensions = flattened_ml_extensions
-            .par_iter()
-            .map(|x| Arc::new(x.clone()))
-            .collect();
-
         Ok(IOPProverMessage {
             evaluations: products_sum,
         })
     }
 }
 
-fn barycentric_weights<F: PrimeField>(points: &[F]) -> Vec<F> {
+impl<F: PrimeField> FoldedTable<F> {
+    /// Fix the first variable of `mle` to `point`, into a new table of half
+    /// the size in the same representation. Tables of small integers are
+    /// folded into dense ones.
+    pub(crate) fn fix_first_variable(mle: &dyn MultilinearPolynomial<F>, point: &F) -> Self {
+        let num_vars = mle.num_variables() - 1;
+        if let Some(evals) = mle.dense_evaluations() {
+            return Self::Dense(DenseMultilinearExtension::from_evaluations_vec(
+                num_vars,
+                fix_first_variable(evals, point),
+            ));
+        }
+        if let Some(evals) = mle.sparse_evaluations() {
+            let mut table =
+                SparseMultilinearPolynomial::from_evaluations(num_vars + 1, evals.iter().copied());
+            table.fix_first_variable_in_place(point);
+            return Self::Sparse(table);
+        }
+        let evals = match mle.small_evaluations() {
+            Some((evals, _)) => fix_first_variable_small(evals, point),
+            None => fix_first_variable(&mle.to_dense().evaluations, point),
+        };
+        Self::Dense(DenseMultilinearExtension::from_evaluations_vec(
+            num_vars, evals,
+        ))
+    }
+
+    /// Fix the first variable of the table to `point` in place.
+    pub(crate) fn fix_first_variable_in_place(&mut self, point: &F) {
+        match self {
+            Self::Dense(table) => {
+                fix_first_variable_in_place(&mut table.evaluations, point);
+                table.num_vars -= 1;
+            },
+            Self::Sparse(table) => table.fix_first_variable_in_place(point),
+        }
+    }
+}
+
+/// Evaluations at `0, ..., products.len()` of
+///
+///   \sum_b w(b) \prod_{f \in products} f(X, b)
+///
+/// where `w(b) = weights[b]`, or one if there are no weights.
+///
+/// If a multiplicand is sparse, only the `b` where it is non-zero are
+/// visited, so the cost is proportional to its number of non-zero
+/// evaluations.
+///
+/// Multiplicands with small integer evaluations are multiplied as `i128`s,
+/// as many as their product provably fits. The sums themselves are
+/// accumulated as integers if all the multiplicands are, and there are no
+/// weights.
+pub(crate) fn product_round_evaluations<F: PrimeField>(
+    tables: &[&dyn MultilinearPolynomial<F>],
+    products: &[usize],
+    weights: Option<&[F]>,
+) -> Vec<F> {
+    let num_vars = tables[products[0]].num_variables();
+    let degree = products.len();
+
+    // the pairs `b` to visit
+    let sparsest = products
+        .iter()
+        .filter_map(|&f| tables[f].sparse_evaluations())
+        .min_by_key(|evals| evals.len());
+    let support: Vec<usize> = match sparsest {
+        Some(evals) => {
+            let mut support: Vec<usize> = evals.iter().map(|(index, _)| index >> 1).collect();
+            support.dedup();
+            support
+        },
+        None => vec![],
+    };
+    let num_pairs = match sparsest {
+        Some(_) => support.len(),
+        None => 1 << (num_vars - 1),
+    };
+
+    // at X = 0, ..., degree, a multiplicand of integers less than
+    // `2^num_bits` is less than `(degree + 1) 2^num_bits` in absolute value
+    let point_bits = usize::BITS - (degree + 1).leading_zeros();
+    let mut budget = i128::BITS - 1;
+    let mut small = vec![];
+    let mut large = vec![];
+    for &f in products.iter() {
+        match tables[f].small_evaluations() {
+            Some((evals, num_bits)) if num_bits + point_bits <= budget => {
+                budget -= num_bits + point_bits;
+                small.push(evals);
+            },
+            _ => large.push(tables[f]),
+        }
+    }
+    let integer_sums = large.is_empty() && weights.is_none();
+
+    // read dense tables directly, and the others by index
+    let dense: Vec<Option<&[F]>> = large.iter().map(|f| f.dense_evaluations()).collect();
+    let eval = |i: usize, index: usize| match dense[i] {
+        Some(table) => table[index],
+        None => large[i].evaluation(index),
+    };
+
+    cfg_into_iter!(0..num_pairs)
+        .fold(
+            || {
+                (
+                    vec![(F::zero(), F::zero()); large.len()],
+                    vec![(0i128, 0i128); small.len()],
+                    vec![F::zero(); degree + 1],
+                    vec![0i128; degree + 1],
+                )
+            },
+            |(mut buf, mut small_buf, mut acc, mut integer_acc), pair| {
+                let b = match sparsest {
+                    Some(_) => support[pair],
+                    None => pair,
+                };
+                buf.iter_mut().enumerate().for_each(|(i, (eval_0, step))| {
+                    *eval_0 = eval(i, b << 1);
+                    *step = eval(i, (b << 1) + 1) - *eval_0;
+                });
+                small_buf
+                    .iter_mut()
+                    .zip(small.iter())
+                    .for_each(|((eval_0, step), table)| {
+                        *eval_0 = table[b << 1] as i128;
+                        *step = table[(b << 1) + 1] as i128 - *eval_0;
+                    });
+                let weight = weights.map(|weights| weights[b]);
+                // evaluate ...0, ...1, ...11, ...111
+                for x in 0..=degree {
+                    if x > 0 {
+                        buf.iter_mut().for_each(|(eval, step)| *eval += step as &_);
+                        small_buf.iter_mut().for_each(|(eval, step)| *eval += *step);
+                    }
+                    let small_product = (!small.is_empty())
+                        .then(|| small_buf.iter().map(|(eval, _)| eval).product::<i128>());
+                    if integer_sums {
+                        // safe unwrap: there is a multiplicand, and it is small
+                        let product = small_product.unwrap();
+                        match integer_acc[x].checked_add(product) {
+                            Some(sum) => integer_acc[x] = sum,
+                            None => {
+                                acc[x] += from_i128::<F>(integer_acc[x]);
+                                integer_acc[x] = product;
+                            },
+                        }
+                        continue;
+                    }
+                    let mut product = buf.iter().map(|(eval, _)| eval).product::<F>();
+                    match small_product {
+                        Some(0) => continue,
+                        Some(small_product) => product *= from_i128::<F>(small_product),
+                        None => (),
+                    }
+                    if let Some(weight) = weight {
+                        product *= weight;
+                    }
+                    acc[x] += product;
+                }
+                (buf, small_buf, acc, integer_acc)
+            },
+        )
+        .map(|(_, _, mut partial, integer_partial)| {
+            partial
+                .iter_mut()
+                .zip(integer_partial)
+                .for_each(|(partial, integer_partial)| *partial += from_i128::<F>(integer_partial));
+            partial
+        })
+        .reduce(
+            || vec![F::zero(); degree + 1],
+            |mut sum, partial| {
+                sum.iter_mut()
+                    .zip(partial.iter())
+                    .for_each(|(sum, partial)| *sum += partial);
+                sum
+            },
+        )
+}
+
+fn from_i128<F: PrimeField>(value: i128) -> F {
+    if value < 0 {
+        -F::from(value.unsigned_abs())
+    } else {
+        F::from(value as u128)
+    }
+}
+
+pub(crate) fn barycentric_weights<F: PrimeField>(points: &[F]) -> Vec<F> {
     let mut weights = points
         .iter()
         .enumerate()
@@ -216,7 +356,8 @@ fn barycentric_weights<F: PrimeField>(points: &[F]) -> Vec<F> {
             points
                 .iter()
                 .enumerate()
-                .filter_map(|(i, point_i)| (i != j).then(|| *point_j - point_i))
+                .filter(|(i, _)| *i != j)
+                .map(|(_, point_i)| *point_j - point_i)
                 .reduce(|acc, value| acc * value)
                 .unwrap_or_else(F::one)
         })
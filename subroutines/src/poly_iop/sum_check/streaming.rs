@@ -0,0 +1,651 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! This module implements a streaming prover for the sum check protocol, for
+//! polynomials too large to be held in memory.
+//!
+//! At round `i`, after fixing `x_0, ..., x_{i-1}` to the challenges `a`,
+//!
+//! `f(a, X, b) = \sum_{c \in {0,1}^i} eq(c, a) f(c, X, b)`
+//!
+//! so the round polynomial is computed with one pass over the evaluations of
+//! `f`, in order, and a table of `eq(c, a)` of size `2^i`, as in
+//! [CTY11](https://arxiv.org/abs/1109.6882). After `k` such rounds, a last
+//! pass folds the multilinear extensions into tables of size `2^{n-k}`, and
+//! the in-memory prover takes over. The prover thus runs in time
+//! `O(k 2^n)` and space `O(2^k + 2^{n-k})`.
+//!
+//! `HyperPlonkSNARK::prove` runs the streaming zero check on the constraint
+//! polynomial when its proving key sets `num_streamed_rounds`. It still holds
+//! the witness and selector MLEs, the permutation check polynomials and the
+//! PCS inputs in memory. Proving 2^30-row circuits in 64 GB also needs those
+//! parts to stream, which is out of scope here.
+
+use super::{
+    prover::{barycentric_weights, extrapolate},
+    SumCheck,
+};
+use crate::poly_iop::{
+    errors::PolyIOPErrors,
+    structs::{IOPProof, IOPProverMessage, IOPProverState},
+    sum_check::SumCheckProver,
+    PolyIOP,
+};
+use arithmetic::{build_eq_x_r_vec, VPAuxInfo, VirtualPolynomial};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::SerializationError;
+use ark_std::{cfg_into_iter, end_timer, marker::PhantomData, start_timer};
+#[cfg(feature = "parallel")]
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use transcript::Transcript;
+
+/// Number of evaluations of each multilinear extension read at once
+#[cfg(not(test))]
+const STREAM_CHUNK_SIZE: usize = 1 << 16;
+#[cfg(test)]
+const STREAM_CHUNK_SIZE: usize = 1 << 4;
+
+/// The evaluations of a multilinear extension, read in order from some
+/// storage rather than held in memory. The evaluation at `x` has index `x_0
+/// + 2 x_1 + ... + 2^{n-1} x_{n-1}`.
+pub trait MultilinearStream<F: PrimeField>: Send + Sync {
+    /// Number of variables of the multilinear extension
+    fn num_variables(&self) -> usize;
+
+    /// Read the evaluations at `start..start + buf.len()` into `buf`.
+    fn read(&self, start: usize, buf: &mut [F]) -> Result<(), PolyIOPErrors>;
+}
+
+impl<F: PrimeField> MultilinearStream<F> for DenseMultilinearExtension<F> {
+    fn num_variables(&self) -> usize {
+        self.num_vars
+    }
+
+    fn read(&self, start: usize, buf: &mut [F]) -> Result<(), PolyIOPErrors> {
+        buf.copy_from_slice(&self.evaluations[start..start + buf.len()]);
+        Ok(())
+    }
+}
+
+/// A multilinear extension whose evaluations are computed on the fly by a
+/// function of their index, e.g. a witness generator.
+pub struct FnMultilinearStream<G> {
+    num_vars: usize,
+    eval: G,
+}
+
+impl<G> FnMultilinearStream<G> {
+    /// The multilinear extension in `num_vars` variables whose evaluation at
+    /// index `i` is `eval(i)`
+    pub fn new(num_vars: usize, eval: G) -> Self {
+        Self { num_vars, eval }
+    }
+}
+
+impl<F: PrimeField, G: Fn(usize) -> F + Send + Sync> MultilinearStream<F>
+    for FnMultilinearStream<G>
+{
+    fn num_variables(&self) -> usize {
+        self.num_vars
+    }
+
+    fn read(&self, start: usize, buf: &mut [F]) -> Result<(), PolyIOPErrors> {
+        buf.iter_mut()
+            .enumerate()
+            .for_each(|(i, eval)| *eval = (self.eval)(start + i));
+        Ok(())
+    }
+}
+
+/// A multilinear extension whose evaluations are stored in a file, in order,
+/// each in its uncompressed serialization.
+pub struct FileMultilinearStream<F: PrimeField> {
+    path: PathBuf,
+    num_vars: usize,
+    #[doc(hidden)]
+    phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField> FileMultilinearStream<F> {
+    /// Write the `2^num_vars` evaluations into a new file at `path`.
+    pub fn create(
+        path: impl AsRef<Path>,
+        num_vars: usize,
+        evaluations: impl IntoIterator<Item = F>,
+    ) -> Result<Self, PolyIOPErrors> {
+        let mut writer =
+            BufWriter::new(File::create(path.as_ref()).map_err(SerializationError::from)?);
+        let mut len = 0;
+        for eval in evaluations {
+            eval.serialize_uncompressed(&mut writer)?;
+            len += 1;
+        }
+        if len != 1 << num_vars {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "number of evaluations ({}) is not 2^{}",
+                len, num_vars
+            )));
+        }
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            num_vars,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Use the evaluations in the file at `path`, written by `create`.
+    pub fn open(path: impl AsRef<Path>, num_vars: usize) -> Result<Self, PolyIOPErrors> {
+        let len = std::fs::metadata(path.as_ref())
+            .map_err(SerializationError::from)?
+            .len();
+        if len != ((F::zero().uncompressed_size() as u64) << num_vars) {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "file size ({}) does not match 2^{} evaluations",
+                len, num_vars
+            )));
+        }
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            num_vars,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<F: PrimeField> MultilinearStream<F> for FileMultilinearStream<F> {
+    fn num_variables(&self) -> usize {
+        self.num_vars
+    }
+
+    fn read(&self, start: usize, buf: &mut [F]) -> Result<(), PolyIOPErrors> {
+        let mut file = File::open(&self.path).map_err(SerializationError::from)?;
+        file.seek(SeekFrom::Start(
+            (start * F::zero().uncompressed_size()) as u64,
+        ))
+        .map_err(SerializationError::from)?;
+        let mut reader = BufReader::new(file);
+        for eval in buf.iter_mut() {
+            *eval = F::deserialize_uncompressed_unchecked(&mut reader)?;
+        }
+        Ok(())
+    }
+}
+
+/// A sum of products of multilinear extensions, as in `VirtualPolynomial`,
+/// whose evaluations are streamed.
+#[derive(Clone, Default)]
+pub struct StreamingPolynomial<F: PrimeField> {
+    /// Auxiliary information of the polynomial
+    pub aux_info: VPAuxInfo<F>,
+    /// list of reference to products (as usize) of multilinear extension
+    pub products: Vec<(F, Vec<usize>)>,
+    /// Stores the multilinear extensions in which product multiplicand can
+    /// refer to.
+    pub streams: Vec<Arc<dyn MultilinearStream<F>>>,
+}
+
+impl<F: PrimeField> StreamingPolynomial<F> {
+    /// Creates an empty polynomial with `num_variables`.
+    pub fn new(num_variables: usize) -> Self {
+        Self {
+            aux_info: VPAuxInfo {
+                max_degree: 0,
+                num_variables,
+                phantom: PhantomData,
+            },
+            products: Vec::new(),
+            streams: Vec::new(),
+        }
+    }
+
+    /// Add a product of streams to self, multiplied by `coefficient`.
+    ///
+    /// A stream added several times, as the same `Arc`, is only read once per
+    /// pass.
+    pub fn add_stream_list(
+        &mut self,
+        streams: impl IntoIterator<Item = Arc<dyn MultilinearStream<F>>>,
+        coefficient: F,
+    ) -> Result<(), PolyIOPErrors> {
+        let mut indexed_product = vec![];
+        for stream in streams {
+            if stream.num_variables() != self.aux_info.num_variables {
+                return Err(PolyIOPErrors::InvalidParameters(format!(
+                    "product has a multiplicand with wrong number of variables {} vs {}",
+                    stream.num_variables(),
+                    self.aux_info.num_variables
+                )));
+            }
+            let index = match self
+                .streams
+                .iter()
+                .position(|other| Arc::ptr_eq(other, &stream))
+            {
+                Some(index) => index,
+                None => {
+                    self.streams.push(stream);
+                    self.streams.len() - 1
+                },
+            };
+            indexed_product.push(index);
+        }
+        if indexed_product.is_empty() {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "input stream list is empty".to_string(),
+            ));
+        }
+        self.aux_info.max_degree = self.aux_info.max_degree.max(indexed_product.len());
+        self.products.push((coefficient, indexed_product));
+        Ok(())
+    }
+
+    /// The same polynomial over the `folded` multilinear extensions, one per
+    /// stream.
+    pub(crate) fn folded_polynomial(
+        &self,
+        folded: &[Arc<DenseMultilinearExtension<F>>],
+    ) -> Result<VirtualPolynomial<F>, PolyIOPErrors> {
+        let mut poly = VirtualPolynomial::new(folded[0].num_vars);
+        for (coefficient, products) in self.products.iter() {
+            poly.add_mle_list(products.iter().map(|&i| folded[i].clone()), *coefficient)?;
+        }
+        Ok(poly)
+    }
+}
+
+/// A sum check whose prover streams the evaluations of the polynomial in its
+/// first rounds. The proofs are the same as the ones of `SumCheck::prove`.
+pub trait StreamingSumCheck<F: PrimeField>: SumCheck<F> {
+    type StreamingPolynomial;
+
+    /// Generate proof of the sum of polynomial over {0,1}^`num_vars`,
+    /// streaming the evaluations in the first `num_streamed_rounds` rounds,
+    /// and holding tables of size `2^{num_vars - num_streamed_rounds}`
+    /// afterwards.
+    fn prove_streaming(
+        poly: &Self::StreamingPolynomial,
+        num_streamed_rounds: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::SumCheckProof, PolyIOPErrors>;
+}
+
+impl<F: PrimeField, T: Transcript<F>> StreamingSumCheck<F> for PolyIOP<F, T> {
+    type StreamingPolynomial = StreamingPolynomial<F>;
+
+    fn prove_streaming(
+        poly: &Self::StreamingPolynomial,
+        num_streamed_rounds: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::SumCheckProof, PolyIOPErrors> {
+        check_streamed_rounds(poly, num_streamed_rounds)?;
+        let start = start_timer!(|| "streaming sum check prove");
+
+        transcript.append_serializable_element(b"aux info", &poly.aux_info)?;
+
+        let (mut prover_msgs, mut point, folded) =
+            prove_streamed_rounds(poly, None, num_streamed_rounds, transcript)?;
+
+        let folded_poly = poly.folded_polynomial(&folded)?;
+        let mut prover_state = IOPProverState::prover_init(&folded_poly)?;
+        let mut challenge = None;
+        for _ in num_streamed_rounds..poly.aux_info.num_variables {
+            let prover_msg =
+                IOPProverState::prove_round_and_update_state(&mut prover_state, &challenge)?;
+            transcript.append_serializable_element(b"prover msg", &prover_msg)?;
+            prover_msgs.push(prover_msg);
+            challenge = Some(transcript.get_and_append_challenge(b"Internal round")?);
+        }
+        // pushing the last challenge point to the state
+        if let Some(p) = challenge {
+            prover_state.challenges.push(p)
+        };
+        point.extend(prover_state.challenges);
+
+        end_timer!(start);
+        Ok(IOPProof {
+            point,
+            proofs: prover_msgs,
+        })
+    }
+}
+
+/// Check that the first `num_rounds` rounds of the sum check on `poly` can
+/// be streamed, leaving at least one round to the in-memory prover.
+pub(crate) fn check_streamed_rounds<F: PrimeField>(
+    poly: &StreamingPolynomial<F>,
+    num_rounds: usize,
+) -> Result<(), PolyIOPErrors> {
+    let num_vars = poly.aux_info.num_variables;
+    if num_rounds >= num_vars {
+        return Err(PolyIOPErrors::InvalidParameters(format!(
+            "cannot stream {} rounds of {}",
+            num_rounds, num_vars
+        )));
+    }
+    if poly.products.is_empty() {
+        return Err(PolyIOPErrors::InvalidParameters(
+            "the polynomial has no product".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Run the first `num_rounds` rounds of the sum check on `poly`, or on `eq *
+/// poly` if `eq` is given, streaming the evaluations. Then fold the streams
+/// of `poly` at the challenges.
+///
+/// Returns the prover messages, the challenges, and the folded multilinear
+/// extensions, one per stream of `poly`.
+#[allow(clippy::type_complexity)]
+pub(crate) fn prove_streamed_rounds<F: PrimeField, T: Transcript<F>>(
+    poly: &StreamingPolynomial<F>,
+    eq: Option<&dyn MultilinearStream<F>>,
+    num_rounds: usize,
+    transcript: &mut T,
+) -> Result<
+    (
+        Vec<IOPProverMessage<F>>,
+        Vec<F>,
+        Vec<Arc<DenseMultilinearExtension<F>>>,
+    ),
+    PolyIOPErrors,
+> {
+    check_streamed_rounds(poly, num_rounds)?;
+    let num_vars = poly.aux_info.num_variables;
+
+    // the eq stream, if any, is a multiplicand of every product
+    let streams: Vec<&dyn MultilinearStream<F>> = poly
+        .streams
+        .iter()
+        .map(|stream| stream.as_ref())
+        .chain(eq)
+        .collect();
+    let eq_index = eq.map(|_| poly.streams.len());
+    let products: Vec<Vec<usize>> = poly
+        .products
+        .iter()
+        .map(|(_, product)| product.iter().copied().chain(eq_index).collect())
+        .collect();
+    let max_degree = poly.aux_info.max_degree + eq_index.iter().count();
+    let extrapolation_aux: Vec<_> = (1..=max_degree)
+        .map(|degree| {
+            let points = (0..1 + degree as u64).map(F::from).collect::<Vec<_>>();
+            let weights = barycentric_weights(&points);
+            (points, weights)
+        })
+        .collect();
+
+    let mut prover_msgs = Vec::with_capacity(num_rounds);
+    let mut challenges = Vec::with_capacity(num_rounds);
+    for _ in 0..num_rounds {
+        let step = start_timer!(|| format!("streamed round {}", challenges.len()));
+        let sums = streamed_round_evaluations(&streams, &products, &eq_table(&challenges)?)?;
+        let mut evaluations = vec![F::zero(); max_degree + 1];
+        for ((coefficient, _), sum) in poly.products.iter().zip(sums.iter()) {
+            let (points, weights) = &extrapolation_aux[sum.len() - 2];
+            evaluations
+                .iter_mut()
+                .enumerate()
+                .for_each(|(i, evaluation)| {
+                    let eval = match sum.get(i) {
+                        Some(eval) => *eval,
+                        None => extrapolate(points, weights, sum, &F::from(i as u64)),
+                    };
+                    *evaluation += *coefficient * eval;
+                });
+        }
+        let prover_msg = IOPProverMessage { evaluations };
+        transcript.append_serializable_element(b"prover msg", &prover_msg)?;
+        prover_msgs.push(prover_msg);
+        challenges.push(transcript.get_and_append_challenge(b"Internal round")?);
+        end_timer!(step);
+    }
+
+    let step = start_timer!(|| "fold streams");
+    let eq_table = eq_table(&challenges)?;
+    let folded = poly
+        .streams
+        .iter()
+        .map(|stream| {
+            let evaluations = fold_stream(stream.as_ref(), &eq_table)?;
+            Ok(Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                num_vars - num_rounds,
+                evaluations,
+            )))
+        })
+        .collect::<Result<Vec<_>, PolyIOPErrors>>()?;
+    end_timer!(step);
+
+    Ok((prover_msgs, challenges, folded))
+}
+
+/// `eq(c, a)` for all `c`
+fn eq_table<F: PrimeField>(a: &[F]) -> Result<Vec<F>, PolyIOPErrors> {
+    if a.is_empty() {
+        Ok(vec![F::one()])
+    } else {
+        Ok(build_eq_x_r_vec(a)?)
+    }
+}
+
+/// Length of the chunks read to process blocks of `block` evaluations of
+/// `num_vars` variables multilinear extensions.
+fn chunk_len(block: usize, num_vars: usize) -> usize {
+    block.max(STREAM_CHUNK_SIZE.min(1 << num_vars))
+}
+
+/// Evaluations at `0, ..., product.len()` of
+///
+///   \sum_b \prod_{f \in product} f(a, X, b)
+///
+/// for each product, from `eq_table[c] = eq(c, a)`.
+fn streamed_round_evaluations<F: PrimeField>(
+    streams: &[&dyn MultilinearStream<F>],
+    products: &[Vec<usize>],
+    eq_table: &[F],
+) -> Result<Vec<Vec<F>>, PolyIOPErrors> {
+    let num_vars = streams[0].num_variables();
+    let half = eq_table.len();
+    let block = half << 1;
+    let chunk = chunk_len(block, num_vars);
+
+    let partial_sums = cfg_into_iter!(0..(1 << num_vars) / chunk)
+        .map(|i| {
+            let mut bufs = vec![vec![F::zero(); chunk]; streams.len()];
+            for (stream, buf) in streams.iter().zip(bufs.iter_mut()) {
+                stream.read(i * chunk, buf)?;
+            }
+            let mut sums: Vec<Vec<F>> = products
+                .iter()
+                .map(|product| vec![F::zero(); product.len() + 1])
+                .collect();
+            let mut evals = vec![(F::zero(), F::zero()); streams.len()];
+            let mut product_evals = vec![];
+            for start in (0..chunk).step_by(block) {
+                // f(a, 0, b) and f(a, 1, b) - f(a, 0, b)
+                evals
+                    .iter_mut()
+                    .zip(bufs.iter())
+                    .for_each(|((eval_0, step), buf)| {
+                        *eval_0 = inner_product(eq_table, &buf[start..start + half]);
+                        *step =
+                            inner_product(eq_table, &buf[start + half..start + block]) - *eval_0;
+                    });
+                for (product, sum) in products.iter().zip(sums.iter_mut()) {
+                    product_evals.clear();
+                    product_evals.extend(product.iter().map(|&j| evals[j]));
+                    // evaluate ...0, ...1, ...11, ...111
+                    for (x, sum) in sum.iter_mut().enumerate() {
+                        if x > 0 {
+                            product_evals
+                                .iter_mut()
+                                .for_each(|(eval, step)| *eval += *step);
+                        }
+                        *sum += product_evals.iter().map(|(eval, _)| eval).product::<F>();
+                    }
+                }
+            }
+            Ok(sums)
+        })
+        .collect::<Result<Vec<_>, PolyIOPErrors>>()?;
+
+    Ok(partial_sums
+        .into_iter()
+        .reduce(|mut sums, partial| {
+            sums.iter_mut()
+                .flatten()
+                .zip(partial.iter().flatten())
+                .for_each(|(sum, partial)| *sum += partial);
+            sums
+        })
+        .unwrap_or_default())
+}
+
+/// The table of `f(a, b)` for all `b`, from `eq_table[c] = eq(c, a)`.
+fn fold_stream<F: PrimeField>(
+    stream: &dyn MultilinearStream<F>,
+    eq_table: &[F],
+) -> Result<Vec<F>, PolyIOPErrors> {
+    let num_vars = stream.num_variables();
+    let block = eq_table.len();
+    let chunk = chunk_len(block, num_vars);
+    let folded = cfg_into_iter!(0..(1 << num_vars) / chunk)
+        .map(|i| {
+            let mut buf = vec![F::zero(); chunk];
+            stream.read(i * chunk, &mut buf)?;
+            Ok(buf
+                .chunks(block)
+                .map(|evals| inner_product(eq_table, evals))
+                .collect::<Vec<_>>())
+        })
+        .collect::<Result<Vec<_>, PolyIOPErrors>>()?;
+    Ok(folded.concat())
+}
+
+fn inner_product<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b.iter()).map(|(a, b)| *a * b).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use arithmetic::MultilinearPolynomial;
+    use ark_bls12_381::Fr;
+    use ark_std::test_rng;
+
+    /// The streams of the multilinear extensions of `poly`, held in memory
+    fn streaming_polynomial(poly: &VirtualPolynomial<Fr>) -> StreamingPolynomial<Fr> {
+        let streams: Vec<Arc<dyn MultilinearStream<Fr>>> = poly
+            .flattened_ml_extensions
+            .iter()
+            .map(|mle| Arc::new(mle.to_dense()) as Arc<dyn MultilinearStream<Fr>>)
+            .collect();
+        let mut res = StreamingPolynomial::new(poly.aux_info.num_variables);
+        for (coefficient, products) in poly.products.iter() {
+            res.add_stream_list(products.iter().map(|&i| streams[i].clone()), *coefficient)
+                .unwrap();
+        }
+        res
+    }
+
+    #[test]
+    fn test_streaming_sum_check() -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+        let nv = 7;
+        let (poly, asserted_sum) = VirtualPolynomial::<Fr>::rand(nv, (2, 4), 3, &mut rng)?;
+        let streaming_poly = streaming_polynomial(&poly);
+
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let proof = <PolyIOP<Fr> as SumCheck<Fr>>::prove(&poly, &mut transcript)?;
+
+        for num_streamed_rounds in 0..nv {
+            let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+            let streaming_proof = <PolyIOP<Fr> as StreamingSumCheck<Fr>>::prove_streaming(
+                &streaming_poly,
+                num_streamed_rounds,
+                &mut transcript,
+            )?;
+            assert_eq!(streaming_proof, proof);
+        }
+
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let subclaim = <PolyIOP<Fr> as SumCheck<Fr>>::verify(
+            asserted_sum,
+            &proof,
+            &poly.aux_info,
+            &mut transcript,
+        )?;
+        assert!(
+            poly.evaluate(&subclaim.point)? == subclaim.expected_evaluation,
+            "wrong subclaim"
+        );
+
+        // all the rounds cannot be streamed
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        assert!(<PolyIOP<Fr> as StreamingSumCheck<Fr>>::prove_streaming(
+            &streaming_poly,
+            nv,
+            &mut transcript
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_and_fn_streams() -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+        let nv = 6;
+        let (poly, _) = VirtualPolynomial::<Fr>::rand(nv, (2, 3), 2, &mut rng)?;
+
+        // the first multilinear extension from a file, the others computed
+        // on the fly
+        let path = std::env::temp_dir().join(format!(
+            "hyperplonk_streaming_sum_check_{}",
+            std::process::id()
+        ));
+        let mles: Vec<_> = poly
+            .flattened_ml_extensions
+            .iter()
+            .map(|mle| mle.to_dense())
+            .collect();
+        FileMultilinearStream::create(&path, nv, mles[0].evaluations.iter().copied())?;
+        let mut streams: Vec<Arc<dyn MultilinearStream<Fr>>> =
+            vec![Arc::new(FileMultilinearStream::<Fr>::open(&path, nv)?)];
+        for mle in mles[1..].iter() {
+            let mle = mle.clone();
+            streams.push(Arc::new(FnMultilinearStream::new(nv, move |i| {
+                mle.evaluations[i]
+            })));
+        }
+        let mut streaming_poly = StreamingPolynomial::new(nv);
+        for (coefficient, products) in poly.products.iter() {
+            streaming_poly
+                .add_stream_list(products.iter().map(|&i| streams[i].clone()), *coefficient)?;
+        }
+
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let proof = <PolyIOP<Fr> as SumCheck<Fr>>::prove(&poly, &mut transcript)?;
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let streaming_proof = <PolyIOP<Fr> as StreamingSumCheck<Fr>>::prove_streaming(
+            &streaming_poly,
+            nv - 1,
+            &mut transcript,
+        )?;
+        assert_eq!(streaming_proof, proof);
+
+        // a file of the wrong size is rejected
+        assert!(FileMultilinearStream::<Fr>::open(&path, nv + 1).is_err());
+        std::fs::remove_file(&path).map_err(SerializationError::from)?;
+        Ok(())
+    }
+}
@@ -8,11 +8,15 @@
 
 use std::fmt::Debug;
 
-use crate::poly_iop::{errors::PolyIOPErrors, sum_check::SumCheck, PolyIOP};
+use crate::poly_iop::{errors::PolyIOPErrors, structs::IOPProof, sum_check::SumCheck, PolyIOP};
 use arithmetic::eq_eval;
 use ark_ff::PrimeField;
 use ark_std::{end_timer, start_timer};
-use transcript::IOPTranscript;
+use prover::ZeroCheckProverState;
+use transcript::Transcript;
+
+mod prover;
+pub mod streaming;
 
 /// A zero check IOP subclaim for `f(x)` consists of the following:
 ///   - the initial challenge vector r which is used to build eq(x, r) in
@@ -58,12 +62,12 @@ pub trait ZeroCheck<F: PrimeField>: SumCheck<F> {
     ) -> Result<Self::ZeroCheckSubClaim, PolyIOPErrors>;
 }
 
-impl<F: PrimeField> ZeroCheck<F> for PolyIOP<F> {
+impl<F: PrimeField, T: Transcript<F>> ZeroCheck<F> for PolyIOP<F, T> {
     type ZeroCheckSubClaim = ZeroCheckSubClaim<F>;
     type ZeroCheckProof = Self::SumCheckProof;
 
     fn init_transcript() -> Self::Transcript {
-        IOPTranscript::<F>::new(b"Initializing ZeroCheck transcript")
+        T::new(b"Initializing ZeroCheck transcript")
     }
 
     fn prove(
@@ -74,11 +78,32 @@ impl<F: PrimeField> ZeroCheck<F> for PolyIOP<F> {
 
         let length = poly.aux_info.num_variables;
         let r = transcript.get_and_append_challenge_vectors(b"0check r", length)?; // dispa
-        let f_hat = poly.build_f_hat(r.as_ref())?; // worker
-        let res = <Self as SumCheck<F>>::prove(&f_hat, transcript);
+
+        // run the sum check on f_hat(x) = f(x) * eq(x, r), with eq(x, r) kept
+        // out of the products of f
+        let mut hat_fx_aux_info = poly.aux_info.clone();
+        hat_fx_aux_info.max_degree += 1;
+        transcript.append_serializable_element(b"aux info", &hat_fx_aux_info)?;
+
+        let mut prover_state = ZeroCheckProverState::prover_init(poly, &r)?;
+        let mut challenge = None;
+        let mut prover_msgs = Vec::with_capacity(length);
+        for _ in 0..length {
+            let prover_msg = prover_state.prove_round_and_update_state(&challenge)?;
+            transcript.append_serializable_element(b"prover msg", &prover_msg)?;
+            prover_msgs.push(prover_msg);
+            challenge = Some(transcript.get_and_append_challenge(b"Internal round")?);
+        }
+        // pushing the last challenge point to the state
+        if let Some(p) = challenge {
+            prover_state.challenges.push(p)
+        };
 
         end_timer!(start);
-        res
+        Ok(IOPProof {
+            point: prover_state.challenges,
+            proofs: prover_msgs,
+        })
     }
 
     fn verify(
@@ -124,10 +149,11 @@ impl<F: PrimeField> ZeroCheck<F> for PolyIOP<F> {
 mod test {
 
     use super::ZeroCheck;
-    use crate::poly_iop::{errors::PolyIOPErrors, PolyIOP};
+    use crate::poly_iop::{errors::PolyIOPErrors, sum_check::SumCheck, PolyIOP};
     use arithmetic::VirtualPolynomial;
     use ark_bls12_381::Fr;
     use ark_std::test_rng;
+    use transcript::Transcript;
 
     fn test_zerocheck(
         nv: usize,
@@ -195,6 +221,29 @@ mod test {
         test_zerocheck(nv, num_multiplicands_range, num_products)
     }
 
+    #[test]
+    fn test_eq_factored_prover() -> Result<(), PolyIOPErrors> {
+        // the proof is the one of the sum check on f(x) * eq(x, r)
+        let mut rng = test_rng();
+        for nv in 1..7 {
+            let zero_poly = VirtualPolynomial::<Fr>::rand_zero(nv, (2, 5), 3, &mut rng)?;
+            let (poly, _sum) = VirtualPolynomial::<Fr>::rand(nv, (1, 5), 3, &mut rng)?;
+            for poly in [zero_poly, poly] {
+                let mut transcript = <PolyIOP<Fr> as ZeroCheck<Fr>>::init_transcript();
+                transcript.append_message(b"testing", b"initializing transcript for testing")?;
+                let proof = <PolyIOP<Fr> as ZeroCheck<Fr>>::prove(&poly, &mut transcript)?;
+
+                let mut transcript = <PolyIOP<Fr> as ZeroCheck<Fr>>::init_transcript();
+                transcript.append_message(b"testing", b"initializing transcript for testing")?;
+                let r = transcript.get_and_append_challenge_vectors(b"0check r", nv)?;
+                let f_hat = poly.build_f_hat(&r)?;
+                let expected = <PolyIOP<Fr> as SumCheck<Fr>>::prove(&f_hat, &mut transcript)?;
+                assert_eq!(proof, expected);
+            }
+        }
+        Ok(())
+    }
+
     #[test]
     fn zero_polynomial_should_error() -> Result<(), PolyIOPErrors> {
         let nv = 0;
@@ -0,0 +1,208 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Prover subroutines for a ZeroCheck protocol.
+//!
+//! The zero check runs a sum check on `f(x) * eq(x, r)`. Instead of
+//! multiplying `eq(x, r)` into every product of `f`, the prover keeps it
+//! factored out, as in [Gru24](https://eprint.iacr.org/2024/108.pdf). At
+//! round `i`, after fixing `x_0, ..., x_{i-1}` to the challenges
+//! `a_0, ..., a_{i-1}`, the round polynomial is
+//!
+//! `s_i(X) = c_i * eq(X, r_i) * t_i(X)`
+//!
+//! where
+//! - `c_i = eq(a_0, r_0) * ... * eq(a_{i-1}, r_{i-1})` is known,
+//! - `t_i(X) = \sum_{b} eq(b, (r_{i+1}, ..., r_{n-1})) f(a_0, ..., a_{i-1}, X,
+//!   b)` has the degree of `f`, one less than `s_i`.
+//!
+//! The table of `eq(b, (r_{i+1}, ..., r_{n-1}))` starts with `2^{n-1}`
+//! entries, and is halved in place every round by summing out its first
+//! variable, so the `2^n` table of `eq(x, r)` is never built.
+
+use crate::poly_iop::{
+    errors::PolyIOPErrors,
+    structs::{FoldedTable, IOPProverMessage},
+    sum_check::prover::{barycentric_weights, extrapolate, product_round_evaluations},
+};
+use arithmetic::{build_eq_x_r_vec, MultilinearPolynomial, VirtualPolynomial};
+use ark_ff::PrimeField;
+use ark_std::{cfg_iter_mut, vec::Vec};
+
+#[cfg(feature = "parallel")]
+use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+
+/// Prover state of a ZeroCheck, with `eq(x, r)` factored out.
+pub(crate) struct ZeroCheckProverState<'a, F: PrimeField> {
+    /// sampled randomness given by the verifier
+    pub(crate) challenges: Vec<F>,
+    /// the current round number
+    round: usize,
+    /// the polynomial `f` whose evaluations are all zero
+    poly: &'a VirtualPolynomial<F>,
+    /// the challenge `r` of `eq(x, r)`
+    r: &'a [F],
+    /// evaluations of the multilinear extensions of `poly` with the
+    /// challenges so far fixed; empty until the first challenge
+    folded_ml_extensions: Vec<FoldedTable<F>>,
+    /// `eq(b, (r_{round + 1}, ..., r_{n-1}))` for all `b`
+    eq_table: Vec<F>,
+    /// `eq(a_0, r_0) * ... * eq(a_{round-1}, r_{round-1})`
+    eq_factor: F,
+    /// points with precomputed barycentric weights for extrapolating
+    /// `t_i(X)` of the products of each degree to `max_degree + 2`
+    /// evaluations.
+    extrapolation_aux: Vec<(Vec<F>, Vec<F>)>,
+}
+
+impl<'a, F: PrimeField> ZeroCheckProverState<'a, F> {
+    /// Initialize the prover to argue that `poly` is zero over
+    /// {0,1}^`num_vars`, with the challenge `r` of `eq(x, r)`.
+    pub(crate) fn prover_init(
+        poly: &'a VirtualPolynomial<F>,
+        r: &'a [F],
+    ) -> Result<Self, PolyIOPErrors> {
+        let num_vars = poly.aux_info.num_variables;
+        if num_vars == 0 {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "Attempt to prove a constant.".to_string(),
+            ));
+        }
+        if r.len() != num_vars {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "r.len() is different from number of variables: {} vs {}",
+                r.len(),
+                num_vars
+            )));
+        }
+        let eq_table = if num_vars == 1 {
+            vec![F::one()]
+        } else {
+            build_eq_x_r_vec(&r[1..])?
+        };
+
+        Ok(Self {
+            challenges: Vec::with_capacity(num_vars),
+            round: 0,
+            poly,
+            r,
+            folded_ml_extensions: Vec::new(),
+            eq_table,
+            eq_factor: F::one(),
+            extrapolation_aux: (1..=poly.aux_info.max_degree)
+                .map(|degree| {
+                    let points = (0..1 + degree as u64).map(F::from).collect::<Vec<_>>();
+                    let weights = barycentric_weights(&points);
+                    (points, weights)
+                })
+                .collect(),
+        })
+    }
+
+    /// Receive message from verifier, generate prover message, and proceed to
+    /// next round.
+    ///
+    /// The message holds the evaluations of `s_i` at `0, ..., max_degree + 1`,
+    /// as a sum check on `f(x) * eq(x, r)` would.
+    pub(crate) fn prove_round_and_update_state(
+        &mut self,
+        challenge: &Option<F>,
+    ) -> Result<IOPProverMessage<F>, PolyIOPErrors> {
+        if self.round >= self.poly.aux_info.num_variables {
+            return Err(PolyIOPErrors::InvalidProver(
+                "Prover is not active".to_string(),
+            ));
+        }
+
+        // Step 1: fix the previous variable to the challenge, in the mles of
+        // `f` and in `eq(x, r)`
+        if let Some(chal) = challenge {
+            if self.round == 0 {
+                return Err(PolyIOPErrors::InvalidProver(
+                    "first round should be prover first.".to_string(),
+                ));
+            }
+            self.challenges.push(*chal);
+
+            let a = *chal;
+            let r = self.r[self.round - 1];
+            if self.folded_ml_extensions.is_empty() {
+                self.folded_ml_extensions = self
+                    .poly
+                    .flattened_ml_extensions
+                    .iter()
+                    .map(|mle| FoldedTable::fix_first_variable(mle.as_ref(), &a))
+                    .collect();
+            } else {
+                cfg_iter_mut!(self.folded_ml_extensions)
+                    .for_each(|table| table.fix_first_variable_in_place(&a));
+            }
+            self.eq_factor *= eq_1(a, r);
+
+            // eq(b, r') = eq((0, b), (r_i, r')) + eq((1, b), (r_i, r'))
+            let half = self.eq_table.len() >> 1;
+            for b in 0..half {
+                self.eq_table[b] = self.eq_table[b << 1] + self.eq_table[(b << 1) + 1];
+            }
+            self.eq_table.truncate(half);
+        } else if self.round > 0 {
+            return Err(PolyIOPErrors::InvalidProver(
+                "verifier message is empty".to_string(),
+            ));
+        }
+
+        let r = self.r[self.round];
+        self.round += 1;
+
+        let flattened_ml_extensions: Vec<&dyn MultilinearPolynomial<F>> =
+            if self.folded_ml_extensions.is_empty() {
+                self.poly
+                    .flattened_ml_extensions
+                    .iter()
+                    .map(|mle| mle.as_ref())
+                    .collect()
+            } else {
+                self.folded_ml_extensions
+                    .iter()
+                    .map(FoldedTable::as_multilinear)
+                    .collect()
+            };
+
+        // Step 2: evaluate t_i(X) at 0, ..., max_degree + 1, from the
+        // evaluations of each product at 0, ..., degree
+        let num_evals = self.poly.aux_info.max_degree + 2;
+        let mut t_evals = vec![F::zero(); num_evals];
+        for (coefficient, products) in self.poly.products.iter() {
+            let sum = product_round_evaluations(
+                &flattened_ml_extensions,
+                products,
+                Some(&self.eq_table),
+            );
+            let (points, weights) = &self.extrapolation_aux[products.len() - 1];
+            t_evals.iter_mut().enumerate().for_each(|(i, t_eval)| {
+                let eval = match sum.get(i) {
+                    Some(eval) => *eval,
+                    None => extrapolate(points, weights, &sum, &F::from(i as u64)),
+                };
+                *t_eval += *coefficient * eval;
+            });
+        }
+
+        // Step 3: s_i(X) = c_i * eq(X, r_i) * t_i(X)
+        let evaluations = t_evals
+            .iter()
+            .enumerate()
+            .map(|(i, t_eval)| self.eq_factor * eq_1(F::from(i as u64), r) * t_eval)
+            .collect();
+
+        Ok(IOPProverMessage { evaluations })
+    }
+}
+
+/// `eq(x, r) = x * r + (1 - x) * (1 - r)` for a single variable
+pub(crate) fn eq_1<F: PrimeField>(x: F, r: F) -> F {
+    x * r + (F::one() - x) * (F::one() - r)
+}
@@ -0,0 +1,211 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! A zero check whose prover streams the evaluations of the polynomial in its
+//! first rounds.
+//!
+//! The streamed rounds run the streaming sum check on `f(x) * eq(x, r)`,
+//! with `eq(x, r)` computed on the fly from two tables of size `2^{n/2}`.
+//! The in-memory prover then takes over on the folded `f`, with the factor
+//! `eq(a, (r_0, ..., r_{k-1}))` of the streamed challenges `a` multiplied
+//! into its messages.
+
+use super::{
+    prover::{eq_1, ZeroCheckProverState},
+    ZeroCheck,
+};
+use crate::poly_iop::{
+    errors::PolyIOPErrors,
+    structs::IOPProof,
+    sum_check::streaming::{
+        check_streamed_rounds, prove_streamed_rounds, MultilinearStream, StreamingPolynomial,
+    },
+    PolyIOP,
+};
+use arithmetic::build_eq_x_r_vec;
+use ark_ff::PrimeField;
+use ark_std::{end_timer, start_timer};
+use transcript::Transcript;
+
+/// The evaluations of `eq(x, r)`, as `eq(x_lo, r_lo) * eq(x_hi, r_hi)` for
+/// the low and high halves of the variables.
+pub(crate) struct EqStream<F: PrimeField> {
+    low: Vec<F>,
+    high: Vec<F>,
+    low_vars: usize,
+}
+
+impl<F: PrimeField> EqStream<F> {
+    pub(crate) fn new(r: &[F]) -> Result<Self, PolyIOPErrors> {
+        let low_vars = r.len() / 2;
+        let table = |r: &[F]| -> Result<Vec<F>, PolyIOPErrors> {
+            if r.is_empty() {
+                Ok(vec![F::one()])
+            } else {
+                Ok(build_eq_x_r_vec(r)?)
+            }
+        };
+        Ok(Self {
+            low: table(&r[..low_vars])?,
+            high: table(&r[low_vars..])?,
+            low_vars,
+        })
+    }
+}
+
+impl<F: PrimeField> MultilinearStream<F> for EqStream<F> {
+    fn num_variables(&self) -> usize {
+        self.low_vars + ark_std::log2(self.high.len()) as usize
+    }
+
+    fn read(&self, start: usize, buf: &mut [F]) -> Result<(), PolyIOPErrors> {
+        let mask = self.low.len() - 1;
+        buf.iter_mut().enumerate().for_each(|(i, eval)| {
+            let x = start + i;
+            *eval = self.low[x & mask] * self.high[x >> self.low_vars];
+        });
+        Ok(())
+    }
+}
+
+/// A zero check whose prover streams the evaluations of the polynomial in its
+/// first rounds. The proofs are the same as the ones of `ZeroCheck::prove`.
+pub trait StreamingZeroCheck<F: PrimeField>: ZeroCheck<F> {
+    type StreamingPolynomial;
+
+    /// Initialize the prover to argue that the polynomial is zero over
+    /// {0,1}^`num_vars`, streaming its evaluations in the first
+    /// `num_streamed_rounds` rounds.
+    fn prove_streaming(
+        poly: &Self::StreamingPolynomial,
+        num_streamed_rounds: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::ZeroCheckProof, PolyIOPErrors>;
+}
+
+impl<F: PrimeField, T: Transcript<F>> StreamingZeroCheck<F> for PolyIOP<F, T> {
+    type StreamingPolynomial = StreamingPolynomial<F>;
+
+    fn prove_streaming(
+        poly: &Self::StreamingPolynomial,
+        num_streamed_rounds: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::ZeroCheckProof, PolyIOPErrors> {
+        check_streamed_rounds(poly, num_streamed_rounds)?;
+        let start = start_timer!(|| "streaming zero check prove");
+
+        let length = poly.aux_info.num_variables;
+        let r = transcript.get_and_append_challenge_vectors(b"0check r", length)?;
+
+        let mut hat_fx_aux_info = poly.aux_info.clone();
+        hat_fx_aux_info.max_degree += 1;
+        transcript.append_serializable_element(b"aux info", &hat_fx_aux_info)?;
+
+        let eq = EqStream::new(&r)?;
+        let (mut prover_msgs, mut point, folded) =
+            prove_streamed_rounds(poly, Some(&eq), num_streamed_rounds, transcript)?;
+        let eq_factor = point
+            .iter()
+            .zip(r.iter())
+            .map(|(a, r)| eq_1(*a, *r))
+            .product::<F>();
+
+        let folded_poly = poly.folded_polynomial(&folded)?;
+        let mut prover_state =
+            ZeroCheckProverState::prover_init(&folded_poly, &r[num_streamed_rounds..])?;
+        let mut challenge = None;
+        for _ in num_streamed_rounds..length {
+            let mut prover_msg = prover_state.prove_round_and_update_state(&challenge)?;
+            prover_msg
+                .evaluations
+                .iter_mut()
+                .for_each(|eval| *eval *= eq_factor);
+            transcript.append_serializable_element(b"prover msg", &prover_msg)?;
+            prover_msgs.push(prover_msg);
+            challenge = Some(transcript.get_and_append_challenge(b"Internal round")?);
+        }
+        // pushing the last challenge point to the state
+        if let Some(p) = challenge {
+            prover_state.challenges.push(p)
+        };
+        point.extend(prover_state.challenges);
+
+        end_timer!(start);
+        Ok(IOPProof {
+            point,
+            proofs: prover_msgs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::poly_iop::sum_check::{streaming::MultilinearStream, SumCheck};
+    use arithmetic::{MultilinearPolynomial, VirtualPolynomial};
+    use ark_bls12_381::Fr;
+    use ark_std::{test_rng, UniformRand};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_eq_stream() -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+        for nv in 1..6 {
+            let r: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+            let eq = EqStream::new(&r)?;
+            assert_eq!(eq.num_variables(), nv);
+            let mut evals = vec![Fr::from(0u64); 1 << nv];
+            eq.read(0, &mut evals)?;
+            assert_eq!(evals, build_eq_x_r_vec(&r)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_streaming_zero_check() -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+        let nv = 6;
+        let poly = VirtualPolynomial::<Fr>::rand_zero(nv, (2, 4), 3, &mut rng)?;
+        let streams: Vec<Arc<dyn MultilinearStream<Fr>>> = poly
+            .flattened_ml_extensions
+            .iter()
+            .map(|mle| Arc::new(mle.to_dense()) as Arc<dyn MultilinearStream<Fr>>)
+            .collect();
+        let mut streaming_poly = StreamingPolynomial::new(nv);
+        for (coefficient, products) in poly.products.iter() {
+            streaming_poly
+                .add_stream_list(products.iter().map(|&i| streams[i].clone()), *coefficient)?;
+        }
+
+        let mut transcript = <PolyIOP<Fr> as ZeroCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let proof = <PolyIOP<Fr> as ZeroCheck<Fr>>::prove(&poly, &mut transcript)?;
+        for num_streamed_rounds in 0..nv {
+            let mut transcript = <PolyIOP<Fr> as ZeroCheck<Fr>>::init_transcript();
+            transcript.append_message(b"testing", b"initializing transcript for testing")?;
+            let streaming_proof = <PolyIOP<Fr> as StreamingZeroCheck<Fr>>::prove_streaming(
+                &streaming_poly,
+                num_streamed_rounds,
+                &mut transcript,
+            )?;
+            assert_eq!(streaming_proof, proof);
+        }
+
+        let mut transcript = <PolyIOP<Fr> as ZeroCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let subclaim =
+            <PolyIOP<Fr> as ZeroCheck<Fr>>::verify(&proof, &poly.aux_info, &mut transcript)?;
+        assert!(
+            poly.evaluate(&subclaim.point)? == subclaim.expected_evaluation,
+            "wrong subclaim"
+        );
+        assert_eq!(
+            <PolyIOP<Fr> as SumCheck<Fr>>::extract_sum(&proof),
+            Fr::from(0u64)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Keccak256 transcript.
+
+use crate::{Transcript, TranscriptError};
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalSerialize;
+use std::marker::PhantomData;
+
+/// Output of the hash function
+pub type Digest = [u8; 32];
+
+/// Rate of the Keccak256 sponge in bytes
+const KECCAK_RATE: usize = 136;
+
+/// The Keccak256 hash of `data`, as used by Ethereum.
+pub fn keccak256(data: &[u8]) -> Digest {
+    let mut state = [0u64; 25];
+    let mut padded = data.to_vec();
+    padded.push(0x01);
+    padded.resize(padded.len().div_ceil(KECCAK_RATE) * KECCAK_RATE, 0);
+    *padded.last_mut().unwrap() |= 0x80;
+    for block in padded.chunks(KECCAK_RATE) {
+        for (lane, bytes) in state.iter_mut().zip(block.chunks(8)) {
+            *lane ^= u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        keccak::f1600(&mut state);
+    }
+    let mut digest = [0u8; 32];
+    for (bytes, lane) in digest.chunks_mut(8).zip(state.iter()) {
+        bytes.copy_from_slice(&lane.to_le_bytes());
+    }
+    digest
+}
+
+/// A transcript over a Keccak256 hash chain, which is cheap to replay on the
+/// EVM:
+/// - the state starts as `keccak256(label)`
+/// - a message updates it to `keccak256(state || message)`, where field
+///   elements are encoded in big endian and other elements with their
+///   uncompressed serialization
+/// - a challenge is the 512 bits `state || keccak256(state)` read in little
+///   endian and reduced mod p, and is then appended
+///
+/// The labels of the messages and challenges are fixed by the protocol, so
+/// they are not hashed.
+#[derive(Clone)]
+pub struct KeccakTranscript<F: PrimeField> {
+    state: Digest,
+    is_empty: bool,
+    #[doc(hidden)]
+    phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField> Transcript<F> for KeccakTranscript<F> {
+    fn new(label: &'static [u8]) -> Self {
+        Self {
+            state: keccak256(label),
+            is_empty: true,
+            phantom: PhantomData,
+        }
+    }
+
+    fn append_message(&mut self, _label: &'static [u8], msg: &[u8]) -> Result<(), TranscriptError> {
+        self.state = keccak256(&[&self.state[..], msg].concat());
+        self.is_empty = false;
+        Ok(())
+    }
+
+    fn append_field_element(
+        &mut self,
+        label: &'static [u8],
+        field_elem: &F,
+    ) -> Result<(), TranscriptError> {
+        self.append_message(label, &field_elem.into_bigint().to_bytes_be())
+    }
+
+    fn append_serializable_element<S: CanonicalSerialize>(
+        &mut self,
+        label: &'static [u8],
+        group_elem: &S,
+    ) -> Result<(), TranscriptError> {
+        let mut buf = vec![];
+        group_elem.serialize_uncompressed(&mut buf)?;
+        self.append_message(label, &buf)
+    }
+
+    // Reducing 512 bits keeps the bias of the output field element negligible,
+    // whereas a single 256-bit digest would be far from uniform mod p for
+    // fields such as the 255-bit scalar field of BLS12-381.
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Result<F, TranscriptError> {
+        //  we need to reject when transcript is empty
+        if self.is_empty {
+            return Err(TranscriptError::InvalidTranscript(
+                "transcript is empty".to_string(),
+            ));
+        }
+
+        let bytes = [&self.state[..], &keccak256(&self.state)[..]].concat();
+        let challenge = F::from_le_bytes_mod_order(&bytes);
+        self.append_field_element(label, &challenge)?;
+        Ok(challenge)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bls12_381::Fr;
+
+    fn to_hex(digest: Digest) -> String {
+        digest
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()
+    }
+
+    #[test]
+    fn test_keccak256() {
+        assert_eq!(
+            to_hex(keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            to_hex(keccak256(b"abc")),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+        // a message filling a whole block is padded with another block
+        assert_ne!(
+            keccak256(&[0; KECCAK_RATE]),
+            keccak256(&[0; KECCAK_RATE - 1])
+        );
+    }
+
+    #[test]
+    fn test_keccak_transcript() -> Result<(), TranscriptError> {
+        let mut transcript = KeccakTranscript::<Fr>::new(b"test");
+        assert!(transcript.get_and_append_challenge(b"c").is_err());
+
+        // the challenge is the state and its hash reduced modulo p, and is then
+        // appended
+        let x = Fr::from(42u64);
+        transcript.append_field_element(b"x", &x)?;
+        let state = keccak256(&[&keccak256(b"test")[..], &x.into_bigint().to_bytes_be()].concat());
+        let challenge = transcript.get_and_append_challenge(b"c")?;
+        let bytes = [&state[..], &keccak256(&state)[..]].concat();
+        assert_eq!(challenge, Fr::from_le_bytes_mod_order(&bytes));
+        let state = keccak256(&[&state[..], &challenge.into_bigint().to_bytes_be()].concat());
+        assert_eq!(transcript.state, state);
+
+        // successive challenges differ
+        let challenges = transcript.get_and_append_challenge_vectors(b"c", 2)?;
+        assert_ne!(challenges[0], challenges[1]);
+
+        Ok(())
+    }
+}
@@ -5,81 +5,111 @@
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
 //! Module for PolyIOP transcript.
+//!
+//! The protocols are generic over the [`Transcript`] trait, which is
+//! implemented by
+//! - [`IOPTranscript`], over a Merlin transcript
+//! - [`KeccakTranscript`], which a Solidity verifier can replay
+//! - [`PoseidonTranscript`], which a recursive circuit can replay
+//!
 //! TODO(ZZ): move this module to HyperPlonk where the transcript will also be
 //! useful.
 //! TODO(ZZ): decide which APIs need to be public.
 
 mod errors;
+mod keccak;
+mod poseidon;
 pub use errors::TranscriptError;
+pub use keccak::{keccak256, Digest, KeccakTranscript};
+pub use poseidon::{poseidon_config, PoseidonTranscript};
 
 use ark_ff::PrimeField;
 use ark_serialize::CanonicalSerialize;
-use merlin::Transcript;
+use merlin::Transcript as MerlinTranscript;
 use std::marker::PhantomData;
 
+/// A Fiat-Shamir transcript, which absorbs the prover messages and derives
+/// the verifier challenges over the prime field `F`.
+///
+/// A transcript must reject to derive a challenge while it is empty: this is
+/// useful in the case where a protocol is initiated by the verifier, in which
+/// case the prover should start its phase by receiving a `non-empty`
+/// transcript.
+pub trait Transcript<F: PrimeField>: Clone + Send + Sync {
+    /// Create a new transcript.
+    fn new(label: &'static [u8]) -> Self;
+
+    /// Append the message to the transcript.
+    fn append_message(&mut self, label: &'static [u8], msg: &[u8]) -> Result<(), TranscriptError>;
+
+    /// Append the field element to the transcript.
+    fn append_field_element(
+        &mut self,
+        label: &'static [u8],
+        field_elem: &F,
+    ) -> Result<(), TranscriptError> {
+        self.append_message(label, &to_bytes!(field_elem)?)
+    }
+
+    /// Append the serializable element to the transcript.
+    fn append_serializable_element<S: CanonicalSerialize>(
+        &mut self,
+        label: &'static [u8],
+        group_elem: &S,
+    ) -> Result<(), TranscriptError> {
+        self.append_message(label, &to_bytes!(group_elem)?)
+    }
+
+    /// Generate the challenge from the current transcript
+    /// and append it to the transcript.
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Result<F, TranscriptError>;
+
+    /// Generate a list of challenges from the current transcript
+    /// and append them to the transcript.
+    fn get_and_append_challenge_vectors(
+        &mut self,
+        label: &'static [u8],
+        len: usize,
+    ) -> Result<Vec<F>, TranscriptError> {
+        let mut res = vec![];
+        for _ in 0..len {
+            res.push(self.get_and_append_challenge(label)?)
+        }
+        Ok(res)
+    }
+}
+
 /// An IOP transcript consists of a Merlin transcript and a flag `is_empty` to
 /// indicate that if the transcript is empty.
 ///
 /// It is associated with a prime field `F` for which challenges are generated
 /// over.
-///
-/// The `is_empty` flag is useful in the case where a protocol is initiated by
-/// the verifier, in which case the prover should start its phase by receiving a
-/// `non-empty` transcript.
 #[derive(Clone)]
 pub struct IOPTranscript<F: PrimeField> {
-    transcript: Transcript,
+    transcript: MerlinTranscript,
     is_empty: bool,
     #[doc(hidden)]
     phantom: PhantomData<F>,
 }
 
-// TODO: Make this into a Trait
-impl<F: PrimeField> IOPTranscript<F> {
-    /// Create a new IOP transcript.
-    pub fn new(label: &'static [u8]) -> Self {
+impl<F: PrimeField> Transcript<F> for IOPTranscript<F> {
+    fn new(label: &'static [u8]) -> Self {
         Self {
-            transcript: Transcript::new(label),
+            transcript: MerlinTranscript::new(label),
             is_empty: true,
             phantom: PhantomData::default(),
         }
     }
 
-    // Append the message to the transcript.
-    pub fn append_message(
-        &mut self,
-        label: &'static [u8],
-        msg: &[u8],
-    ) -> Result<(), TranscriptError> {
+    fn append_message(&mut self, label: &'static [u8], msg: &[u8]) -> Result<(), TranscriptError> {
         self.transcript.append_message(label, msg);
         self.is_empty = false;
         Ok(())
     }
 
-    // Append the message to the transcript.
-    pub fn append_field_element(
-        &mut self,
-        label: &'static [u8],
-        field_elem: &F,
-    ) -> Result<(), TranscriptError> {
-        self.append_message(label, &to_bytes!(field_elem)?)
-    }
-
-    // Append the message to the transcript.
-    pub fn append_serializable_element<S: CanonicalSerialize>(
-        &mut self,
-        label: &'static [u8],
-        group_elem: &S,
-    ) -> Result<(), TranscriptError> {
-        self.append_message(label, &to_bytes!(group_elem)?)
-    }
-
-    // Generate the challenge from the current transcript
-    // and append it to the transcript.
-    //
     // The output field element is statistical uniform as long
     // as the field has a size less than 2^384.
-    pub fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Result<F, TranscriptError> {
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Result<F, TranscriptError> {
         //  we need to reject when transcript is empty
         if self.is_empty {
             return Err(TranscriptError::InvalidTranscript(
@@ -93,30 +123,6 @@ impl<F: PrimeField> IOPTranscript<F> {
         self.append_serializable_element(label, &challenge)?;
         Ok(challenge)
     }
-
-    // Generate a list of challenges from the current transcript
-    // and append them to the transcript.
-    //
-    // The output field element are statistical uniform as long
-    // as the field has a size less than 2^384.
-    pub fn get_and_append_challenge_vectors(
-        &mut self,
-        label: &'static [u8],
-        len: usize,
-    ) -> Result<Vec<F>, TranscriptError> {
-        //  we need to reject when transcript is empty
-        if self.is_empty {
-            return Err(TranscriptError::InvalidTranscript(
-                "transcript is empty".to_string(),
-            ));
-        }
-
-        let mut res = vec![];
-        for _ in 0..len {
-            res.push(self.get_and_append_challenge(label)?)
-        }
-        Ok(res)
-    }
 }
 
 /// Takes as input a struct, and converts them to a series of bytes. All traits
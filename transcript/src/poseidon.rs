@@ -0,0 +1,146 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Poseidon transcript.
+
+use crate::{Transcript, TranscriptError};
+use ark_crypto_primitives::sponge::{
+    poseidon::{find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge},
+    Absorb, CryptographicSponge,
+};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+
+/// Rate of the sponge, with a capacity of one element
+const RATE: usize = 2;
+/// Number of full rounds
+const FULL_ROUNDS: usize = 8;
+/// Number of partial rounds, for 128 bits of security with a width of 3 over
+/// a field of about 255 bits
+const PARTIAL_ROUNDS: usize = 57;
+/// Exponent of the S-box
+const ALPHA: u64 = 5;
+
+/// The parameters of the Poseidon permutation over `F`, with the round
+/// constants and the MDS matrix generated by the Grain LFSR of the Poseidon
+/// paper.
+pub fn poseidon_config<F: PrimeField>() -> PoseidonConfig<F> {
+    let (ark, mds) = find_poseidon_ark_and_mds::<F>(
+        F::MODULUS_BIT_SIZE as u64,
+        RATE,
+        FULL_ROUNDS as u64,
+        PARTIAL_ROUNDS as u64,
+        0,
+    );
+    PoseidonConfig::new(FULL_ROUNDS, PARTIAL_ROUNDS, ALPHA, mds, ark, RATE, 1)
+}
+
+/// A transcript over a Poseidon sponge, which is cheap to replay in a
+/// circuit over `F`:
+/// - field elements are absorbed as they are
+/// - bytes are absorbed as their length followed by chunks of
+///   `(MODULUS_BIT_SIZE - 1) / 8` little endian bytes, and other elements as
+///   the bytes of their uncompressed serialization
+/// - a challenge is squeezed from the sponge
+///
+/// The label of the transcript is absorbed as bytes, while the labels of the
+/// messages and challenges are fixed by the protocol and not absorbed.
+#[derive(Clone)]
+pub struct PoseidonTranscript<F: PrimeField + Absorb> {
+    sponge: PoseidonSponge<F>,
+    is_empty: bool,
+}
+
+impl<F: PrimeField + Absorb> PoseidonTranscript<F> {
+    fn absorb_bytes(&mut self, msg: &[u8]) {
+        let chunk_size = (F::MODULUS_BIT_SIZE as usize - 1) / 8;
+        self.sponge.absorb(&F::from(msg.len() as u64));
+        for chunk in msg.chunks(chunk_size) {
+            self.sponge.absorb(&F::from_le_bytes_mod_order(chunk));
+        }
+    }
+}
+
+impl<F: PrimeField + Absorb> Transcript<F> for PoseidonTranscript<F> {
+    fn new(label: &'static [u8]) -> Self {
+        let mut transcript = Self {
+            sponge: PoseidonSponge::new(&poseidon_config()),
+            is_empty: true,
+        };
+        transcript.absorb_bytes(label);
+        transcript
+    }
+
+    fn append_message(&mut self, _label: &'static [u8], msg: &[u8]) -> Result<(), TranscriptError> {
+        self.absorb_bytes(msg);
+        self.is_empty = false;
+        Ok(())
+    }
+
+    fn append_field_element(
+        &mut self,
+        _label: &'static [u8],
+        field_elem: &F,
+    ) -> Result<(), TranscriptError> {
+        self.sponge.absorb(field_elem);
+        self.is_empty = false;
+        Ok(())
+    }
+
+    fn append_serializable_element<S: CanonicalSerialize>(
+        &mut self,
+        label: &'static [u8],
+        group_elem: &S,
+    ) -> Result<(), TranscriptError> {
+        let mut buf = vec![];
+        group_elem.serialize_uncompressed(&mut buf)?;
+        self.append_message(label, &buf)
+    }
+
+    fn get_and_append_challenge(&mut self, _label: &'static [u8]) -> Result<F, TranscriptError> {
+        //  we need to reject when transcript is empty
+        if self.is_empty {
+            return Err(TranscriptError::InvalidTranscript(
+                "transcript is empty".to_string(),
+            ));
+        }
+
+        Ok(self.sponge.squeeze_field_elements::<F>(1)[0])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bls12_381::Fr;
+
+    #[test]
+    fn test_poseidon_transcript() -> Result<(), TranscriptError> {
+        let mut transcript = PoseidonTranscript::<Fr>::new(b"test");
+        assert!(transcript.get_and_append_challenge(b"c").is_err());
+
+        // field elements are absorbed natively
+        let x = Fr::from(42u64);
+        transcript.append_field_element(b"x", &x)?;
+        let mut sponge = PoseidonSponge::<Fr>::new(&poseidon_config());
+        sponge.absorb(&Fr::from(4u64));
+        sponge.absorb(&Fr::from_le_bytes_mod_order(b"test"));
+        sponge.absorb(&x);
+        let challenge = transcript.get_and_append_challenge(b"c")?;
+        assert_eq!(challenge, sponge.squeeze_field_elements::<Fr>(1)[0]);
+
+        // the challenges depend on the messages
+        let mut other = PoseidonTranscript::<Fr>::new(b"test");
+        other.append_field_element(b"x", &Fr::from(43u64))?;
+        assert_ne!(other.get_and_append_challenge(b"c")?, challenge);
+
+        // successive challenges differ
+        let challenges = transcript.get_and_append_challenge_vectors(b"c", 2)?;
+        assert_ne!(challenges[0], challenges[1]);
+
+        Ok(())
+    }
+}